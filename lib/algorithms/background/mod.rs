@@ -0,0 +1,209 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+
+use screeps::game::cpu;
+use screeps::RoomName;
+use wasm_bindgen::prelude::*;
+
+use crate::algorithms::distance_map::progressive::ProgressiveDijkstra;
+use crate::algorithms::logistics::{update_logistics, with_logistics};
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, MultiroomDistanceMap};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+
+// The distance buckets a plain distance map job settles per step
+const DISTANCE_MAP_BUCKETS_PER_STEP: usize = 10;
+
+/// A unit of precomputation that can be spread across ticks.
+enum Job {
+    /// Compute a Dijkstra distance map a few distance buckets per step, and
+    /// keep the result until it's collected.
+    DistanceMap {
+        search: Box<ProgressiveDijkstra>,
+        buckets_per_step: usize,
+    },
+    /// Recompute dirty logistics anchors, one per step, until none remain.
+    LogisticsUpdate,
+}
+
+/// The outcome of running a single step of a job.
+enum JobStep {
+    Pending,
+    Done(Option<MultiroomDistanceMap>),
+}
+
+impl Job {
+    fn step(
+        &mut self,
+        get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    ) -> JobStep {
+        match self {
+            Job::DistanceMap {
+                search,
+                buckets_per_step,
+            } => {
+                if search.step(get_cost_matrix, *buckets_per_step) {
+                    JobStep::Done(Some(search.take_distance_map()))
+                } else {
+                    JobStep::Pending
+                }
+            }
+            Job::LogisticsUpdate => {
+                update_logistics(get_cost_matrix, 1);
+                if with_logistics(|logistics| logistics.pending()) == 0 {
+                    JobStep::Done(None)
+                } else {
+                    JobStep::Pending
+                }
+            }
+        }
+    }
+}
+
+/// A FIFO queue of background jobs, processed within a CPU budget each tick.
+#[derive(Default)]
+pub struct JobQueue {
+    jobs: VecDeque<(u32, Job)>,
+    results: HashMap<u32, MultiroomDistanceMap>,
+    next_id: u32,
+}
+
+impl JobQueue {
+    fn push(&mut self, job: Job) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push_back((id, job));
+        id
+    }
+}
+
+thread_local! {
+    static JOB_QUEUE: RefCell<JobQueue> = RefCell::new(JobQueue::default());
+}
+
+/// Queues a Dijkstra distance map computation, spread across calls to
+/// `js_run_background_jobs` a few distance buckets at a time. The overlay is
+/// applied to each room's cost matrix as it loads, and the movement rules
+/// restrict how the search moves between tiles. Returns a job id; collect the
+/// result with `js_take_background_result` once the job is done.
+#[wasm_bindgen(js_name = clockwork_queue_distance_map_job)]
+pub fn js_queue_distance_map_job(
+    start_packed: Vec<u32>,
+    max_rooms: usize,
+    max_path_cost: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> u32 {
+    let start = start_packed
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    JOB_QUEUE.with(|queue| {
+        queue.borrow_mut().push(Job::DistanceMap {
+            search: Box::new(ProgressiveDijkstra::new(
+                start,
+                max_rooms,
+                max_path_cost,
+                overlay,
+                movement.unwrap_or_default(),
+            )),
+            buckets_per_step: DISTANCE_MAP_BUCKETS_PER_STEP,
+        })
+    })
+}
+
+/// Queues a Dijkstra distance map computation that's spread across as many
+/// calls to `js_run_background_jobs` as it needs, settling up to
+/// `buckets_per_step` distance values between CPU checks. `overlay` and
+/// `movement` work as in `js_queue_distance_map_job`. Returns a job id;
+/// collect the result with `js_take_background_result` once the job is done.
+#[wasm_bindgen(js_name = clockwork_queue_progressive_distance_map_job)]
+pub fn js_queue_progressive_distance_map_job(
+    start_packed: Vec<u32>,
+    max_rooms: usize,
+    max_path_cost: usize,
+    buckets_per_step: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> u32 {
+    let start = start_packed
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    JOB_QUEUE.with(|queue| {
+        queue.borrow_mut().push(Job::DistanceMap {
+            search: Box::new(ProgressiveDijkstra::new(
+                start,
+                max_rooms,
+                max_path_cost,
+                overlay,
+                movement.unwrap_or_default(),
+            )),
+            buckets_per_step: buckets_per_step.max(1),
+        })
+    })
+}
+
+/// Queues a job that recomputes dirty logistics anchors until none remain.
+#[wasm_bindgen(js_name = clockwork_queue_logistics_job)]
+pub fn js_queue_logistics_job() -> u32 {
+    JOB_QUEUE.with(|queue| queue.borrow_mut().push(Job::LogisticsUpdate))
+}
+
+/// Returns the number of jobs still waiting to run.
+#[wasm_bindgen(js_name = clockwork_pending_background_jobs)]
+pub fn js_pending_background_jobs() -> usize {
+    JOB_QUEUE.with(|queue| queue.borrow().jobs.len())
+}
+
+/// Runs jobs until the queue is empty or `cpu_budget` has been spent. The
+/// job in progress when the budget runs out stays at the front of the queue.
+/// Returns the number of jobs completed.
+///
+/// Each job is taken out of the queue while it runs, so the cost matrix
+/// callback can queue jobs or collect results without hitting a borrowed
+/// queue.
+pub fn run_background_jobs(
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    cpu_budget: f64,
+) -> usize {
+    let start = cpu::get_used();
+    let mut completed = 0;
+    while cpu::get_used() - start < cpu_budget {
+        let Some((id, mut job)) = JOB_QUEUE.with(|queue| queue.borrow_mut().jobs.pop_front())
+        else {
+            break;
+        };
+        let step = job.step(&get_cost_matrix);
+        JOB_QUEUE.with(|queue| {
+            let mut queue = queue.borrow_mut();
+            match step {
+                JobStep::Pending => queue.jobs.push_front((id, job)),
+                JobStep::Done(result) => {
+                    if let Some(result) = result {
+                        queue.results.insert(id, result);
+                    }
+                    completed += 1;
+                }
+            }
+        });
+    }
+    completed
+}
+
+/// Runs queued jobs within the given CPU budget. Returns the number of jobs
+/// completed.
+#[wasm_bindgen(js_name = clockwork_run_background_jobs)]
+pub fn js_run_background_jobs(get_cost_matrix: &js_sys::Function, cpu_budget: f64) -> usize {
+    run_background_jobs(
+        packed_room_cost_matrix_callback(get_cost_matrix),
+        cpu_budget,
+    )
+}
+
+/// Takes the result of a completed job, if it's ready.
+#[wasm_bindgen(js_name = clockwork_take_background_result)]
+pub fn js_take_background_result(job_id: u32) -> Option<MultiroomDistanceMap> {
+    JOB_QUEUE.with(|queue| queue.borrow_mut().results.remove(&job_id))
+}
@@ -0,0 +1,99 @@
+use screeps::{
+    Position, RoomName, RoomXY, TOWER_FALLOFF, TOWER_FALLOFF_RANGE, TOWER_OPTIMAL_RANGE,
+    TOWER_POWER_ATTACK,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::throw_str;
+
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::rooms::world_position_from_packed;
+
+pub mod nukes;
+pub mod threats;
+pub mod wall_gaps;
+
+/// Attack damage dealt by a tower at the given range, accounting for falloff.
+pub fn tower_damage(range: u8) -> u32 {
+    let range = range.clamp(TOWER_OPTIMAL_RANGE, TOWER_FALLOFF_RANGE);
+    let falloff = (range - TOWER_OPTIMAL_RANGE) as f64
+        / (TOWER_FALLOFF_RANGE - TOWER_OPTIMAL_RANGE) as f64
+        * TOWER_FALLOFF;
+    (TOWER_POWER_ATTACK as f64 * (1.0 - falloff)) as u32
+}
+
+/// Counts, for each tile in the room, how many towers deal at least
+/// `min_damage` there. Tile values are capped at 254.
+pub fn tower_coverage(towers: &[RoomXY], min_damage: u32) -> ClockworkCostMatrix {
+    let mut coverage = ClockworkCostMatrix::new(Some(0));
+    for y in 0..50 {
+        for x in 0..50 {
+            // SAFETY: x and y are both in the range 0..50
+            let xy = unsafe { RoomXY::unchecked_new(x, y) };
+            let count = towers
+                .iter()
+                .filter(|tower| tower_damage(tower.get_range_to(xy)) >= min_damage)
+                .count();
+            coverage.set(xy, count.min(254) as u8);
+        }
+    }
+    coverage
+}
+
+/// Filters a set of rampart positions (e.g. the output of a min-cut) down to
+/// the ones covered by fewer than `min_towers` towers, ordered from least to
+/// most covered. Ramparts inside the exclusion zone are left out, since the
+/// towers can defend those tiles on their own.
+pub fn prioritize_ramparts(
+    ramparts: &[RoomXY],
+    coverage: &ClockworkCostMatrix,
+    min_towers: u8,
+) -> Vec<RoomXY> {
+    let mut exposed: Vec<RoomXY> = ramparts
+        .iter()
+        .copied()
+        .filter(|xy| coverage.get(*xy) < min_towers)
+        .collect();
+    exposed.sort_by_key(|xy| coverage.get(*xy));
+    exposed
+}
+
+fn unpack_room_positions(packed: &[u32]) -> (Option<RoomName>, Vec<RoomXY>) {
+    let positions: Vec<Position> = packed
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    let room_name = positions.first().map(|pos| pos.room_name());
+    if positions
+        .iter()
+        .any(|pos| Some(pos.room_name()) != room_name)
+    {
+        throw_str("All positions must be in the same room");
+    }
+    (room_name, positions.iter().map(|pos| pos.xy()).collect())
+}
+
+/// Counts, for each tile in the room, how many of the given towers deal at
+/// least `min_damage` there.
+#[wasm_bindgen(js_name = clockwork_tower_coverage)]
+pub fn js_tower_coverage(towers_packed: Vec<u32>, min_damage: u32) -> ClockworkCostMatrix {
+    let (_, towers) = unpack_room_positions(&towers_packed);
+    tower_coverage(&towers, min_damage)
+}
+
+/// Returns the ramparts covered by fewer than `min_towers` towers, least
+/// covered first, as packed positions.
+#[wasm_bindgen(js_name = clockwork_prioritize_ramparts)]
+pub fn js_prioritize_ramparts(
+    ramparts_packed: Vec<u32>,
+    coverage: &ClockworkCostMatrix,
+    min_towers: u8,
+) -> Vec<u32> {
+    let (room_name, ramparts) = unpack_room_positions(&ramparts_packed);
+    let Some(room_name) = room_name else {
+        return vec![];
+    };
+    prioritize_ramparts(&ramparts, coverage, min_towers)
+        .into_iter()
+        .map(|xy| Position::new(xy.x, xy.y, room_name).packed_repr())
+        .collect()
+}
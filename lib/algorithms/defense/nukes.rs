@@ -0,0 +1,107 @@
+use screeps::{Position, RoomXY, NUKE_DAMAGE_RANGE_0, NUKE_DAMAGE_RANGE_2};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::throw_str;
+
+use super::unpack_room_positions;
+use crate::datatypes::DistanceMap;
+
+/// Damage a nuke landing at `center` deals to structures at `xy`: the full
+/// amount on the landing tile, half within range 2, and nothing beyond.
+pub fn nuke_damage(center: RoomXY, xy: RoomXY) -> u32 {
+    match center.get_range_to(xy) {
+        0 => NUKE_DAMAGE_RANGE_0,
+        1..=2 => NUKE_DAMAGE_RANGE_2,
+        _ => 0,
+    }
+}
+
+/// Sums the damage dealt to each tile in a room by nukes landing at each of
+/// `centers`.
+pub fn nuke_damage_map(centers: &[RoomXY]) -> DistanceMap {
+    let mut damage = DistanceMap::new_with_value(0);
+    for center in centers {
+        for xy in tiles_in_range(*center) {
+            damage[xy] = damage[xy].saturating_add(nuke_damage(*center, xy) as usize);
+        }
+    }
+    damage
+}
+
+/// The tiles within range 2 of `center`, clipped to the room.
+fn tiles_in_range(center: RoomXY) -> impl Iterator<Item = RoomXY> {
+    (-2..=2).flat_map(move |dy| (-2..=2).filter_map(move |dx| center.checked_add((dx, dy))))
+}
+
+/// Chooses up to `count` landing points, one at a time, each maximizing the
+/// additional damage dealt to the given structures. Damage to a structure is
+/// capped at its hits (pass the combined hits of a rampart and the structure
+/// under it), so nukes spread out once their targets would be destroyed.
+/// Stops early if no landing point would deal more damage.
+pub fn plan_nuke_strike(structures: &[(RoomXY, u32)], count: usize) -> Vec<RoomXY> {
+    let mut remaining: Vec<(RoomXY, u32)> = structures.to_vec();
+    let mut landing_points = Vec::new();
+    for _ in 0..count {
+        let mut best: Option<(RoomXY, u64)> = None;
+        for y in 0..50 {
+            for x in 0..50 {
+                // SAFETY: x and y are both in the range 0..50
+                let center = unsafe { RoomXY::unchecked_new(x, y) };
+                let gain: u64 = remaining
+                    .iter()
+                    .map(|(xy, hits)| nuke_damage(center, *xy).min(*hits) as u64)
+                    .sum();
+                if gain > 0 && best.is_none_or(|(_, best_gain)| gain > best_gain) {
+                    best = Some((center, gain));
+                }
+            }
+        }
+        let Some((center, _)) = best else {
+            break;
+        };
+        for (xy, hits) in remaining.iter_mut() {
+            *hits = hits.saturating_sub(nuke_damage(center, *xy));
+        }
+        landing_points.push(center);
+    }
+    landing_points
+}
+
+/// Maps the cumulative damage dealt to each tile in a room by nukes landing
+/// at the given positions.
+///
+/// # Arguments
+/// * `centers_packed` - The packed landing positions (all in the same room)
+///
+/// # Returns
+/// The damage dealt to each tile
+#[wasm_bindgen(js_name = clockwork_nuke_damage_map)]
+pub fn js_nuke_damage_map(centers_packed: Vec<u32>) -> DistanceMap {
+    let (_, centers) = unpack_room_positions(&centers_packed);
+    nuke_damage_map(&centers)
+}
+
+/// Chooses landing points for up to `count` nukes to maximize damage to the
+/// given structures.
+///
+/// # Arguments
+/// * `structures_packed` - The packed structure positions (all in the same room)
+/// * `hits` - The hits of each structure, including any rampart over it
+/// * `count` - The number of nukes available
+///
+/// # Returns
+/// The packed landing positions, in the order they were chosen
+#[wasm_bindgen(js_name = clockwork_plan_nuke_strike)]
+pub fn js_plan_nuke_strike(structures_packed: Vec<u32>, hits: Vec<u32>, count: usize) -> Vec<u32> {
+    if structures_packed.len() != hits.len() {
+        throw_str("Structures and hits must have the same length");
+    }
+    let (room_name, positions) = unpack_room_positions(&structures_packed);
+    let Some(room_name) = room_name else {
+        return vec![];
+    };
+    let structures: Vec<(RoomXY, u32)> = positions.into_iter().zip(hits).collect();
+    plan_nuke_strike(&structures, count)
+        .into_iter()
+        .map(|xy| Position::new(xy.x, xy.y, room_name).packed_repr())
+        .collect()
+}
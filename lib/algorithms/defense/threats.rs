@@ -0,0 +1,94 @@
+use screeps::{Position, RoomName, RoomXY};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+/// A hostile creep, the range it can attack at, and its damage per tick.
+#[derive(Debug, Clone, Copy)]
+pub struct Hostile {
+    pub position: Position,
+    pub range: u32,
+    pub dps: u32,
+}
+
+/// Builds a cost matrix for kiting or avoiding hostiles: a copy of `base`
+/// where each tile costs `cost_per_dps` more for every point of damage the
+/// hostiles in range of it can deal. Hostiles in neighboring rooms count for
+/// tiles within range across the border. Impassable tiles stay impassable,
+/// and penalized tiles are capped at 254.
+pub fn build_threat_matrix(
+    room_name: RoomName,
+    hostiles: &[Hostile],
+    base: &ClockworkCostMatrix,
+    cost_per_dps: f64,
+) -> ClockworkCostMatrix {
+    let mut damage = [0u32; 2500];
+    for hostile in hostiles {
+        let range = hostile.range as i32;
+        for dy in -range..=range {
+            for dx in -range..=range {
+                let Ok(tile) = hostile.position.checked_add((dx, dy)) else {
+                    continue;
+                };
+                if tile.room_name() == room_name {
+                    let index = tile.y().u8() as usize * 50 + tile.x().u8() as usize;
+                    damage[index] = damage[index].saturating_add(hostile.dps);
+                }
+            }
+        }
+    }
+
+    let mut matrix = base.clone();
+    for y in 0..50 {
+        for x in 0..50 {
+            // SAFETY: x and y are both in the range 0..50
+            let xy = unsafe { RoomXY::unchecked_new(x, y) };
+            let cost = matrix.get(xy);
+            let dps = damage[y as usize * 50 + x as usize];
+            if cost == 255 || dps == 0 {
+                continue;
+            }
+            let penalty = (dps as f64 * cost_per_dps).ceil();
+            matrix.set(xy, (cost as f64 + penalty).min(254.0) as u8);
+        }
+    }
+    matrix
+}
+
+/// Builds a threat cost matrix for a room from a list of hostiles, adding a
+/// damage-based penalty to every tile they can attack.
+///
+/// # Arguments
+/// * `room_name` - The packed name of the room to build the matrix for
+/// * `hostiles` - Packed [position, range, dps] triples
+/// * `base` - The cost matrix to add penalties to (e.g. a terrain matrix)
+/// * `cost_per_dps` - The extra cost per point of damage per tick
+///
+/// # Returns
+/// A new cost matrix with the penalties applied
+#[wasm_bindgen(js_name = clockwork_build_threat_matrix)]
+pub fn js_build_threat_matrix(
+    room_name: u16,
+    hostiles: Vec<u32>,
+    base: &ClockworkCostMatrix,
+    cost_per_dps: f64,
+) -> ClockworkCostMatrix {
+    if !hostiles.len().is_multiple_of(3) {
+        throw_str("Hostiles must be packed as [position, range, dps] triples");
+    }
+    let hostiles: Vec<Hostile> = hostiles
+        .chunks(3)
+        .map(|chunk| Hostile {
+            position: world_position_from_packed(chunk[0]),
+            range: chunk[1],
+            dps: chunk[2],
+        })
+        .collect();
+    build_threat_matrix(
+        world_room_from_packed(room_name),
+        &hostiles,
+        base,
+        cost_per_dps,
+    )
+}
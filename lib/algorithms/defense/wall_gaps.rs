@@ -0,0 +1,131 @@
+use screeps::{Position, RoomName, RoomXY, Terrain};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use crate::datatypes::PackedTerrain;
+use crate::helpers::rooms::world_room_from_packed;
+use crate::helpers::terrain::get_packed_terrain;
+
+/// The gaps in a room's natural walls: spans of walkable tiles that need
+/// blocking to seal the room off from its exits.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct WallGaps {
+    gaps: Vec<Vec<Position>>,
+}
+
+/// How far a tile is from the nearest room edge.
+fn edge_distance(x: u8, y: u8) -> u8 {
+    x.min(y).min(49 - x).min(49 - y)
+}
+
+fn neighbors(xy: RoomXY) -> impl Iterator<Item = RoomXY> {
+    (-1..=1).flat_map(move |dy| {
+        (-1..=1)
+            .filter(move |dx| *dx != 0 || dy != 0)
+            .filter_map(move |dx| xy.checked_add((dx, dy)))
+    })
+}
+
+/// Finds the gaps in a room's natural wall perimeter. Ramparts and walls
+/// can't be built within one tile of an exit, so the perimeter is the ring
+/// of tiles two tiles in from the edges: every walkable ring tile that
+/// creeps can reach from an exit without crossing the ring is part of a gap.
+/// Adjacent gap tiles are grouped into spans, whose widths are the number of
+/// tiles to block.
+pub fn wall_gaps(room_name: RoomName, terrain: &PackedTerrain) -> WallGaps {
+    let walkable = |xy: RoomXY| terrain.get(xy) != Terrain::Wall;
+
+    // Flood fill the band between the exits and the ring
+    let mut reached = [[false; 50]; 50];
+    let mut stack: Vec<RoomXY> = Vec::new();
+    for y in 0..50 {
+        for x in 0..50 {
+            // SAFETY: x and y are both in the range 0..50
+            let xy = unsafe { RoomXY::unchecked_new(x, y) };
+            if edge_distance(x, y) == 0 && walkable(xy) {
+                reached[y as usize][x as usize] = true;
+                stack.push(xy);
+            }
+        }
+    }
+    let mut is_gap = [[false; 50]; 50];
+    while let Some(xy) = stack.pop() {
+        for neighbor in neighbors(xy) {
+            let (x, y) = (neighbor.x.u8(), neighbor.y.u8());
+            if !walkable(neighbor) || reached[y as usize][x as usize] {
+                continue;
+            }
+            match edge_distance(x, y) {
+                0 | 1 => {
+                    reached[y as usize][x as usize] = true;
+                    stack.push(neighbor);
+                }
+                2 => is_gap[y as usize][x as usize] = true,
+                _ => {}
+            }
+        }
+    }
+
+    // Group the gap tiles into spans
+    let mut gaps = WallGaps::default();
+    let mut grouped = [[false; 50]; 50];
+    for y in 0..50u8 {
+        for x in 0..50u8 {
+            if !is_gap[y as usize][x as usize] || grouped[y as usize][x as usize] {
+                continue;
+            }
+            grouped[y as usize][x as usize] = true;
+            // SAFETY: x and y are both in the range 0..50
+            let mut stack = vec![unsafe { RoomXY::unchecked_new(x, y) }];
+            let mut gap = Vec::new();
+            while let Some(xy) = stack.pop() {
+                gap.push(Position::new(xy.x, xy.y, room_name));
+                for neighbor in neighbors(xy) {
+                    let (nx, ny) = (neighbor.x.u8() as usize, neighbor.y.u8() as usize);
+                    if is_gap[ny][nx] && !grouped[ny][nx] {
+                        grouped[ny][nx] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            gaps.gaps.push(gap);
+        }
+    }
+    gaps
+}
+
+#[wasm_bindgen]
+impl WallGaps {
+    /// The packed positions of every gap's tiles, one gap after another.
+    #[wasm_bindgen(getter)]
+    pub fn tiles(&self) -> Vec<u32> {
+        self.gaps
+            .iter()
+            .flatten()
+            .map(|pos| pos.packed_repr())
+            .collect()
+    }
+
+    /// The number of tiles in each gap, in the same order as `tiles`.
+    #[wasm_bindgen(getter)]
+    pub fn widths(&self) -> Vec<u32> {
+        self.gaps.iter().map(|gap| gap.len() as u32).collect()
+    }
+}
+
+/// Finds the walkable gaps in a room's natural walls, for early barricade
+/// planning.
+///
+/// # Arguments
+/// * `room_name` - The packed room name
+///
+/// # Returns
+/// The gaps found
+#[wasm_bindgen(js_name = clockwork_wall_gaps)]
+pub fn js_wall_gaps(room_name: u16) -> WallGaps {
+    let room_name = world_room_from_packed(room_name);
+    match get_packed_terrain(room_name) {
+        Some(terrain) => wall_gaps(room_name, &terrain),
+        None => throw_str(&format!("Invalid room name: {}", room_name)),
+    }
+}
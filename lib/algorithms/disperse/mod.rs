@@ -0,0 +1,131 @@
+use std::collections::VecDeque;
+
+use screeps::{Direction, Position, RoomXY};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::throw_str;
+
+use crate::datatypes::{ClockworkCostMatrix, DistanceMap};
+use crate::helpers::rooms::world_position_from_packed;
+
+/// Picks a target tile for each creep so that no two targets are closer than
+/// `min_spacing` (by range). Creeps are assigned in order, each to the nearest
+/// free tile in `area`; a creep with no free tile left is assigned its own
+/// position.
+///
+/// Spacing is tracked in a DistanceMap holding the range from each tile to the
+/// nearest claimed target, so each collision check is a single lookup.
+pub fn disperse(creeps: &[RoomXY], min_spacing: u8, area: &[RoomXY]) -> Vec<RoomXY> {
+    let mut claimed = DistanceMap::new();
+    let mut targets = Vec::with_capacity(creeps.len());
+
+    for creep in creeps {
+        let target = area
+            .iter()
+            .filter(|xy| claimed[**xy] >= min_spacing as usize)
+            .min_by_key(|xy| creep.get_range_to(**xy))
+            .copied()
+            .unwrap_or(*creep);
+
+        let min_x = target.x.u8().saturating_sub(min_spacing);
+        let max_x = target.x.u8().saturating_add(min_spacing).min(49);
+        let min_y = target.y.u8().saturating_sub(min_spacing);
+        let max_y = target.y.u8().saturating_add(min_spacing).min(49);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                // SAFETY: x and y are clamped to the range 0..50
+                let xy = unsafe { RoomXY::unchecked_new(x, y) };
+                let range = target.get_range_to(xy) as usize;
+                if range < claimed[xy] {
+                    claimed[xy] = range;
+                }
+            }
+        }
+
+        targets.push(target);
+    }
+
+    targets
+}
+
+/// Computes dispersal targets for a set of creeps, spread at least
+/// `min_spacing` apart within the area. Creeps and area tiles must all be in
+/// the same room. Returns one packed target position per creep, in order.
+#[wasm_bindgen(js_name = clockwork_disperse)]
+pub fn js_disperse(creep_positions: Vec<u32>, min_spacing: u8, area: Vec<u32>) -> Vec<u32> {
+    let creeps: Vec<Position> = creep_positions
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    let area: Vec<Position> = area
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    let Some(room_name) = creeps.first().map(|pos| pos.room_name()) else {
+        return vec![];
+    };
+    if creeps
+        .iter()
+        .chain(area.iter())
+        .any(|pos| pos.room_name() != room_name)
+    {
+        throw_str("All creep and area positions must be in the same room");
+    }
+
+    let creeps: Vec<RoomXY> = creeps.iter().map(|pos| pos.xy()).collect();
+    let area: Vec<RoomXY> = area.iter().map(|pos| pos.xy()).collect();
+    disperse(&creeps, min_spacing, &area)
+        .into_iter()
+        .map(|xy| Position::new(xy.x, xy.y, room_name).packed_repr())
+        .collect()
+}
+
+/// Finds the tile closest to `origin` (by moves, within its room) whose cost
+/// is below `threshold`, e.g. the nearest tile off the roads for an idle
+/// creep to park on. Tiles at 255 are impassable; `origin` itself counts if
+/// it qualifies.
+pub fn nearest_free_tile(
+    origin: RoomXY,
+    cost_matrix: &ClockworkCostMatrix,
+    threshold: u8,
+) -> Option<RoomXY> {
+    let mut visited = [false; 2500];
+    let mut frontier = VecDeque::from([origin]);
+    visited[origin.y.u8() as usize * 50 + origin.x.u8() as usize] = true;
+    while let Some(xy) = frontier.pop_front() {
+        if cost_matrix.get(xy) < threshold {
+            return Some(xy);
+        }
+        for direction in Direction::iter() {
+            let Some(neighbor) = xy.checked_add_direction(*direction) else {
+                continue;
+            };
+            let index = neighbor.y.u8() as usize * 50 + neighbor.x.u8() as usize;
+            if !visited[index] && cost_matrix.get(neighbor) < 255 {
+                visited[index] = true;
+                frontier.push_back(neighbor);
+            }
+        }
+    }
+    None
+}
+
+/// Finds the closest tile to the origin, within its room, with a cost below
+/// the threshold.
+///
+/// # Arguments
+/// * `origin_packed` - The packed position to search from
+/// * `cost_matrix` - The room's cost matrix; tiles at 255 are impassable
+/// * `threshold` - Tiles with a lower cost than this are free
+///
+/// # Returns
+/// The packed position of the nearest free tile, or undefined if none can be reached
+#[wasm_bindgen(js_name = clockwork_nearest_free_tile)]
+pub fn js_nearest_free_tile(
+    origin_packed: u32,
+    cost_matrix: &ClockworkCostMatrix,
+    threshold: u8,
+) -> Option<u32> {
+    let origin = world_position_from_packed(origin_packed);
+    nearest_free_tile(origin.xy(), cost_matrix, threshold)
+        .map(|xy| Position::new(xy.x, xy.y, origin.room_name()).packed_repr())
+}
@@ -0,0 +1,217 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use screeps::{Position, RoomName};
+use wasm_bindgen::prelude::*;
+
+use super::astar::astar_multiroom_distance_map;
+use super::diagnostics::FailureReason;
+use super::goals::{unpack_goals, Goal, GoalSet};
+use super::heuristics::base_heuristic_with_range;
+use super::OpenListLimit;
+use crate::algorithms::map::MovementRules;
+use crate::algorithms::path::to_multiroom_distance_map_origin::path_to_multiroom_distance_map_origin;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, Path};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+
+/// The best path an anytime search found within its budget.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct AnytimeResult {
+    path: Path,
+    cost: usize,
+    bound: f64,
+    iterations: usize,
+    ops: usize,
+}
+
+#[wasm_bindgen]
+impl AnytimeResult {
+    /// The best path found, from the start to the destination.
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> Path {
+        self.path.clone()
+    }
+
+    /// The path's cost.
+    #[wasm_bindgen(getter)]
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+
+    /// How far from optimal the path can be: its cost is at most `bound`
+    /// times the cheapest path's. 1 means the path is optimal.
+    #[wasm_bindgen(getter)]
+    pub fn bound(&self) -> f64 {
+        self.bound
+    }
+
+    /// The number of searches run.
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// The number of tiles expanded across every search.
+    #[wasm_bindgen(getter)]
+    pub fn ops(&self) -> usize {
+        self.ops
+    }
+}
+
+/// Anytime A*: finds a path quickly with the heuristic inflated by
+/// `initial_weight`, then searches again with the weight lowered by
+/// `weight_step` each time (down to 1), while `max_ops` has budget left.
+/// Each search only looks for paths cheaper than the best so far, so a
+/// search that runs out of tiles proves the best path optimal.
+///
+/// Returns the best path found and its suboptimality bound, or `None` if no
+/// path was found. Cost matrices are loaded once and shared by every search.
+#[allow(clippy::too_many_arguments)]
+pub fn anytime_astar(
+    start: Position,
+    destinations: Vec<Goal>,
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    initial_weight: f64,
+    weight_step: f64,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+) -> Option<AnytimeResult> {
+    let cost_matrices: RefCell<HashMap<RoomName, Option<ClockworkCostMatrix>>> =
+        RefCell::new(HashMap::new());
+    let cached_cost_matrix = |room_name: RoomName| {
+        cost_matrices
+            .borrow_mut()
+            .entry(room_name)
+            .or_insert_with(|| get_cost_matrix(room_name))
+            .clone()
+    };
+    let goals = GoalSet::new(destinations);
+    let heuristic = base_heuristic_with_range(goals.goals());
+
+    let mut best: Option<AnytimeResult> = None;
+    let mut weight = initial_weight.max(1.0);
+    let mut ops_remaining = max_ops;
+    let mut iterations = 0;
+    loop {
+        // Only paths cheaper than the best so far are worth finding
+        let cost_limit = best.as_ref().map_or(max_path_cost, |best| best.cost);
+        let result = astar_multiroom_distance_map(
+            vec![start],
+            cached_cost_matrix,
+            max_rooms,
+            ops_remaining,
+            cost_limit,
+            |position| (heuristic(position) as f64 * weight) as usize,
+            Some(goals.clone()),
+            None,
+            false,
+            movement,
+            open_limit,
+        );
+        iterations += 1;
+        ops_remaining = ops_remaining.saturating_sub(result.ops);
+
+        let found = result.found_targets.first().and_then(|target| {
+            let cost = result.distance_map.get(*target);
+            path_to_multiroom_distance_map_origin(*target, &result.distance_map)
+                .ok()
+                .map(|path| (path, cost))
+        });
+        match found {
+            Some((path, cost)) => match best.as_mut() {
+                // A goal can be reached from a tile under the cost limit
+                // without beating the best path; the bound still tightens
+                Some(best) if best.cost <= cost => best.bound = weight,
+                _ => {
+                    best = Some(AnytimeResult {
+                        path,
+                        cost,
+                        bound: weight,
+                        iterations,
+                        ops: max_ops - ops_remaining,
+                    })
+                }
+            },
+            None => {
+                // Every tile under the cost limit was expanded, so nothing
+                // beats the best path
+                let exhausted = result
+                    .failure
+                    .as_ref()
+                    .is_some_and(|failure| failure.reason() == FailureReason::Unreachable);
+                if let (true, Some(best)) = (exhausted, best.as_mut()) {
+                    best.bound = 1.0;
+                }
+                break;
+            }
+        }
+        if weight <= 1.0 || ops_remaining == 0 || weight_step <= 0.0 {
+            break;
+        }
+        weight = (weight - weight_step).max(1.0);
+    }
+
+    best.map(|best| AnytimeResult {
+        iterations,
+        ops: max_ops - ops_remaining,
+        ..best
+    })
+}
+
+/// Finds a path with anytime A*, trading speed for quality in one call: a
+/// fast search with an inflated heuristic, refined by searches with lower
+/// weights while the budget lasts.
+///
+/// # Arguments
+/// * `start_packed` - The packed start position
+/// * `destinations` - Packed [position, range, kind] triples; the search ends at the first reached
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_rooms` - Maximum number of rooms to explore per search
+/// * `max_ops` - Maximum number of tiles to expand across every search
+/// * `max_path_cost` - Maximum path cost
+/// * `initial_weight` - The heuristic weight of the first search
+/// * `weight_step` - How much to lower the weight for each following search
+/// * `overlay` - Per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
+///
+/// # Returns
+/// The best path found and its suboptimality bound, or undefined if no path was found
+#[wasm_bindgen(js_name = clockwork_anytime_astar)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_anytime_astar(
+    start_packed: u32,
+    destinations: Vec<u32>,
+    get_cost_matrix: &js_sys::Function,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    initial_weight: f64,
+    weight_step: f64,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> Option<AnytimeResult> {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    anytime_astar(
+        world_position_from_packed(start_packed),
+        unpack_goals(destinations),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_rooms,
+        max_ops,
+        max_path_cost,
+        initial_weight,
+        weight_step,
+        movement,
+        open_limit,
+    )
+}
@@ -1,17 +1,23 @@
-use crate::algorithms::map::{corresponding_room_edge, next_directions};
-use crate::datatypes::ClockworkCostMatrix;
+use crate::algorithms::map::{corresponding_room_edge, next_directions, MovementRules};
 use crate::datatypes::RoomDataCache;
-use crate::utils::set_panic_hook;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::positions::{
+    destinations_from_js, positions_from_js, room_name_cost_matrix_callback,
+};
+use crate::utils::{set_panic_hook, PanicContext};
 use screeps::Direction;
 use screeps::Position;
 use screeps::RoomName;
-use std::convert::TryFrom;
 use std::ops::Fn;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::throw_val;
 
-use super::heuristics::base_heuristic_with_range;
-use super::SearchResult;
+use super::diagnostics::{diagnose, goals_unreachable, remaining_goals_unreachable, FailureReason};
+use super::goals::{goal_set, remove_reached_goals, unpack_goals, Goal, GoalSet};
+use super::heuristic_map::heuristic_map;
+use super::heuristics::{scaled_heuristic_with_range, with_heuristic_map, MinTileCost};
+use super::{ExpansionLog, OpenListLimit, OpenListOverflow, SearchResult};
+use crate::helpers::rooms::world_position_from_packed;
 
 #[derive(Copy, Clone)]
 struct State {
@@ -27,6 +33,7 @@ struct State {
 
 /// Creates a distance map for the given start positions, using A* to optimize the search and
 /// find the shortest path to the given destinations.
+#[allow(clippy::too_many_arguments)]
 pub fn astar_multiroom_distance_map(
     start: Vec<Position>,
     get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
@@ -34,53 +41,113 @@ pub fn astar_multiroom_distance_map(
     max_ops: usize,
     max_path_cost: usize,
     heuristic_fn: impl Fn(Position) -> usize,
-    any_of_destinations: Option<Vec<(Position, usize)>>,
-    all_of_destinations: Option<Vec<(Position, usize)>>,
+    any_of_destinations: Option<GoalSet>,
+    all_of_destinations: Option<Vec<Goal>>,
+    record_expansions: bool,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+) -> SearchResult {
+    astar_with_min_tile_cost(
+        start,
+        get_cost_matrix,
+        max_rooms,
+        max_ops,
+        max_path_cost,
+        heuristic_fn,
+        any_of_destinations,
+        all_of_destinations,
+        record_expansions,
+        movement,
+        open_limit,
+        None,
+    )
+}
+
+/// Moves the open states into the buckets for their current f_score, after
+/// the heuristic has dropped, and returns the new lowest bucket.
+fn rescore_open_list(
+    open: &mut [Vec<State>],
+    min_idx: usize,
+    heuristic_fn: impl Fn(Position) -> usize,
+) -> usize {
+    let states: Vec<State> = open[min_idx..]
+        .iter_mut()
+        .flat_map(std::mem::take)
+        .collect();
+    let mut new_min_idx = min_idx;
+    for state in states {
+        // The heuristic only drops, so the state's bucket can't be past the
+        // end of the open list
+        let f_score = state
+            .g_score
+            .saturating_add(heuristic_fn(state.position))
+            .min(open.len() - 1);
+        new_min_idx = new_min_idx.min(f_score);
+        open[f_score].push(state);
+    }
+    new_min_idx
+}
+
+/// `astar_multiroom_distance_map`, with a heuristic scaled by `min_tile_cost`.
+/// If the minimum drops as rooms load, the open list is rescored so the
+/// states queued before then aren't overestimated.
+#[allow(clippy::too_many_arguments)]
+fn astar_with_min_tile_cost(
+    start: Vec<Position>,
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    heuristic_fn: impl Fn(Position) -> usize,
+    any_of_destinations: Option<GoalSet>,
+    all_of_destinations: Option<Vec<Goal>>,
+    record_expansions: bool,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+    min_tile_cost: Option<&MinTileCost>,
 ) -> SearchResult {
     set_panic_hook();
+    let _context = PanicContext::enter("astar");
     // Since we expect the total cost to be limited (path costs above 1500 rarely make sense),
     // we use a vec indexed by the f_score to store the open states rather than a proper priority queue.
     let mut open: Vec<Vec<State>> = vec![Default::default()];
     let mut min_idx = 0;
-    // We use this to limit the search to the given number of tiles.
-    let mut tiles_remaining = max_ops;
+    // We use this to limit the search to the given number of expanded tiles.
+    let mut ops_remaining = max_ops;
+    let mut pushed = 0;
+    // The number of states currently in the open list
+    let mut open_count = 0;
     let mut cached_room_data = RoomDataCache::new(max_rooms, get_cost_matrix);
-    let any_of_targets: Option<Vec<(Position, usize)>> = any_of_destinations;
-    let mut all_of_targets = all_of_destinations.clone();
+    let any_of_targets = any_of_destinations;
+    let mut all_of_targets = all_of_destinations;
     let mut found_targets = Vec::new();
+    let mut expansions = ExpansionLog::new(record_expansions);
 
     // check if start position matches targets and return early if so
     for neighbor in start.iter() {
         if let Some(any_of_targets) = &any_of_targets {
-            if any_of_targets.iter().any(|(target, range)| {
-                target.room_name() == neighbor.room_name()
-                    && target.get_range_to(*neighbor) <= *range as u32
-            }) {
+            if any_of_targets.any_reached(*neighbor) {
                 found_targets.push(*neighbor);
                 return SearchResult::new(
                     cached_room_data.into(),
                     found_targets,
-                    max_ops - tiles_remaining,
+                    max_ops - ops_remaining,
+                    pushed,
+                    expansions.into_positions(),
                 );
             }
         }
         if let Some(all_of_targets) = &mut all_of_targets {
-            let mut i = 0;
-            while i < all_of_targets.len() {
-                if all_of_targets[i].0.room_name() == neighbor.room_name()
-                    && all_of_targets[i].0.get_range_to(*neighbor) <= all_of_targets[i].1 as u32
-                {
-                    found_targets.push(*neighbor);
-                    all_of_targets.remove(i);
-                } else {
-                    i += 1;
-                }
+            if remove_reached_goals(all_of_targets, *neighbor) {
+                found_targets.push(*neighbor);
             }
             if all_of_targets.is_empty() {
                 return SearchResult::new(
                     cached_room_data.into(),
                     found_targets,
-                    max_ops - tiles_remaining,
+                    max_ops - ops_remaining,
+                    pushed,
+                    expansions.into_positions(),
                 );
             }
         }
@@ -97,9 +164,40 @@ pub fn astar_multiroom_distance_map(
                 room_key,
             });
             cached_room_data[room_key].distance_map[position.xy()] = 0;
-            tiles_remaining -= 1;
+            pushed += 1;
+            open_count += 1;
         }
     }
+    let start_pushed = pushed;
+    // The start states are all at f_score 0 regardless of the heuristic
+    let heuristic_dropped = || min_tile_cost.is_some_and(MinTileCost::take_dropped);
+    heuristic_dropped();
+
+    // Stop right away if the destinations are all in blocked rooms, rather
+    // than exploring until the search runs out of ops
+    let remaining_goals = any_of_targets
+        .as_ref()
+        .map(GoalSet::goals)
+        .or(all_of_targets.as_deref())
+        .unwrap_or_default();
+    if start_pushed > 0 && goals_unreachable(&mut cached_room_data, remaining_goals) {
+        let failure = diagnose(
+            &cached_room_data,
+            remaining_goals,
+            start_pushed,
+            pushed,
+            open_count,
+            FailureReason::GoalRoomsBlocked,
+        );
+        return SearchResult::new(
+            cached_room_data.into(),
+            found_targets,
+            max_ops - ops_remaining,
+            pushed,
+            expansions.into_positions(),
+        )
+        .with_failure(failure);
+    }
 
     // Loop through all open tiles, starting with the lowest f_score.
     while min_idx < open.len() {
@@ -110,15 +208,58 @@ pub fn astar_multiroom_distance_map(
             room_key,
         }) = open[min_idx].pop()
         {
+            open_count -= 1;
+            // If the max number of tiles has been expanded, return the distance map.
+            if ops_remaining == 0 {
+                // Count the state just popped as part of the frontier
+                let frontier = open_count + 1;
+                let failure = diagnose(
+                    &cached_room_data,
+                    any_of_targets
+                        .as_ref()
+                        .map(GoalSet::goals)
+                        .or(all_of_targets.as_deref())
+                        .unwrap_or_default(),
+                    start_pushed,
+                    pushed,
+                    frontier,
+                    FailureReason::MaxOpsReached,
+                );
+                return SearchResult::new(
+                    cached_room_data.into(),
+                    found_targets,
+                    max_ops - ops_remaining,
+                    pushed,
+                    expansions.into_positions(),
+                )
+                .with_failure(failure);
+            }
+            ops_remaining -= 1;
+
             // Ignore paths that cost too much.
             if g_score >= max_path_cost {
                 continue;
             }
 
+            expansions.record(position);
+            cached_room_data.record_expansion(room_key);
+            PanicContext::set_position(position);
+
             let current_room_name = cached_room_data[room_key].room_name;
 
-            // Loop through relevant neighbors (not all directions can improve the path)
-            for neighbor_direction in next_directions(open_direction) {
+            // Loop through relevant neighbors (not all directions can improve the path,
+            // unless movement is restricted)
+            let directions = if movement.is_unrestricted() {
+                next_directions(open_direction)
+            } else {
+                next_directions(None)
+            };
+            for neighbor_direction in directions {
+                if !movement.allows(position, *neighbor_direction, |tile| {
+                    cached_room_data.is_passable(tile)
+                }) {
+                    continue;
+                }
                 // If neighbor would be a room edge, map it to the corresponding tile in
                 // the other room, where the creep would be if it moved in that direction.
                 let neighbor = corresponding_room_edge(
@@ -143,7 +284,7 @@ pub fn astar_multiroom_distance_map(
                 let terrain_cost =
                     if let Some(cost_matrix) = &cached_room_data[room_key].cost_matrix {
                         let terrain_cost = cost_matrix.get(neighbor.xy());
-                        if terrain_cost >= 255 {
+                        if terrain_cost == 255 {
                             // impassable terrain
                             continue;
                         }
@@ -154,7 +295,9 @@ pub fn astar_multiroom_distance_map(
                     };
 
                 // Calculate the cost of the path to the neighbor (from moving through the current position)
-                let next_cost = g_score.saturating_add(terrain_cost as usize);
+                let next_cost = g_score
+                    .saturating_add(terrain_cost as usize)
+                    .saturating_add(movement.step_cost(position, neighbor));
 
                 // Skip this neighbor if we've already found a better path to it.
                 if cached_room_data[room_key].distance_map[neighbor.xy()] <= next_cost {
@@ -182,55 +325,98 @@ pub fn astar_multiroom_distance_map(
                     room_key,
                 });
                 cached_room_data[room_key].distance_map[neighbor.xy()] = next_cost;
-                tiles_remaining -= 1;
+                pushed += 1;
+                open_count += 1;
 
                 // if the f_score is lower than the current min_idx, update min_idx
                 min_idx = min_idx.min(f_score);
 
                 // check off targets as they are reached
                 if let Some(all_of_targets) = &mut all_of_targets {
-                    let mut i = 0;
-                    while i < all_of_targets.len() {
-                        if all_of_targets[i].0.room_name() == neighbor.room_name()
-                            && all_of_targets[i].0.get_range_to(neighbor)
-                                <= all_of_targets[i].1 as u32
-                        {
-                            found_targets.push(neighbor);
-                            all_of_targets.remove(i);
-                        } else {
-                            i += 1;
+                    if remove_reached_goals(all_of_targets, neighbor) {
+                        found_targets.push(neighbor);
+                        if remaining_goals_unreachable(&cached_room_data, all_of_targets) {
+                            let failure = diagnose(
+                                &cached_room_data,
+                                all_of_targets,
+                                start_pushed,
+                                pushed,
+                                open_count,
+                                FailureReason::GoalRoomsBlocked,
+                            );
+                            return SearchResult::new(
+                                cached_room_data.into(),
+                                found_targets,
+                                max_ops - ops_remaining,
+                                pushed,
+                                expansions.into_positions(),
+                            )
+                            .with_failure(failure);
                         }
                     }
                     if all_of_targets.is_empty() {
                         return SearchResult::new(
                             cached_room_data.into(),
                             found_targets,
-                            max_ops - tiles_remaining,
+                            max_ops - ops_remaining,
+                            pushed,
+                            expansions.into_positions(),
                         );
                     }
                 }
 
                 if let Some(any_of_targets) = &any_of_targets {
-                    if any_of_targets.iter().any(|(target, range)| {
-                        target.room_name() == neighbor.room_name()
-                            && target.get_range_to(neighbor) <= *range as u32
-                    }) {
+                    if any_of_targets.any_reached(neighbor) {
                         found_targets.push(neighbor);
                         return SearchResult::new(
                             cached_room_data.into(),
                             found_targets,
-                            max_ops - tiles_remaining,
+                            max_ops - ops_remaining,
+                            pushed,
+                            expansions.into_positions(),
                         );
                     }
                 }
+            }
+
+            if heuristic_dropped() {
+                min_idx = rescore_open_list(&mut open, min_idx, &heuristic_fn);
+            }
 
-                // If the goal is reached or the max number of tiles has been processed, return the distance map.
-                if tiles_remaining == 0 {
+            if open_count > open_limit.max_nodes {
+                if open_limit.overflow == OpenListOverflow::Beam {
+                    open_count = 0;
+                    for bucket in open[min_idx..].iter_mut() {
+                        if bucket.len() > open_limit.beam_width {
+                            // States are popped from the end, so keep the
+                            // ones furthest along there
+                            bucket.sort_by_key(|state| state.g_score);
+                            bucket.drain(..bucket.len() - open_limit.beam_width);
+                        }
+                        open_count += bucket.len();
+                    }
+                }
+                if open_count > open_limit.max_nodes {
+                    let failure = diagnose(
+                        &cached_room_data,
+                        any_of_targets
+                            .as_ref()
+                            .map(GoalSet::goals)
+                            .or(all_of_targets.as_deref())
+                            .unwrap_or_default(),
+                        start_pushed,
+                        pushed,
+                        open_count,
+                        FailureReason::OpenListFull,
+                    );
                     return SearchResult::new(
                         cached_room_data.into(),
                         found_targets,
-                        max_ops - tiles_remaining,
-                    );
+                        max_ops - ops_remaining,
+                        pushed,
+                        expansions.into_positions(),
+                    )
+                    .with_failure(failure);
                 }
             }
         }
@@ -239,84 +425,157 @@ pub fn astar_multiroom_distance_map(
     }
 
     // If we've processed all tiles and haven't found the goal, return the distance map.
+    let failure = diagnose(
+        &cached_room_data,
+        any_of_targets
+            .as_ref()
+            .map(GoalSet::goals)
+            .or(all_of_targets.as_deref())
+            .unwrap_or_default(),
+        start_pushed,
+        pushed,
+        0,
+        FailureReason::MaxOpsReached,
+    );
     SearchResult::new(
         cached_room_data.into(),
         found_targets,
-        max_ops - tiles_remaining,
+        max_ops - ops_remaining,
+        pushed,
+        expansions.into_positions(),
     )
+    .with_failure(failure)
 }
 
-#[wasm_bindgen]
+#[wasm_bindgen(js_name = clockwork_astar_multiroom_distance_map)]
+#[allow(clippy::too_many_arguments)]
 pub fn js_astar_multiroom_distance_map(
     start_packed: Vec<u32>,
     get_cost_matrix: &js_sys::Function,
     max_rooms: usize,
     max_ops: usize,
     max_path_cost: usize,
-    // TODO: Destinations need to include a range
     any_of_destinations: Option<Vec<u32>>,
     all_of_destinations: Option<Vec<u32>>,
+    record_expansions: Option<bool>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+    any_of_goal_set: Option<u32>,
+    heuristic_map_id: Option<u32>,
+    min_tile_cost: Option<u32>,
 ) -> SearchResult {
-    let start_positions = start_packed
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    let start_positions: Vec<Position> = start_packed
         .iter()
-        .map(|pos| Position::from_packed(*pos))
+        .map(|pos| world_position_from_packed(*pos))
         .collect();
 
-    let any_of_destinations: Option<Vec<(Position, usize)>> =
-        any_of_destinations.map(|destinations| {
-            destinations
-                .chunks(2)
-                .map(|chunk| (Position::from_packed(chunk[0]), chunk[1] as usize))
-                .collect()
-        });
-
-    let all_of_destinations: Option<Vec<(Position, usize)>> =
-        all_of_destinations.map(|destinations| {
-            destinations
-                .chunks(2)
-                .map(|chunk| (Position::from_packed(chunk[0]), chunk[1] as usize))
-                .collect()
-        });
-
-    let all_destinations: Vec<(Position, usize)> = all_of_destinations
+    // A prebuilt goal set replaces the packed destinations
+    let any_of_destinations = match any_of_goal_set {
+        Some(id) => Some(goal_set(id)),
+        None => any_of_destinations.map(unpack_goals).map(GoalSet::new),
+    };
+    let all_of_destinations = all_of_destinations.map(unpack_goals);
+
+    let all_destinations: Vec<Goal> = all_of_destinations
+        .iter()
+        .flatten()
+        .chain(any_of_destinations.iter().flat_map(GoalSet::goals))
+        .copied()
+        .collect();
+
+    let scale = MinTileCost::new(min_tile_cost.map(|cost| cost.max(1) as usize));
+    let get_cost_matrix = scale.observing(CostOverlay::wrap(
+        overlay.as_ref(),
+        packed_room_cost_matrix_callback(get_cost_matrix),
+    ));
+    let heuristic_fn = scaled_heuristic_with_range(&all_destinations, scale.clone());
+    // The map measures distance to the destination tiles, but a range goal
+    // is reached up to its range in steps sooner, and each of those steps can
+    // cost as much as the costliest passable tile plus a room change
+    let allowance = all_destinations
+        .iter()
+        .map(Goal::effective_range)
+        .max()
+        .unwrap_or(0)
+        .saturating_mul(254 + movement.room_change_cost);
+    let heuristic_fn = with_heuristic_map(
+        heuristic_fn,
+        heuristic_map_id.map(|id| heuristic_map(id, &all_destinations)),
+        allowance,
+    );
+
+    astar_with_min_tile_cost(
+        start_positions,
+        &get_cost_matrix,
+        max_rooms,
+        max_ops,
+        max_path_cost,
+        heuristic_fn,
+        any_of_destinations,
+        all_of_destinations,
+        record_expansions.unwrap_or(false),
+        movement,
+        open_limit,
+        Some(&scale),
+    )
+    .with_heuristic_weight(scale.get() as f64)
+}
+
+/// Variant of `js_astar_multiroom_distance_map` that accepts `{x, y, roomName}`
+/// objects instead of packed positions. Destinations are `{pos, range}` objects,
+/// and the cost matrix callback receives the room name as a string.
+#[wasm_bindgen(js_name = clockwork_astar_multiroom_distance_map_positions)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_astar_multiroom_distance_map_positions(
+    start: JsValue,
+    get_cost_matrix: &js_sys::Function,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    any_of_destinations: JsValue,
+    all_of_destinations: JsValue,
+    record_expansions: Option<bool>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+    min_tile_cost: Option<u32>,
+) -> SearchResult {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    let start_positions = positions_from_js(start);
+    let any_of_destinations = destinations_from_js(any_of_destinations);
+    let all_of_destinations = destinations_from_js(all_of_destinations);
+
+    let all_destinations: Vec<Goal> = all_of_destinations
         .clone()
         .unwrap_or_default()
         .into_iter()
-        .chain(any_of_destinations.clone().unwrap_or_default().into_iter())
+        .chain(any_of_destinations.clone().unwrap_or_default())
         .collect();
 
-    let heuristic_fn = base_heuristic_with_range(&all_destinations);
+    let scale = MinTileCost::new(min_tile_cost.map(|cost| cost.max(1) as usize));
+    let get_cost_matrix = scale.observing(CostOverlay::wrap(
+        overlay.as_ref(),
+        room_name_cost_matrix_callback(get_cost_matrix),
+    ));
+    let heuristic_fn = scaled_heuristic_with_range(&all_destinations, scale.clone());
 
-    astar_multiroom_distance_map(
+    astar_with_min_tile_cost(
         start_positions,
-        |room| {
-            let result = get_cost_matrix.call1(
-                &JsValue::null(),
-                &JsValue::from_f64(room.packed_repr() as f64),
-            );
-
-            let value = match result {
-                Ok(value) => value,
-                Err(e) => throw_val(e),
-            };
-
-            let cost_matrix = if value.is_undefined() {
-                None
-            } else {
-                Some(
-                    ClockworkCostMatrix::try_from(value)
-                        .ok()
-                        .expect_throw("Invalid ClockworkCostMatrix"),
-                )
-            };
-
-            cost_matrix
-        },
+        &get_cost_matrix,
         max_rooms,
         max_ops,
         max_path_cost,
         heuristic_fn,
-        any_of_destinations,
+        any_of_destinations.map(GoalSet::new),
         all_of_destinations,
+        record_expansions.unwrap_or(false),
+        movement,
+        open_limit,
+        Some(&scale),
     )
+    .with_heuristic_weight(scale.get() as f64)
 }
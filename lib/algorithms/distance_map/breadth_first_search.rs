@@ -1,16 +1,20 @@
-use crate::algorithms::map::neighbors;
-use crate::datatypes::ClockworkCostMatrix;
+use crate::algorithms::map::{corresponding_room_edge, MovementRules, PREFERRED_DIRECTIONS};
 use crate::datatypes::RoomDataCache;
-use crate::utils::set_panic_hook;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::positions::{
+    destinations_from_js, positions_from_js, room_name_cost_matrix_callback,
+};
+use crate::utils::{set_panic_hook, PanicContext};
 use screeps::Position;
 use screeps::RoomName;
-use std::collections::HashSet;
 use std::collections::VecDeque;
-use std::convert::TryFrom;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::throw_val;
 
-use super::SearchResult;
+use super::diagnostics::{diagnose, goals_unreachable, remaining_goals_unreachable, FailureReason};
+use super::goals::{any_goal_reached, remove_reached_goals, unpack_goals, Goal};
+use super::{ExpansionLog, SearchResult};
+use crate::helpers::rooms::world_position_from_packed;
 
 #[derive(Copy, Clone)]
 struct State {
@@ -29,64 +33,62 @@ struct State {
 /// # Arguments
 /// * `start` - The starting position(s)
 /// * `get_cost_matrix` - Function that returns the cost matrix for a given room
-/// * `max_ops` - Maximum number of pathfinding operations to perform
+/// * `max_ops` - Maximum number of tiles to expand
 /// * `max_rooms` - Maximum number of rooms to explore
 /// * `max_path_cost` - Maximum path cost to explore
 /// * `any_of_destinations` - Search exits early if any of these positions are reached
 /// * `all_of_destinations` - Search exits early when all of these positions are reached
+/// * `record_expansions` - Whether to record the expanded positions in the result
 ///
 /// # Returns
 /// A `MultiroomDistanceMap` containing the distances from the start positions
+#[allow(clippy::too_many_arguments)]
 pub fn bfs_multiroom_distance_map(
     start: Vec<Position>,
     get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
     max_ops: usize,
     max_rooms: usize,
     max_path_cost: usize,
-    any_of_destinations: Option<Vec<(Position, usize)>>,
-    all_of_destinations: Option<Vec<(Position, usize)>>,
+    any_of_destinations: Option<Vec<Goal>>,
+    all_of_destinations: Option<Vec<Goal>>,
+    record_expansions: bool,
+    movement: MovementRules,
 ) -> SearchResult {
     set_panic_hook();
+    let _context = PanicContext::enter("bfs");
     let mut frontier = VecDeque::new();
-    let any_of_destinations =
-        any_of_destinations.map(|d| d.iter().cloned().collect::<HashSet<_>>());
-    let mut all_of_destinations =
-        all_of_destinations.map(|d| d.iter().cloned().collect::<HashSet<_>>());
+    let mut all_of_destinations = all_of_destinations;
     let mut cached_room_data = RoomDataCache::new(max_rooms, get_cost_matrix);
     let mut ops_remaining = max_ops;
+    let mut pushed = 0;
     let mut found_targets = Vec::new();
+    let mut expansions = ExpansionLog::new(record_expansions);
 
     // check if start position matches targets and return early if so
     for neighbor in start.iter() {
         if let Some(ref any_of_destinations) = any_of_destinations {
-            if any_of_destinations.iter().any(|(target, range)| {
-                target.room_name() == neighbor.room_name()
-                    && target.get_range_to(*neighbor) <= *range as u32
-            }) {
+            if any_goal_reached(any_of_destinations, *neighbor) {
                 found_targets.push(*neighbor);
                 return SearchResult::new(
                     cached_room_data.into(),
                     found_targets,
                     max_ops - ops_remaining,
+                    pushed,
+                    expansions.into_positions(),
                 );
             }
         }
         if let Some(ref mut all_of_destinations) = all_of_destinations {
-            all_of_destinations.retain(|(target, range)| {
-                if target.room_name() == neighbor.room_name()
-                    && target.get_range_to(*neighbor) <= *range as u32
-                {
-                    found_targets.push(*neighbor);
-                    false
-                } else {
-                    true
-                }
-            });
+            if remove_reached_goals(all_of_destinations, *neighbor) {
+                found_targets.push(*neighbor);
+            }
             if all_of_destinations.is_empty() {
                 return SearchResult::new(
                     cached_room_data.into(),
                     found_targets,
                     max_ops - ops_remaining,
+                    pushed,
+                    expansions.into_positions(),
                 );
             }
         }
@@ -97,6 +99,7 @@ pub fn bfs_multiroom_distance_map(
         let room_key = cached_room_data.get_room_key(position.room_name());
         if let Some(room_key) = room_key {
             cached_room_data[room_key].distance_map[position.xy()] = 0;
+            pushed += 1;
             frontier.push_back(State {
                 g_score: 0,
                 position,
@@ -104,6 +107,32 @@ pub fn bfs_multiroom_distance_map(
             });
         }
     }
+    let start_pushed = pushed;
+
+    // Stop right away if the destinations are all in blocked rooms, rather
+    // than exploring until the search runs out of ops
+    let remaining_goals = any_of_destinations
+        .as_deref()
+        .or(all_of_destinations.as_deref())
+        .unwrap_or_default();
+    if start_pushed > 0 && goals_unreachable(&mut cached_room_data, remaining_goals) {
+        let failure = diagnose(
+            &cached_room_data,
+            remaining_goals,
+            start_pushed,
+            pushed,
+            frontier.len(),
+            FailureReason::GoalRoomsBlocked,
+        );
+        return SearchResult::new(
+            cached_room_data.into(),
+            found_targets,
+            max_ops - ops_remaining,
+            pushed,
+            expansions.into_positions(),
+        )
+        .with_failure(failure);
+    }
 
     while let Some(State {
         g_score,
@@ -112,11 +141,26 @@ pub fn bfs_multiroom_distance_map(
     }) = frontier.pop_front()
     {
         if ops_remaining == 0 {
+            let failure = diagnose(
+                &cached_room_data,
+                any_of_destinations
+                    .as_deref()
+                    .or(all_of_destinations.as_deref())
+                    .unwrap_or_default(),
+                start_pushed,
+                pushed,
+                // Count the state just popped as part of the frontier
+                frontier.len() + 1,
+                FailureReason::MaxOpsReached,
+            );
             return SearchResult::new(
                 cached_room_data.into(),
                 found_targets,
                 max_ops - ops_remaining,
-            );
+                pushed,
+                expansions.into_positions(),
+            )
+            .with_failure(failure);
         }
         ops_remaining -= 1;
 
@@ -124,7 +168,20 @@ pub fn bfs_multiroom_distance_map(
             continue;
         }
 
-        for neighbor in neighbors(position).into_iter() {
+        expansions.record(position);
+        cached_room_data.record_expansion(room_key);
+        PanicContext::set_position(position);
+
+        for direction in PREFERRED_DIRECTIONS.iter() {
+            if !movement.allows(position, *direction, |tile| {
+                cached_room_data.is_passable(tile)
+            }) {
+                continue;
+            }
+            let Ok(neighbor) = position.checked_add_direction(*direction) else {
+                continue;
+            };
+            let neighbor = corresponding_room_edge(neighbor);
             let neighbor_room_key = if neighbor.room_name() == position.room_name() {
                 room_key
             } else {
@@ -138,16 +195,15 @@ pub fn bfs_multiroom_distance_map(
             if !cached_room_data[neighbor_room_key]
                 .cost_matrix
                 .as_ref()
-                .and_then(|matrix| {
-                    let cost = matrix.get(neighbor.xy());
-                    Some(cost < 255)
-                })
+                .map(|matrix| matrix.get(neighbor.xy()) < 255)
                 .unwrap_or(false)
             {
                 continue;
             };
 
-            let next_cost = g_score.saturating_add(1);
+            let next_cost = g_score
+                .saturating_add(1)
+                .saturating_add(movement.step_cost(position, neighbor));
 
             // Skip this neighbor if we've already found a better path to it.
             if cached_room_data[neighbor_room_key].distance_map[neighbor.xy()] <= next_cost {
@@ -156,51 +212,78 @@ pub fn bfs_multiroom_distance_map(
             }
 
             cached_room_data[neighbor_room_key].distance_map[neighbor.xy()] = next_cost;
+            pushed += 1;
             frontier.push_back(State {
                 g_score: next_cost,
                 position: neighbor,
                 room_key: neighbor_room_key,
             });
             if let Some(ref mut all_of_destinations) = all_of_destinations {
-                all_of_destinations.retain(|(target, range)| {
-                    if target.room_name() == neighbor.room_name()
-                        && target.get_range_to(neighbor) <= *range as u32
-                    {
-                        found_targets.push(neighbor);
-                        false
-                    } else {
-                        true
+                if remove_reached_goals(all_of_destinations, neighbor) {
+                    found_targets.push(neighbor);
+                    if remaining_goals_unreachable(&cached_room_data, all_of_destinations) {
+                        let failure = diagnose(
+                            &cached_room_data,
+                            all_of_destinations,
+                            start_pushed,
+                            pushed,
+                            frontier.len(),
+                            FailureReason::GoalRoomsBlocked,
+                        );
+                        return SearchResult::new(
+                            cached_room_data.into(),
+                            found_targets,
+                            max_ops - ops_remaining,
+                            pushed,
+                            expansions.into_positions(),
+                        )
+                        .with_failure(failure);
                     }
-                });
+                }
                 if all_of_destinations.is_empty() {
                     return SearchResult::new(
                         cached_room_data.into(),
                         found_targets,
                         max_ops - ops_remaining,
+                        pushed,
+                        expansions.into_positions(),
                     );
                 }
             }
             if let Some(ref any_of_destinations) = any_of_destinations {
-                if any_of_destinations.iter().any(|(target, range)| {
-                    target.room_name() == neighbor.room_name()
-                        && target.get_range_to(neighbor) <= *range as u32
-                }) {
+                if any_goal_reached(any_of_destinations, neighbor) {
                     found_targets.push(neighbor);
                     return SearchResult::new(
                         cached_room_data.into(),
                         found_targets,
                         max_ops - ops_remaining,
+                        pushed,
+                        expansions.into_positions(),
                     );
                 }
             }
         }
     }
 
+    let failure = diagnose(
+        &cached_room_data,
+        any_of_destinations
+            .as_deref()
+            .or(all_of_destinations.as_deref())
+            .unwrap_or_default(),
+        start_pushed,
+        pushed,
+        0,
+        FailureReason::MaxOpsReached,
+    );
     SearchResult::new(
         cached_room_data.into(),
         found_targets,
         max_ops - ops_remaining,
+        pushed,
+        expansions.into_positions(),
     )
+    .with_failure(failure)
 }
 
 /// WASM wrapper for the BFS multiroom distance map function.
@@ -208,16 +291,21 @@ pub fn bfs_multiroom_distance_map(
 /// # Arguments
 /// * `start_packed` - Array of packed position integers representing start positions
 /// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
-/// * `max_ops` - Maximum number of tiles to explore
+/// * `max_ops` - Maximum number of tiles to expand
 /// * `max_rooms` - Maximum number of rooms to explore
 /// * `max_room_distance` - Maximum Manhattan distance in rooms to explore
 /// * `max_path_cost` - Maximum distance in tiles to explore
-/// * `any_of_destinations` - Array of packed positions to trigger early exit when any are reached
-/// * `all_of_destinations` - Array of packed positions to trigger early exit when all are reached
+/// * `any_of_destinations` - Array of packed [position, range, kind] triples to trigger early exit when any are reached
+/// * `all_of_destinations` - Array of packed [position, range, kind] triples to trigger early exit when all are reached
+/// * `record_expansions` - Whether to record the expanded positions in the result
+/// * `overlay` - Adjustments applied to each room's cost matrix as it's loaded
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
 ///
 /// # Returns
 /// A `MultiroomDistanceMap` containing the distances from the start positions
-#[wasm_bindgen]
+#[wasm_bindgen(js_name = clockwork_bfs_multiroom_distance_map)]
+#[allow(clippy::too_many_arguments)]
 pub fn js_bfs_multiroom_distance_map(
     start_packed: Vec<u32>,
     get_cost_matrix: &js_sys::Function,
@@ -226,55 +314,65 @@ pub fn js_bfs_multiroom_distance_map(
     max_path_cost: usize,
     any_of_destinations: Option<Vec<u32>>,
     all_of_destinations: Option<Vec<u32>>,
+    record_expansions: Option<bool>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
 ) -> SearchResult {
+    let movement = movement.unwrap_or_default();
     let start_positions = start_packed
         .iter()
-        .map(|pos| Position::from_packed(*pos))
+        .map(|pos| world_position_from_packed(*pos))
         .collect();
 
-    let any_of_destinations: Option<Vec<(Position, usize)>> =
-        any_of_destinations.map(|destinations| {
-            destinations
-                .chunks(2)
-                .map(|chunk| (Position::from_packed(chunk[0]), chunk[1] as usize))
-                .collect()
-        });
-
-    let all_of_destinations: Option<Vec<(Position, usize)>> =
-        all_of_destinations.map(|destinations| {
-            destinations
-                .chunks(2)
-                .map(|chunk| (Position::from_packed(chunk[0]), chunk[1] as usize))
-                .collect()
-        });
+    let any_of_destinations = any_of_destinations.map(unpack_goals);
+    let all_of_destinations = all_of_destinations.map(unpack_goals);
 
     bfs_multiroom_distance_map(
         start_positions,
-        |room| {
-            let result = get_cost_matrix.call1(
-                &JsValue::null(),
-                &JsValue::from_f64(room.packed_repr() as f64),
-            );
-
-            let value = match result {
-                Ok(value) => value,
-                Err(e) => throw_val(e),
-            };
-
-            if value.is_undefined() {
-                None
-            } else {
-                Some(
-                    ClockworkCostMatrix::try_from(value)
-                        .ok()
-                        .expect_throw("Invalid ClockworkCostMatrix"),
-                )
-            }
-        },
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
         max_ops,
         max_rooms,
         max_path_cost,
         any_of_destinations,
         all_of_destinations,
+        record_expansions.unwrap_or(false),
+        movement,
+    )
+}
+
+/// Variant of `js_bfs_multiroom_distance_map` that accepts `{x, y, roomName}` objects
+/// instead of packed positions. Destinations are `{pos, range}` objects, and
+/// the cost matrix callback receives the room name as a string.
+#[wasm_bindgen(js_name = clockwork_bfs_multiroom_distance_map_positions)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_bfs_multiroom_distance_map_positions(
+    start: JsValue,
+    get_cost_matrix: &js_sys::Function,
+    max_ops: usize,
+    max_rooms: usize,
+    max_path_cost: usize,
+    any_of_destinations: JsValue,
+    all_of_destinations: JsValue,
+    record_expansions: Option<bool>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> SearchResult {
+    let movement = movement.unwrap_or_default();
+    bfs_multiroom_distance_map(
+        positions_from_js(start),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            room_name_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_ops,
+        max_rooms,
+        max_path_cost,
+        destinations_from_js(any_of_destinations),
+        destinations_from_js(all_of_destinations),
+        record_expansions.unwrap_or(false),
+        movement,
     )
 }
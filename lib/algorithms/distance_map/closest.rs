@@ -0,0 +1,217 @@
+use screeps::{Position, RoomName};
+use wasm_bindgen::prelude::*;
+
+use super::dijkstra::dijkstra_multiroom_distance_map;
+use super::goals::{Goal, GoalKind};
+use super::OpenListLimit;
+use crate::algorithms::map::MovementRules;
+use crate::algorithms::path::to_multiroom_distance_map_origin::path_to_multiroom_distance_map_origin;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, Path};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+
+/// Finds the candidate closest to `from` by path cost, with a single search
+/// that stops as soon as it comes within `range` of any candidate. Returns
+/// the candidate's index, or `None` if none were reached within the limits.
+/// If the search reaches several candidates at once, the first in the list
+/// wins.
+#[allow(clippy::too_many_arguments)]
+pub fn closest_of(
+    from: Position,
+    candidates: &[Position],
+    range: usize,
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_ops: usize,
+    max_rooms: usize,
+    max_path_cost: usize,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+) -> Option<usize> {
+    if candidates.is_empty() {
+        return None;
+    }
+    let goals: Vec<Goal> = candidates
+        .iter()
+        .map(|candidate| Goal::new(*candidate, range, GoalKind::Range))
+        .collect();
+    let result = dijkstra_multiroom_distance_map(
+        vec![from],
+        get_cost_matrix,
+        max_ops,
+        max_rooms,
+        max_path_cost,
+        Some(goals.iter().copied().collect()),
+        None,
+        false,
+        movement,
+        open_limit,
+    );
+    let reached = Position::from_packed(*result.found_targets().first()?);
+    goals.iter().position(|goal| goal.is_reached_by(reached))
+}
+
+/// Finds the candidate closest to a position by path cost, replacing a
+/// search per candidate with a single multi-goal search.
+///
+/// # Arguments
+/// * `from_packed` - The packed position to search from
+/// * `candidates_packed` - The packed positions of the candidates
+/// * `range` - How close the search must come to a candidate to reach it
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_ops` - Maximum number of tiles to expand
+/// * `max_rooms` - Maximum number of rooms to explore
+/// * `max_path_cost` - Maximum path cost to search
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
+///
+/// # Returns
+/// The index of the closest candidate, or undefined if none was reached
+#[wasm_bindgen(js_name = clockwork_closest_of)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_closest_of(
+    from_packed: u32,
+    candidates_packed: Vec<u32>,
+    range: usize,
+    get_cost_matrix: &js_sys::Function,
+    max_ops: usize,
+    max_rooms: usize,
+    max_path_cost: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> Option<usize> {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    let candidates: Vec<Position> = candidates_packed
+        .into_iter()
+        .map(world_position_from_packed)
+        .collect();
+    closest_of(
+        world_position_from_packed(from_packed),
+        &candidates,
+        range,
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_ops,
+        max_rooms,
+        max_path_cost,
+        movement,
+        open_limit,
+    )
+}
+
+/// The origin with the cheapest path to a goal, and that path.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct BestOrigin {
+    index: usize,
+    path: Path,
+}
+
+#[wasm_bindgen]
+impl BestOrigin {
+    /// The index of the origin in the list searched from.
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The path from the origin to the goal.
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> Path {
+        self.path.clone()
+    }
+}
+
+/// Finds which of `origins` has the cheapest path to within `range` of
+/// `goal`, with a single search seeded from every origin at once. The search
+/// stops at the first tile in range, and the path is traced back to the
+/// origin it grew from. Returns `None` if the goal wasn't reached within the
+/// limits.
+#[allow(clippy::too_many_arguments)]
+pub fn best_origin(
+    origins: &[Position],
+    goal: Position,
+    range: usize,
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_ops: usize,
+    max_rooms: usize,
+    max_path_cost: usize,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+) -> Option<BestOrigin> {
+    let result = dijkstra_multiroom_distance_map(
+        origins.to_vec(),
+        get_cost_matrix,
+        max_ops,
+        max_rooms,
+        max_path_cost,
+        Some(std::iter::once(Goal::new(goal, range, GoalKind::Range)).collect()),
+        None,
+        false,
+        movement,
+        open_limit,
+    );
+    let reached = Position::from_packed(*result.found_targets().first()?);
+    let path = path_to_multiroom_distance_map_origin(reached, &result.distance_map()).ok()?;
+    let index = origins
+        .iter()
+        .position(|origin| path.get(0) == Some(origin))?;
+    Some(BestOrigin { index, path })
+}
+
+/// Finds which of several origins has the cheapest path to a goal, e.g. which
+/// spawn should produce a defender, with one search instead of one per origin.
+///
+/// # Arguments
+/// * `origins_packed` - The packed positions to choose between
+/// * `goal_packed` - The packed goal position
+/// * `range` - How close the path must come to the goal
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_ops` - Maximum number of tiles to expand
+/// * `max_rooms` - Maximum number of rooms to explore
+/// * `max_path_cost` - Maximum path cost to search
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
+///
+/// # Returns
+/// The index of the best origin and its path, or undefined if the goal wasn't reached
+#[wasm_bindgen(js_name = clockwork_best_origin)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_best_origin(
+    origins_packed: Vec<u32>,
+    goal_packed: u32,
+    range: usize,
+    get_cost_matrix: &js_sys::Function,
+    max_ops: usize,
+    max_rooms: usize,
+    max_path_cost: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> Option<BestOrigin> {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    let origins: Vec<Position> = origins_packed
+        .into_iter()
+        .map(world_position_from_packed)
+        .collect();
+    best_origin(
+        &origins,
+        world_position_from_packed(goal_packed),
+        range,
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_ops,
+        max_rooms,
+        max_path_cost,
+        movement,
+        open_limit,
+    )
+}
@@ -0,0 +1,139 @@
+use screeps::RoomName;
+use wasm_bindgen::prelude::*;
+
+use super::goals::Goal;
+use crate::datatypes::{ClockworkCostMatrix, RoomDataCache};
+
+/// Why a search stopped without reaching its destinations.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The search couldn't leave its start positions: their rooms have no
+    /// cost matrix, or every tile around them is impassable.
+    OriginBlocked = 0,
+    /// The cost matrix callback returned `undefined` for every room with an
+    /// unreached destination. Searches check for this before they start and
+    /// whenever a destination is reached, and stop as soon as it's true.
+    GoalRoomsBlocked = 1,
+    /// The search expanded `max_ops` tiles with more still left to expand.
+    MaxOpsReached = 2,
+    /// Every reachable tile (within `max_rooms` and `max_path_cost`) was
+    /// expanded without reaching the destinations, so they're enclosed or
+    /// out of range.
+    Unreachable = 3,
+    /// The open list grew past the search's node limit.
+    OpenListFull = 4,
+}
+
+/// Details about a search that didn't reach its destinations.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct SearchFailure {
+    reason: FailureReason,
+    frontier: usize,
+    blocked_rooms: Vec<RoomName>,
+}
+
+#[wasm_bindgen]
+impl SearchFailure {
+    /// Why the search failed.
+    #[wasm_bindgen(getter)]
+    pub fn reason(&self) -> FailureReason {
+        self.reason
+    }
+
+    /// The number of tiles left to expand when the search stopped.
+    #[wasm_bindgen(getter)]
+    pub fn frontier(&self) -> usize {
+        self.frontier
+    }
+
+    /// The packed names of the rooms the cost matrix callback returned
+    /// `undefined` for.
+    #[wasm_bindgen(getter)]
+    pub fn blocked_rooms(&self) -> Vec<u16> {
+        self.blocked_rooms
+            .iter()
+            .map(|room| room.packed_repr())
+            .collect()
+    }
+}
+
+/// Whether none of `goals` can be reached, because each is in a room the
+/// cost matrix callback returns `undefined` for and can't be reached from a
+/// neighboring room. Run before a search starts: goal rooms that haven't been
+/// loaded yet are loaded (and kept for when the search enters them), without
+/// counting against `max_rooms`. Nothing is loaded if a goal is in a room the
+/// search already has, e.g. the start room.
+pub fn goals_unreachable<F>(room_data: &mut RoomDataCache<F>, goals: &[Goal]) -> bool
+where
+    F: Fn(RoomName) -> Option<ClockworkCostMatrix>,
+{
+    if goals.is_empty()
+        || goals.iter().any(|goal| {
+            !goal.is_confined_to_room() || room_data.is_room_known_open(goal.position.room_name())
+        })
+    {
+        return false;
+    }
+    goals
+        .iter()
+        .all(|goal| room_data.is_room_blocked(goal.position.room_name()))
+}
+
+/// Whether every goal left is in a room already known to be blocked. Run as
+/// goals are reached, so it never loads rooms: every goal room that wasn't
+/// loaded before the search is treated as reachable.
+pub fn remaining_goals_unreachable<F>(room_data: &RoomDataCache<F>, goals: &[Goal]) -> bool
+where
+    F: Fn(RoomName) -> Option<ClockworkCostMatrix>,
+{
+    !goals.is_empty()
+        && goals.iter().all(|goal| {
+            goal.is_confined_to_room() && room_data.is_room_known_blocked(goal.position.room_name())
+        })
+}
+
+/// Works out why a search stopped short, from its state when it stopped.
+/// Returns `None` if there were no unreached destinations.
+///
+/// # Arguments
+/// * `room_data` - The rooms the search loaded
+/// * `remaining_goals` - The destinations that weren't reached
+/// * `start_pushed` - The number of start positions the search began from
+/// * `pushed` - The number of tiles added to the open list, including starts
+/// * `frontier` - The number of tiles left in the open list
+/// * `limit` - The limit that stopped the search, if tiles were left
+pub fn diagnose<F>(
+    room_data: &RoomDataCache<F>,
+    remaining_goals: &[Goal],
+    start_pushed: usize,
+    pushed: usize,
+    frontier: usize,
+    limit: FailureReason,
+) -> Option<SearchFailure>
+where
+    F: Fn(RoomName) -> Option<ClockworkCostMatrix>,
+{
+    if remaining_goals.is_empty() {
+        return None;
+    }
+    let blocked_rooms: Vec<RoomName> = room_data.blocked_rooms().collect();
+    let reason = if start_pushed == 0 || (frontier == 0 && pushed == start_pushed) {
+        FailureReason::OriginBlocked
+    } else if remaining_goals
+        .iter()
+        .all(|goal| blocked_rooms.contains(&goal.position.room_name()))
+    {
+        FailureReason::GoalRoomsBlocked
+    } else if frontier > 0 {
+        limit
+    } else {
+        FailureReason::Unreachable
+    };
+    Some(SearchFailure {
+        reason,
+        frontier,
+        blocked_rooms,
+    })
+}
@@ -1,24 +1,34 @@
 use crate::algorithms::distance_map::astar::astar_multiroom_distance_map;
-use crate::datatypes::ClockworkCostMatrix;
-use crate::utils::set_panic_hook;
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::positions::{
+    destinations_from_js, positions_from_js, room_name_cost_matrix_callback,
+};
+use crate::utils::{set_panic_hook, PanicContext};
 use screeps::Position;
 use screeps::RoomName;
-use std::convert::TryFrom;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::throw_val;
 
-use super::SearchResult;
+use super::goals::{goal_set, unpack_goals, Goal, GoalSet};
+use super::{OpenListLimit, SearchResult};
+use crate::helpers::rooms::world_position_from_packed;
 
+#[allow(clippy::too_many_arguments)]
 pub fn dijkstra_multiroom_distance_map(
     start: Vec<Position>,
     get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
     max_ops: usize,
     max_rooms: usize,
     max_path_cost: usize,
-    any_of_destinations: Option<Vec<(Position, usize)>>,
-    all_of_destinations: Option<Vec<(Position, usize)>>,
+    any_of_destinations: Option<GoalSet>,
+    all_of_destinations: Option<Vec<Goal>>,
+    record_expansions: bool,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
 ) -> SearchResult {
     set_panic_hook();
+    let _context = PanicContext::enter("dijkstra");
 
     astar_multiroom_distance_map(
         start,
@@ -31,10 +41,14 @@ pub fn dijkstra_multiroom_distance_map(
         |_| 0,
         any_of_destinations,
         all_of_destinations,
+        record_expansions,
+        movement,
+        open_limit,
     )
 }
 
-#[wasm_bindgen]
+#[wasm_bindgen(js_name = clockwork_dijkstra_multiroom_distance_map)]
+#[allow(clippy::too_many_arguments)]
 pub fn js_dijkstra_multiroom_distance_map(
     start_packed: Vec<u32>,
     get_cost_matrix: &js_sys::Function,
@@ -43,55 +57,76 @@ pub fn js_dijkstra_multiroom_distance_map(
     max_path_cost: usize,
     any_of_destinations: Option<Vec<u32>>,
     all_of_destinations: Option<Vec<u32>>,
+    record_expansions: Option<bool>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+    any_of_goal_set: Option<u32>,
 ) -> SearchResult {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
     let start_positions = start_packed
         .iter()
-        .map(|pos| Position::from_packed(*pos))
+        .map(|pos| world_position_from_packed(*pos))
         .collect();
 
-    let any_of_destinations: Option<Vec<(Position, usize)>> =
-        any_of_destinations.map(|destinations| {
-            destinations
-                .chunks(2)
-                .map(|chunk| (Position::from_packed(chunk[0]), chunk[1] as usize))
-                .collect()
-        });
-
-    let all_of_destinations: Option<Vec<(Position, usize)>> =
-        all_of_destinations.map(|destinations| {
-            destinations
-                .chunks(2)
-                .map(|chunk| (Position::from_packed(chunk[0]), chunk[1] as usize))
-                .collect()
-        });
+    // A prebuilt goal set replaces the packed destinations
+    let any_of_destinations = match any_of_goal_set {
+        Some(id) => Some(goal_set(id)),
+        None => any_of_destinations.map(unpack_goals).map(GoalSet::new),
+    };
+    let all_of_destinations = all_of_destinations.map(unpack_goals);
 
     dijkstra_multiroom_distance_map(
         start_positions,
-        |room| {
-            let result = get_cost_matrix.call1(
-                &JsValue::null(),
-                &JsValue::from_f64(room.packed_repr() as f64),
-            );
-
-            let value = match result {
-                Ok(value) => value,
-                Err(e) => throw_val(e),
-            };
-
-            if value.is_undefined() {
-                None
-            } else {
-                Some(
-                    ClockworkCostMatrix::try_from(value)
-                        .ok()
-                        .expect_throw("Invalid ClockworkCostMatrix"),
-                )
-            }
-        },
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
         max_ops,
         max_rooms,
         max_path_cost,
         any_of_destinations,
         all_of_destinations,
+        record_expansions.unwrap_or(false),
+        movement,
+        open_limit,
+    )
+}
+
+/// Variant of `js_dijkstra_multiroom_distance_map` that accepts `{x, y, roomName}` objects
+/// instead of packed positions. Destinations are `{pos, range}` objects, and
+/// the cost matrix callback receives the room name as a string.
+#[wasm_bindgen(js_name = clockwork_dijkstra_multiroom_distance_map_positions)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_dijkstra_multiroom_distance_map_positions(
+    start: JsValue,
+    get_cost_matrix: &js_sys::Function,
+    max_ops: usize,
+    max_rooms: usize,
+    max_path_cost: usize,
+    any_of_destinations: JsValue,
+    all_of_destinations: JsValue,
+    record_expansions: Option<bool>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> SearchResult {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    dijkstra_multiroom_distance_map(
+        positions_from_js(start),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            room_name_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_ops,
+        max_rooms,
+        max_path_cost,
+        destinations_from_js(any_of_destinations).map(GoalSet::new),
+        destinations_from_js(all_of_destinations),
+        record_expansions.unwrap_or(false),
+        movement,
+        open_limit,
     )
 }
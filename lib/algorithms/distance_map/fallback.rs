@@ -0,0 +1,240 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use screeps::{Position, RoomName};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use super::astar::astar_multiroom_distance_map;
+use super::breadth_first_search::bfs_multiroom_distance_map;
+use super::dijkstra::dijkstra_multiroom_distance_map;
+use super::goals::{remove_reached_goals, unpack_goals, Goal, GoalSet};
+use super::heuristics::base_heuristic_with_range;
+use super::{OpenListLimit, SearchResult};
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+
+/// A search algorithm to try as part of a fallback chain.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SearchStrategy {
+    /// A* with the standard range heuristic.
+    AStar = 0,
+    /// A* with the heuristic scaled up by the chain's heuristic weight. Paths
+    /// may be suboptimal, but far fewer tiles are explored.
+    WeightedAStar = 1,
+    /// Dijkstra's algorithm (no heuristic).
+    Dijkstra = 2,
+    /// Breadth-first search (ignores terrain costs other than walls).
+    Bfs = 3,
+}
+
+impl TryFrom<u32> for SearchStrategy {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SearchStrategy::AStar),
+            1 => Ok(SearchStrategy::WeightedAStar),
+            2 => Ok(SearchStrategy::Dijkstra),
+            3 => Ok(SearchStrategy::Bfs),
+            _ => Err(format!("Invalid search strategy: {}", value)),
+        }
+    }
+}
+
+/// Whether a search reached its destinations: any one of `any_of`, and every
+/// one of `all_of`.
+fn is_complete(
+    result: &SearchResult,
+    any_of: &Option<Vec<Goal>>,
+    all_of: &Option<Vec<Goal>>,
+) -> bool {
+    if any_of.is_some() && result.found_targets.is_empty() {
+        return false;
+    }
+    if let Some(all_of) = all_of {
+        let mut remaining = all_of.clone();
+        for target in &result.found_targets {
+            remove_reached_goals(&mut remaining, *target);
+        }
+        return remaining.is_empty();
+    }
+    true
+}
+
+/// Runs each strategy in turn until one reaches the destinations within
+/// `max_ops`. Cost matrices are loaded once and shared between attempts.
+///
+/// Returns the first complete result, or the last attempt's result if none
+/// completed. Either way, `ops` and `pushed` are the totals across all
+/// attempts.
+#[allow(clippy::too_many_arguments)]
+pub fn search_with_fallback(
+    strategies: &[SearchStrategy],
+    start: Vec<Position>,
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    heuristic_weight: f64,
+    any_of_destinations: Option<Vec<Goal>>,
+    all_of_destinations: Option<Vec<Goal>>,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+) -> SearchResult {
+    let cost_matrices: RefCell<HashMap<RoomName, Option<ClockworkCostMatrix>>> =
+        RefCell::new(HashMap::new());
+    let cached_cost_matrix = |room_name: RoomName| {
+        cost_matrices
+            .borrow_mut()
+            .entry(room_name)
+            .or_insert_with(|| get_cost_matrix(room_name))
+            .clone()
+    };
+
+    let all_destinations: Vec<Goal> = all_of_destinations
+        .iter()
+        .chain(any_of_destinations.iter())
+        .flatten()
+        .copied()
+        .collect();
+    let heuristic = base_heuristic_with_range(&all_destinations);
+    // Indexed once and shared by every attempt
+    let any_of_goals = any_of_destinations.clone().map(GoalSet::new);
+
+    let mut total_ops = 0;
+    let mut total_pushed = 0;
+    let mut last_result = None;
+    for strategy in strategies {
+        let mut result = match strategy {
+            SearchStrategy::AStar => astar_multiroom_distance_map(
+                start.clone(),
+                cached_cost_matrix,
+                max_rooms,
+                max_ops,
+                max_path_cost,
+                &heuristic,
+                any_of_goals.clone(),
+                all_of_destinations.clone(),
+                false,
+                movement,
+                open_limit,
+            )
+            .with_heuristic_weight(1.0),
+            SearchStrategy::WeightedAStar => astar_multiroom_distance_map(
+                start.clone(),
+                cached_cost_matrix,
+                max_rooms,
+                max_ops,
+                max_path_cost,
+                |position| (heuristic(position) as f64 * heuristic_weight) as usize,
+                any_of_goals.clone(),
+                all_of_destinations.clone(),
+                false,
+                movement,
+                open_limit,
+            )
+            .with_heuristic_weight(heuristic_weight),
+            SearchStrategy::Dijkstra => dijkstra_multiroom_distance_map(
+                start.clone(),
+                cached_cost_matrix,
+                max_ops,
+                max_rooms,
+                max_path_cost,
+                any_of_goals.clone(),
+                all_of_destinations.clone(),
+                false,
+                movement,
+                open_limit,
+            ),
+            SearchStrategy::Bfs => bfs_multiroom_distance_map(
+                start.clone(),
+                cached_cost_matrix,
+                max_ops,
+                max_rooms,
+                max_path_cost,
+                any_of_destinations.clone(),
+                all_of_destinations.clone(),
+                false,
+                movement,
+            ),
+        };
+        total_ops += result.ops;
+        result.ops = total_ops;
+        total_pushed += result.pushed;
+        result.pushed = total_pushed;
+        if is_complete(&result, &any_of_destinations, &all_of_destinations) {
+            return result;
+        }
+        last_result = Some(result);
+    }
+
+    last_result
+        .unwrap_or_else(|| SearchResult::new(Default::default(), Vec::new(), 0, 0, Vec::new()))
+}
+
+/// Runs a chain of searches in one call, falling back to the next strategy
+/// when one doesn't reach the destinations within `max_ops`.
+///
+/// # Arguments
+/// * `strategies` - The `SearchStrategy` values to try, in order
+/// * `start_packed` - The packed start positions
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_rooms` - Maximum number of rooms to explore per attempt
+/// * `max_ops` - Maximum number of tiles to expand per attempt
+/// * `max_path_cost` - Maximum path cost
+/// * `heuristic_weight` - Heuristic multiplier for `WeightedAStar`
+/// * `any_of_destinations` - Packed [position, range, kind] triples; any one ends the search
+/// * `all_of_destinations` - Packed [position, range, kind] triples; all must be reached
+/// * `overlay` - Per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
+///
+/// # Returns
+/// The first complete search result, or the last attempt if none completed
+#[wasm_bindgen(js_name = clockwork_search_with_fallback)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_search_with_fallback(
+    strategies: Vec<u32>,
+    start_packed: Vec<u32>,
+    get_cost_matrix: &js_sys::Function,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    heuristic_weight: f64,
+    any_of_destinations: Option<Vec<u32>>,
+    all_of_destinations: Option<Vec<u32>>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> SearchResult {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    let strategies: Vec<SearchStrategy> = strategies
+        .into_iter()
+        .map(|strategy| SearchStrategy::try_from(strategy).unwrap_or_else(|e| throw_str(&e)))
+        .collect();
+
+    search_with_fallback(
+        &strategies,
+        start_packed
+            .into_iter()
+            .map(world_position_from_packed)
+            .collect(),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_rooms,
+        max_ops,
+        max_path_cost,
+        heuristic_weight,
+        any_of_destinations.map(unpack_goals),
+        all_of_destinations.map(unpack_goals),
+        movement,
+        open_limit,
+    )
+}
@@ -0,0 +1,291 @@
+use screeps::{Position, RoomCoordinate, RoomName, RoomXY};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+use std::rc::Rc;
+use wasm_bindgen::{prelude::*, throw_str};
+
+use crate::algorithms::map::los::has_line_of_sight;
+use crate::datatypes::PositionSet;
+use crate::helpers::rooms::world_position_from_packed;
+use crate::helpers::terrain::get_packed_terrain;
+
+/// How a search decides that a destination has been reached.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GoalKind {
+    /// Stand on the destination tile itself (the range is ignored).
+    Tile = 0,
+    /// Get within the given range of the destination.
+    Range = 1,
+    /// Get within the given range of the destination, with no walls in
+    /// between (e.g. for ranged attackers and healers).
+    LineOfSight = 2,
+    /// Enter the destination's room. Any tile in the room counts, so the
+    /// search stops at the closest entry tile (the range is ignored).
+    Room = 3,
+}
+
+impl TryFrom<u32> for GoalKind {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(GoalKind::Tile),
+            1 => Ok(GoalKind::Range),
+            2 => Ok(GoalKind::LineOfSight),
+            3 => Ok(GoalKind::Room),
+            _ => Err(format!("Invalid goal kind: {}", value)),
+        }
+    }
+}
+
+/// A search destination: a position, a range, and how the range is evaluated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Goal {
+    pub position: Position,
+    pub range: usize,
+    pub kind: GoalKind,
+}
+
+impl Goal {
+    pub fn new(position: Position, range: usize, kind: GoalKind) -> Self {
+        match kind {
+            // Any position in the room identifies it; normalize to the center
+            GoalKind::Room => Goal {
+                position: room_center(position.room_name()),
+                range: 0,
+                kind,
+            },
+            _ => Goal {
+                position,
+                range,
+                kind,
+            },
+        }
+    }
+
+    /// The range that actually applies to this goal. For room goals, this is
+    /// the range from the room's center to its farthest edge, which keeps the
+    /// range-based heuristics admissible.
+    pub fn effective_range(&self) -> usize {
+        match self.kind {
+            GoalKind::Tile => 0,
+            GoalKind::Range | GoalKind::LineOfSight => self.range,
+            GoalKind::Room => 25,
+        }
+    }
+
+    /// Whether the goal can only be reached from inside its own room. Range
+    /// goals close enough to an edge can also be reached from the
+    /// neighboring room.
+    pub fn is_confined_to_room(&self) -> bool {
+        match self.kind {
+            GoalKind::Tile | GoalKind::LineOfSight | GoalKind::Room => true,
+            GoalKind::Range => {
+                let x = self.position.x().u8() as usize;
+                let y = self.position.y().u8() as usize;
+                self.range <= x.min(y).min(49 - x).min(49 - y)
+            }
+        }
+    }
+
+    /// Checks whether a creep standing at `position` has reached this goal.
+    ///
+    /// As with the native PathFinder, range is measured in world coordinates,
+    /// so a goal near a room edge can be reached from the neighboring room.
+    /// Line of sight goals are the exception: line of sight is only traced
+    /// within a room, so they must be reached from the goal's own room.
+    pub fn is_reached_by(&self, position: Position) -> bool {
+        let same_room = self.position.room_name() == position.room_name();
+        match self.kind {
+            GoalKind::Room => same_room,
+            GoalKind::Tile => self.position == position,
+            GoalKind::Range => self.position.get_range_to(position) <= self.range as u32,
+            GoalKind::LineOfSight => {
+                same_room
+                    && self.position.get_range_to(position) <= self.range as u32
+                    && get_packed_terrain(position.room_name())
+                        .map(|terrain| has_line_of_sight(position, self.position, &terrain))
+                        .unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// The center tile of a room, used as the anchor position for room goals.
+fn room_center(room_name: RoomName) -> Position {
+    // SAFETY: 25 is a valid room coordinate.
+    let center = unsafe { RoomCoordinate::unchecked_new(25) };
+    Position::new(center, center, room_name)
+}
+
+/// Returns true if `position` reaches any of the goals.
+pub fn any_goal_reached(goals: &[Goal], position: Position) -> bool {
+    goals.iter().any(|goal| goal.is_reached_by(position))
+}
+
+/// Removes every goal reached by `position`, returning true if any were removed.
+pub fn remove_reached_goals(goals: &mut Vec<Goal>, position: Position) -> bool {
+    let count = goals.len();
+    goals.retain(|goal| !goal.is_reached_by(position));
+    goals.len() != count
+}
+
+/// Unpacks destinations passed from JS as triples of
+/// `[packed position, range, goal kind]`.
+pub fn unpack_goals(packed: Vec<u32>) -> Vec<Goal> {
+    packed
+        .chunks(3)
+        .map(|chunk| {
+            if chunk.len() < 3 {
+                throw_str("Destinations must be packed as [position, range, kind] triples");
+            }
+            let kind = GoalKind::try_from(chunk[2]).unwrap_or_else(|e| throw_str(&e));
+            Goal::new(
+                world_position_from_packed(chunk[0]),
+                chunk[1] as usize,
+                kind,
+            )
+        })
+        .collect()
+}
+
+/// Range goals spanning more than this many tiles aren't indexed by room;
+/// they're checked at every position instead.
+const MAX_INDEXED_RANGE: usize = 50;
+
+#[derive(Debug)]
+struct GoalIndex {
+    goals: Vec<Goal>,
+    // The tiles that reach a tile or range goal, by room
+    reached: PositionSet,
+    // The indices of the room and line of sight goals in each room
+    by_room: HashMap<RoomName, Vec<usize>>,
+    // The indices of goals too wide to index by room
+    unindexed: Vec<usize>,
+}
+
+/// A set of goals indexed for fast membership checks. Tile and range goals
+/// are marked on a per-room bitset of the tiles that reach them, so checking
+/// a position takes one lookup however many goals there are. Cloning is
+/// cheap, so a set can be built once and shared by many searches.
+#[derive(Clone, Debug)]
+pub struct GoalSet(Rc<GoalIndex>);
+
+impl GoalSet {
+    pub fn new(goals: Vec<Goal>) -> Self {
+        let mut reached = PositionSet::new();
+        let mut by_room: HashMap<RoomName, Vec<usize>> = HashMap::new();
+        let mut unindexed = Vec::new();
+        for (index, goal) in goals.iter().enumerate() {
+            match goal.kind {
+                GoalKind::Room | GoalKind::LineOfSight => {
+                    by_room
+                        .entry(goal.position.room_name())
+                        .or_default()
+                        .push(index);
+                }
+                GoalKind::Range if goal.range > MAX_INDEXED_RANGE => unindexed.push(index),
+                GoalKind::Tile | GoalKind::Range => {
+                    // Mark the goal's range box, one room at a time
+                    let range = goal.effective_range() as i32;
+                    let (x, y) = goal.position.world_coords();
+                    for room_y in (y - range).div_euclid(50)..=(y + range).div_euclid(50) {
+                        for room_x in (x - range).div_euclid(50)..=(x + range).div_euclid(50) {
+                            let Ok(corner) =
+                                Position::checked_from_world_coords(room_x * 50, room_y * 50)
+                            else {
+                                continue;
+                            };
+                            let bits = reached.room_mut(corner.room_name());
+                            let (left, top) = (room_x * 50, room_y * 50);
+                            for tile_y in (y - range).max(top)..=(y + range).min(top + 49) {
+                                for tile_x in (x - range).max(left)..=(x + range).min(left + 49) {
+                                    // SAFETY: both are clamped to the room's 0-49 range.
+                                    bits.insert(unsafe {
+                                        RoomXY::unchecked_new(
+                                            (tile_x - left) as u8,
+                                            (tile_y - top) as u8,
+                                        )
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        GoalSet(Rc::new(GoalIndex {
+            goals,
+            reached,
+            by_room,
+            unindexed,
+        }))
+    }
+
+    /// The goals in the set.
+    pub fn goals(&self) -> &[Goal] {
+        &self.0.goals
+    }
+
+    /// Returns true if `position` reaches any of the goals.
+    pub fn any_reached(&self, position: Position) -> bool {
+        let index = &self.0;
+        let room_name = position.room_name();
+        index.reached.contains(position)
+            || index
+                .by_room
+                .get(&room_name)
+                .into_iter()
+                .flatten()
+                .chain(index.unindexed.iter())
+                .any(|&goal| index.goals[goal].is_reached_by(position))
+    }
+}
+
+impl FromIterator<Goal> for GoalSet {
+    fn from_iter<I: IntoIterator<Item = Goal>>(goals: I) -> Self {
+        GoalSet::new(goals.into_iter().collect())
+    }
+}
+
+thread_local! {
+    static GOAL_SETS: RefCell<HashMap<u32, GoalSet>> = RefCell::new(HashMap::new());
+    static NEXT_GOAL_SET_ID: RefCell<u32> = const { RefCell::new(1) };
+}
+
+/// Looks up a goal set created with `js_create_goal_set`.
+pub fn goal_set(id: u32) -> GoalSet {
+    GOAL_SETS
+        .with(|sets| sets.borrow().get(&id).cloned())
+        .unwrap_or_else(|| throw_str(&format!("Unknown goal set: {}", id)))
+}
+
+/// Builds a goal set that searches can reuse by id, skipping the work of
+/// unpacking and indexing the same destinations on every call.
+///
+/// # Arguments
+/// * `destinations` - Packed [position, range, kind] triples
+///
+/// # Returns
+/// The goal set's id
+#[wasm_bindgen(js_name = clockwork_create_goal_set)]
+pub fn js_create_goal_set(destinations: Vec<u32>) -> u32 {
+    let set = GoalSet::new(unpack_goals(destinations));
+    let id = NEXT_GOAL_SET_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next = next.wrapping_add(1).max(1);
+        id
+    });
+    GOAL_SETS.with(|sets| sets.borrow_mut().insert(id, set));
+    id
+}
+
+/// Frees a goal set, returning true if it existed.
+#[wasm_bindgen(js_name = clockwork_free_goal_set)]
+pub fn js_free_goal_set(id: u32) -> bool {
+    GOAL_SETS.with(|sets| sets.borrow_mut().remove(&id).is_some())
+}
@@ -0,0 +1,101 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use screeps::Position;
+use wasm_bindgen::{prelude::*, throw_str};
+
+use super::goals::{unpack_goals, Goal};
+use crate::datatypes::MultiroomDistanceMap;
+
+/// A distance map rooted at a search's destinations, used as an A*
+/// heuristic so repeat searches to the same destinations go almost straight
+/// there. The fingerprint of the destinations it was built for is kept so it
+/// isn't used for different ones.
+#[derive(Debug)]
+pub struct HeuristicMap {
+    map: MultiroomDistanceMap,
+    fingerprint: u32,
+}
+
+impl HeuristicMap {
+    /// The distance from `position` to the destinations, or `None` if the
+    /// map doesn't cover it.
+    pub fn get(&self, position: Position) -> Option<usize> {
+        Some(self.map.get(position)).filter(|distance| *distance != usize::MAX)
+    }
+}
+
+/// A fingerprint of a set of goals, independent of their order.
+pub fn goals_fingerprint(goals: &[Goal]) -> u32 {
+    let mut keys: Vec<(u32, usize, u32)> = goals
+        .iter()
+        .map(|goal| (goal.position.packed_repr(), goal.range, goal.kind as u32))
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    // FNV-1a
+    let mut hash: u32 = 0x811c_9dc5;
+    for (position, range, kind) in keys {
+        for value in [position, range as u32, kind] {
+            for byte in value.to_le_bytes() {
+                hash ^= byte as u32;
+                hash = hash.wrapping_mul(0x0100_0193);
+            }
+        }
+    }
+    hash
+}
+
+thread_local! {
+    static HEURISTIC_MAPS: RefCell<HashMap<u32, Rc<HeuristicMap>>> = RefCell::new(HashMap::new());
+    static NEXT_HEURISTIC_MAP_ID: RefCell<u32> = const { RefCell::new(1) };
+}
+
+/// Looks up a heuristic map created with `js_create_heuristic_map`, checking
+/// that it was built for `goals`.
+pub fn heuristic_map(id: u32, goals: &[Goal]) -> Rc<HeuristicMap> {
+    let map = HEURISTIC_MAPS
+        .with(|maps| maps.borrow().get(&id).cloned())
+        .unwrap_or_else(|| throw_str(&format!("Unknown heuristic map: {}", id)));
+    if map.fingerprint != goals_fingerprint(goals) {
+        throw_str(&format!(
+            "Heuristic map {} was built for different destinations",
+            id
+        ));
+    }
+    map
+}
+
+/// Stores a copy of a distance map for A* searches to use as their
+/// heuristic. The map should be rooted at the destinations, e.g. from a
+/// Dijkstra search started at them.
+///
+/// # Arguments
+/// * `map` - The distance map from the destinations
+/// * `destinations` - Packed [position, range, kind] triples, as they'll be
+///   passed to the searches that use the map
+///
+/// # Returns
+/// The heuristic map's id
+#[wasm_bindgen(js_name = clockwork_create_heuristic_map)]
+pub fn js_create_heuristic_map(map: &MultiroomDistanceMap, destinations: Vec<u32>) -> u32 {
+    let heuristic_map = HeuristicMap {
+        map: map.clone(),
+        fingerprint: goals_fingerprint(&unpack_goals(destinations)),
+    };
+    let id = NEXT_HEURISTIC_MAP_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next = next.wrapping_add(1).max(1);
+        id
+    });
+    HEURISTIC_MAPS.with(|maps| maps.borrow_mut().insert(id, Rc::new(heuristic_map)));
+    id
+}
+
+/// Frees a heuristic map, returning true if it existed.
+#[wasm_bindgen(js_name = clockwork_free_heuristic_map)]
+pub fn js_free_heuristic_map(id: u32) -> bool {
+    HEURISTIC_MAPS.with(|maps| maps.borrow_mut().remove(&id).is_some())
+}
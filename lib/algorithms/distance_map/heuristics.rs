@@ -1,4 +1,12 @@
-use screeps::Position;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use screeps::{Position, RoomName};
+
+use crate::datatypes::ClockworkCostMatrix;
+
+use super::goals::Goal;
+use super::heuristic_map::HeuristicMap;
 
 /// A basic global range heuristic.
 #[allow(dead_code)]
@@ -14,13 +22,106 @@ pub fn base_heuristic<'a>(goal: &'a [Position]) -> impl Fn(Position) -> usize +
 /// When the goal is to be within a certain range of a position, simply subtract the target
 /// range from the actual range to the goal.
 #[allow(dead_code)]
-pub fn base_heuristic_with_range<'a>(
-    goal: &'a [(Position, usize)],
-) -> impl Fn(Position) -> usize + 'a {
+pub fn base_heuristic_with_range<'a>(goal: &'a [Goal]) -> impl Fn(Position) -> usize + 'a {
     move |position| {
         goal.iter()
-            .map(|(g, range)| position.get_range_to(*g).saturating_sub(*range as u32))
+            .map(|g| {
+                position
+                    .get_range_to(g.position)
+                    .saturating_sub(g.effective_range() as u32)
+            })
             .min()
             .unwrap_or(0) as usize
     }
 }
+
+/// The cheapest passable tile cost in the rooms a search has loaded so far.
+/// The cost matrix callback reports each room to it as it's loaded, and the
+/// heuristic reads it, so the heuristic is in path cost units without the
+/// caller having to know the cheapest tile up front.
+#[derive(Clone)]
+pub struct MinTileCost {
+    cost: Rc<Cell<usize>>,
+    dropped: Rc<Cell<bool>>,
+    fixed: bool,
+}
+
+impl MinTileCost {
+    /// Tracks the minimum over the rooms observed, unless `fixed` is given,
+    /// in which case that's used as is.
+    pub fn new(fixed: Option<usize>) -> Self {
+        MinTileCost {
+            cost: Rc::new(Cell::new(fixed.unwrap_or(254))),
+            dropped: Rc::new(Cell::new(false)),
+            fixed: fixed.is_some(),
+        }
+    }
+
+    pub fn get(&self) -> usize {
+        self.cost.get()
+    }
+
+    /// Lowers the minimum to the cheapest passable tile in `cost_matrix`.
+    pub fn observe(&self, cost_matrix: &ClockworkCostMatrix) {
+        if self.fixed {
+            return;
+        }
+        if let Some(cost) = cost_matrix.min_passable_cost() {
+            if (cost as usize) < self.cost.get() {
+                self.cost.set(cost as usize);
+                self.dropped.set(true);
+            }
+        }
+    }
+
+    /// Whether the minimum dropped since the last call, which means
+    /// estimates made before then may be too high.
+    pub fn take_dropped(&self) -> bool {
+        self.dropped.replace(false)
+    }
+
+    /// Wraps a cost matrix callback so every room it returns is observed.
+    pub fn observing<'a>(
+        &self,
+        get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix> + 'a,
+    ) -> impl Fn(RoomName) -> Option<ClockworkCostMatrix> + 'a {
+        let min_tile_cost = self.clone();
+        move |room_name| {
+            let cost_matrix = get_cost_matrix(room_name);
+            if let Some(cost_matrix) = &cost_matrix {
+                min_tile_cost.observe(cost_matrix);
+            }
+            cost_matrix
+        }
+    }
+}
+
+/// The range heuristic for `goals`, with each tile of range counted as
+/// `min_tile_cost` (the cheapest passable tile cost the search can step onto)
+/// so it's in the units of the path cost. The heuristic stays admissible as
+/// long as no tile is cheaper than that.
+pub fn scaled_heuristic_with_range(
+    goals: &[Goal],
+    min_tile_cost: MinTileCost,
+) -> impl Fn(Position) -> usize + '_ {
+    let heuristic = base_heuristic_with_range(goals);
+    move |position| heuristic(position) * min_tile_cost.get()
+}
+
+/// Uses a heuristic map's distances where it covers a position, and
+/// `heuristic` elsewhere. The map measures distance to the destination tiles,
+/// so `allowance` (the most the steps within the widest goal range can cost)
+/// is taken off to avoid overestimating range goals.
+pub fn with_heuristic_map<'a>(
+    heuristic: impl Fn(Position) -> usize + 'a,
+    map: Option<Rc<HeuristicMap>>,
+    allowance: usize,
+) -> impl Fn(Position) -> usize + 'a {
+    move |position| {
+        let estimate = heuristic(position);
+        match map.as_ref().and_then(|map| map.get(position)) {
+            Some(distance) => distance.saturating_sub(allowance).max(estimate),
+            None => estimate,
+        }
+    }
+}
@@ -2,11 +2,21 @@ use screeps::Position;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::datatypes::MultiroomDistanceMap;
+use diagnostics::SearchFailure;
 
+pub mod anytime;
 pub mod astar;
 pub mod breadth_first_search;
+pub mod closest;
+pub mod diagnostics;
 pub mod dijkstra;
+pub mod fallback;
+pub mod goals;
+pub mod heuristic_map;
 pub mod heuristics;
+pub mod options;
+pub mod progressive;
+pub mod repair;
 
 /// A distance map search returns both the distance map (filled out
 /// with all tiles explored) and the targets found. These aren't necessarily
@@ -15,11 +25,28 @@ pub mod heuristics;
 /// are specified, and you care about matching the found target with one of
 /// the original targets, you can iterate through your list and figure out the
 /// ones that are in range of the found target(s).
+///
+/// Every search counts the same units of work: `ops` is the number of tiles
+/// expanded (popped from the open list), which is what `max_ops` limits, and
+/// `pushed` is the number of tiles added to the open list.
+///
+/// If the search had destinations and didn't reach them, `failure` says why.
+///
+/// `heuristic_weight` is what each tile of range to the destinations was
+/// worth in the search's heuristic: for A*, the cheapest passable tile cost in
+/// the rooms it loaded (or the minimum tile cost it was given), times any
+/// inflation, or 0 for searches without a heuristic.
+/// Paths cost at most `heuristic_weight` divided by the cheapest tile cost
+/// times the optimal path's.
 #[wasm_bindgen]
 pub struct SearchResult {
     distance_map: MultiroomDistanceMap,
     found_targets: Vec<Position>,
     ops: usize,
+    pushed: usize,
+    expanded: Vec<Position>,
+    failure: Option<SearchFailure>,
+    heuristic_weight: f64,
 }
 
 impl SearchResult {
@@ -27,13 +54,111 @@ impl SearchResult {
         distance_map: MultiroomDistanceMap,
         found_targets: Vec<Position>,
         ops: usize,
+        pushed: usize,
+        expanded: Vec<Position>,
     ) -> Self {
         Self {
             distance_map,
             found_targets,
             ops,
+            pushed,
+            expanded,
+            failure: None,
+            heuristic_weight: 0.0,
         }
     }
+
+    /// Records what a tile of range was worth in the search's heuristic.
+    pub fn with_heuristic_weight(mut self, heuristic_weight: f64) -> Self {
+        self.heuristic_weight = heuristic_weight;
+        self
+    }
+
+    /// Attaches the reason the search didn't reach its destinations.
+    pub fn with_failure(mut self, failure: Option<SearchFailure>) -> Self {
+        self.failure = failure;
+        self
+    }
+}
+
+/// What a search does when its open list grows past `OpenListLimit::max_nodes`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OpenListOverflow {
+    /// Stop and return the partial result.
+    #[default]
+    Stop = 0,
+    /// Keep only the `beam_width` most promising tiles (those furthest
+    /// along) in each bucket of the open list and carry on. Distances to
+    /// dropped tiles stay in the map, but may not be the shortest, and
+    /// results are no longer guaranteed to be optimal.
+    Beam = 1,
+}
+
+/// A cap on the number of tiles waiting in a search's open list, so
+/// pathological maps can't grow wasm memory without bound mid-tick.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct OpenListLimit {
+    pub max_nodes: usize,
+    pub overflow: OpenListOverflow,
+    pub beam_width: usize,
+}
+
+impl Default for OpenListLimit {
+    fn default() -> Self {
+        Self {
+            max_nodes: usize::MAX,
+            overflow: OpenListOverflow::Stop,
+            beam_width: 100,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl OpenListLimit {
+    /// Caps the number of tiles waiting in a search's open list at
+    /// `max_nodes` (JavaScript constructor). When the cap is hit, the search
+    /// either stops with what it has or (with `OpenListOverflow::Beam`) keeps
+    /// only the `beam_width` (default 100) most promising tiles per cost
+    /// bucket.
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(max_nodes: usize, overflow: OpenListOverflow, beam_width: Option<usize>) -> Self {
+        OpenListLimit {
+            max_nodes,
+            overflow,
+            beam_width: beam_width.unwrap_or(100),
+        }
+    }
+}
+
+/// The most expanded positions a search will record, so a large search with
+/// `record_expansions` set doesn't allocate without bound.
+pub const MAX_RECORDED_EXPANSIONS: usize = 10_000;
+
+/// Collects the positions a search expands, in order, when enabled.
+pub struct ExpansionLog {
+    enabled: bool,
+    positions: Vec<Position>,
+}
+
+impl ExpansionLog {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            positions: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, position: Position) {
+        if self.enabled && self.positions.len() < MAX_RECORDED_EXPANSIONS {
+            self.positions.push(position);
+        }
+    }
+
+    pub fn into_positions(self) -> Vec<Position> {
+        self.positions
+    }
 }
 
 #[wasm_bindgen]
@@ -51,8 +176,35 @@ impl SearchResult {
             .collect()
     }
 
+    /// The number of tiles expanded.
     #[wasm_bindgen(getter)]
     pub fn ops(&self) -> usize {
         self.ops
     }
+
+    /// The number of tiles added to the open list.
+    #[wasm_bindgen(getter)]
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+
+    /// The positions expanded by the search, in order, if `record_expansions`
+    /// was set (capped at `MAX_RECORDED_EXPANSIONS`).
+    #[wasm_bindgen(getter)]
+    pub fn expanded(&self) -> Vec<u32> {
+        self.expanded.iter().map(|pos| pos.packed_repr()).collect()
+    }
+
+    /// Why the search didn't reach its destinations, if it didn't.
+    #[wasm_bindgen(getter)]
+    pub fn failure(&self) -> Option<SearchFailure> {
+        self.failure.clone()
+    }
+
+    /// What each tile of range to the destinations was worth in the search's
+    /// heuristic (0 without one).
+    #[wasm_bindgen(getter)]
+    pub fn heuristic_weight(&self) -> f64 {
+        self.heuristic_weight
+    }
 }
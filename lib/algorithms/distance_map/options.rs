@@ -0,0 +1,207 @@
+use std::convert::TryFrom;
+
+use screeps::Position;
+use wasm_bindgen::prelude::*;
+
+use super::goals::GoalKind;
+use super::{OpenListLimit, OpenListOverflow};
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{CostOverlay, UnknownRoomPolicy, DEFAULT_UNKNOWN_ROOM_MAX_AGE};
+use crate::helpers::rooms::{in_world, outside_world_error};
+
+/// The values searches use for options that aren't given.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchDefaults {
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+}
+
+#[wasm_bindgen]
+impl SearchDefaults {
+    #[wasm_bindgen(getter)]
+    pub fn allow_diagonals(&self) -> bool {
+        self.movement.allow_diagonals
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cut_corners(&self) -> bool {
+        self.movement.cut_corners
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn room_change_cost(&self) -> usize {
+        self.movement.room_change_cost
+    }
+
+    /// The open list cap: `usize::MAX`, i.e. unlimited.
+    #[wasm_bindgen(getter)]
+    pub fn max_open_nodes(&self) -> usize {
+        self.open_limit.max_nodes
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn open_list_overflow(&self) -> OpenListOverflow {
+        self.open_limit.overflow
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn beam_width(&self) -> usize {
+        self.open_limit.beam_width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn unknown_rooms(&self) -> UnknownRoomPolicy {
+        UnknownRoomPolicy::default()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn unknown_room_max_age(&self) -> u32 {
+        DEFAULT_UNKNOWN_ROOM_MAX_AGE
+    }
+}
+
+/// A search option that would be rejected, or would quietly change how the
+/// search behaves.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct OptionError {
+    field: String,
+    message: String,
+}
+
+impl OptionError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        OptionError {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl OptionError {
+    /// The option's name in the TypeScript wrappers, e.g. `beamWidth`.
+    #[wasm_bindgen(getter)]
+    pub fn field(&self) -> String {
+        self.field.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+/// Checks the packed [position, range, kind] goal triples for `field`.
+fn check_destinations(field: &str, packed: &[u32], errors: &mut Vec<OptionError>) {
+    if !packed.len().is_multiple_of(3) {
+        errors.push(OptionError::new(
+            field,
+            "Destinations must be packed as [position, range, kind] triples",
+        ));
+        return;
+    }
+    for chunk in packed.chunks(3) {
+        let position = Position::from_packed(chunk[0]);
+        if !in_world(position.room_name()) {
+            errors.push(OptionError::new(
+                field,
+                outside_world_error(position.room_name()),
+            ));
+        }
+        if let Err(e) = GoalKind::try_from(chunk[2]) {
+            errors.push(OptionError::new(field, e));
+        }
+    }
+}
+
+/// The values searches use for options that aren't given.
+///
+/// # Returns
+/// The default movement rules, open list limit, and unknown room policy
+#[wasm_bindgen(js_name = clockwork_default_options)]
+pub fn js_default_options() -> SearchDefaults {
+    SearchDefaults::default()
+}
+
+/// Checks a search's options without running it, so mistakes that would
+/// otherwise throw mid-search (or quietly change the result) can be caught
+/// up front.
+///
+/// # Arguments
+/// * `max_ops` - Maximum number of tiles to expand
+/// * `max_rooms` - Maximum number of rooms to explore
+/// * `max_path_cost` - Maximum path cost
+/// * `any_of_destinations` - Packed [position, range, kind] triples; any one ends the search
+/// * `all_of_destinations` - Packed [position, range, kind] triples; all must be reached
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
+///
+/// # Returns
+/// The problems found, empty if the options are valid
+#[wasm_bindgen(js_name = clockwork_validate_options)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_validate_options(
+    max_ops: usize,
+    max_rooms: usize,
+    max_path_cost: usize,
+    any_of_destinations: Option<Vec<u32>>,
+    all_of_destinations: Option<Vec<u32>>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> Vec<OptionError> {
+    let mut errors = Vec::new();
+
+    let unlimited = [max_ops, max_rooms, max_path_cost]
+        .iter()
+        .all(|limit| *limit == usize::MAX);
+    if unlimited && any_of_destinations.is_none() && all_of_destinations.is_none() {
+        errors.push(OptionError::new(
+            "maxOps",
+            "At least one of maxOps, maxRooms, maxPathCost, anyOfDestinations, or allOfDestinations must be set",
+        ));
+    }
+    if max_rooms == 0 {
+        errors.push(OptionError::new("maxRooms", "Must be at least 1"));
+    }
+    if let Some(packed) = &any_of_destinations {
+        check_destinations("anyOfDestinations", packed, &mut errors);
+    }
+    if let Some(packed) = &all_of_destinations {
+        check_destinations("allOfDestinations", packed, &mut errors);
+    }
+
+    if let Some(overlay) = &overlay {
+        for (field, message) in overlay.problems() {
+            errors.push(OptionError::new(field, message));
+        }
+    }
+
+    let movement = movement.unwrap_or_default();
+    if max_rooms > 1 && movement.room_change_cost > max_path_cost {
+        errors.push(OptionError::new(
+            "roomChangeCost",
+            "Is more than maxPathCost, so the search can never leave the start room",
+        ));
+    }
+
+    if let Some(open_limit) = open_limit {
+        if open_limit.max_nodes == 0 {
+            errors.push(OptionError::new(
+                "maxOpenNodes",
+                "Must be at least 1, or the search stops before expanding anything",
+            ));
+        }
+        if open_limit.overflow == OpenListOverflow::Beam && open_limit.beam_width == 0 {
+            errors.push(OptionError::new(
+                "beamWidth",
+                "Must be at least 1 with OpenListOverflow.Beam",
+            ));
+        }
+    }
+
+    errors
+}
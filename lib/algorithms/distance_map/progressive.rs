@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use screeps::{Position, RoomName};
+
+use crate::algorithms::map::{corresponding_room_edge, MovementRules, PREFERRED_DIRECTIONS};
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, MultiroomDistanceMap};
+use crate::utils::{set_panic_hook, PanicContext};
+
+/// A Dijkstra search that can be paused and resumed, so distance maps (and
+/// the flow fields built from them) covering dozens of rooms can be computed
+/// a few cost buckets at a time over several ticks.
+///
+/// Each room's cost matrix is requested once, the first time the search
+/// reaches it, and kept (with the overlay applied) for the rest of the
+/// search.
+pub struct ProgressiveDijkstra {
+    distance_map: MultiroomDistanceMap,
+    cost_matrices: HashMap<RoomName, Option<ClockworkCostMatrix>>,
+    // Open positions, indexed by distance
+    open: Vec<Vec<Position>>,
+    current: usize,
+    // Rooms with a cost matrix count against `max_rooms`; blocked rooms don't
+    rooms_available: usize,
+    max_path_cost: usize,
+    overlay: Option<CostOverlay>,
+    movement: MovementRules,
+}
+
+impl ProgressiveDijkstra {
+    pub fn new(
+        start: Vec<Position>,
+        max_rooms: usize,
+        max_path_cost: usize,
+        overlay: Option<CostOverlay>,
+        movement: MovementRules,
+    ) -> Self {
+        let mut distance_map = MultiroomDistanceMap::new();
+        for position in &start {
+            distance_map.set(*position, 0);
+        }
+        ProgressiveDijkstra {
+            distance_map,
+            cost_matrices: HashMap::new(),
+            open: vec![start],
+            current: 0,
+            rooms_available: max_rooms,
+            max_path_cost,
+            overlay,
+            movement,
+        }
+    }
+
+    /// Whether every reachable position has been settled.
+    pub fn is_done(&self) -> bool {
+        self.current >= self.open.len()
+    }
+
+    fn cost(
+        &mut self,
+        position: Position,
+        get_cost_matrix: &impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    ) -> Option<u8> {
+        let room_name = position.room_name();
+        if !self.cost_matrices.contains_key(&room_name) {
+            if self.rooms_available == 0 {
+                return None;
+            }
+            let cost_matrix = CostOverlay::wrap(self.overlay.as_ref(), get_cost_matrix)(room_name);
+            if cost_matrix.is_some() {
+                self.rooms_available -= 1;
+            }
+            self.cost_matrices.insert(room_name, cost_matrix);
+        }
+        let cost = self.cost_matrices[&room_name].as_ref()?.get(position.xy());
+        (cost < 255).then_some(cost)
+    }
+
+    /// Settles the positions in up to `max_buckets` distance buckets. Returns
+    /// true once the search is done.
+    pub fn step(
+        &mut self,
+        get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+        max_buckets: usize,
+    ) -> bool {
+        set_panic_hook();
+        let _context = PanicContext::enter("progressive_dijkstra");
+
+        let mut buckets = 0;
+        while buckets < max_buckets && !self.is_done() {
+            let distance = self.current;
+            while let Some(position) = self.open[distance].pop() {
+                // Skip positions that were reached more cheaply since they
+                // were queued
+                if self.distance_map.get(position) < distance || distance >= self.max_path_cost {
+                    continue;
+                }
+                PanicContext::set_position(position);
+                let movement = self.movement;
+                for direction in PREFERRED_DIRECTIONS {
+                    let allowed = movement.allows(position, direction, |tile| {
+                        self.cost(tile, &get_cost_matrix).is_some()
+                    });
+                    if !allowed {
+                        continue;
+                    }
+                    let Ok(step) = position.checked_add_direction(direction) else {
+                        continue;
+                    };
+                    let neighbor = corresponding_room_edge(step);
+                    let Some(cost) = self.cost(neighbor, &get_cost_matrix) else {
+                        continue;
+                    };
+                    let next_distance = distance
+                        .saturating_add(cost as usize)
+                        .saturating_add(movement.step_cost(position, neighbor));
+                    if next_distance >= self.distance_map.get(neighbor) {
+                        continue;
+                    }
+                    self.distance_map.set(neighbor, next_distance);
+                    if self.open.len() <= next_distance {
+                        self.open.resize(next_distance + 1, Vec::new());
+                    }
+                    self.open[next_distance].push(neighbor);
+                }
+            }
+            self.current += 1;
+            buckets += 1;
+        }
+        self.is_done()
+    }
+
+    /// Takes the distance map computed so far, leaving an empty one behind.
+    pub fn take_distance_map(&mut self) -> MultiroomDistanceMap {
+        std::mem::take(&mut self.distance_map)
+    }
+}
@@ -0,0 +1,181 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use screeps::{Position, RoomName};
+use wasm_bindgen::prelude::*;
+
+use crate::algorithms::map::{corresponding_room_edge, MovementRules, PREFERRED_DIRECTIONS};
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, MultiroomDistanceMap};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+use crate::utils::{set_panic_hook, PanicContext};
+
+/// Repairs a distance map (from Dijkstra or a full A* search) after the costs
+/// of a few tiles have changed, without rerunning the whole search.
+///
+/// Every tile within `radius` of a changed tile is cleared and re-relaxed
+/// from the tiles just outside that neighborhood, which are assumed to still
+/// be correct. Changes whose effects reach further than `radius` (e.g. a new
+/// wall that forces a long detour) are only partly repaired, so pick a radius
+/// to match how far the change can matter, or rerun the search.
+///
+/// Returns the positions whose distance changed.
+pub fn repair_distance_map(
+    distance_map: &mut MultiroomDistanceMap,
+    changed: &[Position],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    radius: u32,
+    movement: MovementRules,
+) -> Vec<Position> {
+    set_panic_hook();
+    let _context = PanicContext::enter("repair_distance_map");
+
+    // Origins (distance 0) stay fixed
+    let radius = radius as i32;
+    let mut region: HashSet<Position> = HashSet::new();
+    for center in changed {
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                if let Ok(position) = center.checked_add((dx, dy)) {
+                    if distance_map.contains_room(position.room_name())
+                        && distance_map.get(position) != 0
+                    {
+                        region.insert(position);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut cost_matrices: HashMap<RoomName, Option<ClockworkCostMatrix>> = HashMap::new();
+    for position in &region {
+        cost_matrices
+            .entry(position.room_name())
+            .or_insert_with(|| get_cost_matrix(position.room_name()));
+    }
+    let cost = |position: Position| {
+        cost_matrices
+            .get(&position.room_name())
+            .and_then(|cost_matrix| cost_matrix.as_ref())
+            .map(|cost_matrix| cost_matrix.get(position.xy()))
+            .unwrap_or(255)
+    };
+    // The tiles a creep can move between and `position` in one step, with
+    // the extra cost of the move. Moves are symmetric, so the same tiles work
+    // in both directions.
+    let moves = |position: Position| {
+        PREFERRED_DIRECTIONS
+            .iter()
+            .copied()
+            .filter_map(move |direction| {
+                if !movement.allows(position, direction, |tile| cost(tile) < 255) {
+                    return None;
+                }
+                let neighbor =
+                    corresponding_room_edge(position.checked_add_direction(direction).ok()?);
+                Some((neighbor, movement.step_cost(position, neighbor)))
+            })
+    };
+
+    let previous: Vec<(Position, usize)> = region
+        .iter()
+        .map(|position| (*position, distance_map.get(*position)))
+        .collect();
+    for position in &region {
+        distance_map.set(*position, usize::MAX);
+    }
+
+    // Seed the region from its boundary
+    let mut open = BinaryHeap::new();
+    for position in &region {
+        let tile_cost = cost(*position);
+        if tile_cost == 255 {
+            continue;
+        }
+        let best = moves(*position)
+            .filter(|(neighbor, _)| !region.contains(neighbor))
+            .filter_map(|(neighbor, step_cost)| {
+                let distance = distance_map.get(neighbor);
+                (distance != usize::MAX).then(|| distance.saturating_add(step_cost))
+            })
+            .min();
+        if let Some(best) = best {
+            let distance = best.saturating_add(tile_cost as usize);
+            distance_map.set(*position, distance);
+            open.push(Reverse((distance, position.packed_repr())));
+        }
+    }
+
+    // Dijkstra within the region
+    while let Some(Reverse((distance, packed))) = open.pop() {
+        let position = Position::from_packed(packed);
+        if distance > distance_map.get(position) {
+            continue;
+        }
+        PanicContext::set_position(position);
+        for (neighbor, step_cost) in moves(position) {
+            if !region.contains(&neighbor) {
+                continue;
+            }
+            let tile_cost = cost(neighbor);
+            if tile_cost == 255 {
+                continue;
+            }
+            let next_distance = distance
+                .saturating_add(tile_cost as usize)
+                .saturating_add(step_cost);
+            if next_distance < distance_map.get(neighbor) {
+                distance_map.set(neighbor, next_distance);
+                open.push(Reverse((next_distance, neighbor.packed_repr())));
+            }
+        }
+    }
+
+    previous
+        .into_iter()
+        .filter(|(position, distance)| distance_map.get(*position) != *distance)
+        .map(|(position, _)| position)
+        .collect()
+}
+
+/// Repairs a distance map in place after the costs of a few tiles have
+/// changed.
+///
+/// # Arguments
+/// * `distance_map` - The distance map to repair
+/// * `changed` - The packed positions whose costs changed
+/// * `get_cost_matrix` - JavaScript function that returns the updated cost matrices
+/// * `radius` - How far from each changed tile to re-relax
+/// * `overlay` - Optional per-search cost adjustments, as given to the
+///   original search
+/// * `movement` - Optional movement rules, as given to the original search
+///
+/// # Returns
+/// The packed positions whose distance changed
+#[wasm_bindgen(js_name = clockwork_repair_distance_map)]
+pub fn js_repair_distance_map(
+    distance_map: &mut MultiroomDistanceMap,
+    changed: Vec<u32>,
+    get_cost_matrix: &js_sys::Function,
+    radius: u32,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> Vec<u32> {
+    let changed: Vec<Position> = changed
+        .into_iter()
+        .map(world_position_from_packed)
+        .collect();
+    repair_distance_map(
+        distance_map,
+        &changed,
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        radius,
+        movement.unwrap_or_default(),
+    )
+    .into_iter()
+    .map(|position| position.packed_repr())
+    .collect()
+}
@@ -0,0 +1,454 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use screeps::{game, Position, RoomCoordinate, RoomName};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::throw_str;
+
+use crate::algorithms::distance_map::breadth_first_search::bfs_multiroom_distance_map;
+use crate::algorithms::distance_map::goals::{any_goal_reached, unpack_goals, Goal, GoalKind};
+use crate::algorithms::map::{corresponding_room_edge, MovementRules, PREFERRED_DIRECTIONS};
+use crate::algorithms::path::to_multiroom_distance_map_origin::path_to_multiroom_distance_map_origin;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, Path, RoomDataCache};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+use crate::utils::{set_panic_hook, PanicContext};
+
+/// A hostile position and the range it threatens.
+#[derive(Debug, Clone, Copy)]
+pub struct Threat {
+    pub position: Position,
+    pub range: u32,
+}
+
+impl Threat {
+    fn covers(&self, position: Position) -> bool {
+        self.position.get_range_to(position) <= self.range
+    }
+}
+
+/// Finds the cheapest path from `start` that leaves every threat's range and
+/// ends in one of the safe areas (e.g. ramparts, or a Room goal for an owned
+/// room). Tiles inside a threat's range cost an extra `threat_cost` to cross,
+/// so the path gets out of range quickly rather than just taking the
+/// shortest route through danger.
+///
+/// Returns `None` if no safe, unthreatened tile is reached within `max_ops`
+/// tiles or `max_rooms` rooms.
+#[allow(clippy::too_many_arguments)]
+pub fn flee_to_safety(
+    start: Position,
+    threats: &[Threat],
+    safe_areas: &[Goal],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    threat_cost: usize,
+    max_ops: usize,
+    max_rooms: usize,
+    movement: MovementRules,
+) -> Option<Path> {
+    flee_with_cost(
+        start,
+        threats,
+        safe_areas,
+        get_cost_matrix,
+        threat_cost,
+        max_ops,
+        max_rooms,
+        movement,
+    )
+    .map(|(path, _)| path)
+}
+
+/// Like `flee_to_safety`, but also returns the path's cost, including the
+/// threat cost of any threatened tiles it crosses.
+#[allow(clippy::too_many_arguments)]
+fn flee_with_cost(
+    start: Position,
+    threats: &[Threat],
+    safe_areas: &[Goal],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    threat_cost: usize,
+    max_ops: usize,
+    max_rooms: usize,
+    movement: MovementRules,
+) -> Option<(Path, usize)> {
+    set_panic_hook();
+    let _context = PanicContext::enter("flee_to_safety");
+    let threatened = |position: Position| threats.iter().any(|threat| threat.covers(position));
+    let mut cached_room_data = RoomDataCache::new(max_rooms, get_cost_matrix);
+    // Bucket queue indexed by path cost, as in the A* implementation
+    let mut open: Vec<Vec<Position>> = vec![vec![start]];
+    let mut ops_remaining = max_ops;
+
+    let start_key = cached_room_data.get_room_key(start.room_name())?;
+    cached_room_data[start_key].distance_map[start.xy()] = 0;
+
+    let mut cost = 0;
+    let (target, target_cost) = 'search: loop {
+        if cost >= open.len() {
+            return None;
+        }
+        while let Some(position) = open[cost].pop() {
+            let room_key = cached_room_data.get_room_key(position.room_name())?;
+            if cached_room_data[room_key].distance_map[position.xy()] < cost {
+                // Already settled with a lower cost
+                continue;
+            }
+            if any_goal_reached(safe_areas, position) && !threatened(position) {
+                break 'search (position, cost);
+            }
+            if ops_remaining == 0 {
+                return None;
+            }
+            ops_remaining -= 1;
+            cached_room_data.record_expansion(room_key);
+            PanicContext::set_position(position);
+
+            for direction in PREFERRED_DIRECTIONS {
+                if !movement.allows(position, direction, |tile| {
+                    cached_room_data.is_passable(tile)
+                }) {
+                    continue;
+                }
+                let Ok(step) = position.checked_add_direction(direction) else {
+                    continue;
+                };
+                let neighbor = corresponding_room_edge(step);
+                let Some(neighbor_key) = cached_room_data.get_room_key(neighbor.room_name()) else {
+                    continue;
+                };
+                let terrain_cost = match &cached_room_data[neighbor_key].cost_matrix {
+                    Some(cost_matrix) => cost_matrix.get(neighbor.xy()),
+                    None => continue,
+                };
+                if terrain_cost == 255 {
+                    continue;
+                }
+                let mut next_cost = cost
+                    .saturating_add(terrain_cost as usize)
+                    .saturating_add(movement.step_cost(position, neighbor));
+                if threatened(neighbor) {
+                    next_cost = next_cost.saturating_add(threat_cost);
+                }
+                if cached_room_data[neighbor_key].distance_map[neighbor.xy()] <= next_cost {
+                    continue;
+                }
+                cached_room_data[neighbor_key].distance_map[neighbor.xy()] = next_cost;
+                if open.len() <= next_cost {
+                    open.resize(next_cost.saturating_add(1), Vec::new());
+                }
+                open[next_cost].push(neighbor);
+            }
+        }
+        cost += 1;
+    };
+
+    path_to_multiroom_distance_map_origin(target, &cached_room_data.into())
+        .ok()
+        .map(|path| (path, target_cost))
+}
+
+/// How many ticks a creep's flee state is kept without being used.
+const FLEE_STATE_TICKS: u32 = 100;
+
+/// The tile a creep was last fleeing to, and the tick it was chosen.
+struct FleeState {
+    target: Position,
+    tick: u32,
+}
+
+thread_local! {
+    static FLEE_STATES: RefCell<HashMap<String, FleeState>> = RefCell::new(HashMap::new());
+}
+
+/// Flees like `flee_to_safety`, but sticks with the tile chosen on earlier
+/// calls with the same `key` while it's still safe, unless the best tile now
+/// is cheaper to reach by more than `hysteresis`. Jittering threats then
+/// don't flip the creep back and forth between escape routes.
+#[allow(clippy::too_many_arguments)]
+pub fn flee_with_hysteresis(
+    key: &str,
+    start: Position,
+    threats: &[Threat],
+    safe_areas: &[Goal],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    threat_cost: usize,
+    max_ops: usize,
+    max_rooms: usize,
+    hysteresis: usize,
+    movement: MovementRules,
+) -> Option<Path> {
+    let tick = game::time();
+    let previous = FLEE_STATES.with(|states| {
+        let mut states = states.borrow_mut();
+        states.retain(|_, state| tick.saturating_sub(state.tick) <= FLEE_STATE_TICKS);
+        states.get(key).map(|state| state.target)
+    });
+    let still_safe = |target: Position| {
+        any_goal_reached(safe_areas, target) && !threats.iter().any(|threat| threat.covers(target))
+    };
+
+    let best = flee_with_cost(
+        start,
+        threats,
+        safe_areas,
+        &get_cost_matrix,
+        threat_cost,
+        max_ops,
+        max_rooms,
+        movement,
+    );
+    let best_target = best.as_ref().and_then(|(path, _)| last_position(path));
+    // No need to search again if the best route still ends at the same tile
+    let kept = previous
+        .filter(|target| still_safe(*target) && Some(*target) != best_target)
+        .and_then(|target| {
+            flee_with_cost(
+                start,
+                threats,
+                &[Goal::new(target, 0, GoalKind::Tile)],
+                &get_cost_matrix,
+                threat_cost,
+                max_ops,
+                max_rooms,
+                movement,
+            )
+        });
+    let (path, _) = match (best, kept) {
+        (Some(best), Some(kept)) if best.1.saturating_add(hysteresis) >= kept.1 => kept,
+        (Some(best), _) => best,
+        (None, kept) => kept?,
+    };
+
+    let target = last_position(&path)?;
+    FLEE_STATES.with(|states| {
+        states
+            .borrow_mut()
+            .insert(key.to_string(), FleeState { target, tick })
+    });
+    Some(path)
+}
+
+/// Unpacks threats passed from JS as pairs of `[packed position, range]`.
+fn unpack_threats(packed: Vec<u32>) -> Vec<Threat> {
+    if !packed.len().is_multiple_of(2) {
+        throw_str("Threats must be packed as [position, range] pairs");
+    }
+    packed
+        .chunks(2)
+        .map(|chunk| Threat {
+            position: world_position_from_packed(chunk[0]),
+            range: chunk[1],
+        })
+        .collect()
+}
+
+/// Finds a path that leaves every threat's range and ends in a safe area.
+///
+/// # Arguments
+/// * `start_packed` - The packed start position
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `threats` - Array of packed [position, range] pairs
+/// * `safe_areas` - Array of packed [position, range, kind] triples
+/// * `threat_cost` - Extra cost for crossing a threatened tile
+/// * `max_ops` - Maximum number of tiles to explore
+/// * `max_rooms` - Maximum number of rooms to explore
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+///
+/// # Returns
+/// The path from the start to a safe tile, or undefined if none was found
+#[wasm_bindgen(js_name = clockwork_flee_to_safety)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_flee_to_safety(
+    start_packed: u32,
+    get_cost_matrix: &js_sys::Function,
+    threats: Vec<u32>,
+    safe_areas: Vec<u32>,
+    threat_cost: usize,
+    max_ops: usize,
+    max_rooms: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> Option<Path> {
+    flee_to_safety(
+        world_position_from_packed(start_packed),
+        &unpack_threats(threats),
+        &unpack_goals(safe_areas),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        threat_cost,
+        max_ops,
+        max_rooms,
+        movement.unwrap_or_default(),
+    )
+}
+
+fn last_position(path: &Path) -> Option<Position> {
+    path.get(path.len().checked_sub(1)?).copied()
+}
+
+/// Finds a path out of every threat's range to a safe area, keeping the
+/// escape route chosen on earlier calls with the same key unless a new one
+/// is better by more than `hysteresis`.
+///
+/// # Arguments
+/// * `key` - Identifies the fleeing creep, e.g. its name
+/// * `start_packed` - The packed start position
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `threats` - Array of packed [position, range] pairs
+/// * `safe_areas` - Array of packed [position, range, kind] triples
+/// * `threat_cost` - Extra cost for crossing a threatened tile
+/// * `max_ops` - Maximum number of tiles to explore
+/// * `max_rooms` - Maximum number of rooms to explore
+/// * `hysteresis` - How much cheaper a new route must be to switch to it
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+///
+/// # Returns
+/// The path from the start to a safe tile, or undefined if none was found
+#[wasm_bindgen(js_name = clockwork_flee_with_hysteresis)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_flee_with_hysteresis(
+    key: &str,
+    start_packed: u32,
+    get_cost_matrix: &js_sys::Function,
+    threats: Vec<u32>,
+    safe_areas: Vec<u32>,
+    threat_cost: usize,
+    max_ops: usize,
+    max_rooms: usize,
+    hysteresis: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> Option<Path> {
+    flee_with_hysteresis(
+        key,
+        world_position_from_packed(start_packed),
+        &unpack_threats(threats),
+        &unpack_goals(safe_areas),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        threat_cost,
+        max_ops,
+        max_rooms,
+        hysteresis,
+        movement.unwrap_or_default(),
+    )
+}
+
+/// Forgets the escape route remembered for `key`, returning true if there
+/// was one.
+#[wasm_bindgen(js_name = clockwork_forget_flee_state)]
+pub fn js_forget_flee_state(key: &str) -> bool {
+    FLEE_STATES.with(|states| states.borrow_mut().remove(key).is_some())
+}
+
+/// Finds up to `count` tiles in a room where a creep is at least `min_range`
+/// moves from every threat, measured by walking distance around walls rather
+/// than straight-line range, and that can still be reached from `anchor`
+/// (e.g. a spawn the creep shouldn't be cut off from). Only threats in the
+/// room are considered. Tiles are passable if their cost in `cost_matrix` is
+/// below 255; tiles no threat can reach are safe.
+///
+/// The tiles are ordered by walking distance from the anchor, closest first,
+/// then by distance from the threats, furthest first.
+pub fn safe_positions(
+    room_name: RoomName,
+    threats: &[Position],
+    anchor: Position,
+    min_range: usize,
+    count: usize,
+    cost_matrix: &ClockworkCostMatrix,
+) -> Vec<Position> {
+    let in_room = |room: RoomName| (room == room_name).then(|| cost_matrix.clone());
+    let distance_from = |start: Vec<Position>| {
+        bfs_multiroom_distance_map(
+            start,
+            in_room,
+            usize::MAX,
+            1,
+            usize::MAX,
+            None,
+            None,
+            false,
+            MovementRules::default(),
+        )
+        .distance_map()
+    };
+    let threat_distances = distance_from(
+        threats
+            .iter()
+            .filter(|threat| threat.room_name() == room_name)
+            .copied()
+            .collect(),
+    );
+    let anchor_distances = distance_from(vec![anchor]);
+
+    let mut safe = Vec::new();
+    for y in 0..50 {
+        for x in 0..50 {
+            // SAFETY: x and y are both in the range 0..50
+            let position = unsafe {
+                Position::new(
+                    RoomCoordinate::unchecked_new(x),
+                    RoomCoordinate::unchecked_new(y),
+                    room_name,
+                )
+            };
+            let from_anchor = anchor_distances.get(position);
+            let from_threats = threat_distances.get(position);
+            if from_anchor != usize::MAX && from_threats >= min_range {
+                safe.push((from_anchor, std::cmp::Reverse(from_threats), position));
+            }
+        }
+    }
+    safe.sort_by_key(|&(from_anchor, from_threats, _)| (from_anchor, from_threats));
+    safe.into_iter()
+        .take(count)
+        .map(|(_, _, position)| position)
+        .collect()
+}
+
+/// Finds tiles in a room that are out of the threats' reach and still
+/// reachable from an anchor, for defenders and civilians to hide on.
+///
+/// # Arguments
+/// * `room_name` - The packed name of the room to search
+/// * `threats` - Packed positions of the threats
+/// * `anchor_packed` - The packed position the tiles must be reachable from
+/// * `min_range` - The fewest moves a threat must need to reach a tile
+/// * `count` - The most tiles to return
+/// * `cost_matrix` - The room's cost matrix; tiles at 255 are impassable
+///
+/// # Returns
+/// Packed positions of the safe tiles, closest to the anchor first
+#[wasm_bindgen(js_name = clockwork_safe_positions)]
+pub fn js_safe_positions(
+    room_name: u16,
+    threats: Vec<u32>,
+    anchor_packed: u32,
+    min_range: usize,
+    count: usize,
+    cost_matrix: &ClockworkCostMatrix,
+) -> Vec<u32> {
+    let threats: Vec<Position> = threats
+        .into_iter()
+        .map(world_position_from_packed)
+        .collect();
+    safe_positions(
+        world_room_from_packed(room_name),
+        &threats,
+        world_position_from_packed(anchor_packed),
+        min_range,
+        count,
+        cost_matrix,
+    )
+    .into_iter()
+    .map(Position::packed_repr)
+    .collect()
+}
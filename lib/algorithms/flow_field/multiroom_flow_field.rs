@@ -1,40 +1,90 @@
+use crate::datatypes::DistanceMap;
 use crate::datatypes::MultiroomDistanceMap;
 use crate::datatypes::MultiroomFlowField;
-use crate::utils::set_panic_hook;
-use screeps::Position;
+use crate::helpers::rooms::world_position_from_packed;
+use crate::utils::{set_panic_hook, PanicContext};
+use screeps::{Direction, Position, RoomXY};
 use wasm_bindgen::prelude::*;
 
+/// The directions from a tile to its lowest neighbors, if any are lower than
+/// the tile itself.
+fn downhill_directions(room_map: &DistanceMap, position: RoomXY) -> Vec<Direction> {
+    let value = room_map[position];
+    if value == usize::MAX {
+        return Vec::new(); // unreachable
+    }
+    let min_distance = position
+        .neighbors()
+        .iter()
+        .map(|neighbor| room_map[*neighbor])
+        .min();
+    match min_distance {
+        Some(min_distance) if min_distance < value => position
+            .neighbors()
+            .iter()
+            .filter(|neighbor| room_map[**neighbor] == min_distance)
+            .map(|neighbor| position.get_direction_to(*neighbor).unwrap())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 /// Creates a flow field for the given distance map.
-#[wasm_bindgen(js_name = "multiroomFlowField")]
+#[wasm_bindgen(js_name = clockwork_multiroom_flow_field)]
 pub fn multiroom_flow_field(distance_map: MultiroomDistanceMap) -> MultiroomFlowField {
     set_panic_hook();
+    let _context = PanicContext::enter("multiroom_flow_field");
     let mut flow_field = MultiroomFlowField::new();
 
     for room in distance_map.rooms() {
         let room_map = distance_map.get_room_map(room).unwrap();
-        for (position, &value) in room_map.enumerate() {
-            if value == usize::MAX {
-                continue; // unreachable
-            }
-            let min_distance = position
-                .neighbors()
-                .iter()
-                .map(|neighbor| room_map[*neighbor])
-                .min();
-            if let Some(min_distance) = min_distance {
-                if min_distance < value {
-                    let directions = position
-                        .neighbors()
-                        .iter()
-                        .filter(|neighbor| room_map[**neighbor] == min_distance)
-                        .map(|neighbor| position.get_direction_to(*neighbor).unwrap())
-                        .collect();
-                    flow_field
-                        .set_directions(Position::new(position.x, position.y, room), directions);
-                }
+        for (position, _) in room_map.enumerate() {
+            let directions = downhill_directions(room_map, position);
+            if !directions.is_empty() {
+                flow_field.set_directions(Position::new(position.x, position.y, room), directions);
             }
         }
     }
 
     flow_field
 }
+
+/// Recomputes the flow field around the given positions (typically the ones
+/// changed by `repair_distance_map`), rather than rebuilding the whole field.
+pub fn repair_multiroom_flow_field(
+    flow_field: &mut MultiroomFlowField,
+    distance_map: &MultiroomDistanceMap,
+    changed: &[Position],
+) {
+    set_panic_hook();
+    let _context = PanicContext::enter("repair_multiroom_flow_field");
+
+    for position in changed {
+        let Some(room_map) = distance_map.get_room_map(position.room_name()) else {
+            continue;
+        };
+        // A tile's directions depend on its neighbors, so refresh those too
+        let xy = position.xy();
+        for tile in std::iter::once(xy).chain(xy.neighbors()) {
+            flow_field.set_directions(
+                Position::new(tile.x, tile.y, position.room_name()),
+                downhill_directions(room_map, tile),
+            );
+        }
+    }
+}
+
+/// Recomputes a flow field in place around the given packed positions, after
+/// its distance map has been repaired.
+#[wasm_bindgen(js_name = clockwork_repair_multiroom_flow_field)]
+pub fn js_repair_multiroom_flow_field(
+    flow_field: &mut MultiroomFlowField,
+    distance_map: &MultiroomDistanceMap,
+    changed: Vec<u32>,
+) {
+    let changed: Vec<Position> = changed
+        .into_iter()
+        .map(world_position_from_packed)
+        .collect();
+    repair_multiroom_flow_field(flow_field, distance_map, &changed);
+}
@@ -1,13 +1,14 @@
 use crate::datatypes::MultiroomDistanceMap;
 use crate::datatypes::MultiroomMonoFlowField;
-use crate::utils::set_panic_hook;
+use crate::utils::{set_panic_hook, PanicContext};
 use screeps::Position;
 use wasm_bindgen::prelude::*;
 
 /// Creates a monodirectional flow field for the given distance map.
-#[wasm_bindgen(js_name = "multiroomMonoFlowField")]
+#[wasm_bindgen(js_name = clockwork_multiroom_mono_flow_field)]
 pub fn multiroom_mono_flow_field(distance_map: MultiroomDistanceMap) -> MultiroomMonoFlowField {
     set_panic_hook();
+    let _context = PanicContext::enter("multiroom_mono_flow_field");
     let mut flow_field = MultiroomMonoFlowField::new();
 
     for room in distance_map.rooms() {
@@ -0,0 +1,357 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use screeps::{Position, RoomName, RoomXY};
+use wasm_bindgen::prelude::*;
+
+use crate::algorithms::distance_map::dijkstra::dijkstra_multiroom_distance_map;
+use crate::algorithms::distance_map::repair::repair_distance_map;
+use crate::algorithms::distance_map::OpenListLimit;
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, MultiroomDistanceMap};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+// How far from each changed tile a dirty room's repair re-relaxes
+const REPAIR_RADIUS: u32 = 5;
+// Above this many changed tiles, an anchor is recomputed instead of repaired
+const MAX_REPAIR_TILES: usize = 100;
+
+type CostMatrices = HashMap<RoomName, Option<ClockworkCostMatrix>>;
+
+/// A maintained distance map rooted at a set of anchor positions (typically
+/// a room's storage or spawn cluster).
+struct Anchor {
+    origin: Vec<Position>,
+    max_rooms: usize,
+    max_path_cost: usize,
+    distance_map: Option<MultiroomDistanceMap>,
+    // The cost matrices the distance map was built from, so the tiles that
+    // changed in a dirty room can be found and repaired
+    cost_matrices: CostMatrices,
+    dirty_rooms: HashSet<RoomName>,
+}
+
+/// The work for one dirty anchor, taken out of the shared state so the cost
+/// matrix callback can run without it borrowed.
+enum AnchorUpdate {
+    Recompute {
+        origin: Vec<Position>,
+        max_rooms: usize,
+        max_path_cost: usize,
+    },
+    Repair {
+        dirty_rooms: Vec<RoomName>,
+    },
+}
+
+/// The tiles whose cost differs between two versions of a room's cost
+/// matrix, or `None` if the room became blocked or unblocked.
+fn changed_tiles(
+    room_name: RoomName,
+    old: &Option<ClockworkCostMatrix>,
+    new: &Option<ClockworkCostMatrix>,
+) -> Option<Vec<Position>> {
+    match (old, new) {
+        (Some(old), Some(new)) => Some(
+            (0..50)
+                .flat_map(|y| (0..50).map(move |x| (x, y)))
+                .filter_map(|(x, y)| {
+                    // SAFETY: x and y are in the range 0..50
+                    let xy = unsafe { RoomXY::unchecked_new(x, y) };
+                    (old.get(xy) != new.get(xy)).then(|| Position::new(xy.x, xy.y, room_name))
+                })
+                .collect(),
+        ),
+        (None, None) => Some(Vec::new()),
+        _ => None,
+    }
+}
+
+impl Anchor {
+    fn covers_room(&self, room_name: RoomName) -> bool {
+        self.distance_map
+            .as_ref()
+            .map(|map| map.contains_room(room_name))
+            .unwrap_or(false)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.distance_map.is_none() || !self.dirty_rooms.is_empty()
+    }
+}
+
+/// Keeps distance maps from each anchor up to date. Rather than running a new
+/// search for every logistics request, callers mark rooms dirty when their cost
+/// matrices change, and the affected anchors are updated in the background.
+/// Only the tiles that changed are repaired (see `repair_distance_map`), unless
+/// so many changed, or a room became blocked or unblocked, that recomputing
+/// the whole map is simpler.
+#[derive(Default)]
+pub struct Logistics {
+    anchors: HashMap<u32, Anchor>,
+    next_id: u32,
+}
+
+impl Logistics {
+    /// Registers a new anchor. Its distance map is computed on the next update.
+    pub fn add_anchor(
+        &mut self,
+        origin: Vec<Position>,
+        max_rooms: usize,
+        max_path_cost: usize,
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.anchors.insert(
+            id,
+            Anchor {
+                origin,
+                max_rooms,
+                max_path_cost,
+                distance_map: None,
+                cost_matrices: HashMap::new(),
+                dirty_rooms: HashSet::new(),
+            },
+        );
+        id
+    }
+
+    /// Removes an anchor, returning true if it existed.
+    pub fn remove_anchor(&mut self, id: u32) -> bool {
+        self.anchors.remove(&id).is_some()
+    }
+
+    /// Flags every anchor whose distance map covers the room for recomputation.
+    pub fn mark_room_dirty(&mut self, room_name: RoomName) {
+        for anchor in self.anchors.values_mut() {
+            if anchor.covers_room(room_name) {
+                anchor.dirty_rooms.insert(room_name);
+            }
+        }
+    }
+
+    /// Returns the number of anchors waiting to be recomputed.
+    pub fn pending(&self) -> usize {
+        self.anchors
+            .values()
+            .filter(|anchor| anchor.is_dirty())
+            .count()
+    }
+
+    /// Takes the work for up to `max_updates` dirty anchors, oldest first,
+    /// clearing their dirty rooms. Rooms marked dirty while the work is done
+    /// are kept for the next update.
+    fn take_updates(&mut self, max_updates: usize) -> Vec<(u32, AnchorUpdate)> {
+        let mut ids: Vec<u32> = self
+            .anchors
+            .iter()
+            .filter(|(_, anchor)| anchor.is_dirty())
+            .map(|(id, _)| *id)
+            .collect();
+        // Oldest anchors first, so updates are processed in a stable order
+        ids.sort_unstable();
+        ids.truncate(max_updates);
+
+        ids.into_iter()
+            .map(|id| {
+                let anchor = self.anchors.get_mut(&id).unwrap();
+                let dirty_rooms = std::mem::take(&mut anchor.dirty_rooms);
+                let update = if anchor.distance_map.is_none() {
+                    AnchorUpdate::Recompute {
+                        origin: anchor.origin.clone(),
+                        max_rooms: anchor.max_rooms,
+                        max_path_cost: anchor.max_path_cost,
+                    }
+                } else {
+                    AnchorUpdate::Repair {
+                        dirty_rooms: dirty_rooms.into_iter().collect(),
+                    }
+                };
+                (id, update)
+            })
+            .collect()
+    }
+
+    /// Repairs an anchor's distance map with the fresh cost matrices of its
+    /// dirty rooms. Returns false if the anchor needs a full recompute
+    /// instead.
+    fn repair(&mut self, id: u32, fresh: CostMatrices) -> bool {
+        let Some(anchor) = self.anchors.get_mut(&id) else {
+            return true;
+        };
+        let Some(distance_map) = anchor.distance_map.as_mut() else {
+            return false;
+        };
+        let mut changed = Vec::new();
+        for (room_name, cost_matrix) in fresh.iter() {
+            let old = anchor.cost_matrices.get(room_name).unwrap_or(&None);
+            match changed_tiles(*room_name, old, cost_matrix) {
+                Some(tiles) => changed.extend(tiles),
+                None => return false,
+            }
+        }
+        if changed.len() > MAX_REPAIR_TILES {
+            return false;
+        }
+        anchor.cost_matrices.extend(fresh);
+        let cost_matrices = &anchor.cost_matrices;
+        repair_distance_map(
+            distance_map,
+            &changed,
+            |room_name| cost_matrices.get(&room_name).cloned().flatten(),
+            REPAIR_RADIUS,
+            MovementRules::default(),
+        );
+        true
+    }
+
+    /// Stores a recomputed distance map, if the anchor still exists.
+    fn store(&mut self, id: u32, distance_map: MultiroomDistanceMap, cost_matrices: CostMatrices) {
+        if let Some(anchor) = self.anchors.get_mut(&id) {
+            anchor.distance_map = Some(distance_map);
+            anchor.cost_matrices = cost_matrices;
+        }
+    }
+
+    /// Gets the distance from an anchor to a position, or `usize::MAX` if the
+    /// anchor doesn't exist, hasn't been computed yet, or can't reach it.
+    pub fn distance(&self, id: u32, position: Position) -> usize {
+        self.anchors
+            .get(&id)
+            .and_then(|anchor| anchor.distance_map.as_ref())
+            .map(|map| map.get(position))
+            .unwrap_or(usize::MAX)
+    }
+}
+
+thread_local! {
+    static LOGISTICS: RefCell<Logistics> = RefCell::new(Logistics::default());
+}
+
+/// Runs a closure with the shared logistics state.
+pub fn with_logistics<R>(f: impl FnOnce(&Logistics) -> R) -> R {
+    LOGISTICS.with(|logistics| f(&logistics.borrow()))
+}
+
+/// Runs a closure with mutable access to the shared logistics state.
+pub fn with_logistics_mut<R>(f: impl FnOnce(&mut Logistics) -> R) -> R {
+    LOGISTICS.with(|logistics| f(&mut logistics.borrow_mut()))
+}
+
+/// Updates up to `max_updates` dirty anchors, returning how many were
+/// updated. The shared state is only borrowed between calls to
+/// `get_cost_matrix`, so the callback may use the other logistics functions
+/// (e.g. to mark more rooms dirty).
+pub fn update_logistics(
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_updates: usize,
+) -> usize {
+    let updates = with_logistics_mut(|logistics| logistics.take_updates(max_updates));
+    let count = updates.len();
+    for (id, update) in updates {
+        let recompute = match update {
+            AnchorUpdate::Recompute {
+                origin,
+                max_rooms,
+                max_path_cost,
+            } => Some((origin, max_rooms, max_path_cost)),
+            AnchorUpdate::Repair { dirty_rooms } => {
+                let fresh: CostMatrices = dirty_rooms
+                    .into_iter()
+                    .map(|room_name| (room_name, get_cost_matrix(room_name)))
+                    .collect();
+                if with_logistics_mut(|logistics| logistics.repair(id, fresh)) {
+                    None
+                } else {
+                    with_logistics(|logistics| {
+                        logistics.anchors.get(&id).map(|anchor| {
+                            (
+                                anchor.origin.clone(),
+                                anchor.max_rooms,
+                                anchor.max_path_cost,
+                            )
+                        })
+                    })
+                }
+            }
+        };
+        let Some((origin, max_rooms, max_path_cost)) = recompute else {
+            continue;
+        };
+        let cost_matrices = RefCell::new(CostMatrices::new());
+        let result = dijkstra_multiroom_distance_map(
+            origin,
+            |room_name| {
+                let cost_matrix = get_cost_matrix(room_name);
+                cost_matrices
+                    .borrow_mut()
+                    .insert(room_name, cost_matrix.clone());
+                cost_matrix
+            },
+            usize::MAX,
+            max_rooms,
+            max_path_cost,
+            None,
+            None,
+            false,
+            MovementRules::default(),
+            OpenListLimit::default(),
+        );
+        with_logistics_mut(|logistics| {
+            logistics.store(id, result.distance_map(), cost_matrices.into_inner())
+        });
+    }
+    count
+}
+
+/// Registers a logistics anchor rooted at the given positions. Returns the
+/// anchor id used for subsequent queries.
+#[wasm_bindgen(js_name = clockwork_logistics_add_anchor)]
+pub fn js_logistics_add_anchor(
+    origin_packed: Vec<u32>,
+    max_rooms: usize,
+    max_path_cost: usize,
+) -> u32 {
+    let origin = origin_packed
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    with_logistics_mut(|logistics| logistics.add_anchor(origin, max_rooms, max_path_cost))
+}
+
+/// Removes a logistics anchor. Returns false if the id was unknown.
+#[wasm_bindgen(js_name = clockwork_logistics_remove_anchor)]
+pub fn js_logistics_remove_anchor(anchor_id: u32) -> bool {
+    with_logistics_mut(|logistics| logistics.remove_anchor(anchor_id))
+}
+
+/// Marks a room's cost matrix as changed, so anchors covering it are
+/// recomputed on the next update.
+#[wasm_bindgen(js_name = clockwork_logistics_mark_room_dirty)]
+pub fn js_logistics_mark_room_dirty(room_name: u16) {
+    with_logistics_mut(|logistics| logistics.mark_room_dirty(world_room_from_packed(room_name)))
+}
+
+/// Returns the number of anchors waiting to be recomputed.
+#[wasm_bindgen(js_name = clockwork_logistics_pending)]
+pub fn js_logistics_pending() -> usize {
+    with_logistics(|logistics| logistics.pending())
+}
+
+/// Updates up to `max_updates` dirty anchors, returning how many were updated.
+#[wasm_bindgen(js_name = clockwork_logistics_update)]
+pub fn js_logistics_update(get_cost_matrix: &js_sys::Function, max_updates: usize) -> usize {
+    update_logistics(
+        packed_room_cost_matrix_callback(get_cost_matrix),
+        max_updates,
+    )
+}
+
+/// Gets the maintained distance from an anchor to a position.
+#[wasm_bindgen(js_name = clockwork_logistics_distance)]
+pub fn js_logistics_distance(anchor_id: u32, packed_pos: u32) -> usize {
+    with_logistics(|logistics| {
+        logistics.distance(anchor_id, world_position_from_packed(packed_pos))
+    })
+}
@@ -0,0 +1,127 @@
+use screeps::{Position, RoomCoordinate, RoomName};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use super::{neighbors_without_edges, MovementRules};
+use crate::algorithms::distance_map::dijkstra::dijkstra_multiroom_distance_map;
+use crate::algorithms::distance_map::OpenListLimit;
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+/// How reachable each of a room's sources, controller, and mineral (or any
+/// other targets) are.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct Accessibility {
+    walkable: Vec<u8>,
+    distances: Vec<usize>,
+}
+
+/// Scores each target (all in `room_name`) by the number of walkable tiles
+/// adjacent to it and the path cost from `anchor` (or, if there's no anchor,
+/// the nearest exit tile) to the closest of those tiles. Unreachable targets
+/// have a distance of `usize::MAX`.
+pub fn accessibility(
+    room_name: RoomName,
+    targets: &[Position],
+    anchor: Option<Position>,
+    cost_matrix: &ClockworkCostMatrix,
+) -> Accessibility {
+    let is_walkable = |position: &Position| cost_matrix.get(position.xy()) < 255;
+    let start: Vec<Position> = match anchor {
+        Some(anchor) => vec![anchor],
+        None => (0..50)
+            .flat_map(|i| [(0, i), (49, i), (i, 0), (i, 49)])
+            .map(|(x, y)| {
+                // SAFETY: x and y are both in the range 0..50
+                unsafe {
+                    Position::new(
+                        RoomCoordinate::unchecked_new(x),
+                        RoomCoordinate::unchecked_new(y),
+                        room_name,
+                    )
+                }
+            })
+            .filter(is_walkable)
+            .collect(),
+    };
+    let distance_map = dijkstra_multiroom_distance_map(
+        start,
+        |room| (room == room_name).then(|| cost_matrix.clone()),
+        usize::MAX,
+        1,
+        usize::MAX,
+        None,
+        None,
+        false,
+        MovementRules::default(),
+        OpenListLimit::default(),
+    )
+    .distance_map();
+
+    let mut result = Accessibility::default();
+    for target in targets {
+        let adjacent: Vec<Position> = neighbors_without_edges(*target)
+            .filter(|neighbor| neighbor.room_name() == room_name)
+            .filter(is_walkable)
+            .collect();
+        result.walkable.push(adjacent.len() as u8);
+        result.distances.push(
+            adjacent
+                .iter()
+                .map(|neighbor| distance_map.get(*neighbor))
+                .min()
+                .unwrap_or(usize::MAX),
+        );
+    }
+    result
+}
+
+#[wasm_bindgen]
+impl Accessibility {
+    /// The number of walkable tiles adjacent to each target.
+    #[wasm_bindgen(getter)]
+    pub fn walkable(&self) -> Vec<u8> {
+        self.walkable.clone()
+    }
+
+    /// The path cost from the anchor or nearest exit to each target.
+    #[wasm_bindgen(getter)]
+    pub fn distances(&self) -> Vec<usize> {
+        self.distances.clone()
+    }
+}
+
+/// Scores the accessibility of each target in a room, for remote selection
+/// and container placement.
+///
+/// # Arguments
+/// * `room_name` - The packed room name
+/// * `targets_packed` - The packed positions of the sources, controller, etc.
+/// * `anchor_packed` - The packed position to measure from, or the room's
+///   exits if omitted
+/// * `cost_matrix` - The cost matrix for the room
+///
+/// # Returns
+/// The walkable adjacent tile count and distance for each target
+#[wasm_bindgen(js_name = clockwork_source_accessibility)]
+pub fn js_source_accessibility(
+    room_name: u16,
+    targets_packed: Vec<u32>,
+    anchor_packed: Option<u32>,
+    cost_matrix: &ClockworkCostMatrix,
+) -> Accessibility {
+    let room_name = world_room_from_packed(room_name);
+    let targets: Vec<Position> = targets_packed
+        .into_iter()
+        .map(world_position_from_packed)
+        .collect();
+    let anchor = anchor_packed.map(world_position_from_packed);
+    if targets
+        .iter()
+        .chain(anchor.iter())
+        .any(|pos| pos.room_name() != room_name)
+    {
+        throw_str("All positions must be in the given room");
+    }
+    accessibility(room_name, &targets, anchor, cost_matrix)
+}
@@ -0,0 +1,49 @@
+use screeps::{Position, RoomCoordinate, RoomXY, Terrain};
+
+use crate::datatypes::PackedTerrain;
+
+/// Checks whether there is an unobstructed line between two positions in the
+/// same room. The line is traced with Bresenham's algorithm, and any wall tile
+/// strictly between the endpoints blocks it. Positions in different rooms
+/// never have line of sight.
+pub fn has_line_of_sight(from: Position, to: Position, terrain: &PackedTerrain) -> bool {
+    if from.room_name() != to.room_name() {
+        return false;
+    }
+    if from == to {
+        return true;
+    }
+
+    let (mut x, mut y) = (from.x().u8() as i32, from.y().u8() as i32);
+    let (x1, y1) = (to.x().u8() as i32, to.y().u8() as i32);
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    loop {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        if x == x1 && y == y1 {
+            return true;
+        }
+        // SAFETY: the line stays within the bounding box of two valid coordinates.
+        let xy = unsafe {
+            RoomXY::new(
+                RoomCoordinate::unchecked_new(x as u8),
+                RoomCoordinate::unchecked_new(y as u8),
+            )
+        };
+        if terrain.get(xy) == Terrain::Wall {
+            return false;
+        }
+    }
+}
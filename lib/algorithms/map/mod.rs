@@ -1,29 +1,55 @@
 use screeps::{Direction, Position, RoomCoordinate};
 
+pub mod accessibility;
+pub mod los;
+pub mod room_graph;
+pub mod scout_frontier;
+pub mod swamp_bridges;
+pub mod traversal_cost;
+
 use lazy_static::lazy_static;
+use wasm_bindgen::prelude::*;
+
+use crate::helpers::rooms::in_world;
+
 /// If the position is on a room edge, return the corresponding room edge.
-/// Otherwise, just return the position.
+/// Otherwise, just return the position. Edges facing beyond the edge of the
+/// world (see `js_set_world_size`) lead nowhere, so they're returned as-is,
+/// which keeps every search's neighbors inside the world.
 pub fn corresponding_room_edge(position: Position) -> Position {
-    if position.x() == RoomCoordinate(0) {
-        return position.checked_add_direction(Direction::Left).unwrap();
-    }
-
-    if position.x() == RoomCoordinate(49) {
-        return position.checked_add_direction(Direction::Right).unwrap();
-    }
+    let direction = if position.x() == RoomCoordinate(0) {
+        Direction::Left
+    } else if position.x() == RoomCoordinate(49) {
+        Direction::Right
+    } else if position.y() == RoomCoordinate(0) {
+        Direction::Top
+    } else if position.y() == RoomCoordinate(49) {
+        Direction::Bottom
+    } else {
+        return position;
+    };
 
-    if position.y() == RoomCoordinate(0) {
-        return position.checked_add_direction(Direction::Top).unwrap();
+    match position.checked_add_direction(direction) {
+        Ok(across) if in_world(across.room_name()) => across,
+        _ => position,
     }
+}
 
-    if position.y() == RoomCoordinate(49) {
-        return position.checked_add_direction(Direction::Bottom).unwrap();
-    }
+/// Gets the tile a creep on a room edge is moved to, or the same position if
+/// it isn't on an edge. Paths from the searches cross room borders this way.
+#[wasm_bindgen(js_name = clockwork_corresponding_room_edge)]
+pub fn js_corresponding_room_edge(packed_pos: u32) -> u32 {
+    corresponding_room_edge(Position::from_packed(packed_pos)).packed_repr()
+}
 
-    position
+/// Checks whether a position is on a room edge (x or y is 0 or 49).
+#[wasm_bindgen(js_name = clockwork_is_room_edge)]
+pub fn js_is_room_edge(packed_pos: u32) -> bool {
+    Position::from_packed(packed_pos).is_room_edge()
 }
 
-static PREFERRED_DIRECTIONS: [Direction; 8] = [
+/// All eight directions, orthogonal first.
+pub static PREFERRED_DIRECTIONS: [Direction; 8] = [
     Direction::Top,
     Direction::Right,
     Direction::Bottom,
@@ -115,6 +141,88 @@ lazy_static! {
     ];
 }
 
+/// Restrictions on how creeps may move between tiles, for traffic managers
+/// that are stricter than the game.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovementRules {
+    /// Whether diagonal moves are allowed at all.
+    pub allow_diagonals: bool,
+    /// Whether a diagonal move may squeeze past an impassable tile on either
+    /// side of it.
+    pub cut_corners: bool,
+    /// Extra cost for a move that crosses into another room, so paths don't
+    /// dip into neighboring rooms (and lose ticks to the room edges) to save
+    /// a tile or two.
+    pub room_change_cost: usize,
+}
+
+impl Default for MovementRules {
+    fn default() -> Self {
+        MovementRules {
+            allow_diagonals: true,
+            cut_corners: true,
+            room_change_cost: 0,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl MovementRules {
+    /// Creates movement rules (JavaScript constructor). `allow_diagonals`
+    /// permits diagonal moves, `cut_corners` lets a diagonal move pass an
+    /// impassable tile on either side (as the game does), and
+    /// `room_change_cost` (default 0) is added to every move into another
+    /// room.
+    #[wasm_bindgen(constructor)]
+    pub fn js_new(allow_diagonals: bool, cut_corners: bool, room_change_cost: Option<usize>) -> Self {
+        MovementRules {
+            allow_diagonals,
+            cut_corners,
+            room_change_cost: room_change_cost.unwrap_or(0),
+        }
+    }
+}
+
+impl MovementRules {
+    /// Whether every move the game allows is allowed, in which case searches
+    /// can prune directions as usual.
+    pub fn is_unrestricted(&self) -> bool {
+        self.allow_diagonals && self.cut_corners
+    }
+
+    /// The extra cost of moving from `from` to `to`, on top of the cost
+    /// matrix cost of `to`.
+    pub fn step_cost(&self, from: Position, to: Position) -> usize {
+        if from.room_name() == to.room_name() {
+            0
+        } else {
+            self.room_change_cost
+        }
+    }
+
+    /// Whether the move from `position` in `direction` is allowed. The tiles
+    /// beside a diagonal move are only checked with `is_passable` when corners
+    /// can't be cut.
+    pub fn allows(
+        &self,
+        position: Position,
+        direction: Direction,
+        mut is_passable: impl FnMut(Position) -> bool,
+    ) -> bool {
+        if !direction.is_diagonal() || self.is_unrestricted() {
+            return true;
+        }
+        if !self.allow_diagonals {
+            return false;
+        }
+        let (dx, dy): (i32, i32) = direction.into();
+        [(dx, 0), (0, dy)]
+            .iter()
+            .all(|offset| position.checked_add(*offset).is_ok_and(&mut is_passable))
+    }
+}
+
 /// Returns the next directions to consider, based on the direction from which the tile
 /// was entered. Lateral directions can be ruled out as an optimization.
 pub fn next_directions(open_direction: Option<Direction>) -> &'static [Direction] {
@@ -0,0 +1,192 @@
+use std::collections::{HashMap, VecDeque};
+
+use screeps::{RoomName, RoomXY};
+use wasm_bindgen::prelude::*;
+
+use super::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::{room_at_offset, world_room_from_packed};
+
+/// A connection between two adjacent rooms.
+#[derive(Debug, Clone, Copy)]
+pub struct RoomEdge {
+    pub from: RoomName,
+    pub to: RoomName,
+    /// The number of border tiles passable on both sides.
+    pub width: u32,
+    /// The lowest cost of entering `to` across the border, including the
+    /// room change cost.
+    pub cost: u32,
+}
+
+/// The room-level connectivity of an area: which rooms were explored, which
+/// are blocked (no cost matrix), and the passable borders between them.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct RoomGraph {
+    rooms: Vec<RoomName>,
+    blocked_rooms: Vec<RoomName>,
+    edges: Vec<RoomEdge>,
+}
+
+// Room offsets for each border, with a function mapping an index along the
+// border to the tile on this side and the tile on the other side.
+pub(super) type Border = ((i32, i32), fn(u8) -> (RoomXY, RoomXY));
+
+fn xy(x: u8, y: u8) -> RoomXY {
+    // SAFETY: only called with coordinates in the range 0..50
+    unsafe { RoomXY::unchecked_new(x, y) }
+}
+
+pub(super) const BORDERS: [Border; 4] = [
+    ((0, -1), |i| (xy(i, 0), xy(i, 49))),
+    ((1, 0), |i| (xy(49, i), xy(0, i))),
+    ((0, 1), |i| (xy(i, 49), xy(i, 0))),
+    ((-1, 0), |i| (xy(0, i), xy(49, i))),
+];
+
+/// Explores rooms outward from `start_rooms`, loading at most `max_rooms`
+/// cost matrices, and records the passable borders between them. Corner
+/// tiles aren't exits, so they're ignored.
+pub fn room_graph(
+    start_rooms: &[RoomName],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_rooms: usize,
+    movement: MovementRules,
+) -> RoomGraph {
+    let mut graph = RoomGraph::default();
+    let mut cost_matrices: HashMap<RoomName, Option<ClockworkCostMatrix>> = HashMap::new();
+    // Loads a room's cost matrix, returning false if the room limit is hit
+    let load = |cost_matrices: &mut HashMap<_, _>, room_name: RoomName| -> bool {
+        if cost_matrices.contains_key(&room_name) {
+            return true;
+        }
+        if cost_matrices.len() >= max_rooms {
+            return false;
+        }
+        cost_matrices.insert(room_name, get_cost_matrix(room_name));
+        true
+    };
+
+    let mut queue: VecDeque<RoomName> = VecDeque::new();
+    for room_name in start_rooms {
+        if load(&mut cost_matrices, *room_name) {
+            queue.push_back(*room_name);
+        }
+    }
+    while let Some(room_name) = queue.pop_front() {
+        if graph.rooms.contains(&room_name) || graph.blocked_rooms.contains(&room_name) {
+            continue;
+        }
+        if cost_matrices[&room_name].is_none() {
+            graph.blocked_rooms.push(room_name);
+            continue;
+        }
+        graph.rooms.push(room_name);
+
+        for (offset, border) in BORDERS.iter() {
+            let Some(neighbor) = room_at_offset(room_name, *offset) else {
+                continue;
+            };
+            if !load(&mut cost_matrices, neighbor) {
+                continue;
+            }
+            queue.push_back(neighbor);
+            let (Some(here), Some(there)) = (&cost_matrices[&room_name], &cost_matrices[&neighbor])
+            else {
+                continue;
+            };
+            let mut width = 0;
+            let mut cost = u8::MAX;
+            for i in 1..49 {
+                let (exit, entry) = border(i);
+                if here.get(exit) == 255 || there.get(entry) == 255 {
+                    continue;
+                }
+                width += 1;
+                cost = cost.min(there.get(entry));
+            }
+            if width > 0 {
+                graph.edges.push(RoomEdge {
+                    from: room_name,
+                    to: neighbor,
+                    width,
+                    cost: (cost as u32).saturating_add(movement.room_change_cost as u32),
+                });
+            }
+        }
+    }
+    graph
+}
+
+#[wasm_bindgen]
+impl RoomGraph {
+    /// The packed names of the rooms explored.
+    #[wasm_bindgen(getter)]
+    pub fn rooms(&self) -> Vec<u16> {
+        self.rooms.iter().map(|room| room.packed_repr()).collect()
+    }
+
+    /// The packed names of the rooms with no cost matrix.
+    #[wasm_bindgen(getter)]
+    pub fn blocked_rooms(&self) -> Vec<u16> {
+        self.blocked_rooms
+            .iter()
+            .map(|room| room.packed_repr())
+            .collect()
+    }
+
+    /// The edges, packed as [from, to, width, cost] quadruples. Each
+    /// connection appears once in each direction.
+    #[wasm_bindgen(getter)]
+    pub fn edges(&self) -> Vec<u32> {
+        self.edges
+            .iter()
+            .flat_map(|edge| {
+                [
+                    edge.from.packed_repr() as u32,
+                    edge.to.packed_repr() as u32,
+                    edge.width,
+                    edge.cost,
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Exports the room-level graph around the given rooms, for external
+/// analysis or visualization.
+///
+/// # Arguments
+/// * `start_rooms` - The packed names of the rooms to explore from
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_rooms` - Maximum number of rooms to load
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional movement rules; the room change cost is added to
+///   each edge's cost
+///
+/// # Returns
+/// The rooms, blocked rooms, and edges between them
+#[wasm_bindgen(js_name = clockwork_export_room_graph)]
+pub fn js_export_room_graph(
+    start_rooms: Vec<u16>,
+    get_cost_matrix: &js_sys::Function,
+    max_rooms: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> RoomGraph {
+    let start_rooms: Vec<RoomName> = start_rooms
+        .into_iter()
+        .map(world_room_from_packed)
+        .collect();
+    room_graph(
+        &start_rooms,
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_rooms,
+        movement.unwrap_or_default(),
+    )
+}
@@ -0,0 +1,86 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+
+use screeps::{RoomName, Terrain};
+use wasm_bindgen::prelude::*;
+
+use super::room_graph::BORDERS;
+use crate::helpers::rooms::{room_at_offset, world_room_from_packed};
+use crate::helpers::terrain::get_packed_terrain;
+
+/// Walks outward from `known_rooms` through the exits between rooms, up to
+/// `max_depth` rooms away, and returns the unexplored rooms found. Rooms are
+/// ordered by route distance from the nearest known room, then by how wide
+/// the exit they were reached through is. Rooms that don't exist or can't be
+/// entered (no walkable tiles on both sides of the border) are skipped.
+pub fn scout_frontier(known_rooms: &[RoomName], max_depth: usize) -> Vec<RoomName> {
+    let mut depths: HashMap<RoomName, usize> = HashMap::new();
+    let mut queue: VecDeque<RoomName> = VecDeque::new();
+    for room_name in known_rooms {
+        if depths.insert(*room_name, 0).is_none() {
+            queue.push_back(*room_name);
+        }
+    }
+
+    let mut frontier: Vec<(RoomName, usize, u32)> = Vec::new();
+    while let Some(room_name) = queue.pop_front() {
+        let depth = depths[&room_name];
+        if depth >= max_depth {
+            continue;
+        }
+        let Some(terrain) = get_packed_terrain(room_name) else {
+            continue;
+        };
+        for (offset, border) in BORDERS.iter() {
+            let Some(neighbor) = room_at_offset(room_name, *offset) else {
+                continue;
+            };
+            if depths.contains_key(&neighbor) {
+                continue;
+            }
+            let Some(neighbor_terrain) = get_packed_terrain(neighbor) else {
+                continue;
+            };
+            let width = (1..49)
+                .map(border)
+                .filter(|(exit, entry)| {
+                    terrain.get(*exit) != Terrain::Wall
+                        && neighbor_terrain.get(*entry) != Terrain::Wall
+                })
+                .count() as u32;
+            if width == 0 {
+                continue;
+            }
+            depths.insert(neighbor, depth + 1);
+            queue.push_back(neighbor);
+            frontier.push((neighbor, depth + 1, width));
+        }
+    }
+
+    frontier.sort_by_key(|(_, depth, width)| (*depth, Reverse(*width)));
+    frontier
+        .into_iter()
+        .map(|(room_name, _, _)| room_name)
+        .collect()
+}
+
+/// Lists the unexplored rooms near the known rooms, nearest first, as a
+/// priority queue for scouts.
+///
+/// # Arguments
+/// * `known_rooms` - The packed names of the rooms already explored
+/// * `max_depth` - The most rooms away from a known room to look
+///
+/// # Returns
+/// The packed names of the unexplored rooms, in priority order
+#[wasm_bindgen(js_name = clockwork_scout_frontier)]
+pub fn js_scout_frontier(known_rooms: Vec<u16>, max_depth: usize) -> Vec<u16> {
+    let known_rooms: Vec<RoomName> = known_rooms
+        .into_iter()
+        .map(world_room_from_packed)
+        .collect();
+    scout_frontier(&known_rooms, max_depth)
+        .into_iter()
+        .map(|room_name| room_name.packed_repr())
+        .collect()
+}
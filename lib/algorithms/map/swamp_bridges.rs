@@ -0,0 +1,145 @@
+use screeps::{Position, RoomName, RoomXY, Terrain};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use crate::datatypes::PackedTerrain;
+use crate::helpers::rooms::world_room_from_packed;
+use crate::helpers::terrain::get_packed_terrain;
+
+/// Narrow plain-tile corridors through swamp found in a room: candidate road
+/// bridges and chokepoints.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct SwampBridges {
+    corridors: Vec<Vec<Position>>,
+}
+
+fn xy(x: i32, y: i32) -> Option<RoomXY> {
+    if (0..50).contains(&x) && (0..50).contains(&y) {
+        // SAFETY: x and y are both in the range 0..50
+        Some(unsafe { RoomXY::unchecked_new(x as u8, y as u8) })
+    } else {
+        None
+    }
+}
+
+/// Whether the run of plain tiles through `(x, y)` along `(dx, dy)` is at
+/// most `max_width` long, with swamp on at least one end. Room edges count
+/// as walls.
+fn is_narrow(terrain: &PackedTerrain, x: i32, y: i32, (dx, dy): (i32, i32), max_width: u8) -> bool {
+    let mut width = 1;
+    let mut swamp = false;
+    for sign in [-1, 1].iter() {
+        let mut step = 1;
+        loop {
+            match xy(x + dx * step * sign, y + dy * step * sign).map(|xy| terrain.get(xy)) {
+                Some(Terrain::Plain) => width += 1,
+                Some(Terrain::Swamp) => {
+                    swamp = true;
+                    break;
+                }
+                _ => break,
+            }
+            step += 1;
+        }
+    }
+    swamp && width <= max_width as i32
+}
+
+/// Finds the plain-tile corridors at most `max_width` tiles wide (measured
+/// horizontally or vertically) that are bordered by swamp, keeping only the
+/// corridors (8-connected groups of such tiles) with at least `min_length`
+/// tiles. Narrow gaps between walls alone aren't counted.
+pub fn swamp_bridges(
+    room_name: RoomName,
+    terrain: &PackedTerrain,
+    max_width: u8,
+    min_length: usize,
+) -> SwampBridges {
+    let mut narrow = [[false; 50]; 50];
+    for y in 0..50 {
+        for x in 0..50 {
+            let Some(tile) = xy(x, y) else { continue };
+            if terrain.get(tile) != Terrain::Plain {
+                continue;
+            }
+            narrow[y as usize][x as usize] = is_narrow(terrain, x, y, (1, 0), max_width)
+                || is_narrow(terrain, x, y, (0, 1), max_width);
+        }
+    }
+
+    let mut bridges = SwampBridges::default();
+    let mut visited = [[false; 50]; 50];
+    for y in 0..50 {
+        for x in 0..50 {
+            if !narrow[y][x] || visited[y][x] {
+                continue;
+            }
+            visited[y][x] = true;
+            let mut corridor = Vec::new();
+            let mut stack = vec![(x as i32, y as i32)];
+            while let Some((cx, cy)) = stack.pop() {
+                let Some(tile) = xy(cx, cy) else { continue };
+                corridor.push(Position::new(tile.x, tile.y, room_name));
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let (nx, ny) = (cx + dx, cy + dy);
+                        if xy(nx, ny).is_none() {
+                            continue;
+                        }
+                        let (ux, uy) = (nx as usize, ny as usize);
+                        if narrow[uy][ux] && !visited[uy][ux] {
+                            visited[uy][ux] = true;
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+            if corridor.len() >= min_length {
+                bridges.corridors.push(corridor);
+            }
+        }
+    }
+    bridges
+}
+
+#[wasm_bindgen]
+impl SwampBridges {
+    /// The packed positions of every corridor's tiles, one corridor after
+    /// another.
+    #[wasm_bindgen(getter)]
+    pub fn tiles(&self) -> Vec<u32> {
+        self.corridors
+            .iter()
+            .flatten()
+            .map(|pos| pos.packed_repr())
+            .collect()
+    }
+
+    /// The number of tiles in each corridor, in the same order as `tiles`.
+    #[wasm_bindgen(getter)]
+    pub fn lengths(&self) -> Vec<u32> {
+        self.corridors
+            .iter()
+            .map(|corridor| corridor.len() as u32)
+            .collect()
+    }
+}
+
+/// Finds narrow plain-tile corridors through swamp in a room, for road
+/// planning and ambush placement.
+///
+/// # Arguments
+/// * `room_name` - The packed room name
+/// * `max_width` - The widest corridor to include
+/// * `min_length` - The fewest tiles a corridor can have
+///
+/// # Returns
+/// The corridors found
+#[wasm_bindgen(js_name = clockwork_swamp_bridges)]
+pub fn js_swamp_bridges(room_name: u16, max_width: u8, min_length: usize) -> SwampBridges {
+    let room_name = world_room_from_packed(room_name);
+    match get_packed_terrain(room_name) {
+        Some(terrain) => swamp_bridges(room_name, &terrain, max_width, min_length),
+        None => throw_str(&format!("Invalid room name: {}", room_name)),
+    }
+}
@@ -0,0 +1,44 @@
+use screeps::RoomName;
+use wasm_bindgen::prelude::*;
+
+use crate::helpers::cost_matrix::terrain_cost_matrix;
+use crate::helpers::known_rooms::last_known_cost_matrix;
+use crate::helpers::rooms::world_room_from_packed;
+
+// The number of tiles in a straight crossing of a room
+const CROSSING_TILES: f64 = 50.0;
+
+/// Estimates the cost of crossing a room: the average cost of its passable
+/// tiles, times the 50 tiles of a straight crossing. Uses the last cost
+/// matrix a search received for the room if it's no more than `max_age`
+/// ticks old, and the terrain (plains 1, swamps 5) otherwise. It's a rough
+/// room-level weight: paths that route around swamps cost less.
+///
+/// Returns `None` if the room's terrain isn't available or it has no
+/// passable tiles.
+pub fn room_traversal_cost(room_name: RoomName, max_age: u32) -> Option<f64> {
+    let cost_matrix = last_known_cost_matrix(room_name, max_age)
+        .or_else(|| terrain_cost_matrix(room_name, 1, 5, 255))?;
+    let (total, count) = cost_matrix
+        .get_internal()
+        .iter()
+        .filter(|(_, cost)| *cost < 255)
+        .fold((0u32, 0u32), |(total, count), (_, cost)| {
+            (total + cost as u32, count + 1)
+        });
+    (count > 0).then(|| total as f64 / count as f64 * CROSSING_TILES)
+}
+
+/// Estimates the cost of crossing a room, for weighing rooms in route
+/// planning callbacks.
+///
+/// # Arguments
+/// * `room_name` - The packed room name
+/// * `max_age` - The oldest (in ticks) a remembered cost matrix can be to be used (default 1500)
+///
+/// # Returns
+/// The estimated crossing cost, or undefined if the room's terrain isn't available
+#[wasm_bindgen(js_name = clockwork_room_traversal_cost)]
+pub fn js_room_traversal_cost(room_name: u16, max_age: Option<u32>) -> Option<f64> {
+    room_traversal_cost(world_room_from_packed(room_name), max_age.unwrap_or(1500))
+}
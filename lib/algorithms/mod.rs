@@ -1,4 +1,10 @@
+pub mod background;
+pub mod defense;
+pub mod disperse;
+pub mod flee;
 pub mod distance_map;
 pub mod flow_field;
+pub mod logistics;
 pub mod map;
 pub mod path;
+pub mod region;
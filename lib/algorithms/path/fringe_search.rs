@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use screeps::{Direction, Position, RoomName};
+use wasm_bindgen::prelude::*;
+
+use crate::algorithms::distance_map::goals::{any_goal_reached, unpack_goals, Goal};
+use crate::algorithms::distance_map::heuristics::base_heuristic_with_range;
+use crate::algorithms::map::{corresponding_room_edge, MovementRules, PREFERRED_DIRECTIONS};
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, DistanceMap, MonoFlowField, Path};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+use crate::utils::{set_panic_hook, PanicContext};
+
+/// A room the search has loaded: its cost matrix, and for each tile the best
+/// known cost and the direction it was entered from. Blocked rooms (no cost
+/// matrix) are never entered, so they get no slabs.
+struct FringeRoom {
+    cost_matrix: ClockworkCostMatrix,
+    costs: DistanceMap,
+    parents: MonoFlowField,
+}
+
+/// Finds a path from `start` to any of the goals with Fringe Search. Unlike
+/// A*, it keeps no priority queue: it re-scans its fringe with a rising
+/// f-limit, and each room it touches only needs a cost slab and a packed
+/// direction slab to trace the path back. That trades extra CPU for less
+/// memory, which suits rare, very long searches.
+///
+/// Returns `None` if no goal is reached within `max_ops` expansions or
+/// `max_rooms` rooms, or if the start room has no cost matrix.
+pub fn fringe_search(
+    start: Position,
+    goals: &[Goal],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_ops: usize,
+    max_rooms: usize,
+    movement: MovementRules,
+) -> Option<Path> {
+    set_panic_hook();
+    let _context = PanicContext::enter("fringe_search");
+    let heuristic = base_heuristic_with_range(goals);
+    let mut rooms: HashMap<RoomName, Option<FringeRoom>> = HashMap::new();
+    let load = |room_name: RoomName| {
+        get_cost_matrix(room_name).map(|cost_matrix| FringeRoom {
+            cost_matrix,
+            costs: DistanceMap::new(),
+            parents: MonoFlowField::new(),
+        })
+    };
+    // Fringe entries carry the cost they were queued with, so entries made
+    // stale by a cheaper route can be skipped without removing them.
+    let mut now: Vec<(Position, usize)> = vec![(start, 0)];
+    let mut later: Vec<(Position, usize)> = Vec::new();
+    let mut f_limit = heuristic(start);
+    let mut ops_remaining = max_ops;
+    let mut start_room = load(start.room_name())?;
+    start_room.costs[start.xy()] = 0;
+    rooms.insert(start.room_name(), Some(start_room));
+    // The best known cost of a visited tile, or usize::MAX
+    let cost_of = |rooms: &HashMap<RoomName, Option<FringeRoom>>, position: Position| {
+        rooms
+            .get(&position.room_name())
+            .and_then(Option::as_ref)
+            .map_or(usize::MAX, |room| room.costs[position.xy()])
+    };
+
+    loop {
+        let mut next_limit = usize::MAX;
+        while let Some((position, g_score)) = now.pop() {
+            if cost_of(&rooms, position) != g_score {
+                continue;
+            }
+            let f_score = g_score.saturating_add(heuristic(position));
+            if f_score > f_limit {
+                next_limit = next_limit.min(f_score);
+                later.push((position, g_score));
+                continue;
+            }
+            if any_goal_reached(goals, position) {
+                let mut path = Path::new();
+                let mut current = position;
+                path.add(current);
+                while let Some(direction) = rooms[&current.room_name()]
+                    .as_ref()
+                    .and_then(|room| room.parents.get(current.xy()))
+                {
+                    // Room crossings list both edge tiles, like paths traced
+                    // back through a distance map
+                    let stepped_on = corresponding_room_edge(current);
+                    if stepped_on != current {
+                        path.add(stepped_on);
+                    }
+                    current = parent(current, direction);
+                    path.add(current);
+                }
+                return Some(path);
+            }
+            if ops_remaining == 0 {
+                return None;
+            }
+            ops_remaining -= 1;
+            PanicContext::set_position(position);
+
+            for direction in PREFERRED_DIRECTIONS {
+                let allowed = movement.allows(position, direction, |tile| {
+                    rooms
+                        .get(&tile.room_name())
+                        .and_then(Option::as_ref)
+                        .is_some_and(|room| room.cost_matrix.get(tile.xy()) < 255)
+                });
+                if !allowed {
+                    continue;
+                }
+                let Ok(step) = position.checked_add_direction(direction) else {
+                    continue;
+                };
+                let neighbor = corresponding_room_edge(step);
+                let room_name = neighbor.room_name();
+                if !rooms.contains_key(&room_name) {
+                    if rooms.len() >= max_rooms {
+                        continue;
+                    }
+                    rooms.insert(room_name, load(room_name));
+                }
+                let Some(room) = rooms.get_mut(&room_name).and_then(Option::as_mut) else {
+                    continue;
+                };
+                let terrain_cost = room.cost_matrix.get(neighbor.xy());
+                if terrain_cost == 255 {
+                    continue;
+                }
+                let next_cost = g_score
+                    .saturating_add(terrain_cost as usize)
+                    .saturating_add(movement.step_cost(position, neighbor));
+                if room.costs[neighbor.xy()] <= next_cost {
+                    continue;
+                }
+                room.costs[neighbor.xy()] = next_cost;
+                room.parents.set(neighbor.xy(), Some(direction));
+                // Pushed onto `now`, so children are examined right after
+                // their parent, as in the original algorithm
+                now.push((neighbor, next_cost));
+            }
+        }
+
+        if later.is_empty() {
+            return None;
+        }
+        f_limit = next_limit;
+        // Keep the fringe in its original order for the next pass
+        later.reverse();
+        std::mem::swap(&mut now, &mut later);
+    }
+}
+
+/// The tile that `position` was entered from, moving in `direction`. Moves
+/// onto a room edge land on the matching edge of the next room, so the step
+/// back starts from the edge tile that was actually stepped on.
+fn parent(position: Position, direction: Direction) -> Position {
+    corresponding_room_edge(position)
+        .checked_add_direction(-direction)
+        .unwrap()
+}
+
+/// Finds a path with Fringe Search, a low-memory alternative to A*.
+///
+/// # Arguments
+/// * `start_packed` - The packed start position
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_ops` - Maximum number of tiles to expand
+/// * `max_rooms` - Maximum number of rooms to explore
+/// * `destinations` - Array of packed [position, range, kind] triples; the search ends at the first reached
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+///
+/// # Returns
+/// The path from the start to the goal, or undefined if none was found
+#[wasm_bindgen(js_name = clockwork_fringe_search)]
+pub fn js_fringe_search(
+    start_packed: u32,
+    get_cost_matrix: &js_sys::Function,
+    max_ops: usize,
+    max_rooms: usize,
+    destinations: Vec<u32>,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> Option<Path> {
+    fringe_search(
+        world_position_from_packed(start_packed),
+        &unpack_goals(destinations),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_ops,
+        max_rooms,
+        movement.unwrap_or_default(),
+    )
+}
@@ -1,3 +1,8 @@
+pub mod fringe_search;
+pub mod patrol;
+pub mod repair_tour;
+pub mod room_astar;
 pub mod to_multiroom_distance_map_origin;
 pub mod to_multiroom_flow_field_origin;
 pub mod to_multiroom_mono_flow_field_origin;
+pub mod waypoints;
@@ -0,0 +1,202 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use screeps::{Position, RoomName};
+use wasm_bindgen::prelude::*;
+
+use super::waypoints::waypoint_path;
+use crate::algorithms::distance_map::closest::closest_of;
+use crate::algorithms::distance_map::dijkstra::dijkstra_multiroom_distance_map;
+use crate::algorithms::distance_map::goals::{Goal, GoalKind};
+use crate::algorithms::distance_map::OpenListLimit;
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, Path};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+
+/// A closed patrol route: the order to visit the points in, and the path
+/// through them and back to the first.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct PatrolLoop {
+    order: Vec<usize>,
+    path: Path,
+}
+
+#[wasm_bindgen]
+impl PatrolLoop {
+    /// The indices of the points, in the order they're visited. The loop
+    /// starts and ends at the first point.
+    #[wasm_bindgen(getter)]
+    pub fn order(&self) -> Vec<usize> {
+        self.order.clone()
+    }
+
+    /// The path from the first point through the others and back.
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> Path {
+        self.path.clone()
+    }
+}
+
+/// The path cost of the closed tour visiting `order`, by the pairwise costs.
+fn tour_cost(order: &[usize], costs: &[Vec<usize>]) -> usize {
+    order
+        .iter()
+        .zip(order.iter().cycle().skip(1))
+        .fold(0, |total, (from, to)| {
+            total.saturating_add(costs[*from][*to])
+        })
+}
+
+/// Builds a closed patrol loop through `points`, starting and ending at the
+/// first. The order is greedy: each step heads to the closest point not yet
+/// visited (by path cost, with a closest-of search). With `improve`, the
+/// order is then refined by 2-opt (reversing stretches of the tour while
+/// that makes it cheaper), which takes a search from every point.
+///
+/// Returns `None` if a point can't be reached.
+#[allow(clippy::too_many_arguments)]
+pub fn patrol_loop(
+    points: &[Position],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_rooms: usize,
+    max_ops: usize,
+    improve: bool,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+) -> Option<PatrolLoop> {
+    let first = *points.first()?;
+    let cost_matrices: RefCell<HashMap<RoomName, Option<ClockworkCostMatrix>>> =
+        RefCell::new(HashMap::new());
+    let cached_cost_matrix = |room_name: RoomName| {
+        cost_matrices
+            .borrow_mut()
+            .entry(room_name)
+            .or_insert_with(|| get_cost_matrix(room_name))
+            .clone()
+    };
+
+    let mut order = vec![0];
+    let mut remaining: Vec<usize> = (1..points.len()).collect();
+    while !remaining.is_empty() {
+        let candidates: Vec<Position> = remaining.iter().map(|i| points[*i]).collect();
+        let next = closest_of(
+            points[*order.last()?],
+            &candidates,
+            0,
+            cached_cost_matrix,
+            max_ops,
+            max_rooms,
+            usize::MAX,
+            movement,
+            open_limit,
+        )?;
+        order.push(remaining.remove(next));
+    }
+
+    if improve && order.len() > 3 {
+        let costs: Vec<Vec<usize>> = points
+            .iter()
+            .map(|from| {
+                let others: Vec<Goal> = points
+                    .iter()
+                    .map(|to| Goal::new(*to, 0, GoalKind::Tile))
+                    .collect();
+                let distance_map = dijkstra_multiroom_distance_map(
+                    vec![*from],
+                    cached_cost_matrix,
+                    max_ops,
+                    max_rooms,
+                    usize::MAX,
+                    None,
+                    Some(others),
+                    false,
+                    movement,
+                    open_limit,
+                )
+                .distance_map();
+                points.iter().map(|to| distance_map.get(*to)).collect()
+            })
+            .collect();
+        let mut best = tour_cost(&order, &costs);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 1..order.len() - 1 {
+                for j in i + 1..order.len() {
+                    let mut candidate = order.clone();
+                    candidate[i..=j].reverse();
+                    let cost = tour_cost(&candidate, &costs);
+                    if cost < best {
+                        best = cost;
+                        order = candidate;
+                        improved = true;
+                    }
+                }
+            }
+        }
+    }
+
+    let waypoints: Vec<Goal> = order
+        .iter()
+        .skip(1)
+        .chain(std::iter::once(&0))
+        .map(|i| Goal::new(points[*i], 0, GoalKind::Tile))
+        .collect();
+    let path = waypoint_path(
+        first,
+        &waypoints,
+        cached_cost_matrix,
+        max_rooms,
+        max_ops,
+        usize::MAX,
+        movement,
+        open_limit,
+    )?;
+    Some(PatrolLoop { order, path })
+}
+
+/// Builds a closed patrol loop through a set of points, e.g. for defenders
+/// and scouts patrolling remote rooms.
+///
+/// # Arguments
+/// * `points` - The packed positions to patrol; the loop starts and ends at the first
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_rooms` - Maximum number of rooms to explore per search
+/// * `max_ops` - Maximum number of tiles to expand per search
+/// * `improve` - Whether to refine the greedy order with 2-opt
+/// * `overlay` - Per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
+///
+/// # Returns
+/// The visiting order and the loop's path, or undefined if a point can't be reached
+#[wasm_bindgen(js_name = clockwork_patrol_loop)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_patrol_loop(
+    points: Vec<u32>,
+    get_cost_matrix: &js_sys::Function,
+    max_rooms: usize,
+    max_ops: usize,
+    improve: bool,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> Option<PatrolLoop> {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    let points: Vec<Position> = points.into_iter().map(world_position_from_packed).collect();
+    patrol_loop(
+        &points,
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_rooms,
+        max_ops,
+        improve,
+        movement,
+        open_limit,
+    )
+}
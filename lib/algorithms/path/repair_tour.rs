@@ -0,0 +1,142 @@
+use screeps::{Position, RoomName};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use super::to_multiroom_distance_map_origin::path_to_multiroom_distance_map_origin;
+use crate::algorithms::distance_map::dijkstra::dijkstra_multiroom_distance_map;
+use crate::algorithms::distance_map::goals::{Goal, GoalKind};
+use crate::algorithms::distance_map::OpenListLimit;
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, Path};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+
+/// A road and its current and maximum hits.
+#[derive(Copy, Clone, Debug)]
+pub struct RoadHits {
+    pub position: Position,
+    pub hits: u32,
+    pub hits_max: u32,
+}
+
+/// Plans a repair tour from `start` through the roads with less than
+/// `threshold` of their max hits, visiting the nearest remaining road each
+/// time (a greedy approximation of the shortest tour). Roads the tour passes
+/// over on the way count as visited. The tour ends when every damaged road has
+/// been visited, or when the next leg would take the total path cost over
+/// `budget`.
+pub fn plan_repair_tour(
+    start: Position,
+    roads: &[RoadHits],
+    threshold: f32,
+    budget: usize,
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_rooms: usize,
+    movement: MovementRules,
+) -> Path {
+    let mut remaining: Vec<Position> = roads
+        .iter()
+        .filter(|road| (road.hits as f32) < road.hits_max as f32 * threshold)
+        .map(|road| road.position)
+        .collect();
+    remaining.retain(|position| *position != start);
+
+    let mut legs = vec![vec![start]];
+    let mut current = start;
+    let mut spent = 0;
+    while !remaining.is_empty() && spent < budget {
+        let goals = remaining
+            .iter()
+            .map(|position| Goal::new(*position, 0, GoalKind::Tile))
+            .collect();
+        let result = dijkstra_multiroom_distance_map(
+            vec![current],
+            &get_cost_matrix,
+            usize::MAX,
+            max_rooms,
+            (budget - spent).saturating_add(1),
+            Some(goals),
+            None,
+            false,
+            movement,
+            OpenListLimit::default(),
+        );
+        let Some(target) = result
+            .found_targets()
+            .first()
+            .map(|packed| Position::from_packed(*packed))
+        else {
+            break;
+        };
+        let distance_map = result.distance_map();
+        let cost = distance_map.get(target);
+        if spent.saturating_add(cost) > budget {
+            break;
+        }
+        let Ok(leg) = path_to_multiroom_distance_map_origin(target, &distance_map) else {
+            break;
+        };
+        let leg: Vec<Position> = (0..leg.len()).filter_map(|i| leg.get(i).copied()).collect();
+        remaining.retain(|position| !leg.contains(position));
+        legs.push(leg);
+        spent += cost;
+        current = target;
+    }
+
+    Path::stitch(&legs).unwrap_or_else(|_| Path::from(vec![start]))
+}
+
+/// Plans a repair tour for a repairer creep through the damaged roads.
+///
+/// # Arguments
+/// * `start_packed` - The packed position the repairer starts from
+/// * `roads_packed` - The packed positions of the roads
+/// * `hits` - The current hits of each road
+/// * `hits_max` - The max hits of each road
+/// * `threshold` - The fraction of max hits below which a road needs repair
+/// * `budget` - The most path cost the tour may take
+/// * `max_rooms` - The most rooms each leg's search may explore
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `overlay` - Optional per-search cost adjustments
+/// * `movement` - Optional movement rules
+///
+/// # Returns
+/// The path visiting the damaged roads, in order
+#[wasm_bindgen(js_name = clockwork_plan_repair_tour)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_plan_repair_tour(
+    start_packed: u32,
+    roads_packed: Vec<u32>,
+    hits: Vec<u32>,
+    hits_max: Vec<u32>,
+    threshold: f32,
+    budget: usize,
+    max_rooms: usize,
+    get_cost_matrix: &js_sys::Function,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> Path {
+    if roads_packed.len() != hits.len() || roads_packed.len() != hits_max.len() {
+        throw_str("Roads, hits, and hitsMax must have the same length");
+    }
+    let roads: Vec<RoadHits> = roads_packed
+        .iter()
+        .zip(hits.iter().zip(hits_max.iter()))
+        .map(|(packed, (hits, hits_max))| RoadHits {
+            position: world_position_from_packed(*packed),
+            hits: *hits,
+            hits_max: *hits_max,
+        })
+        .collect();
+    plan_repair_tour(
+        world_position_from_packed(start_packed),
+        &roads,
+        threshold,
+        budget,
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_rooms,
+        movement.unwrap_or_default(),
+    )
+}
@@ -0,0 +1,166 @@
+use screeps::{linear_index_to_xy, xy_to_linear_index, Direction, Position, RoomXY, ROOM_AREA};
+use wasm_bindgen::prelude::*;
+
+use crate::algorithms::distance_map::goals::{unpack_goals, Goal};
+use crate::algorithms::map::{MovementRules, PREFERRED_DIRECTIONS};
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, DistanceMap, Path};
+use crate::helpers::rooms::world_position_from_packed;
+use crate::utils::{set_panic_hook, PanicContext};
+
+/// Finds a path that stays within the start's room, with an A* search built
+/// for the common case of a move inside one room. Everything lives in flat,
+/// room-sized arrays: the cost to each tile, the direction it was entered
+/// from, and a bucket queue of open tiles. No room data cache is needed, and
+/// the path is traced back from the stored directions.
+///
+/// Room edge tiles can end the path but aren't moved through, since a creep
+/// stepping onto one leaves the room. Returns `None` if no goal is reached
+/// within `max_ops` expansions or `max_path_cost`.
+pub fn room_astar_path(
+    start: Position,
+    goals: &[Goal],
+    cost_matrix: &ClockworkCostMatrix,
+    max_ops: usize,
+    max_path_cost: usize,
+    movement: MovementRules,
+) -> Option<Path> {
+    set_panic_hook();
+    let _context = PanicContext::enter("room_astar_path");
+    let room_name = start.room_name();
+    let at = |xy: RoomXY| Position::new(xy.x, xy.y, room_name);
+    let reached = |xy: RoomXY| goals.iter().any(|goal| goal.is_reached_by(at(xy)));
+
+    // Each tile of range costs at least the cheapest passable tile
+    let min_cost = cost_matrix
+        .get_internal()
+        .iter()
+        .map(|(_, cost)| cost)
+        .filter(|cost| *cost < 255)
+        .min()
+        .unwrap_or(1) as usize;
+    let heuristic = |xy: RoomXY| {
+        let position = at(xy);
+        goals
+            .iter()
+            .map(|goal| {
+                (position.get_range_to(goal.position) as usize)
+                    .saturating_sub(goal.effective_range())
+            })
+            .min()
+            .unwrap_or(0)
+            * min_cost
+    };
+
+    let mut g_scores = DistanceMap::new();
+    let mut entered_by: [Option<Direction>; ROOM_AREA] = [None; ROOM_AREA];
+    let mut open: Vec<Vec<u16>> = vec![Vec::new()];
+    let mut min_idx = 0;
+    let mut ops_remaining = max_ops;
+
+    let start_xy = start.xy();
+    g_scores[start_xy] = 0;
+    open[0].push(xy_to_linear_index(start_xy) as u16);
+
+    let trace = |end: RoomXY, entered_by: &[Option<Direction>; ROOM_AREA]| {
+        let mut path = Path::new();
+        let mut current = end;
+        path.add(at(current));
+        while let Some(direction) = entered_by[xy_to_linear_index(current)] {
+            current = current.checked_add_direction(-direction)?;
+            path.add(at(current));
+        }
+        Some(path)
+    };
+
+    while min_idx < open.len() {
+        while let Some(index) = open[min_idx].pop() {
+            let xy = linear_index_to_xy(index as usize);
+            let g_score = g_scores[xy];
+            if g_score.saturating_add(heuristic(xy)) < min_idx {
+                // Queued again with a lower cost since
+                continue;
+            }
+            if reached(xy) {
+                return trace(xy, &entered_by);
+            }
+            if ops_remaining == 0 {
+                return None;
+            }
+            ops_remaining -= 1;
+            if g_score >= max_path_cost || (xy != start_xy && xy.is_room_edge()) {
+                continue;
+            }
+            PanicContext::set_position(at(xy));
+
+            for direction in PREFERRED_DIRECTIONS {
+                if !movement.allows(at(xy), direction, |tile| {
+                    tile.room_name() == room_name && cost_matrix.get(tile.xy()) < 255
+                }) {
+                    continue;
+                }
+                let Some(neighbor) = xy.checked_add_direction(direction) else {
+                    continue;
+                };
+                let terrain_cost = cost_matrix.get(neighbor);
+                if terrain_cost == 255 {
+                    continue;
+                }
+                let next_cost = g_score.saturating_add(terrain_cost as usize);
+                if g_scores[neighbor] <= next_cost {
+                    continue;
+                }
+                g_scores[neighbor] = next_cost;
+                entered_by[xy_to_linear_index(neighbor)] = Some(direction);
+                let f_score = next_cost.saturating_add(heuristic(neighbor));
+                if f_score >= open.len() {
+                    open.resize(f_score + 1, Vec::new());
+                }
+                open[f_score].push(xy_to_linear_index(neighbor) as u16);
+                min_idx = min_idx.min(f_score);
+            }
+        }
+        min_idx += 1;
+    }
+    None
+}
+
+/// Finds a path within a single room, e.g. for creeps moving around their
+/// base. Faster than the multi-room searches for short, room-local moves.
+///
+/// # Arguments
+/// * `start_packed` - The packed start position
+/// * `destinations` - Packed [position, range, kind] triples; the search ends at the first reached
+/// * `cost_matrix` - The cost matrix for the start's room
+/// * `max_ops` - Maximum number of tiles to expand
+/// * `max_path_cost` - Maximum path cost
+/// * `overlay` - Optional per-search cost adjustments, applied to a copy of
+///   the cost matrix
+/// * `movement` - Optional restrictions on how creeps may move
+///
+/// # Returns
+/// The path from the start to the goal, or undefined if none was found
+#[wasm_bindgen(js_name = clockwork_room_astar_path)]
+pub fn js_room_astar_path(
+    start_packed: u32,
+    destinations: Vec<u32>,
+    cost_matrix: &ClockworkCostMatrix,
+    max_ops: usize,
+    max_path_cost: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+) -> Option<Path> {
+    let start = world_position_from_packed(start_packed);
+    let overlaid = overlay.map(|overlay| {
+        let mut overlaid = cost_matrix.clone();
+        overlay.apply(start.room_name(), &mut overlaid);
+        overlaid
+    });
+    room_astar_path(
+        start,
+        &unpack_goals(destinations),
+        overlaid.as_ref().unwrap_or(cost_matrix),
+        max_ops,
+        max_path_cost,
+        movement.unwrap_or_default(),
+    )
+}
@@ -2,9 +2,11 @@ use crate::algorithms::map::corresponding_room_edge;
 use crate::algorithms::map::neighbors_without_edges;
 use crate::datatypes::MultiroomDistanceMap;
 use crate::datatypes::Path;
+use crate::datatypes::PositionSet;
+use crate::helpers::positions::position_from_js;
+use crate::helpers::rooms::world_position_from_packed;
 use crate::log;
 use screeps::Position;
-use std::collections::HashSet;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::throw_str;
 
@@ -16,7 +18,7 @@ pub fn path_to_multiroom_distance_map_origin(
     distance_map: &MultiroomDistanceMap,
 ) -> Result<Path, &'static str> {
     let mut path = Path::new();
-    let mut visited = HashSet::new();
+    let mut visited = PositionSet::new();
     let mut current = start;
     let mut steps = 0;
 
@@ -44,7 +46,7 @@ pub fn path_to_multiroom_distance_map_origin(
 
         // If no valid next position is found, return an error
         if let Some(next) = next_pos {
-            if visited.contains(&next) {
+            if visited.contains(next) {
                 log(&format!("Cycle detected in distance map at {:?}", next));
                 log(&format!("Visited: {:?}", visited));
                 return Err("Cycle detected in distance map");
@@ -66,12 +68,27 @@ pub fn path_to_multiroom_distance_map_origin(
     Err("Path exceeded maximum length")
 }
 
-#[wasm_bindgen]
+#[wasm_bindgen(js_name = clockwork_path_to_multiroom_distance_map_origin)]
 pub fn js_path_to_multiroom_distance_map_origin(
     start: u32,
     distance_map: &MultiroomDistanceMap,
 ) -> Path {
-    match path_to_multiroom_distance_map_origin(Position::from_packed(start), distance_map) {
+    match path_to_multiroom_distance_map_origin(world_position_from_packed(start), distance_map) {
+        Ok(path) => path,
+        Err(e) => throw_str(&format!(
+            "Error calculating path to multiroom distance map origin: {}",
+            e
+        )),
+    }
+}
+
+/// Variant of `js_path_to_multiroom_distance_map_origin` that accepts a `{x, y, roomName}` object.
+#[wasm_bindgen(js_name = clockwork_path_to_multiroom_distance_map_origin_position)]
+pub fn js_path_to_multiroom_distance_map_origin_position(
+    start: JsValue,
+    distance_map: &MultiroomDistanceMap,
+) -> Path {
+    match path_to_multiroom_distance_map_origin(position_from_js(start), distance_map) {
         Ok(path) => path,
         Err(e) => throw_str(&format!(
             "Error calculating path to multiroom distance map origin: {}",
@@ -1,8 +1,8 @@
-use std::collections::HashSet;
-
+use crate::helpers::rooms::world_position_from_packed;
 use crate::{
     algorithms::map::corresponding_room_edge,
-    datatypes::{MultiroomMonoFlowField, Path},
+    datatypes::{MultiroomMonoFlowField, Path, PositionSet},
+    helpers::positions::position_from_js,
 };
 use screeps::Position;
 use wasm_bindgen::{prelude::*, throw_str, UnwrapThrowExt};
@@ -15,7 +15,7 @@ pub fn path_to_multiroom_mono_flow_field_origin(
     flow_field: &MultiroomMonoFlowField,
 ) -> Result<Path, &'static str> {
     let mut path = Path::new();
-    let mut visited = HashSet::new();
+    let mut visited = PositionSet::new();
     let mut current = start;
 
     let mut steps = 0;
@@ -34,7 +34,7 @@ pub fn path_to_multiroom_mono_flow_field_origin(
         let next_pos = current.checked_add_direction(direction).unwrap_throw();
 
         // Check if we've already visited this position
-        if visited.contains(&next_pos) {
+        if visited.contains(next_pos) {
             return Err("Cycle detected in flow field");
         }
 
@@ -50,12 +50,27 @@ pub fn path_to_multiroom_mono_flow_field_origin(
     Err("Path exceeded maximum length")
 }
 
-#[wasm_bindgen]
+#[wasm_bindgen(js_name = clockwork_path_to_multiroom_mono_flow_field_origin)]
 pub fn js_path_to_multiroom_mono_flow_field_origin(
     start: u32,
     flow_field: &MultiroomMonoFlowField,
 ) -> Path {
-    match path_to_multiroom_mono_flow_field_origin(Position::from_packed(start), flow_field) {
+    match path_to_multiroom_mono_flow_field_origin(world_position_from_packed(start), flow_field) {
+        Ok(path) => path,
+        Err(e) => throw_str(&format!(
+            "Error calculating path to multiroom mono flow field origin: {}",
+            e
+        )),
+    }
+}
+
+/// Variant of `js_path_to_multiroom_mono_flow_field_origin` that accepts a `{x, y, roomName}` object.
+#[wasm_bindgen(js_name = clockwork_path_to_multiroom_mono_flow_field_origin_position)]
+pub fn js_path_to_multiroom_mono_flow_field_origin_position(
+    start: JsValue,
+    flow_field: &MultiroomMonoFlowField,
+) -> Path {
+    match path_to_multiroom_mono_flow_field_origin(position_from_js(start), flow_field) {
         Ok(path) => path,
         Err(e) => throw_str(&format!(
             "Error calculating path to multiroom mono flow field origin: {}",
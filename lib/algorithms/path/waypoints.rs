@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use screeps::{Position, RoomName};
+use wasm_bindgen::prelude::*;
+
+use super::room_astar::room_astar_path;
+use super::to_multiroom_distance_map_origin::path_to_multiroom_distance_map_origin;
+use crate::algorithms::distance_map::astar::astar_multiroom_distance_map;
+use crate::algorithms::distance_map::goals::{unpack_goals, Goal, GoalKind, GoalSet};
+use crate::algorithms::distance_map::heuristics::base_heuristic_with_range;
+use crate::algorithms::distance_map::OpenListLimit;
+use crate::algorithms::map::MovementRules;
+use crate::datatypes::{ClockworkCostMatrix, CostOverlay, Path};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+
+/// Finds a single path from `origin` through each waypoint in order, with an
+/// A* search per leg. Each leg starts where the previous one reached its
+/// waypoint (which may be short of it, for waypoints with a range), and cost
+/// matrices are loaded once and shared by every leg. `max_rooms`, `max_ops`
+/// and `max_path_cost` apply to each leg separately.
+///
+/// Legs that start and end in the same room try the single-room search
+/// first, falling back to the multi-room one if it finds no path inside the
+/// room.
+///
+/// Returns `None` if any waypoint can't be reached.
+#[allow(clippy::too_many_arguments)]
+pub fn waypoint_path(
+    origin: Position,
+    waypoints: &[Goal],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    movement: MovementRules,
+    open_limit: OpenListLimit,
+) -> Option<Path> {
+    let cost_matrices: RefCell<HashMap<RoomName, Option<ClockworkCostMatrix>>> =
+        RefCell::new(HashMap::new());
+    let cached_cost_matrix = |room_name: RoomName| {
+        cost_matrices
+            .borrow_mut()
+            .entry(room_name)
+            .or_insert_with(|| get_cost_matrix(room_name))
+            .clone()
+    };
+
+    let mut legs = vec![vec![origin]];
+    let mut current = origin;
+    for waypoint in waypoints {
+        let room_local = waypoint.position.room_name() == current.room_name()
+            && matches!(waypoint.kind, GoalKind::Tile | GoalKind::Range);
+        if room_local {
+            let leg = cached_cost_matrix(current.room_name()).and_then(|cost_matrix| {
+                room_astar_path(
+                    current,
+                    std::slice::from_ref(waypoint),
+                    &cost_matrix,
+                    max_ops,
+                    max_path_cost,
+                    movement,
+                )
+            });
+            if let Some(leg) = leg {
+                let positions: Vec<Position> =
+                    (0..leg.len()).filter_map(|i| leg.get(i).copied()).collect();
+                current = *positions.last()?;
+                legs.push(positions);
+                continue;
+            }
+        }
+        let heuristic = base_heuristic_with_range(std::slice::from_ref(waypoint));
+        let result = astar_multiroom_distance_map(
+            vec![current],
+            cached_cost_matrix,
+            max_rooms,
+            max_ops,
+            max_path_cost,
+            heuristic,
+            Some(GoalSet::new(vec![*waypoint])),
+            None,
+            false,
+            movement,
+            open_limit,
+        );
+        let target = Position::from_packed(*result.found_targets().first()?);
+        let leg = path_to_multiroom_distance_map_origin(target, &result.distance_map()).ok()?;
+        legs.push((0..leg.len()).filter_map(|i| leg.get(i).copied()).collect());
+        current = target;
+    }
+
+    Path::stitch(&legs).ok()
+}
+
+/// Finds a single path through ordered waypoints, e.g. for patrol routes.
+///
+/// # Arguments
+/// * `origin_packed` - The packed start position
+/// * `waypoints` - Packed [position, range, kind] triples, in the order to visit them
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_rooms` - Maximum number of rooms to explore per leg
+/// * `max_ops` - Maximum number of tiles to expand per leg
+/// * `max_path_cost` - Maximum path cost per leg
+/// * `overlay` - Per-search cost adjustments
+/// * `movement` - Optional restrictions on how creeps may move
+/// * `open_limit` - Optional cap on the size of the open list
+///
+/// # Returns
+/// The path from the origin through every waypoint, or undefined if one can't be reached
+#[wasm_bindgen(js_name = clockwork_waypoint_path)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_waypoint_path(
+    origin_packed: u32,
+    waypoints: Vec<u32>,
+    get_cost_matrix: &js_sys::Function,
+    max_rooms: usize,
+    max_ops: usize,
+    max_path_cost: usize,
+    overlay: Option<CostOverlay>,
+    movement: Option<MovementRules>,
+    open_limit: Option<OpenListLimit>,
+) -> Option<Path> {
+    let movement = movement.unwrap_or_default();
+    let open_limit = open_limit.unwrap_or_default();
+    waypoint_path(
+        world_position_from_packed(origin_packed),
+        &unpack_goals(waypoints),
+        CostOverlay::wrap(
+            overlay.as_ref(),
+            packed_room_cost_matrix_callback(get_cost_matrix),
+        ),
+        max_rooms,
+        max_ops,
+        max_path_cost,
+        movement,
+        open_limit,
+    )
+}
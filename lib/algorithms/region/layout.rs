@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use screeps::{Position, RoomCoordinate, RoomName, RoomXY, Terrain};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::throw_str;
+
+use super::depth_map;
+use crate::algorithms::map::neighbors_without_edges;
+use crate::datatypes::{ClockworkCostMatrix, MultiroomDistanceMap};
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+use crate::helpers::terrain::get_packed_terrain;
+
+/// What a planned structure does to the tile it's on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlannedTile {
+    /// A structure creeps can't walk through, like a spawn or extension.
+    Blocking = 0,
+    Road = 1,
+    Container = 2,
+    Rampart = 3,
+}
+
+impl TryFrom<u32> for PlannedTile {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PlannedTile::Blocking),
+            1 => Ok(PlannedTile::Road),
+            2 => Ok(PlannedTile::Container),
+            3 => Ok(PlannedTile::Rampart),
+            _ => Err(format!("Invalid planned tile kind: {}", value)),
+        }
+    }
+}
+
+/// A problem with a planned layout.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LayoutViolation {
+    /// More structures are planned on a tile than can share it.
+    Collision = 0,
+    /// A structure other than a road is planned on a wall.
+    OnWall = 1,
+    /// A spawn has no walkable tile next to it to spawn creeps onto.
+    SpawnBlocked = 2,
+    /// A spawn can't reach any exit of the room.
+    SpawnCutOff = 3,
+    /// A source can't reach the storage.
+    SourceCutOff = 4,
+}
+
+/// The problems found with a planned layout, each with the tile it's at.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct LayoutCheck {
+    violations: Vec<u8>,
+    positions: Vec<u32>,
+}
+
+impl LayoutCheck {
+    fn push(&mut self, violation: LayoutViolation, position: Position) {
+        self.violations.push(violation as u8);
+        self.positions.push(position.packed_repr());
+    }
+}
+
+#[wasm_bindgen]
+impl LayoutCheck {
+    /// The kind of each problem, as `LayoutViolation` values.
+    #[wasm_bindgen(getter)]
+    pub fn violations(&self) -> Vec<u8> {
+        self.violations.clone()
+    }
+
+    /// The packed position of each problem.
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<u32> {
+        self.positions.clone()
+    }
+}
+
+/// Checks a planned layout for a room against its terrain and the current
+/// cost matrix, before any energy is spent building it:
+///
+/// - No tile holds more than one structure, except that ramparts go over
+///   anything and containers can sit on roads.
+/// - Nothing but roads is planned on a wall.
+/// - Every spawn has a free tile next to it and can reach an exit.
+/// - Every source can reach the storage, if one is planned.
+///
+/// Reachability is checked with blocking structures added to the cost
+/// matrix and roads making walls walkable.
+pub fn check_layout(
+    room_name: RoomName,
+    planned: &[(RoomXY, PlannedTile)],
+    spawns: &[RoomXY],
+    sources: &[RoomXY],
+    storage: Option<RoomXY>,
+    cost_matrix: &ClockworkCostMatrix,
+) -> LayoutCheck {
+    let at = |xy: RoomXY| Position::new(xy.x, xy.y, room_name);
+    let terrain = get_packed_terrain(room_name);
+    let is_wall = |xy: RoomXY| {
+        terrain
+            .as_ref()
+            .is_some_and(|terrain| terrain.get(xy) == Terrain::Wall)
+    };
+    let mut check = LayoutCheck::default();
+
+    let mut by_tile: HashMap<RoomXY, Vec<PlannedTile>> = HashMap::new();
+    for (xy, kind) in planned {
+        by_tile.entry(*xy).or_default().push(*kind);
+    }
+    let mut tiles: Vec<(&RoomXY, &Vec<PlannedTile>)> = by_tile.iter().collect();
+    tiles.sort_by_key(|(xy, _)| (xy.y, xy.x));
+
+    let mut layout_matrix = cost_matrix.clone();
+    for (xy, kinds) in tiles {
+        let count = |kind: PlannedTile| kinds.iter().filter(|k| **k == kind).count();
+        let (blocking, roads, containers, ramparts) = (
+            count(PlannedTile::Blocking),
+            count(PlannedTile::Road),
+            count(PlannedTile::Container),
+            count(PlannedTile::Rampart),
+        );
+        if blocking + containers > 1 || roads > 1 || ramparts > 1 || (blocking > 0 && roads > 0) {
+            check.push(LayoutViolation::Collision, at(*xy));
+        }
+        if is_wall(*xy) && blocking + containers + ramparts > 0 {
+            check.push(LayoutViolation::OnWall, at(*xy));
+        }
+        if blocking > 0 {
+            layout_matrix.set(*xy, 255);
+        } else if roads > 0 && layout_matrix.get(*xy) == 255 {
+            layout_matrix.set(*xy, 1);
+        }
+    }
+
+    let walkable_neighbors = |xy: RoomXY| -> Vec<Position> {
+        neighbors_without_edges(at(xy))
+            .filter(|neighbor| neighbor.room_name() == room_name)
+            .filter(|neighbor| layout_matrix.get(neighbor.xy()) < 255)
+            .collect()
+    };
+    let reachable_from = |seeds: Vec<Position>| -> MultiroomDistanceMap {
+        depth_map(
+            &seeds,
+            |room| (room == room_name).then(|| layout_matrix.clone()),
+            usize::MAX,
+            1,
+        )
+    };
+    let reaches = |map: &MultiroomDistanceMap, xy: RoomXY| {
+        walkable_neighbors(xy)
+            .iter()
+            .any(|neighbor| map.get(*neighbor) != usize::MAX)
+    };
+
+    if !spawns.is_empty() {
+        let exits: Vec<Position> = (0..50)
+            .flat_map(|i| [(0, i), (49, i), (i, 0), (i, 49)])
+            .map(|(x, y)| {
+                // SAFETY: x and y are both in the range 0..50
+                unsafe {
+                    RoomXY::new(
+                        RoomCoordinate::unchecked_new(x),
+                        RoomCoordinate::unchecked_new(y),
+                    )
+                }
+            })
+            .filter(|xy| layout_matrix.get(*xy) < 255)
+            .map(at)
+            .collect();
+        let from_exits = reachable_from(exits);
+        for spawn in spawns {
+            if walkable_neighbors(*spawn).is_empty() {
+                check.push(LayoutViolation::SpawnBlocked, at(*spawn));
+            } else if !reaches(&from_exits, *spawn) {
+                check.push(LayoutViolation::SpawnCutOff, at(*spawn));
+            }
+        }
+    }
+
+    if let Some(storage) = storage {
+        let from_storage = reachable_from(walkable_neighbors(storage));
+        for source in sources {
+            if !reaches(&from_storage, *source) {
+                check.push(LayoutViolation::SourceCutOff, at(*source));
+            }
+        }
+    }
+
+    check
+}
+
+/// Checks a planned room layout for structure collisions and for spawns or
+/// sources it would cut off.
+///
+/// # Arguments
+/// * `room_name` - The packed room name
+/// * `planned` - Packed [position, kind] pairs, where kind is 0 for blocking
+///   structures, 1 for roads, 2 for containers, and 3 for ramparts
+/// * `spawns` - Packed positions of the planned spawns
+/// * `sources` - Packed positions of the room's sources
+/// * `storage` - The packed position of the planned storage, if any
+/// * `cost_matrix` - The room's current cost matrix
+///
+/// # Returns
+/// The problems found, each with its position
+#[wasm_bindgen(js_name = clockwork_check_layout)]
+pub fn js_check_layout(
+    room_name: u16,
+    planned: Vec<u32>,
+    spawns: Vec<u32>,
+    sources: Vec<u32>,
+    storage: Option<u32>,
+    cost_matrix: &ClockworkCostMatrix,
+) -> LayoutCheck {
+    let room_name = world_room_from_packed(room_name);
+    let in_room = |packed: u32| {
+        let position = world_position_from_packed(packed);
+        if position.room_name() != room_name {
+            throw_str("All positions must be in the given room");
+        }
+        position.xy()
+    };
+    if !planned.len().is_multiple_of(2) {
+        throw_str("Planned structures must be packed as [position, kind] pairs");
+    }
+    let planned: Vec<(RoomXY, PlannedTile)> = planned
+        .chunks(2)
+        .map(|chunk| {
+            let kind = PlannedTile::try_from(chunk[1]).unwrap_or_else(|e| throw_str(&e));
+            (in_room(chunk[0]), kind)
+        })
+        .collect();
+    let spawns: Vec<RoomXY> = spawns.into_iter().map(in_room).collect();
+    let sources: Vec<RoomXY> = sources.into_iter().map(in_room).collect();
+    check_layout(
+        room_name,
+        &planned,
+        &spawns,
+        &sources,
+        storage.map(in_room),
+        cost_matrix,
+    )
+}
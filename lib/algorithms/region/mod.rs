@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+
+use screeps::{Position, RoomName, RoomXY};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::throw_str;
+
+use crate::algorithms::map::neighbors;
+use crate::datatypes::{ClockworkCostMatrix, DistanceMap, MultiroomDistanceMap, RoomDataCache};
+use crate::helpers::cost_matrix::packed_room_cost_matrix_callback;
+use crate::helpers::rooms::world_position_from_packed;
+use crate::utils::{set_panic_hook, PanicContext};
+
+pub mod layout;
+
+/// A region grown from a set of seed tiles, split into the tiles on its
+/// boundary and the tiles inside it.
+#[wasm_bindgen]
+pub struct Region {
+    room_name: RoomName,
+    interior: Vec<RoomXY>,
+    boundary: Vec<RoomXY>,
+}
+
+#[wasm_bindgen]
+impl Region {
+    /// Tiles inside the region (not adjacent to any walkable tile outside it),
+    /// as packed positions.
+    #[wasm_bindgen(getter)]
+    pub fn interior(&self) -> Vec<u32> {
+        self.interior
+            .iter()
+            .map(|xy| Position::new(xy.x, xy.y, self.room_name).packed_repr())
+            .collect()
+    }
+
+    /// Tiles on the edge of the region (adjacent to at least one walkable tile
+    /// outside it), as packed positions.
+    #[wasm_bindgen(getter)]
+    pub fn boundary(&self) -> Vec<u32> {
+        self.boundary
+            .iter()
+            .map(|xy| Position::new(xy.x, xy.y, self.room_name).packed_repr())
+            .collect()
+    }
+}
+
+/// Grows a region outward from the seed tiles, including every tile whose
+/// path cost from the nearest seed is at most `max_cost`. Tiles with a cost of
+/// 255 are impassable and never included.
+pub fn grow_region(
+    room_name: RoomName,
+    seeds: &[RoomXY],
+    max_cost: usize,
+    cost_matrix: &ClockworkCostMatrix,
+) -> Region {
+    set_panic_hook();
+    let _context = PanicContext::enter("grow_region");
+    let mut distance_map = DistanceMap::new();
+    // Bucket queue indexed by path cost, as in the A* implementation, grown
+    // as costs are pushed
+    let mut open: Vec<Vec<RoomXY>> = vec![Vec::new()];
+
+    for seed in seeds {
+        if cost_matrix.get(*seed) == 255 {
+            continue;
+        }
+        distance_map[*seed] = 0;
+        open[0].push(*seed);
+    }
+
+    let mut region = Vec::new();
+    let mut cost = 0;
+    while cost < open.len() {
+        while let Some(xy) = open[cost].pop() {
+            if distance_map[xy] < cost {
+                // Already settled with a lower cost
+                continue;
+            }
+            region.push(xy);
+            for neighbor in xy.neighbors() {
+                let terrain_cost = cost_matrix.get(neighbor);
+                if terrain_cost == 255 {
+                    continue;
+                }
+                let next_cost = cost + terrain_cost as usize;
+                if next_cost > max_cost || distance_map[neighbor] <= next_cost {
+                    continue;
+                }
+                distance_map[neighbor] = next_cost;
+                if open.len() <= next_cost {
+                    open.resize_with(next_cost + 1, Vec::new);
+                }
+                open[next_cost].push(neighbor);
+            }
+        }
+        cost += 1;
+    }
+
+    let (boundary, interior) = region.into_iter().partition(|xy: &RoomXY| {
+        xy.neighbors()
+            .into_iter()
+            .any(|neighbor| distance_map[neighbor] == usize::MAX && cost_matrix.get(neighbor) < 255)
+    });
+
+    Region {
+        room_name,
+        interior,
+        boundary,
+    }
+}
+
+/// Grows a region from the seed positions (which must all be in the same
+/// room) until the path cost exceeds `max_cost`, returning the boundary tiles
+/// separately from the interior.
+#[wasm_bindgen(js_name = clockwork_grow_region)]
+pub fn js_grow_region(
+    seeds_packed: Vec<u32>,
+    max_cost: usize,
+    cost_matrix: &ClockworkCostMatrix,
+) -> Region {
+    let seeds: Vec<Position> = seeds_packed
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    let room_name = match seeds.first() {
+        Some(seed) => seed.room_name(),
+        None => throw_str("At least one seed position is required"),
+    };
+    if seeds.iter().any(|seed| seed.room_name() != room_name) {
+        throw_str("All seed positions must be in the same room");
+    }
+    let seeds: Vec<RoomXY> = seeds.iter().map(|seed| seed.xy()).collect();
+    grow_region(room_name, &seeds, max_cost, cost_matrix)
+}
+
+/// Maps the walking distance in tiles from the nearest seed, out to
+/// `max_depth` tiles and across up to `max_rooms` rooms. Only passability
+/// matters: any tile under 255 costs one step, so swamps count the same as
+/// plains. That makes this much cheaper than a weighted flood fill when only
+/// reachability or tile distance matters. Tiles beyond `max_depth` are left
+/// at `usize::MAX`.
+pub fn depth_map(
+    seeds: &[Position],
+    get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix>,
+    max_depth: usize,
+    max_rooms: usize,
+) -> MultiroomDistanceMap {
+    set_panic_hook();
+    let _context = PanicContext::enter("depth_map");
+    let mut rooms = RoomDataCache::new(max_rooms, get_cost_matrix);
+    let mut frontier = VecDeque::new();
+
+    for seed in seeds {
+        let Some(room_key) = rooms.get_room_key(seed.room_name()) else {
+            continue;
+        };
+        if rooms[room_key].distance_map[seed.xy()] == 0 {
+            continue;
+        }
+        rooms[room_key].distance_map[seed.xy()] = 0;
+        frontier.push_back((*seed, 0));
+    }
+
+    while let Some((position, depth)) = frontier.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+        PanicContext::set_position(position);
+        for neighbor in neighbors(position) {
+            let Some(room_key) = rooms.get_room_key(neighbor.room_name()) else {
+                continue;
+            };
+            let room = &rooms[room_key];
+            let passable = room
+                .cost_matrix
+                .as_ref()
+                .is_some_and(|cost_matrix| cost_matrix.get(neighbor.xy()) < 255);
+            if !passable || room.distance_map[neighbor.xy()] <= depth + 1 {
+                continue;
+            }
+            rooms[room_key].distance_map[neighbor.xy()] = depth + 1;
+            frontier.push_back((neighbor, depth + 1));
+        }
+    }
+
+    rooms.into()
+}
+
+/// Maps the walking distance in tiles from the nearest seed, treating every
+/// passable tile alike.
+///
+/// # Arguments
+/// * `seeds_packed` - The packed seed positions
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+/// * `max_depth` - The farthest distance to map, in tiles
+/// * `max_rooms` - Maximum number of rooms to explore
+///
+/// # Returns
+/// A distance map of tile distances from the seeds
+#[wasm_bindgen(js_name = clockwork_depth_map)]
+pub fn js_depth_map(
+    seeds_packed: Vec<u32>,
+    get_cost_matrix: &js_sys::Function,
+    max_depth: usize,
+    max_rooms: usize,
+) -> MultiroomDistanceMap {
+    let seeds: Vec<Position> = seeds_packed
+        .iter()
+        .map(|pos| world_position_from_packed(*pos))
+        .collect();
+    depth_map(
+        &seeds,
+        packed_room_cost_matrix_callback(get_cost_matrix),
+        max_depth,
+        max_rooms,
+    )
+}
@@ -0,0 +1,58 @@
+use wasm_bindgen::prelude::*;
+
+/// The parts of a creep body that affect how fast it moves.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct BodySpec {
+    move_power: u32,
+    fatigue_parts: u32,
+    carry_parts: u32,
+    carry_load: u32,
+    carry_capacity: u32,
+}
+
+impl BodySpec {
+    /// The number of parts that generate fatigue when moving: every part
+    /// except MOVE, plus CARRY parts with something in them. Carry parts fill
+    /// up in order, so a partly loaded creep only weighs the parts in use.
+    pub fn weight(&self) -> u32 {
+        let loaded_carry = self
+            .carry_load
+            .div_ceil(self.carry_capacity.max(1))
+            .min(self.carry_parts);
+        self.fatigue_parts + loaded_carry
+    }
+
+    /// The fatigue removed each tick.
+    pub fn fatigue_regen(&self) -> u32 {
+        self.move_power * 2
+    }
+}
+
+#[wasm_bindgen]
+impl BodySpec {
+    /// Creates a body spec.
+    ///
+    /// * `move_power` - The number of MOVE parts, counting boosted parts by
+    ///   their multiplier (e.g. an XZHO2-boosted part counts as 4)
+    /// * `fatigue_parts` - The number of parts other than MOVE and CARRY
+    /// * `carry_parts` - The number of CARRY parts
+    /// * `carry_load` - The amount of resources carried
+    /// * `carry_capacity` - The capacity of each CARRY part (default 50)
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        move_power: u32,
+        fatigue_parts: u32,
+        carry_parts: u32,
+        carry_load: u32,
+        carry_capacity: Option<u32>,
+    ) -> BodySpec {
+        BodySpec {
+            move_power,
+            fatigue_parts,
+            carry_parts,
+            carry_load,
+            carry_capacity: carry_capacity.unwrap_or(50),
+        }
+    }
+}
@@ -11,6 +11,9 @@ use wasm_bindgen::prelude::*;
 #[derive(Clone, Debug)]
 pub struct ClockworkCostMatrix {
     internal: LocalCostMatrix,
+    // Bumped whenever the matrix is changed from JS, so copies cached from it
+    // can tell they're stale
+    revision: u32,
 }
 
 #[wasm_bindgen]
@@ -22,9 +25,11 @@ impl ClockworkCostMatrix {
         match default {
             Some(default) => ClockworkCostMatrix {
                 internal: LocalCostMatrix::new_with_value(default),
+                revision: 0,
             },
             None => ClockworkCostMatrix {
                 internal: LocalCostMatrix::new(),
+                revision: 0,
             },
         }
     }
@@ -47,6 +52,62 @@ impl ClockworkCostMatrix {
         let y = RoomCoordinate::new(y)
             .unwrap_or_else(|_| wasm_bindgen::throw_str(&format!("Invalid y coordinate: {}", y)));
         self.internal.set(RoomXY::new(x, y), value);
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    /// Compresses the matrix into a compact byte array, for persisting in
+    /// memory segments. Restore it with `ClockworkCostMatrix.decompress`.
+    #[wasm_bindgen(js_name = "compress")]
+    pub fn js_compress(&self) -> Vec<u8> {
+        self.compress()
+    }
+
+    /// Restores a matrix from a byte array created by `compress`.
+    #[wasm_bindgen(js_name = "decompress")]
+    pub fn js_decompress(data: Vec<u8>) -> ClockworkCostMatrix {
+        ClockworkCostMatrix::decompress(&data).unwrap_or_else(|e| wasm_bindgen::throw_str(&e))
+    }
+
+    /// Whether every tile has the same cost as in `other`. Useful to check
+    /// whether a regenerated matrix actually changed before invalidating
+    /// anything that was built from the old one.
+    #[wasm_bindgen(js_name = "equals")]
+    pub fn js_equals(&self, other: &ClockworkCostMatrix) -> bool {
+        self.equals(other)
+    }
+
+    /// The number of tiles whose cost differs from `other`.
+    #[wasm_bindgen(js_name = "diffCount")]
+    pub fn js_diff_count(&self, other: &ClockworkCostMatrix) -> usize {
+        self.diff_count(other)
+    }
+
+    /// A copy of the matrix flipped left to right, so x becomes 49 - x.
+    #[wasm_bindgen(js_name = "mirrorHorizontal")]
+    pub fn js_mirror_horizontal(&self) -> ClockworkCostMatrix {
+        self.mirror_horizontal()
+    }
+
+    /// A copy of the matrix flipped top to bottom, so y becomes 49 - y.
+    #[wasm_bindgen(js_name = "mirrorVertical")]
+    pub fn js_mirror_vertical(&self) -> ClockworkCostMatrix {
+        self.mirror_vertical()
+    }
+
+    /// A copy of the matrix rotated clockwise by 90, 180, or 270 degrees.
+    #[wasm_bindgen(js_name = "rotate")]
+    pub fn js_rotate(&self, degrees: u16) -> ClockworkCostMatrix {
+        if !degrees.is_multiple_of(90) {
+            wasm_bindgen::throw_str(&format!("Invalid rotation: {} degrees", degrees));
+        }
+        self.rotate((degrees / 90) as u8)
+    }
+
+    /// A copy of the matrix shifted by `dx`, `dy`. Tiles shifted past the
+    /// edge are dropped, and tiles shifted in from outside are set to `fill`.
+    #[wasm_bindgen(js_name = "translate")]
+    pub fn js_translate(&self, dx: i8, dy: i8, fill: u8) -> ClockworkCostMatrix {
+        self.translate(dx, dy, fill)
     }
 }
 
@@ -60,6 +121,80 @@ impl ClockworkCostMatrix {
     pub fn set(&mut self, xy: RoomXY, value: u8) {
         self.internal.set(xy, value);
     }
+
+    /// The cost of the cheapest passable (below 255) tile, or `None` if
+    /// every tile is impassable.
+    pub fn min_passable_cost(&self) -> Option<u8> {
+        self.internal
+            .iter()
+            .map(|(_, cost)| cost)
+            .filter(|cost| *cost < 255)
+            .min()
+    }
+
+    /// Whether every tile has the same cost as in `other`.
+    pub fn equals(&self, other: &ClockworkCostMatrix) -> bool {
+        self.internal == other.internal
+    }
+
+    /// The number of tiles whose cost differs from `other`.
+    pub fn diff_count(&self, other: &ClockworkCostMatrix) -> usize {
+        self.internal
+            .iter()
+            .zip(other.internal.iter())
+            .filter(|((_, a), (_, b))| a != b)
+            .count()
+    }
+
+    pub fn mirror_horizontal(&self) -> ClockworkCostMatrix {
+        self.transform(|x, y| Some((ROOM_MAX - x, y)), 0)
+    }
+
+    pub fn mirror_vertical(&self) -> ClockworkCostMatrix {
+        self.transform(|x, y| Some((x, ROOM_MAX - y)), 0)
+    }
+
+    /// Rotates clockwise by `quarter_turns` multiples of 90 degrees.
+    pub fn rotate(&self, quarter_turns: u8) -> ClockworkCostMatrix {
+        match quarter_turns % 4 {
+            0 => self.clone(),
+            1 => self.transform(|x, y| Some((y, ROOM_MAX - x)), 0),
+            2 => self.transform(|x, y| Some((ROOM_MAX - x, ROOM_MAX - y)), 0),
+            _ => self.transform(|x, y| Some((ROOM_MAX - y, x)), 0),
+        }
+    }
+
+    /// Shifts by `dx`, `dy`, setting tiles with no source to `fill`.
+    pub fn translate(&self, dx: i8, dy: i8, fill: u8) -> ClockworkCostMatrix {
+        self.transform(
+            |x, y| {
+                let x = u8::try_from(x as i16 - dx as i16).ok()?;
+                let y = u8::try_from(y as i16 - dy as i16).ok()?;
+                (x <= ROOM_MAX && y <= ROOM_MAX).then_some((x, y))
+            },
+            fill,
+        )
+    }
+
+    /// Builds a new matrix where each tile takes its cost from the tile
+    /// `source` maps it to, or `fill` if it maps to none (or to a tile
+    /// outside the room).
+    fn transform(
+        &self,
+        source: impl Fn(u8, u8) -> Option<(u8, u8)>,
+        fill: u8,
+    ) -> ClockworkCostMatrix {
+        let mut matrix = ClockworkCostMatrix::new(None);
+        for (xy, cost) in matrix.internal.iter_mut() {
+            *cost = match source(xy.x.u8(), xy.y.u8())
+                .and_then(|(x, y)| RoomXY::checked_new(x, y).ok())
+            {
+                Some(source) => self.internal.get(source),
+                None => fill,
+            };
+        }
+        matrix
+    }
 }
 
 impl ClockworkCostMatrix {
@@ -67,6 +202,145 @@ impl ClockworkCostMatrix {
     pub fn get_internal(&self) -> &LocalCostMatrix {
         &self.internal
     }
+
+    /// Compresses the matrix for long-term storage.
+    ///
+    /// The format is a version byte, a dictionary of the (up to 15) most
+    /// common costs, and then a series of runs. Each run starts with a token
+    /// byte: the high nibble is a dictionary index, or 15 for literal bytes,
+    /// and the low nibble is the run length minus one. A low nibble of 15
+    /// means the length is 16 plus a LEB128 varint that follows. Literal
+    /// runs are followed by their bytes.
+    ///
+    /// Typical matrices (terrain defaults with sparse structures) compress to
+    /// a few hundred bytes or less.
+    pub fn compress(&self) -> Vec<u8> {
+        let values: Vec<u8> = self.internal.iter().map(|(_, cost)| cost).collect();
+
+        let mut counts = [0usize; 256];
+        for value in &values {
+            counts[*value as usize] += 1;
+        }
+        let mut dictionary: Vec<u8> = (0..=255).filter(|v| counts[*v as usize] > 0).collect();
+        dictionary.sort_by_key(|v| std::cmp::Reverse(counts[*v as usize]));
+        dictionary.truncate(LITERAL_RUN as usize);
+
+        let mut output = vec![COMPRESSION_VERSION, dictionary.len() as u8];
+        output.extend_from_slice(&dictionary);
+
+        let mut literals: Vec<u8> = Vec::new();
+        let mut index = 0;
+        while index < values.len() {
+            let value = values[index];
+            let run = values[index..].iter().take_while(|v| **v == value).count();
+            match dictionary.iter().position(|v| *v == value) {
+                Some(entry) => {
+                    flush_literals(&mut output, &mut literals);
+                    write_run(&mut output, entry as u8, run);
+                }
+                None => literals.extend(std::iter::repeat_n(value, run)),
+            }
+            index += run;
+        }
+        flush_literals(&mut output, &mut literals);
+        output
+    }
+
+    /// Restores a matrix from the output of `compress`.
+    pub fn decompress(data: &[u8]) -> Result<ClockworkCostMatrix, String> {
+        let mut bytes = data.iter().copied();
+        match bytes.next() {
+            Some(COMPRESSION_VERSION) => {}
+            Some(version) => return Err(format!("Unsupported compression version: {}", version)),
+            None => return Err("Compressed cost matrix is empty".to_string()),
+        }
+        let dictionary_len = bytes.next().ok_or("Missing dictionary length")? as usize;
+        let dictionary: Vec<u8> = bytes.by_ref().take(dictionary_len).collect();
+        if dictionary.len() != dictionary_len {
+            return Err("Truncated dictionary".to_string());
+        }
+
+        let mut values = Vec::with_capacity(ROOM_AREA);
+        while let Some(token) = bytes.next() {
+            let entry = token >> 4;
+            let run = match token & 0x0f {
+                0x0f => 16 + read_varint(&mut bytes)?,
+                length => length as usize + 1,
+            };
+            if values.len() + run > ROOM_AREA {
+                return Err("Compressed cost matrix has too many tiles".to_string());
+            }
+            if entry == LITERAL_RUN {
+                let start = values.len();
+                values.extend(bytes.by_ref().take(run));
+                if values.len() - start != run {
+                    return Err("Truncated literal run".to_string());
+                }
+            } else {
+                let value = *dictionary
+                    .get(entry as usize)
+                    .ok_or_else(|| format!("Invalid dictionary index: {}", entry))?;
+                values.extend(std::iter::repeat_n(value, run));
+            }
+        }
+        if values.len() != ROOM_AREA {
+            return Err(format!(
+                "Compressed cost matrix has {} tiles, expected {}",
+                values.len(),
+                ROOM_AREA
+            ));
+        }
+
+        let mut matrix = ClockworkCostMatrix::new(None);
+        for ((_, cost), value) in matrix.internal.iter_mut().zip(values) {
+            *cost = value;
+        }
+        Ok(matrix)
+    }
+}
+
+const ROOM_AREA: usize = 2500;
+// The highest x or y coordinate in a room
+const ROOM_MAX: u8 = 49;
+const COMPRESSION_VERSION: u8 = 1;
+// Token high nibble marking a run of literal bytes rather than a dictionary entry
+const LITERAL_RUN: u8 = 0x0f;
+
+fn write_run(output: &mut Vec<u8>, entry: u8, run: usize) {
+    if run < 16 {
+        output.push(entry << 4 | (run - 1) as u8);
+    } else {
+        output.push(entry << 4 | 0x0f);
+        let mut remaining = run - 16;
+        loop {
+            let byte = (remaining & 0x7f) as u8;
+            remaining >>= 7;
+            if remaining == 0 {
+                output.push(byte);
+                break;
+            }
+            output.push(byte | 0x80);
+        }
+    }
+}
+
+fn flush_literals(output: &mut Vec<u8>, literals: &mut Vec<u8>) {
+    if !literals.is_empty() {
+        write_run(output, LITERAL_RUN, literals.len());
+        output.append(literals);
+    }
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Result<usize, String> {
+    let mut value = 0usize;
+    for shift in (0..).step_by(7).take(3) {
+        let byte = bytes.next().ok_or("Truncated run length")?;
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("Run length is too long".to_string())
 }
 
 #[wasm_bindgen(inline_js = "
@@ -84,6 +358,20 @@ extern "C" {
     fn clockworkcostmatrix_get_pointer(value: JsValue) -> u32;
 }
 
+impl ClockworkCostMatrix {
+    /// How many times a JS cost matrix has been changed with `set`, or `None`
+    /// if `value` isn't a live `ClockworkCostMatrix`.
+    pub fn revision_of(value: &JsValue) -> Option<u32> {
+        let ptr = clockworkcostmatrix_get_pointer(value.clone());
+        if ptr == 0 {
+            return None;
+        }
+        let me = unsafe { &*(ptr as *mut WasmRefCell<ClockworkCostMatrix>) };
+        let revision = me.borrow().revision;
+        Some(revision)
+    }
+}
+
 impl TryFrom<JsValue> for ClockworkCostMatrix {
     type Error = &'static str;
 
@@ -101,6 +389,9 @@ impl TryFrom<JsValue> for ClockworkCostMatrix {
 
 impl From<LocalCostMatrix> for ClockworkCostMatrix {
     fn from(value: LocalCostMatrix) -> Self {
-        ClockworkCostMatrix { internal: value }
+        ClockworkCostMatrix {
+            internal: value,
+            revision: 0,
+        }
     }
 }
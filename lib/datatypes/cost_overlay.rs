@@ -0,0 +1,317 @@
+use screeps::{Position, RoomName, RoomXY};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+use super::ClockworkCostMatrix;
+use crate::helpers::avoid_zones::apply_avoid_zones;
+use crate::helpers::cost_matrix::terrain_cost_matrix;
+use crate::helpers::known_rooms::{last_known_cost_matrix, remember_cost_matrix};
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+// The size of a bit-packed mask covering every tile in a room
+const PROTECTED_MASK_BYTES: usize = 2500_usize.div_ceil(8);
+
+/// The oldest (in ticks) a last known cost matrix can be for
+/// `UnknownRoomPolicy::LastKnown`, unless the overlay sets another age.
+pub const DEFAULT_UNKNOWN_ROOM_MAX_AGE: u32 = 1500;
+
+/// What a search does with rooms the cost matrix callback returns nothing for
+/// (usually rooms without vision).
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownRoomPolicy {
+    /// Don't path through the room.
+    #[default]
+    Blocked = 0,
+    /// Use the room's terrain, with the default plain, swamp, and wall costs.
+    Terrain = 1,
+    /// Use the last cost matrix a search with this policy received for the
+    /// room, if it's recent enough, and block the room otherwise.
+    LastKnown = 2,
+}
+
+/// Keeps a follower within `range` of its leader: passable tiles farther
+/// away cost `penalty` more (capped at 254), or are impassable if the
+/// penalty is 255.
+#[derive(Debug, Clone, Copy)]
+struct Formation {
+    leader: Position,
+    range: u32,
+    penalty: u8,
+}
+
+/// Per-search adjustments to the cost matrices returned by a search's cost
+/// matrix callback. The overlay is applied as each room is loaded, so the
+/// caller's matrices are never modified.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct CostOverlay {
+    preferred_tiles: HashMap<RoomName, Vec<RoomXY>>,
+    preferred_discount: u8,
+    preferred_floor: u8,
+    blocked_tiles: HashMap<RoomName, Vec<RoomXY>>,
+    room_multipliers: HashMap<RoomName, f32>,
+    protected_tiles: HashMap<RoomName, Vec<u8>>,
+    exposure_penalty: u8,
+    formation: Option<Formation>,
+    breachable_tiles: HashMap<RoomName, Vec<(RoomXY, u8)>>,
+    unknown_rooms: UnknownRoomPolicy,
+    unknown_room_max_age: u32,
+}
+
+impl CostOverlay {
+    /// Applies the overlay to a room's cost matrix.
+    pub fn apply(&self, room_name: RoomName, cost_matrix: &mut ClockworkCostMatrix) {
+        if let Some(multiplier) = self.room_multipliers.get(&room_name) {
+            let costs: Vec<(RoomXY, u8)> = cost_matrix.get_internal().iter().collect();
+            for (xy, cost) in costs {
+                if cost == 255 {
+                    continue;
+                }
+                // Keep tiles passable and at least 1, so the A* heuristic stays
+                // admissible
+                let scaled = (cost as f32 * multiplier).round().clamp(1.0, 254.0) as u8;
+                cost_matrix.set(xy, scaled);
+            }
+        }
+        if let Some(tiles) = self.preferred_tiles.get(&room_name) {
+            for xy in tiles {
+                let cost = cost_matrix.get(*xy);
+                if cost == 255 {
+                    continue;
+                }
+                let discounted = cost
+                    .saturating_sub(self.preferred_discount)
+                    .max(self.preferred_floor)
+                    .min(cost);
+                cost_matrix.set(*xy, discounted);
+            }
+        }
+        if let Some(mask) = self.protected_tiles.get(&room_name) {
+            let costs: Vec<(RoomXY, u8)> = cost_matrix.get_internal().iter().collect();
+            for (xy, cost) in costs {
+                let bit = xy.y.u8() as usize * 50 + xy.x.u8() as usize;
+                if cost == 255
+                    || mask
+                        .get(bit / 8)
+                        .is_some_and(|byte| byte & (1 << (bit % 8)) != 0)
+                {
+                    continue;
+                }
+                let exposed = match self.exposure_penalty {
+                    255 => 255,
+                    penalty => cost.saturating_add(penalty).min(254),
+                };
+                cost_matrix.set(xy, exposed);
+            }
+        }
+        if let Some(formation) = self.formation {
+            let costs: Vec<(RoomXY, u8)> = cost_matrix.get_internal().iter().collect();
+            for (xy, cost) in costs {
+                let position = Position::new(xy.x, xy.y, room_name);
+                if cost == 255 || formation.leader.get_range_to(position) <= formation.range {
+                    continue;
+                }
+                let straying = match formation.penalty {
+                    255 => 255,
+                    penalty => cost.saturating_add(penalty).min(254),
+                };
+                cost_matrix.set(xy, straying);
+            }
+        }
+        if let Some(tiles) = self.breachable_tiles.get(&room_name) {
+            for (xy, cost) in tiles {
+                cost_matrix.set(*xy, *cost);
+            }
+        }
+        if let Some(tiles) = self.blocked_tiles.get(&room_name) {
+            for xy in tiles {
+                cost_matrix.set(*xy, 255);
+            }
+        }
+    }
+
+    /// The cost matrix to use for a room the callback returned nothing for,
+    /// according to the unknown room policy.
+    fn unknown_room_cost_matrix(&self, room_name: RoomName) -> Option<ClockworkCostMatrix> {
+        match self.unknown_rooms {
+            UnknownRoomPolicy::Blocked => None,
+            UnknownRoomPolicy::Terrain => terrain_cost_matrix(room_name, 1, 5, 255),
+            UnknownRoomPolicy::LastKnown => {
+                last_known_cost_matrix(room_name, self.unknown_room_max_age)
+            }
+        }
+    }
+
+    /// The settings that would make the overlay misbehave, as (option,
+    /// problem) pairs named after the TypeScript options.
+    pub fn problems(&self) -> Vec<(&'static str, String)> {
+        let mut problems = Vec::new();
+        for (room_name, multiplier) in &self.room_multipliers {
+            if !multiplier.is_finite() || *multiplier <= 0.0 {
+                problems.push((
+                    "roomMultipliers",
+                    format!("The multiplier for {} must be above 0", room_name),
+                ));
+            }
+        }
+        if self.unknown_rooms == UnknownRoomPolicy::LastKnown && self.unknown_room_max_age == 0 {
+            problems.push((
+                "unknownRoomMaxAge",
+                "Must be at least 1 with UnknownRoomPolicy.LastKnown, or no matrix is recent enough"
+                    .to_string(),
+            ));
+        }
+        problems
+    }
+
+    /// Wraps a cost matrix callback so the registered avoidance zones and
+    /// the overlay (if any) are applied to every matrix it returns. Rooms the
+    /// callback returns nothing for are handled by the overlay's unknown room
+    /// policy, and are blocked without an overlay.
+    pub fn wrap<'a>(
+        overlay: Option<&'a CostOverlay>,
+        get_cost_matrix: impl Fn(RoomName) -> Option<ClockworkCostMatrix> + 'a,
+    ) -> impl Fn(RoomName) -> Option<ClockworkCostMatrix> + 'a {
+        move |room_name| {
+            let mut cost_matrix = match get_cost_matrix(room_name) {
+                Some(cost_matrix) => {
+                    if overlay.is_some_and(|overlay| {
+                        overlay.unknown_rooms == UnknownRoomPolicy::LastKnown
+                    }) {
+                        remember_cost_matrix(room_name, &cost_matrix);
+                    }
+                    cost_matrix
+                }
+                None => overlay?.unknown_room_cost_matrix(room_name)?,
+            };
+            apply_avoid_zones(room_name, &mut cost_matrix);
+            if let Some(overlay) = overlay {
+                overlay.apply(room_name, &mut cost_matrix);
+            }
+            Some(cost_matrix)
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl CostOverlay {
+    /// Creates an empty overlay (JavaScript constructor)
+    #[wasm_bindgen(constructor)]
+    pub fn js_new() -> Self {
+        Self::default()
+    }
+
+    /// Discounts the cost of the given tiles by `discount`, without going
+    /// below `floor` (which is at least 1, so the A* heuristic stays
+    /// admissible). Impassable tiles are left alone.
+    #[wasm_bindgen(js_name = setPreferredTiles)]
+    pub fn js_set_preferred_tiles(&mut self, packed_positions: Vec<u32>, discount: u8, floor: u8) {
+        self.preferred_tiles.clear();
+        for pos in packed_positions {
+            let pos = world_position_from_packed(pos);
+            self.preferred_tiles
+                .entry(pos.room_name())
+                .or_default()
+                .push(pos.xy());
+        }
+        self.preferred_discount = discount;
+        self.preferred_floor = floor.max(1);
+    }
+
+    /// Treats the given tiles as impassable for this search only (e.g. tiles
+    /// other creeps have reserved this tick).
+    #[wasm_bindgen(js_name = setBlockedTiles)]
+    pub fn js_set_blocked_tiles(&mut self, packed_positions: Vec<u32>) {
+        self.blocked_tiles.clear();
+        for pos in packed_positions {
+            let pos = world_position_from_packed(pos);
+            self.blocked_tiles
+                .entry(pos.room_name())
+                .or_default()
+                .push(pos.xy());
+        }
+    }
+
+    /// Scales the cost of every passable tile in the given rooms, e.g. 1.5
+    /// for neutral rooms and 3 for rooms next to hostiles, so long routes
+    /// prefer safe territory. Scaled costs are rounded and kept between 1 and
+    /// 254. Tile discounts and blocks are applied after scaling.
+    #[wasm_bindgen(js_name = setRoomMultipliers)]
+    pub fn js_set_room_multipliers(&mut self, room_names: Vec<u16>, multipliers: Vec<f32>) {
+        self.room_multipliers = room_names
+            .into_iter()
+            .map(world_room_from_packed)
+            .zip(multipliers)
+            .collect();
+    }
+
+    /// Keeps the search under cover (e.g. owned ramparts) in the given rooms.
+    /// `masks` holds a bit-packed mask of the protected tiles for each room,
+    /// one after another: bit `y * 50 + x` (least significant bit first) of
+    /// each 313-byte mask is set for protected tiles. Passable tiles outside
+    /// the mask cost `exposure_penalty` more (capped at 254), or are
+    /// impassable if the penalty is 255. Blocked tiles are applied after.
+    #[wasm_bindgen(js_name = setProtectedTiles)]
+    pub fn js_set_protected_tiles(
+        &mut self,
+        room_names: Vec<u16>,
+        masks: Vec<u8>,
+        exposure_penalty: u8,
+    ) {
+        self.protected_tiles = room_names
+            .into_iter()
+            .map(world_room_from_packed)
+            .zip(masks.chunks(PROTECTED_MASK_BYTES).map(<[u8]>::to_vec))
+            .collect();
+        self.exposure_penalty = exposure_penalty;
+    }
+
+    /// Keeps the search within `range` of a leader's position (measured
+    /// across room edges), for followers moving in formation. Passable tiles
+    /// farther away cost `penalty` more (capped at 254), or are impassable
+    /// if the penalty is 255. Blocked tiles are applied after.
+    #[wasm_bindgen(js_name = setFormation)]
+    pub fn js_set_formation(&mut self, leader_packed: u32, range: u32, penalty: u8) {
+        self.formation = Some(Formation {
+            leader: world_position_from_packed(leader_packed),
+            range,
+            penalty,
+        });
+    }
+
+    /// Makes destructible obstacles (e.g. hostile walls and ramparts)
+    /// passable for siege paths. Each tile costs the ticks it takes to break
+    /// through, `hits / dismantle_power` rounded up, times `cost_per_tick`,
+    /// kept between 1 and 254. This replaces the tile's cost after the other
+    /// adjustments; blocked tiles are applied after.
+    #[wasm_bindgen(js_name = setBreachableTiles)]
+    pub fn js_set_breachable_tiles(
+        &mut self,
+        packed_positions: Vec<u32>,
+        hits: Vec<u32>,
+        dismantle_power: u32,
+        cost_per_tick: f32,
+    ) {
+        self.breachable_tiles.clear();
+        for (pos, hits) in packed_positions.into_iter().zip(hits) {
+            let pos = world_position_from_packed(pos);
+            let ticks = hits.div_ceil(dismantle_power.max(1));
+            let cost = (ticks as f32 * cost_per_tick).round().clamp(1.0, 254.0) as u8;
+            self.breachable_tiles
+                .entry(pos.room_name())
+                .or_default()
+                .push((pos.xy(), cost));
+        }
+    }
+
+    /// Sets what the search does with rooms the cost matrix callback returns
+    /// nothing for. `max_age` is the oldest (in ticks) a last known cost
+    /// matrix can be for `UnknownRoomPolicy::LastKnown` (default
+    /// `DEFAULT_UNKNOWN_ROOM_MAX_AGE`).
+    #[wasm_bindgen(js_name = setUnknownRooms)]
+    pub fn js_set_unknown_rooms(&mut self, policy: UnknownRoomPolicy, max_age: Option<u32>) {
+        self.unknown_rooms = policy;
+        self.unknown_room_max_age = max_age.unwrap_or(DEFAULT_UNKNOWN_ROOM_MAX_AGE);
+    }
+}
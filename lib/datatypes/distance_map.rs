@@ -18,6 +18,12 @@ impl DistanceMap {
         DistanceMap([usize::MAX; ROOM_AREA])
     }
 
+    /// Creates a new distance map with all values set to `value`.
+    #[inline]
+    pub fn new_with_value(value: usize) -> DistanceMap {
+        DistanceMap([value; ROOM_AREA])
+    }
+
     /// Converts the distance map into a vector of distances.
     pub fn to_vec(&self) -> Vec<usize> {
         self.0.to_vec()
@@ -101,8 +107,46 @@ impl<'a> Iterator for DistanceMapEnumerate<'a> {
 }
 
 impl DistanceMap {
+    /// Replaces each value with the smaller of it and the corresponding value
+    /// in `other`.
+    pub fn min_with(&mut self, other: &DistanceMap) {
+        for (value, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *value = (*value).min(*other);
+        }
+    }
+
+    /// Adds `k` times each value in `other` to the corresponding value here,
+    /// rounding to the nearest integer and clamping at zero. Tiles that are
+    /// unreachable (`usize::MAX`) in either map stay unreachable.
+    pub fn add_scaled(&mut self, other: &DistanceMap, k: f64) {
+        for (value, other) in self.0.iter_mut().zip(other.0.iter()) {
+            if *value == usize::MAX || *other == usize::MAX {
+                *value = usize::MAX;
+                continue;
+            }
+            let sum = (*value as f64 + *other as f64 * k).round();
+            *value = if sum <= 0.0 {
+                0
+            } else if sum >= usize::MAX as f64 {
+                usize::MAX
+            } else {
+                sum as usize
+            };
+        }
+    }
+
+    /// Sets each value to `usize::MAX` where `pred` holds for the
+    /// corresponding value in `other`.
+    pub fn mask_where(&mut self, other: &DistanceMap, pred: impl Fn(usize) -> bool) {
+        for (value, other) in self.0.iter_mut().zip(other.0.iter()) {
+            if pred(*other) {
+                *value = usize::MAX;
+            }
+        }
+    }
+
     /// Returns an iterator that yields (RoomXY, &T) pairs
-    pub fn enumerate(&self) -> DistanceMapEnumerate {
+    pub fn enumerate(&self) -> DistanceMapEnumerate<'_> {
         DistanceMapEnumerate {
             tile_map: self,
             current_index: 0,
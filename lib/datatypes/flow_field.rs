@@ -1,4 +1,5 @@
 use screeps::{Direction, RoomCoordinate};
+use std::convert::TryInto;
 use wasm_bindgen::prelude::*;
 
 /// A flow field is a 50x50 grid (representing a room), representing viable directions
@@ -17,6 +18,19 @@ impl FlowField {
         FlowField { data: [0; 2500] }
     }
 
+    /// Create a flow field from its raw bitmasks, indexed by `y * 50 + x`.
+    /// Returns `None` unless there are exactly 2500 of them.
+    pub fn from_slice(data: &[u8]) -> Option<Self> {
+        Some(FlowField {
+            data: data.try_into().ok()?,
+        })
+    }
+
+    /// The raw bitmask of every tile, indexed by `y * 50 + x`.
+    pub fn data(&self) -> &[u8; 2500] {
+        &self.data
+    }
+
     /// Get the internal value for a given coordinate.
     pub fn get(&self, x: RoomCoordinate, y: RoomCoordinate) -> u8 {
         self.data[(y.u8() as usize) * 50 + (x.u8() as usize)]
@@ -61,6 +75,13 @@ impl FlowField {
 
 #[wasm_bindgen]
 impl FlowField {
+    /// Converts the flow field into a flat array of direction bitmasks,
+    /// indexed by `y * 50 + x`.
+    #[wasm_bindgen(js_name = toArray)]
+    pub fn to_array(&self) -> Vec<u8> {
+        self.data.to_vec()
+    }
+
     /// Get the internal value for a given coordinate.
     #[wasm_bindgen(js_name = get)]
     pub fn js_get(&self, x: u8, y: u8) -> u8 {
@@ -1,17 +1,28 @@
+mod body_spec;
 mod cost_matrix;
+mod cost_overlay;
 mod distance_map;
 mod flow_field;
 mod mono_flow_field;
 mod multiroom_distance_map;
 mod multiroom_flow_field;
 mod multiroom_mono_flow_field;
+mod packed_terrain;
 mod path;
+mod path_handle;
+mod position_set;
 mod room_data_cache;
+mod sharded_distance_map;
 
+pub use body_spec::BodySpec;
 pub use cost_matrix::ClockworkCostMatrix;
+pub use cost_overlay::{CostOverlay, UnknownRoomPolicy, DEFAULT_UNKNOWN_ROOM_MAX_AGE};
 pub use distance_map::DistanceMap;
+pub use mono_flow_field::MonoFlowField;
 pub use multiroom_distance_map::MultiroomDistanceMap;
 pub use multiroom_flow_field::MultiroomFlowField;
 pub use multiroom_mono_flow_field::MultiroomMonoFlowField;
+pub use packed_terrain::PackedTerrain;
 pub use path::Path;
+pub use position_set::PositionSet;
 pub use room_data_cache::RoomDataCache;
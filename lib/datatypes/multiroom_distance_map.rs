@@ -1,8 +1,79 @@
-use screeps::{Position, RoomName};
+use screeps::{Position, RoomName, ROOM_AREA};
 use std::collections::HashMap;
-use wasm_bindgen::prelude::*;
+use wasm_bindgen::{prelude::*, throw_str};
 
 use super::distance_map::DistanceMap;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+/// The most tiles `values_in_rect` will return: a 3x3 block of rooms.
+pub const MAX_RECT_AREA: usize = 9 * ROOM_AREA;
+
+/// Comparisons available to `MultiroomDistanceMap::mask_where` from JS.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskComparison {
+    LessThan = 0,
+    LessOrEqual = 1,
+    GreaterThan = 2,
+    GreaterOrEqual = 3,
+    Equal = 4,
+    NotEqual = 5,
+}
+
+impl MaskComparison {
+    fn test(self, value: usize, threshold: usize) -> bool {
+        match self {
+            MaskComparison::LessThan => value < threshold,
+            MaskComparison::LessOrEqual => value <= threshold,
+            MaskComparison::GreaterThan => value > threshold,
+            MaskComparison::GreaterOrEqual => value >= threshold,
+            MaskComparison::Equal => value == threshold,
+            MaskComparison::NotEqual => value != threshold,
+        }
+    }
+}
+
+/// Summary statistics over the reachable (not `usize::MAX`) tiles of a map.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct RoomStats {
+    /// Lowest value, or `usize::MAX` if no tiles are reachable
+    pub min: usize,
+    /// Highest value, or 0 if no tiles are reachable
+    pub max: usize,
+    /// Mean value, or 0 if no tiles are reachable
+    pub mean: f64,
+    /// Number of reachable tiles
+    pub count: usize,
+    /// Number of reachable tiles with a value below the threshold
+    pub count_below: usize,
+}
+
+impl RoomStats {
+    fn from_values(values: impl Iterator<Item = usize>, threshold: usize) -> Self {
+        let mut stats = RoomStats {
+            min: usize::MAX,
+            max: 0,
+            mean: 0.0,
+            count: 0,
+            count_below: 0,
+        };
+        let mut sum = 0.0;
+        for value in values.filter(|value| *value != usize::MAX) {
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+            stats.count += 1;
+            if value < threshold {
+                stats.count_below += 1;
+            }
+            sum += value as f64;
+        }
+        if stats.count > 0 {
+            stats.mean = sum / stats.count as f64;
+        }
+        stats
+    }
+}
 
 /// Maps distance values across multiple rooms, storing a DistanceMap for each room
 #[wasm_bindgen]
@@ -31,7 +102,7 @@ impl MultiroomDistanceMap {
     /// Sets the distance value at a given position
     pub fn set(&mut self, pos: Position, value: usize) {
         let room_name = pos.room_name();
-        let map = self.maps.entry(room_name).or_insert_with(DistanceMap::new);
+        let map = self.maps.entry(room_name).or_default();
         map[pos.xy()] = value;
     }
 
@@ -47,13 +118,180 @@ impl MultiroomDistanceMap {
 
     /// Gets a mutable reference to the DistanceMap for a given room, creating it if it doesn't exist
     pub fn get_or_create_room_map(&mut self, room_name: RoomName) -> &mut DistanceMap {
-        self.maps.entry(room_name).or_insert_with(DistanceMap::new)
+        self.maps.entry(room_name).or_default()
     }
 
     /// Gets the list of rooms in the map
     pub fn rooms(&self) -> Vec<RoomName> {
         self.maps.keys().cloned().collect()
     }
+
+    /// Iterates over every reachable (not `usize::MAX`) position and value,
+    /// optionally restricted to a set of rooms.
+    fn reachable<'a>(
+        &'a self,
+        rooms: Option<&'a [RoomName]>,
+    ) -> impl Iterator<Item = (Position, usize)> + 'a {
+        self.maps
+            .iter()
+            .filter(move |(room_name, _)| rooms.is_none_or(|rooms| rooms.contains(room_name)))
+            .flat_map(|(room_name, map)| {
+                map.enumerate()
+                    .filter(|(_, value)| **value != usize::MAX)
+                    .map(move |(xy, value)| (Position::new(xy.x, xy.y, *room_name), *value))
+            })
+    }
+
+    /// Gets the values in a rectangle given by two opposite corners, in
+    /// row-major order over world coordinates (so the rectangle can span
+    /// rooms). Only the rooms overlapping the rectangle are visited; tiles in
+    /// rooms without data are `usize::MAX`.
+    ///
+    /// Returns an error if the rectangle covers more than `MAX_RECT_AREA`
+    /// tiles, rather than allocating for it.
+    pub fn values_in_rect(
+        &self,
+        corner_1: Position,
+        corner_2: Position,
+    ) -> Result<Vec<usize>, String> {
+        let (min_x, max_x) = (
+            corner_1.world_x().min(corner_2.world_x()),
+            corner_1.world_x().max(corner_2.world_x()),
+        );
+        let (min_y, max_y) = (
+            corner_1.world_y().min(corner_2.world_y()),
+            corner_1.world_y().max(corner_2.world_y()),
+        );
+        let width = (max_x - min_x + 1) as usize;
+        let height = (max_y - min_y + 1) as usize;
+        if width * height > MAX_RECT_AREA {
+            return Err(format!(
+                "Rectangle of {}x{} tiles is larger than the {} tile limit",
+                width, height, MAX_RECT_AREA
+            ));
+        }
+        let mut values = vec![usize::MAX; width * height];
+
+        for room_y in min_y.div_euclid(50)..=max_y.div_euclid(50) {
+            for room_x in min_x.div_euclid(50)..=max_x.div_euclid(50) {
+                let room_name = Position::from_world_coords(room_x * 50, room_y * 50).room_name();
+                let Some(map) = self.maps.get(&room_name) else {
+                    continue;
+                };
+                for y in min_y.max(room_y * 50)..=max_y.min(room_y * 50 + 49) {
+                    for x in min_x.max(room_x * 50)..=max_x.min(room_x * 50 + 49) {
+                        let xy = Position::from_world_coords(x, y).xy();
+                        values[(y - min_y) as usize * width + (x - min_x) as usize] = map[xy];
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Finds the reachable position with the lowest value.
+    pub fn argmin(&self, rooms: Option<&[RoomName]>) -> Option<Position> {
+        self.reachable(rooms)
+            .min_by_key(|(_, value)| *value)
+            .map(|(pos, _)| pos)
+    }
+
+    /// Finds the reachable position with the highest value.
+    pub fn argmax(&self, rooms: Option<&[RoomName]>) -> Option<Position> {
+        self.reachable(rooms)
+            .max_by_key(|(_, value)| *value)
+            .map(|(pos, _)| pos)
+    }
+
+    /// Finds the `k` reachable positions with the lowest values (or highest,
+    /// if `largest` is set), ordered best first.
+    pub fn top_k(&self, k: usize, largest: bool, rooms: Option<&[RoomName]>) -> Vec<Position> {
+        let mut values: Vec<(Position, usize)> = self.reachable(rooms).collect();
+        if largest {
+            values.sort_unstable_by_key(|(_, value)| std::cmp::Reverse(*value));
+        } else {
+            values.sort_unstable_by_key(|(_, value)| *value);
+        }
+        values.into_iter().take(k).map(|(pos, _)| pos).collect()
+    }
+
+    /// Finds all reachable positions with a value below `threshold`.
+    pub fn positions_below(&self, threshold: usize, rooms: Option<&[RoomName]>) -> Vec<Position> {
+        self.reachable(rooms)
+            .filter(|(_, value)| *value < threshold)
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// Summarizes the reachable values in a room, or `None` if the map has
+    /// no data for the room.
+    pub fn room_stats(&self, room_name: RoomName, threshold: usize) -> Option<RoomStats> {
+        self.maps
+            .get(&room_name)
+            .map(|map| RoomStats::from_values(map.enumerate().map(|(_, value)| *value), threshold))
+    }
+
+    /// Summarizes the reachable values across all rooms.
+    pub fn summary(&self, threshold: usize) -> RoomStats {
+        RoomStats::from_values(
+            self.maps
+                .values()
+                .flat_map(|map| map.enumerate().map(|(_, value)| *value)),
+            threshold,
+        )
+    }
+
+    /// Counts a room's reachable values in buckets of `bucket_width`, from 0
+    /// up to the room's maximum value. Returns `None` if the map has no data
+    /// for the room.
+    pub fn histogram(&self, room_name: RoomName, bucket_width: usize) -> Option<Vec<u32>> {
+        let map = self.maps.get(&room_name)?;
+        let bucket_width = bucket_width.max(1);
+        let mut buckets: Vec<u32> = Vec::new();
+        for (_, value) in map.enumerate().filter(|(_, value)| **value != usize::MAX) {
+            let bucket = value / bucket_width;
+            if bucket >= buckets.len() {
+                buckets.resize(bucket + 1, 0);
+            }
+            buckets[bucket] += 1;
+        }
+        Some(buckets)
+    }
+
+    /// Replaces each value with the smaller of it and the corresponding value
+    /// in `other`. Rooms only present in `other` are copied over.
+    pub fn min_with(&mut self, other: &MultiroomDistanceMap) {
+        for (room_name, other_map) in other.maps.iter() {
+            match self.maps.get_mut(room_name) {
+                Some(map) => map.min_with(other_map),
+                None => {
+                    self.maps.insert(*room_name, other_map.clone());
+                }
+            }
+        }
+    }
+
+    /// Adds `k` times each value in `other` to the corresponding value here.
+    /// Tiles that are unreachable in either map (including rooms missing from
+    /// `other`) become unreachable.
+    pub fn add_scaled(&mut self, other: &MultiroomDistanceMap, k: f64) {
+        for (room_name, map) in self.maps.iter_mut() {
+            match other.maps.get(room_name) {
+                Some(other_map) => map.add_scaled(other_map, k),
+                None => *map = DistanceMap::new(),
+            }
+        }
+    }
+
+    /// Sets each value to `usize::MAX` where `pred` holds for the
+    /// corresponding value in `other`. Rooms missing from `other` are treated
+    /// as entirely `usize::MAX`.
+    pub fn mask_where(&mut self, other: &MultiroomDistanceMap, pred: impl Fn(usize) -> bool) {
+        let unreachable = DistanceMap::new();
+        for (room_name, map) in self.maps.iter_mut() {
+            map.mask_where(other.maps.get(room_name).unwrap_or(&unreachable), &pred);
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -67,14 +305,14 @@ impl MultiroomDistanceMap {
     /// Gets the distance value at a given position
     #[wasm_bindgen(js_name = get)]
     pub fn js_get(&self, packed_pos: u32) -> usize {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.get(pos)
     }
 
     /// Sets the distance value at a given position
     #[wasm_bindgen(js_name = set)]
     pub fn js_set(&mut self, packed_pos: u32, value: usize) {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.set(pos, value);
     }
 
@@ -87,9 +325,107 @@ impl MultiroomDistanceMap {
     /// Gets the DistanceMap for a given room
     #[wasm_bindgen(js_name = get_room)]
     pub fn js_get_room(&self, room_name: u16) -> Option<DistanceMap> {
-        let room_name = RoomName::from_packed(room_name);
+        let room_name = world_room_from_packed(room_name);
         self.maps.get(&room_name).cloned()
     }
+
+    /// Finds the reachable position with the lowest value, optionally only
+    /// searching the given rooms
+    #[wasm_bindgen(js_name = argmin)]
+    pub fn js_argmin(&self, rooms: Option<Vec<u16>>) -> Option<u32> {
+        let rooms = unpack_rooms(rooms);
+        self.argmin(rooms.as_deref()).map(|pos| pos.packed_repr())
+    }
+
+    /// Finds the reachable position with the highest value, optionally only
+    /// searching the given rooms
+    #[wasm_bindgen(js_name = argmax)]
+    pub fn js_argmax(&self, rooms: Option<Vec<u16>>) -> Option<u32> {
+        let rooms = unpack_rooms(rooms);
+        self.argmax(rooms.as_deref()).map(|pos| pos.packed_repr())
+    }
+
+    /// Finds the `k` reachable positions with the lowest (or highest) values,
+    /// best first
+    #[wasm_bindgen(js_name = top_k)]
+    pub fn js_top_k(&self, k: usize, largest: bool, rooms: Option<Vec<u16>>) -> Vec<u32> {
+        let rooms = unpack_rooms(rooms);
+        self.top_k(k, largest, rooms.as_deref())
+            .iter()
+            .map(|pos| pos.packed_repr())
+            .collect()
+    }
+
+    /// Gets the values in the rectangle between two corners (in either
+    /// order), row-major over world coordinates. Tiles in rooms without data
+    /// are `usize::MAX`. The rectangle's width is the horizontal world
+    /// distance between the corners plus one. Throws if the rectangle covers
+    /// more than `MAX_RECT_AREA` tiles.
+    #[wasm_bindgen(js_name = values_in_rect)]
+    pub fn js_values_in_rect(&self, corner_1: u32, corner_2: u32) -> Vec<usize> {
+        self.values_in_rect(
+            world_position_from_packed(corner_1),
+            world_position_from_packed(corner_2),
+        )
+        .unwrap_or_else(|e| throw_str(&e))
+    }
+
+    /// Finds all reachable positions with a value below `threshold`
+    #[wasm_bindgen(js_name = positions_below)]
+    pub fn js_positions_below(&self, threshold: usize, rooms: Option<Vec<u16>>) -> Vec<u32> {
+        let rooms = unpack_rooms(rooms);
+        self.positions_below(threshold, rooms.as_deref())
+            .iter()
+            .map(|pos| pos.packed_repr())
+            .collect()
+    }
+
+    /// Summarizes the reachable values in a room
+    #[wasm_bindgen(js_name = room_stats)]
+    pub fn js_room_stats(&self, room_name: u16, threshold: usize) -> Option<RoomStats> {
+        self.room_stats(world_room_from_packed(room_name), threshold)
+    }
+
+    /// Summarizes the reachable values across all rooms
+    #[wasm_bindgen(js_name = summary)]
+    pub fn js_summary(&self, threshold: usize) -> RoomStats {
+        self.summary(threshold)
+    }
+
+    /// Counts a room's reachable values in buckets of `bucket_width`
+    #[wasm_bindgen(js_name = histogram)]
+    pub fn js_histogram(&self, room_name: u16, bucket_width: usize) -> Option<Vec<u32>> {
+        self.histogram(world_room_from_packed(room_name), bucket_width)
+    }
+
+    /// Replaces each value with the smaller of it and the corresponding value
+    /// in `other`
+    #[wasm_bindgen(js_name = min_with)]
+    pub fn js_min_with(&mut self, other: &MultiroomDistanceMap) {
+        self.min_with(other);
+    }
+
+    /// Adds `k` times each value in `other` to the corresponding value here
+    #[wasm_bindgen(js_name = add_scaled)]
+    pub fn js_add_scaled(&mut self, other: &MultiroomDistanceMap, k: f64) {
+        self.add_scaled(other, k);
+    }
+
+    /// Sets each value to `usize::MAX` where the corresponding value in
+    /// `other` satisfies the comparison with `threshold`
+    #[wasm_bindgen(js_name = mask_where)]
+    pub fn js_mask_where(
+        &mut self,
+        other: &MultiroomDistanceMap,
+        comparison: MaskComparison,
+        threshold: usize,
+    ) {
+        self.mask_where(other, |value| comparison.test(value, threshold));
+    }
+}
+
+fn unpack_rooms(rooms: Option<Vec<u16>>) -> Option<Vec<RoomName>> {
+    rooms.map(|rooms| rooms.into_iter().map(world_room_from_packed).collect())
 }
 
 impl Default for MultiroomDistanceMap {
@@ -1,8 +1,9 @@
 use screeps::{Direction, Position, RoomName};
 use std::collections::HashMap;
-use wasm_bindgen::prelude::*;
+use wasm_bindgen::{prelude::*, throw_str};
 
 use super::flow_field::FlowField;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
 
 /// Maps flow field values across multiple rooms, storing a FlowField for each room
 #[wasm_bindgen]
@@ -54,7 +55,7 @@ impl MultiroomFlowField {
         self.maps
             .get(&pos.room_name())
             .map(|map| map.get_directions(pos.x(), pos.y()))
-            .unwrap_or(Vec::new())
+            .unwrap_or_default()
     }
 
     /// Sets the list of valid directions at a given position across rooms
@@ -83,14 +84,14 @@ impl MultiroomFlowField {
     /// Gets the flow field value at a given position
     #[wasm_bindgen(js_name = get)]
     pub fn js_get(&self, packed_pos: u32) -> u8 {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.get(pos)
     }
 
     /// Sets the flow field value at a given position
     #[wasm_bindgen(js_name = set)]
     pub fn js_set(&mut self, packed_pos: u32, value: u8) {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.set(pos, value);
     }
 
@@ -103,28 +104,46 @@ impl MultiroomFlowField {
     /// Gets the FlowField for a given room
     #[wasm_bindgen(js_name = getRoom)]
     pub fn js_get_room(&self, room_name: u16) -> Option<FlowField> {
-        let room_name = RoomName::from_packed(room_name);
+        let room_name = world_room_from_packed(room_name);
         self.maps.get(&room_name).cloned()
     }
 
+    /// Gets the direction bitmasks for a given room as a flat array indexed
+    /// by `y * 50 + x`, or undefined if the room isn't in the flow field
+    #[wasm_bindgen(js_name = getRoomData)]
+    pub fn js_get_room_data(&self, room_name: u16) -> Option<Vec<u8>> {
+        let room_name = world_room_from_packed(room_name);
+        self.maps.get(&room_name).map(|map| map.data().to_vec())
+    }
+
+    /// Replaces a room's direction bitmasks with a flat array of 2500 values
+    /// indexed by `y * 50 + x`, adding the room if it isn't already present
+    #[wasm_bindgen(js_name = setRoomData)]
+    pub fn js_set_room_data(&mut self, room_name: u16, data: Vec<u8>) {
+        let room_name = world_room_from_packed(room_name);
+        let map = FlowField::from_slice(&data)
+            .unwrap_or_else(|| throw_str("Room data must have 2500 values"));
+        self.maps.insert(room_name, map);
+    }
+
     /// Gets the list of valid directions at a given position (JavaScript)
     #[wasm_bindgen(js_name = getDirections)]
     pub fn js_get_directions(&self, packed_pos: u32) -> Vec<Direction> {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.get_directions(pos)
     }
 
     /// Sets the list of valid directions at a given position (JavaScript)
     #[wasm_bindgen(js_name = setDirections)]
     pub fn js_set_directions(&mut self, packed_pos: u32, directions: Vec<Direction>) {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.set_directions(pos, directions);
     }
 
     /// Adds a direction to the list of valid directions at a given position (JavaScript)
     #[wasm_bindgen(js_name = addDirection)]
     pub fn js_add_direction(&mut self, packed_pos: u32, direction: Direction) {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.add_direction(pos, direction);
     }
 }
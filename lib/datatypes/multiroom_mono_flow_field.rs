@@ -1,8 +1,12 @@
 use screeps::{Direction, Position, RoomName};
 use std::collections::HashMap;
-use wasm_bindgen::prelude::*;
+use wasm_bindgen::{prelude::*, throw_str};
 
 use super::mono_flow_field::MonoFlowField;
+use super::Path;
+use super::PositionSet;
+use crate::algorithms::map::corresponding_room_edge;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
 
 /// Maps monodirectional flow field values across multiple rooms, storing a MonoFlowField for each room
 #[wasm_bindgen]
@@ -46,6 +50,33 @@ impl MultiroomMonoFlowField {
         self.maps.get(&room_name)
     }
 
+    /// Follows the stored directions from `from` until reaching a tile with
+    /// no direction (the field's target), returning the positions in walking
+    /// order. Room crossings include the edge tiles on both sides. The path
+    /// stops early once it holds `max_len` positions.
+    pub fn trace_path(&self, from: Position, max_len: usize) -> Result<Path, &'static str> {
+        let mut positions = Vec::new();
+        let mut visited = PositionSet::new();
+        let mut current = from;
+        while positions.len() < max_len {
+            if !visited.insert(current) {
+                return Err("Cycle detected in flow field");
+            }
+            positions.push(current);
+            let Some(direction) = self.get(current) else {
+                break;
+            };
+            let next = current
+                .checked_add_direction(direction)
+                .map_err(|_| "Flow field points outside the world")?;
+            if next.is_room_edge() && positions.len() < max_len {
+                positions.push(next);
+            }
+            current = corresponding_room_edge(next);
+        }
+        Ok(Path::from(positions))
+    }
+
     /// Gets a mutable reference to the MonoFlowField for a given room, creating it if it doesn't exist
     pub fn get_or_create_room_map(&mut self, room_name: RoomName) -> &mut MonoFlowField {
         self.maps
@@ -65,14 +96,14 @@ impl MultiroomMonoFlowField {
     /// Gets the direction at a given position
     #[wasm_bindgen(js_name = get)]
     pub fn js_get(&self, packed_pos: u32) -> Option<Direction> {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.get(pos)
     }
 
     /// Sets the direction at a given position
     #[wasm_bindgen(js_name = set)]
     pub fn js_set(&mut self, packed_pos: u32, direction: Option<Direction>) {
-        let pos = Position::from_packed(packed_pos);
+        let pos = world_position_from_packed(packed_pos);
         self.set(pos, direction);
     }
 
@@ -82,10 +113,18 @@ impl MultiroomMonoFlowField {
         self.maps.keys().map(|k| k.packed_repr()).collect()
     }
 
+    /// Traces the path from a position to the field's target, up to
+    /// `max_len` positions. Throws if the directions form a cycle.
+    #[wasm_bindgen(js_name = tracePath)]
+    pub fn js_trace_path(&self, from_packed: u32, max_len: usize) -> Path {
+        self.trace_path(world_position_from_packed(from_packed), max_len)
+            .unwrap_or_else(|e| throw_str(e))
+    }
+
     /// Gets the MonoFlowField for a given room
     #[wasm_bindgen(js_name = getRoom)]
     pub fn js_get_room(&self, room_name: u16) -> Option<MonoFlowField> {
-        let room_name = RoomName::from_packed(room_name);
+        let room_name = world_room_from_packed(room_name);
         self.maps.get(&room_name).cloned()
     }
 }
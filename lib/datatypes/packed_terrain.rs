@@ -0,0 +1,48 @@
+use screeps::{
+    terrain_index_to_xy, xy_to_terrain_index, LocalRoomTerrain, RoomXY, Terrain, ROOM_AREA,
+};
+
+/// Terrain for a single room, packed at 2 bits per tile (4 tiles per byte).
+/// Tiles are stored in row-major order (the same order as the game's
+/// terrain buffer), using the `TERRAIN_MASK_*` values: 0 for plain, 1 for
+/// wall, and 2 for swamp.
+#[derive(Debug, Clone)]
+pub struct PackedTerrain([u8; ROOM_AREA / 4]);
+
+impl PackedTerrain {
+    /// Gets the terrain at a given coordinate.
+    pub fn get(&self, xy: RoomXY) -> Terrain {
+        match self.get_bits(xy) {
+            0 => Terrain::Plain,
+            2 => Terrain::Swamp,
+            _ => Terrain::Wall,
+        }
+    }
+
+    /// Gets the raw 2-bit terrain value at a given coordinate.
+    pub fn get_bits(&self, xy: RoomXY) -> u8 {
+        let index = xy_to_terrain_index(xy);
+        (self.0[index / 4] >> ((index % 4) * 2)) & 0b11
+    }
+
+    /// Gets the packed buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<&LocalRoomTerrain> for PackedTerrain {
+    fn from(terrain: &LocalRoomTerrain) -> Self {
+        let mut data = [0; ROOM_AREA / 4];
+        for index in 0..ROOM_AREA {
+            let xy = terrain_index_to_xy(index);
+            let bits = match terrain.get_xy(xy) {
+                Terrain::Plain => 0,
+                Terrain::Wall => 1,
+                Terrain::Swamp => 2,
+            };
+            data[index / 4] |= bits << ((index % 4) * 2);
+        }
+        PackedTerrain(data)
+    }
+}
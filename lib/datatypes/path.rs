@@ -2,9 +2,14 @@ use js_sys::Math::ceil;
 use screeps::{
     game::{map::get_room_terrain, rooms},
     look::STRUCTURES,
-    Position, RoomXY, StructureProperties, StructureType, Terrain,
+    Direction, Position, RoomName, RoomXY, StructureProperties, StructureType, Terrain,
 };
-use wasm_bindgen::{prelude::*, UnwrapThrowExt};
+use wasm_bindgen::{prelude::*, throw_str, UnwrapThrowExt};
+
+use crate::algorithms::map::corresponding_room_edge;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+use super::BodySpec;
 
 #[repr(u8)]
 #[derive(Debug, Clone)]
@@ -24,7 +29,12 @@ pub struct Path(Vec<Position>);
 #[wasm_bindgen]
 /// Tracks fatigue cost for each position in a path. Used to calculate move time
 /// for a given creep build.
-pub struct PathFatigue(Vec<Fatigue>);
+pub struct PathFatigue {
+    fatigue: Vec<Fatigue>,
+    // Whether each position is entered with a move: not the start, nor the
+    // tile a creep is carried to across a room border
+    moved: Vec<bool>,
+}
 
 impl Path {
     pub fn new() -> Self {
@@ -58,13 +68,183 @@ impl Path {
         }
         next_adjacent_index
     }
+
+    /// Gets the direction to move from the position at `index` to the next
+    /// position in the path. Returns None at the end of the path, or when the
+    /// next position is the corresponding edge tile in the adjacent room (the
+    /// creep is moved across the room border without a move intent).
+    pub fn direction_at(&self, index: usize) -> Option<Direction> {
+        let current = self.0.get(index)?;
+        let next = self.0.get(index + 1)?;
+        if current.room_name() != next.room_name() && current.is_room_edge() {
+            return None;
+        }
+        current.get_direction_to(*next)
+    }
+
+    /// Gets the move directions for the whole path, in order. Room border
+    /// crossings don't need a move intent, so they're skipped.
+    pub fn directions(&self) -> Vec<Direction> {
+        (0..self.0.len())
+            .filter_map(|index| self.direction_at(index))
+            .collect()
+    }
+
+    /// Returns a new path with up to `len` positions, starting at `start`.
+    pub fn slice(&self, start: usize, len: usize) -> Path {
+        let start = start.min(self.0.len());
+        let end = start.saturating_add(len).min(self.0.len());
+        Path(self.0[start..end].to_vec())
+    }
+
+    /// Returns a new path ending at the last position before the path first
+    /// enters `room_name`. If the path starts in the room, the result is
+    /// empty; if it never enters the room, the whole path is kept.
+    pub fn truncate_at_room(&self, room_name: RoomName) -> Path {
+        let end = self
+            .0
+            .iter()
+            .position(|pos| pos.room_name() == room_name)
+            .unwrap_or(self.0.len());
+        Path(self.0[..end].to_vec())
+    }
+
+    /// Splits the path into one segment per room visit, in order. Each
+    /// segment starts at the tile where the path enters the room and ends at
+    /// the tile where it leaves, so segments can be cached and reused by
+    /// other routes through the same room, then joined with `stitch`.
+    pub fn split_by_room(&self) -> Vec<Path> {
+        let mut segments: Vec<Path> = Vec::new();
+        for position in &self.0 {
+            match segments.last_mut() {
+                Some(segment) if segment.0[0].room_name() == position.room_name() => {
+                    segment.0.push(*position)
+                }
+                _ => segments.push(Path(vec![*position])),
+            }
+        }
+        segments
+    }
+
+    /// Returns a new path with `other` appended. The first position of
+    /// `other` must follow the last position of this path (adjacent, or the
+    /// corresponding tile across a room border); if it's the same position,
+    /// it isn't repeated.
+    pub fn concat(&self, other: &Path) -> Result<Path, String> {
+        let mut positions = self.0.clone();
+        let mut rest = other.0.as_slice();
+        if let (Some(last), Some(first)) = (self.0.last(), other.0.first()) {
+            if last == first {
+                rest = &rest[1..];
+            } else if !is_step(*last, *first) {
+                return Err(format!(
+                    "Path is not continuous between {} and {}",
+                    last, first
+                ));
+            }
+        }
+        positions.extend_from_slice(rest);
+        Ok(Path(positions))
+    }
+
+    /// Finds the index of a position in the path.
+    pub fn find_index_of(&self, position: &Position) -> Option<usize> {
+        self.0.iter().position(|p| p == position)
+    }
+
+    /// Finds the index of the path position closest to `position`, if it's
+    /// within `tolerance` range. Ties go to the earliest index, so a creep on
+    /// the path gets its own index. Useful for checking whether a creep has
+    /// strayed from its path.
+    pub fn find_index_near(&self, position: &Position, tolerance: u32) -> Option<usize> {
+        index_near(self.0.iter().copied(), *position, tolerance)
+    }
+
+    /// Stitches piecewise paths (e.g. from room-restricted searches or cached
+    /// segments) into a single continuous path. Duplicate positions where two
+    /// pieces meet are dropped, and a missing edge tile at a room border is
+    /// filled in with its corresponding tile. Returns an error describing the
+    /// first gap that can't be bridged.
+    pub fn stitch(pieces: &[Vec<Position>]) -> Result<Path, String> {
+        let mut stitched: Vec<Position> = Vec::new();
+        for piece in pieces {
+            for position in piece {
+                let Some(last) = stitched.last().copied() else {
+                    stitched.push(*position);
+                    continue;
+                };
+                if last == *position {
+                    continue;
+                }
+                if !is_step(last, *position) {
+                    // Bridge a room border where one of the two edge tiles is missing
+                    let bridge = [
+                        corresponding_room_edge(last),
+                        corresponding_room_edge(*position),
+                    ]
+                    .iter()
+                    .copied()
+                    .find(|edge| is_step(last, *edge) && is_step(*edge, *position));
+                    match bridge {
+                        Some(edge) => stitched.push(edge),
+                        None => {
+                            return Err(format!(
+                                "Path is not continuous between {} and {}",
+                                last, position
+                            ))
+                        }
+                    }
+                }
+                stitched.push(*position);
+            }
+        }
+        Ok(Path(stitched))
+    }
+
+    /// Returns a copy of the path with consecutive duplicate positions
+    /// removed and back-and-forth steps (A, B, A) folded into a single
+    /// visit. Doesn't check that the result is continuous; see `first_gap`.
+    pub fn normalize(&self) -> Path {
+        let mut positions: Vec<Position> = Vec::with_capacity(self.0.len());
+        for position in &self.0 {
+            if positions.last() == Some(position) {
+                continue;
+            }
+            if positions.len() >= 2 && positions[positions.len() - 2] == *position {
+                positions.pop();
+                continue;
+            }
+            positions.push(*position);
+        }
+        Path(positions)
+    }
+
+    /// Finds the index of the first position that doesn't follow the one
+    /// before it (see `concat`), or `None` if the path is continuous.
+    pub fn first_gap(&self) -> Option<usize> {
+        self.0
+            .windows(2)
+            .position(|pair| !is_step(pair[0], pair[1]))
+            .map(|index| index + 1)
+    }
+}
+
+/// Checks whether `to` immediately follows `from` in a path: either an
+/// adjacent tile in the same room, or the corresponding edge tile across a
+/// room border.
+fn is_step(from: Position, to: Position) -> bool {
+    if from.room_name() == to.room_name() {
+        from.get_range_to(to) == 1
+    } else {
+        from.is_room_edge() && corresponding_room_edge(from) == to
+    }
 }
 
 #[wasm_bindgen]
 impl Path {
     #[wasm_bindgen(js_name = add)]
     pub fn js_add(&mut self, packed_position: u32) {
-        let position = Position::from_packed(packed_position);
+        let position = world_position_from_packed(packed_position);
         self.add(position);
     }
 
@@ -84,10 +264,108 @@ impl Path {
     /// the position is neither on nor adjacent to the path, return None.
     #[wasm_bindgen(js_name = find_next_index)]
     pub fn js_find_next_index(&self, packed_position: u32) -> Option<usize> {
-        let position = Position::from_packed(packed_position);
+        let position = world_position_from_packed(packed_position);
         self.find_next_index(&position)
     }
 
+    /// Gets the direction to move from the position at `index` to the next
+    /// position in the path, or undefined at the end of the path or at a room
+    /// border crossing.
+    #[wasm_bindgen(js_name = direction_at)]
+    pub fn js_direction_at(&self, index: usize) -> Option<Direction> {
+        self.direction_at(index)
+    }
+
+    /// Gets the move directions for the whole path, skipping room border
+    /// crossings (which don't need a move intent).
+    #[wasm_bindgen(js_name = directions)]
+    pub fn js_directions(&self) -> Vec<Direction> {
+        self.directions()
+    }
+
+    /// Returns a new path with up to `len` positions, starting at `start`.
+    #[wasm_bindgen(js_name = slice)]
+    pub fn js_slice(&self, start: usize, len: usize) -> Path {
+        self.slice(start, len)
+    }
+
+    /// Returns a new path ending before the path first enters the room.
+    #[wasm_bindgen(js_name = truncate_at_room)]
+    pub fn js_truncate_at_room(&self, room_name: u16) -> Path {
+        self.truncate_at_room(world_room_from_packed(room_name))
+    }
+
+    /// Gets the length of each segment of `split_by_room`, in order.
+    #[wasm_bindgen(js_name = room_segment_lengths)]
+    pub fn js_room_segment_lengths(&self) -> Vec<usize> {
+        self.split_by_room().iter().map(Path::len).collect()
+    }
+
+    /// Returns a new path with `other` appended. Throws if `other` doesn't
+    /// continue from the end of this path.
+    #[wasm_bindgen(js_name = concat)]
+    pub fn js_concat(&self, other: &Path) -> Path {
+        self.concat(other).unwrap_or_else(|e| throw_str(&e))
+    }
+
+    /// Finds the index of a position in the path.
+    #[wasm_bindgen(js_name = find_index_of)]
+    pub fn js_find_index_of(&self, packed_position: u32) -> Option<usize> {
+        self.find_index_of(&world_position_from_packed(packed_position))
+    }
+
+    /// Finds the index of the path position closest to the given position,
+    /// if it's within `tolerance` range.
+    #[wasm_bindgen(js_name = find_index_near)]
+    pub fn js_find_index_near(&self, packed_position: u32, tolerance: u32) -> Option<usize> {
+        self.find_index_near(&world_position_from_packed(packed_position), tolerance)
+    }
+
+    /// Stitches piecewise paths into a single continuous path. The pieces are
+    /// passed as one flat array of packed positions, along with the length
+    /// of each piece. Throws if the pieces can't be joined.
+    #[wasm_bindgen(js_name = stitch)]
+    pub fn js_stitch(packed_positions: Vec<u32>, lengths: Vec<usize>) -> Path {
+        let mut pieces = Vec::with_capacity(lengths.len());
+        let mut start = 0;
+        for length in lengths {
+            let end = start + length;
+            if end > packed_positions.len() {
+                throw_str("Path lengths exceed the number of positions");
+            }
+            pieces.push(
+                packed_positions[start..end]
+                    .iter()
+                    .map(|pos| world_position_from_packed(*pos))
+                    .collect(),
+            );
+            start = end;
+        }
+        Path::stitch(&pieces).unwrap_or_else(|e| throw_str(&e))
+    }
+
+    /// Returns a copy of the path without duplicate positions or
+    /// back-and-forth steps.
+    #[wasm_bindgen(js_name = normalize)]
+    pub fn js_normalize(&self) -> Path {
+        self.normalize()
+    }
+
+    /// Finds the index of the first position that doesn't follow the one
+    /// before it, or undefined if the path is continuous.
+    #[wasm_bindgen(js_name = first_gap)]
+    pub fn js_first_gap(&self) -> Option<usize> {
+        self.first_gap()
+    }
+
+    /// Estimates the ticks a creep with the given body takes to walk the
+    /// path, from the fatigue cost of its tiles. Roads are only detected in
+    /// visible rooms.
+    #[wasm_bindgen(js_name = estimate_travel_ticks)]
+    pub fn js_estimate_travel_ticks(&self, body: &BodySpec) -> Option<usize> {
+        PathFatigue::new(self).travel_ticks(body)
+    }
+
     #[wasm_bindgen(js_name = to_array)]
     pub fn js_to_array(&self) -> Vec<u32> {
         self.0.iter().map(|p| p.packed_repr()).collect()
@@ -103,7 +381,10 @@ impl Path {
 impl PathFatigue {
     #[wasm_bindgen(constructor)]
     pub fn new(path: &Path) -> Self {
-        let mut fatigue = PathFatigue(Vec::new());
+        let mut fatigue = PathFatigue {
+            fatigue: Vec::new(),
+            moved: Vec::new(),
+        };
         fatigue.refresh(path);
         fatigue
     }
@@ -116,14 +397,17 @@ impl PathFatigue {
     /// When there is vision, this also accounts for roads at the position.
     #[wasm_bindgen(js_name = refresh)]
     pub fn refresh(&mut self, path: &Path) {
-        let initializing = self.0.len() != path.len();
+        let initializing = self.fatigue.len() != path.len();
         if initializing {
-            self.0.resize(path.len(), Fatigue::Exits);
+            self.fatigue.resize(path.len(), Fatigue::Exits);
         }
+        self.moved = (0..path.len())
+            .map(|i| i > 0 && path.direction_at(i - 1).is_some())
+            .collect();
         for (i, p) in path.0.iter().enumerate() {
             // Only update fatigue if the room is visible (or, optimistically
             // set fatigue based on terrain, if it hasn't been initialized yet)
-            if !initializing && !rooms().get(p.room_name()).is_some() {
+            if !initializing && rooms().get(p.room_name()).is_none() {
                 continue;
             }
             let mut terrain = get_room_terrain(p.room_name()).unwrap_throw();
@@ -135,22 +419,22 @@ impl PathFatigue {
                     .iter()
                     .any(|s| s.structure_type() == StructureType::Road)
                 {
-                    self.0[i] = Fatigue::Roads;
+                    self.fatigue[i] = Fatigue::Roads;
                     continue;
                 }
             }
 
             match terrain.get_xy(RoomXY::from(*p)) {
-                Terrain::Plain => self.0[i] = Fatigue::Plains,
-                Terrain::Swamp => self.0[i] = Fatigue::Swamps,
-                _ => self.0[i] = Fatigue::Exits,
+                Terrain::Plain => self.fatigue[i] = Fatigue::Plains,
+                Terrain::Swamp => self.fatigue[i] = Fatigue::Swamps,
+                _ => self.fatigue[i] = Fatigue::Exits,
             }
         }
     }
 
     #[wasm_bindgen]
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.fatigue.len()
     }
 
     /// Calculates the total move time for the path, given a fatigue ratio.
@@ -159,11 +443,45 @@ impl PathFatigue {
     /// where `fatigue_parts` are any part except MOVE and empty CARRY parts.
     #[wasm_bindgen(js_name = moveTime)]
     pub fn move_time(&self, fatigue_ratio: usize) -> usize {
-        self.0
+        self.fatigue
             .iter()
             .map(|f| ceil(f.clone() as u8 as f64 / fatigue_ratio as f64) as usize)
             .sum()
     }
+
+    /// Estimates the ticks a creep with the given body takes to walk the path.
+    /// Each position after the first counts as a tile entered, except the far
+    /// side of a room border, which the creep is carried to for free. Moving
+    /// adds the tile's fatigue cost times the body's weight, and the creep
+    /// can't move again until its MOVE parts have worn the fatigue back down
+    /// to zero.
+    ///
+    /// Returns `None` if the creep has weight but no MOVE parts.
+    #[wasm_bindgen(js_name = travelTicks)]
+    pub fn travel_ticks(&self, body: &BodySpec) -> Option<usize> {
+        let weight = body.weight() as usize;
+        let regen = body.fatigue_regen() as usize;
+        let moves = self
+            .fatigue
+            .iter()
+            .zip(&self.moved)
+            .filter(|(_, moved)| **moved)
+            .map(|(fatigue, _)| fatigue);
+        if regen == 0 {
+            return if weight == 0 {
+                Some(moves.count())
+            } else {
+                None
+            };
+        }
+        let mut ticks = 0;
+        for fatigue in moves {
+            // Fatigue is regenerated at the end of the tick the creep moves in
+            let generated = (fatigue.clone() as u8 as usize * weight).saturating_sub(regen);
+            ticks += 1 + generated.div_ceil(regen);
+        }
+        Some(ticks)
+    }
 }
 
 impl From<Vec<Position>> for Path {
@@ -171,3 +489,58 @@ impl From<Vec<Position>> for Path {
         Path(positions)
     }
 }
+
+fn index_near(
+    positions: impl Iterator<Item = Position>,
+    position: Position,
+    tolerance: u32,
+) -> Option<usize> {
+    let mut nearest: Option<(usize, u32)> = None;
+    for (i, p) in positions.enumerate() {
+        let range = p.get_range_to(position);
+        if range == 0 {
+            return Some(i);
+        }
+        if range <= tolerance && nearest.is_none_or(|(_, best)| range < best) {
+            nearest = Some((i, range));
+        }
+    }
+    nearest.map(|(i, _)| i)
+}
+
+/// Checks whether a position is on (or within `tolerance` range of) a path
+/// given as an array of packed positions, without creating a Path.
+///
+/// # Arguments
+/// * `path_packed` - The packed positions of the path
+/// * `packed_position` - The packed position to look for
+/// * `tolerance` - The greatest range from the path that still counts
+///
+/// # Returns
+/// The index of the closest path position, or undefined if it's out of tolerance
+#[wasm_bindgen(js_name = clockwork_path_contains)]
+pub fn js_path_contains(
+    path_packed: Vec<u32>,
+    packed_position: u32,
+    tolerance: u32,
+) -> Option<usize> {
+    index_near(
+        path_packed.into_iter().map(world_position_from_packed),
+        world_position_from_packed(packed_position),
+        tolerance,
+    )
+}
+
+/// Estimates the ticks a creep with the given body takes to walk a path,
+/// accounting for MOVE part ratio, carried load, roads, and swamps.
+///
+/// # Arguments
+/// * `path` - The path to walk
+/// * `body` - The creep's body
+///
+/// # Returns
+/// The number of ticks, or undefined if the creep can't move
+#[wasm_bindgen(js_name = clockwork_estimate_travel_ticks)]
+pub fn js_estimate_travel_ticks(path: &Path, body: &BodySpec) -> Option<usize> {
+    path.js_estimate_travel_ticks(body)
+}
@@ -0,0 +1,132 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use screeps::{Direction, Position};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use super::Path;
+use crate::helpers::rooms::world_position_from_packed;
+
+// How far ahead of the last known index to look for the creep before
+// searching the whole path
+const LOOKAHEAD: usize = 3;
+
+/// A path kept in wasm memory, with the index the follower was last seen at.
+struct StoredPath {
+    path: Path,
+    cursor: usize,
+}
+
+impl StoredPath {
+    /// The index of the path position to step onto from `position`, or
+    /// `None` if the position is neither on nor next to the path.
+    fn next_index(&mut self, position: Position) -> Option<usize> {
+        let end = self.cursor.saturating_add(LOOKAHEAD).min(self.path.len());
+        let next = (self.cursor..end)
+            .find(|index| self.path.get(*index) == Some(&position))
+            .map(|index| index + 1)
+            .or_else(|| self.path.find_next_index(&position))?;
+        self.cursor = next.saturating_sub(1);
+        Some(next)
+    }
+}
+
+thread_local! {
+    static PATHS: RefCell<HashMap<u32, StoredPath>> = RefCell::new(HashMap::new());
+    static NEXT_PATH_ID: RefCell<u32> = const { RefCell::new(1) };
+}
+
+/// Runs `f` on a stored path, throwing if there's no path with that id. The
+/// error is thrown after the registry is released.
+fn with_stored_path<R>(id: u32, f: impl FnOnce(&mut StoredPath) -> R) -> R {
+    PATHS
+        .with(|paths| paths.borrow_mut().get_mut(&id).map(f))
+        .unwrap_or_else(|| throw_str(&format!("Unknown path: {}", id)))
+}
+
+/// Stores a copy of a path in wasm memory, so a creep can follow it over many
+/// ticks without copying its positions back to JS each time.
+///
+/// # Arguments
+/// * `path` - The path to store
+///
+/// # Returns
+/// The stored path's id
+#[wasm_bindgen(js_name = clockwork_path_store)]
+pub fn js_path_store(path: &Path) -> u32 {
+    let id = NEXT_PATH_ID.with(|next| {
+        let mut next = next.borrow_mut();
+        let id = *next;
+        *next = next.wrapping_add(1).max(1);
+        id
+    });
+    PATHS.with(|paths| {
+        paths.borrow_mut().insert(
+            id,
+            StoredPath {
+                path: path.clone(),
+                cursor: 0,
+            },
+        )
+    });
+    id
+}
+
+/// Gets the direction to move from `packed_position` along a stored path.
+/// The search starts where the creep was last seen, so following a path
+/// tick by tick doesn't rescan it.
+///
+/// # Arguments
+/// * `id` - The stored path's id
+/// * `packed_position` - The creep's current packed position
+///
+/// # Returns
+/// The direction to move, or undefined at the end of the path, at a room
+/// border crossing, or if the position is neither on nor next to the path
+#[wasm_bindgen(js_name = clockwork_path_next)]
+pub fn js_path_next(id: u32, packed_position: u32) -> Option<Direction> {
+    let position = world_position_from_packed(packed_position);
+    let next = with_stored_path(id, |stored| {
+        stored
+            .next_index(position)
+            .and_then(|index| stored.path.get(index).copied())
+    })?;
+    if next.room_name() != position.room_name() {
+        return None;
+    }
+    position.get_direction_to(next)
+}
+
+/// Gets the number of positions in a stored path.
+#[wasm_bindgen(js_name = clockwork_path_len)]
+pub fn js_path_len(id: u32) -> usize {
+    with_stored_path(id, |stored| stored.path.len())
+}
+
+/// Gets part of a stored path, so long paths can be read a few steps at a
+/// time instead of copied to JS whole.
+///
+/// # Arguments
+/// * `id` - The stored path's id
+/// * `offset` - The index of the first position to get
+/// * `len` - The most positions to get
+///
+/// # Returns
+/// The packed positions, fewer than `len` (or none) past the end of the path
+#[wasm_bindgen(js_name = clockwork_path_chunk)]
+pub fn js_path_chunk(id: u32, offset: usize, len: usize) -> Vec<u32> {
+    with_stored_path(id, |stored| {
+        let start = offset.min(stored.path.len());
+        let end = offset.saturating_add(len).min(stored.path.len());
+        (start..end)
+            .filter_map(|index| stored.path.get(index))
+            .map(|position| position.packed_repr())
+            .collect()
+    })
+}
+
+/// Frees a stored path, returning true if it existed.
+#[wasm_bindgen(js_name = clockwork_path_drop)]
+pub fn js_path_drop(id: u32) -> bool {
+    PATHS.with(|paths| paths.borrow_mut().remove(&id).is_some())
+}
@@ -0,0 +1,126 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+use screeps::{linear_index_to_xy, xy_to_linear_index, Position, RoomName, RoomXY, ROOM_AREA};
+
+// The most cleared room bitsets to keep for reuse
+const MAX_POOLED: usize = 64;
+
+const WORDS: usize = ROOM_AREA.div_ceil(64);
+
+thread_local! {
+    static POOL: RefCell<Vec<RoomBits>> = const { RefCell::new(Vec::new()) };
+}
+
+/// One bit for each tile of a room.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RoomBits([u64; WORDS]);
+
+impl RoomBits {
+    pub fn new() -> Self {
+        RoomBits([0; WORDS])
+    }
+
+    /// Sets the bit for `xy`, returning true if it wasn't already set.
+    pub fn insert(&mut self, xy: RoomXY) -> bool {
+        let index = xy_to_linear_index(xy);
+        let mask = 1 << (index % 64);
+        let word = &mut self.0[index / 64];
+        let inserted = *word & mask == 0;
+        *word |= mask;
+        inserted
+    }
+
+    pub fn contains(&self, xy: RoomXY) -> bool {
+        let index = xy_to_linear_index(xy);
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn clear(&mut self) {
+        self.0 = [0; WORDS];
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..ROOM_AREA).filter(move |index| self.0[index / 64] & (1 << (index % 64)) != 0)
+    }
+}
+
+impl Default for RoomBits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RoomBits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// A set of positions, stored as a bitset per room. Bitsets are allocated
+/// the first time a room is touched, and returned to a shared pool when the
+/// set is dropped, so a search only pays for the rooms it visits, and
+/// membership checks hash the room name rather than the whole position.
+#[derive(Default)]
+pub struct PositionSet {
+    rooms: HashMap<RoomName, RoomBits>,
+}
+
+impl PositionSet {
+    pub fn new() -> Self {
+        PositionSet {
+            rooms: HashMap::new(),
+        }
+    }
+
+    /// The bitset for a room, allocating it if needed.
+    pub fn room_mut(&mut self, room: RoomName) -> &mut RoomBits {
+        self.rooms.entry(room).or_insert_with(|| {
+            POOL.with(|pool| pool.borrow_mut().pop())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Adds `position`, returning true if it wasn't already in the set.
+    pub fn insert(&mut self, position: Position) -> bool {
+        self.room_mut(position.room_name()).insert(position.xy())
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.rooms
+            .get(&position.room_name())
+            .is_some_and(|bits| bits.contains(position.xy()))
+    }
+
+    /// The positions in the set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.rooms.iter().flat_map(|(room, bits)| {
+            bits.iter().map(move |index| {
+                let xy = linear_index_to_xy(index);
+                Position::new(xy.x, xy.y, *room)
+            })
+        })
+    }
+}
+
+impl fmt::Debug for PositionSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Drop for PositionSet {
+    fn drop(&mut self) {
+        POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            for (_, mut bits) in self.rooms.drain() {
+                if pool.len() >= MAX_POOLED {
+                    break;
+                }
+                bits.clear();
+                pool.push(bits);
+            }
+        });
+    }
+}
@@ -1,7 +1,9 @@
 use crate::datatypes::ClockworkCostMatrix;
 use crate::datatypes::DistanceMap;
 use crate::datatypes::MultiroomDistanceMap;
-use screeps::RoomName;
+use crate::helpers::cache_stats::record_cache_stats;
+use crate::helpers::search_load::record_search_load;
+use screeps::{game, Position, RoomName};
 use std::collections::HashMap;
 use std::ops::Fn;
 use std::ops::Index;
@@ -12,6 +14,11 @@ pub struct RoomData {
     pub cost_matrix: Option<ClockworkCostMatrix>,
     pub distance_map: DistanceMap,
     pub room_name: RoomName,
+    // Tiles the search expanded in this room
+    pub expanded: u32,
+    // Whether the room counts against `max_rooms`. Goal rooms checked before
+    // the search are only counted once the search enters them.
+    counted: bool,
 }
 
 pub struct RoomDataCache<F>
@@ -22,6 +29,7 @@ where
     room_map: HashMap<RoomName, usize>,
     cost_matrix_creator: F,
     rooms_available: usize,
+    start_cpu: f64,
 }
 
 impl<F> RoomDataCache<F>
@@ -34,27 +42,119 @@ where
             room_map: HashMap::new(),
             cost_matrix_creator,
             rooms_available: max_rooms,
+            start_cpu: game::cpu::get_used(),
         }
     }
 
     pub fn get_room_key(&mut self, room: RoomName) -> Option<usize> {
-        if let Some(room_key) = self.room_map.get(&room) {
-            return Some(*room_key);
+        if let Some(&room_key) = self.room_map.get(&room) {
+            // Blocked rooms never count against `max_rooms`
+            if self.room_data[room_key].counted || self.room_data[room_key].cost_matrix.is_none() {
+                record_cache_stats(|stats| stats.room_data_hits += 1);
+                return Some(room_key);
+            }
+            // Loaded by `is_room_blocked`, and entered for the first time
+            if self.rooms_available == 0 {
+                return None;
+            }
+            self.rooms_available -= 1;
+            self.room_data[room_key].counted = true;
+            return Some(room_key);
         }
         if self.rooms_available == 0 {
             return None;
         }
+        let key = self.load_room(room);
+        if self.room_data[key].cost_matrix.is_some() {
+            self.rooms_available -= 1;
+            self.room_data[key].counted = true;
+        }
+        Some(key)
+    }
+
+    fn load_room(&mut self, room: RoomName) -> usize {
+        record_cache_stats(|stats| stats.room_data_misses += 1);
         self.room_data.push(RoomData {
             cost_matrix: (self.cost_matrix_creator)(room),
             distance_map: DistanceMap::new(),
             room_name: room,
+            expanded: 0,
+            counted: false,
         });
         let key = self.room_data.len() - 1;
         self.room_map.insert(room, key);
-        if self.room_data[key].cost_matrix.is_some() {
-            self.rooms_available -= 1;
+        key
+    }
+
+    /// Whether a room has no cost matrix, loading it if needed. Unlike
+    /// `get_room_key`, this doesn't count the room against `max_rooms` until
+    /// the search enters it, so it can be used to check goal rooms before
+    /// searching.
+    pub fn is_room_blocked(&mut self, room: RoomName) -> bool {
+        let key = match self.room_map.get(&room) {
+            Some(&key) => key,
+            None => self.load_room(room),
+        };
+        self.room_data[key].cost_matrix.is_none()
+    }
+
+    /// Whether a room is already known to have no cost matrix, without
+    /// loading it.
+    pub fn is_room_known_blocked(&self, room: RoomName) -> bool {
+        self.room_map
+            .get(&room)
+            .is_some_and(|key| self.room_data[*key].cost_matrix.is_none())
+    }
+
+    /// Whether a room is already loaded and has a cost matrix.
+    pub fn is_room_known_open(&self, room: RoomName) -> bool {
+        self.room_map
+            .get(&room)
+            .is_some_and(|key| self.room_data[*key].cost_matrix.is_some())
+    }
+
+    /// Whether a tile is passable, loading its room if needed. Tiles in rooms
+    /// that can't be loaded are impassable.
+    pub fn is_passable(&mut self, position: Position) -> bool {
+        self.get_room_key(position.room_name())
+            .and_then(|key| self.room_data[key].cost_matrix.as_ref())
+            .is_some_and(|cost_matrix| cost_matrix.get(position.xy()) < 255)
+    }
+
+    /// Counts a tile expanded in a room, for the per-room search load report.
+    pub fn record_expansion(&mut self, room_key: usize) {
+        self.room_data[room_key].expanded += 1;
+    }
+
+    /// Adds the tiles expanded so far to the per-room search load report, and
+    /// resets the counts so they're only reported once.
+    fn record_load(&mut self) {
+        record_search_load(
+            self.room_data
+                .iter()
+                .map(|room_data| (room_data.room_name, room_data.expanded)),
+            game::cpu::get_used() - self.start_cpu,
+        );
+        for room_data in self.room_data.iter_mut() {
+            room_data.expanded = 0;
         }
-        Some(key)
+    }
+
+    /// The rooms loaded so far that have no cost matrix.
+    pub fn blocked_rooms(&self) -> impl Iterator<Item = RoomName> + '_ {
+        self.room_data
+            .iter()
+            .filter(|room_data| room_data.cost_matrix.is_none())
+            .map(|room_data| room_data.room_name)
+    }
+}
+
+impl<F> Drop for RoomDataCache<F>
+where
+    F: Fn(RoomName) -> Option<ClockworkCostMatrix>,
+{
+    fn drop(&mut self) {
+        self.record_load();
     }
 }
 
@@ -82,10 +182,14 @@ impl<F> From<RoomDataCache<F>> for MultiroomDistanceMap
 where
     F: Fn(RoomName) -> Option<ClockworkCostMatrix>,
 {
-    fn from(cached_room_data: RoomDataCache<F>) -> Self {
+    fn from(mut cached_room_data: RoomDataCache<F>) -> Self {
+        cached_room_data.record_load();
         let mut maps = HashMap::new();
-        for room_data in cached_room_data.room_data {
-            maps.insert(room_data.room_name, room_data.distance_map);
+        for room_data in std::mem::take(&mut cached_room_data.room_data) {
+            // Goal rooms that were checked but never entered have no distances
+            if room_data.counted || room_data.cost_matrix.is_none() {
+                maps.insert(room_data.room_name, room_data.distance_map);
+            }
         }
         MultiroomDistanceMap { maps }
     }
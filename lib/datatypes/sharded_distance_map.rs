@@ -0,0 +1,134 @@
+use screeps::{Position, RoomName};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+use super::multiroom_distance_map::MultiroomDistanceMap;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+/// Packs a shard id and a room name into a single key. The packed room name
+/// takes the low 16 bits, and the shard id the next 8.
+pub fn pack_sharded_room_name(shard: u8, room_name: RoomName) -> u32 {
+    ((shard as u32) << 16) | room_name.packed_repr() as u32
+}
+
+/// Unpacks a key created with `pack_sharded_room_name`.
+pub fn unpack_sharded_room_name(key: u32) -> (u8, RoomName) {
+    ((key >> 16) as u8, RoomName::from_packed(key as u16))
+}
+
+/// Packs a shard id and a position into a single key. The packed position
+/// takes the low 32 bits, and the shard id the next 8.
+pub fn pack_sharded_position(shard: u8, position: Position) -> u64 {
+    ((shard as u64) << 32) | position.packed_repr() as u64
+}
+
+/// Unpacks a key created with `pack_sharded_position`.
+pub fn unpack_sharded_position(key: u64) -> (u8, Position) {
+    ((key >> 32) as u8, Position::from_packed(key as u32))
+}
+
+/// Maps distance values across multiple shards, storing a MultiroomDistanceMap
+/// for each shard so identically-named rooms on different shards don't collide.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct ShardedDistanceMap {
+    shards: HashMap<u8, MultiroomDistanceMap>,
+}
+
+impl ShardedDistanceMap {
+    /// Gets the distance value at a given position on a shard
+    pub fn get(&self, shard: u8, pos: Position) -> usize {
+        self.shards
+            .get(&shard)
+            .map(|map| map.get(pos))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Sets the distance value at a given position on a shard
+    pub fn set(&mut self, shard: u8, pos: Position, value: usize) {
+        self.shards.entry(shard).or_default().set(pos, value);
+    }
+}
+
+#[wasm_bindgen]
+impl ShardedDistanceMap {
+    /// Creates a new empty sharded distance map (JavaScript constructor)
+    #[wasm_bindgen(constructor)]
+    pub fn js_new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the distance value at a given position on a shard
+    #[wasm_bindgen(js_name = get)]
+    pub fn js_get(&self, shard: u8, packed_pos: u32) -> usize {
+        self.get(shard, world_position_from_packed(packed_pos))
+    }
+
+    /// Sets the distance value at a given position on a shard
+    #[wasm_bindgen(js_name = set)]
+    pub fn js_set(&mut self, shard: u8, packed_pos: u32, value: usize) {
+        self.set(shard, world_position_from_packed(packed_pos), value);
+    }
+
+    /// Gets the distance value for a packed sharded position key
+    #[wasm_bindgen(js_name = getSharded)]
+    pub fn js_get_sharded(&self, key: u64) -> usize {
+        let (shard, pos) = unpack_sharded_position(key);
+        self.get(shard, pos)
+    }
+
+    /// Gets the list of shards in the map
+    #[wasm_bindgen(js_name = getShards)]
+    pub fn js_get_shards(&self) -> Vec<u8> {
+        self.shards.keys().cloned().collect()
+    }
+
+    /// Gets a copy of the MultiroomDistanceMap for a given shard
+    #[wasm_bindgen(js_name = getShard)]
+    pub fn js_get_shard(&self, shard: u8) -> Option<MultiroomDistanceMap> {
+        self.shards.get(&shard).cloned()
+    }
+
+    /// Stores a copy of a MultiroomDistanceMap for a given shard, replacing any
+    /// existing data for that shard
+    #[wasm_bindgen(js_name = setShard)]
+    pub fn js_set_shard(&mut self, shard: u8, map: &MultiroomDistanceMap) {
+        self.shards.insert(shard, map.clone());
+    }
+}
+
+/// Packs a shard id and a packed room name into a single key.
+#[wasm_bindgen(js_name = clockwork_pack_sharded_room_name)]
+pub fn js_pack_sharded_room_name(shard: u8, room_name: u16) -> u32 {
+    pack_sharded_room_name(shard, world_room_from_packed(room_name))
+}
+
+/// Gets the shard id from a packed sharded room name key.
+#[wasm_bindgen(js_name = clockwork_sharded_room_name_shard)]
+pub fn js_sharded_room_name_shard(key: u32) -> u8 {
+    unpack_sharded_room_name(key).0
+}
+
+/// Gets the packed room name from a packed sharded room name key.
+#[wasm_bindgen(js_name = clockwork_sharded_room_name_room)]
+pub fn js_sharded_room_name_room(key: u32) -> u16 {
+    unpack_sharded_room_name(key).1.packed_repr()
+}
+
+/// Packs a shard id and a packed position into a single key (a BigInt in JS).
+#[wasm_bindgen(js_name = clockwork_pack_sharded_position)]
+pub fn js_pack_sharded_position(shard: u8, packed_pos: u32) -> u64 {
+    pack_sharded_position(shard, world_position_from_packed(packed_pos))
+}
+
+/// Gets the shard id from a packed sharded position key.
+#[wasm_bindgen(js_name = clockwork_sharded_position_shard)]
+pub fn js_sharded_position_shard(key: u64) -> u8 {
+    unpack_sharded_position(key).0
+}
+
+/// Gets the packed position from a packed sharded position key.
+#[wasm_bindgen(js_name = clockwork_sharded_position_pos)]
+pub fn js_sharded_position_pos(key: u64) -> u32 {
+    unpack_sharded_position(key).1.packed_repr()
+}
@@ -0,0 +1,235 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+use screeps::{game, Position, RoomCoordinate, RoomName, RoomXY};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::rooms::{world_position_from_packed, world_room_from_packed};
+
+/// The tiles covered by an avoidance zone.
+#[derive(Debug, Clone)]
+pub enum ZoneShape {
+    /// A rectangle within one room, inclusive of both corners.
+    Rect {
+        room_name: RoomName,
+        min: RoomXY,
+        max: RoomXY,
+    },
+    /// Every tile within `radius` range of `center`. May span rooms.
+    Circle { center: Position, radius: u32 },
+    /// An arbitrary set of tiles.
+    Tiles(HashMap<RoomName, Vec<RoomXY>>),
+}
+
+/// A named area that searches should avoid, e.g. a nuke landing site or an
+/// enemy rally point.
+#[derive(Debug, Clone)]
+pub struct AvoidZone {
+    pub shape: ZoneShape,
+    /// The minimum cost of tiles in the zone (255 blocks them).
+    pub cost: u8,
+    /// The game tick the zone expires on, if any.
+    pub expires_at: Option<u32>,
+}
+
+impl AvoidZone {
+    fn is_expired(&self, now: Option<u32>) -> bool {
+        matches!((self.expires_at, now), (Some(expires_at), Some(now)) if now >= expires_at)
+    }
+
+    /// Raises the cost of the zone's tiles in a room's cost matrix to at
+    /// least the zone's cost.
+    fn apply(&self, room_name: RoomName, cost_matrix: &mut ClockworkCostMatrix) {
+        let mut raise = |xy: RoomXY| cost_matrix.set(xy, cost_matrix.get(xy).max(self.cost));
+        match &self.shape {
+            ZoneShape::Rect {
+                room_name: zone_room,
+                min,
+                max,
+            } => {
+                if *zone_room != room_name {
+                    return;
+                }
+                for x in min.x.u8()..=max.x.u8() {
+                    for y in min.y.u8()..=max.y.u8() {
+                        // SAFETY: x and y are between two valid coordinates
+                        raise(unsafe { RoomXY::unchecked_new(x, y) });
+                    }
+                }
+            }
+            ZoneShape::Circle { center, radius } => {
+                // Skip rooms the circle can't reach.
+                // SAFETY: 25 is a valid room coordinate
+                let room_center = unsafe { RoomXY::unchecked_new(25, 25) };
+                if Position::new(room_center.x, room_center.y, room_name).get_range_to(*center)
+                    > radius + 25
+                {
+                    return;
+                }
+                for x in 0..50 {
+                    for y in 0..50 {
+                        // SAFETY: x and y are both in the range 0..50
+                        let xy = unsafe { RoomXY::unchecked_new(x, y) };
+                        if Position::new(xy.x, xy.y, room_name).get_range_to(*center) <= *radius {
+                            raise(xy);
+                        }
+                    }
+                }
+            }
+            ZoneShape::Tiles(tiles) => {
+                for xy in tiles.get(&room_name).into_iter().flatten() {
+                    raise(*xy);
+                }
+            }
+        }
+    }
+}
+
+thread_local! {
+    static AVOID_ZONES: RefCell<BTreeMap<String, AvoidZone>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Runs a closure with mutable access to the avoidance zone registry.
+pub fn with_avoid_zones<R>(f: impl FnOnce(&mut BTreeMap<String, AvoidZone>) -> R) -> R {
+    AVOID_ZONES.with(|zones| f(&mut zones.borrow_mut()))
+}
+
+/// Removes expired zones. The game time is only read if some zone expires.
+fn prune_expired(zones: &mut BTreeMap<String, AvoidZone>) {
+    if zones.values().any(|zone| zone.expires_at.is_some()) {
+        let now = Some(game::time());
+        zones.retain(|_, zone| !zone.is_expired(now));
+    }
+}
+
+/// Applies every registered zone to a room's cost matrix.
+pub fn apply_avoid_zones(room_name: RoomName, cost_matrix: &mut ClockworkCostMatrix) {
+    with_avoid_zones(|zones| {
+        if zones.is_empty() {
+            return;
+        }
+        prune_expired(zones);
+        for zone in zones.values() {
+            zone.apply(room_name, cost_matrix);
+        }
+    })
+}
+
+fn room_xy(x: u8, y: u8) -> RoomXY {
+    let x = RoomCoordinate::new(x)
+        .unwrap_or_else(|_| throw_str(&format!("Invalid x coordinate: {}", x)));
+    let y = RoomCoordinate::new(y)
+        .unwrap_or_else(|_| throw_str(&format!("Invalid y coordinate: {}", y)));
+    RoomXY::new(x, y)
+}
+
+/// Registers (or replaces) a rectangular avoidance zone in one room.
+///
+/// # Arguments
+/// * `name` - The zone's name
+/// * `room_name` - The packed room name
+/// * `x1`, `y1`, `x2`, `y2` - Opposite corners of the rectangle, inclusive
+/// * `cost` - The minimum cost of tiles in the zone (255 blocks them)
+/// * `expires_at` - The game tick the zone expires on, if any
+#[wasm_bindgen(js_name = clockwork_add_rect_zone)]
+#[allow(clippy::too_many_arguments)]
+pub fn js_add_rect_zone(
+    name: String,
+    room_name: u16,
+    x1: u8,
+    y1: u8,
+    x2: u8,
+    y2: u8,
+    cost: u8,
+    expires_at: Option<u32>,
+) {
+    let (a, b) = (room_xy(x1, y1), room_xy(x2, y2));
+    let shape = ZoneShape::Rect {
+        room_name: world_room_from_packed(room_name),
+        min: room_xy(a.x.u8().min(b.x.u8()), a.y.u8().min(b.y.u8())),
+        max: room_xy(a.x.u8().max(b.x.u8()), a.y.u8().max(b.y.u8())),
+    };
+    with_avoid_zones(|zones| {
+        zones.insert(
+            name,
+            AvoidZone {
+                shape,
+                cost,
+                expires_at,
+            },
+        )
+    });
+}
+
+/// Registers (or replaces) an avoidance zone covering every tile within
+/// `radius` range of a position.
+#[wasm_bindgen(js_name = clockwork_add_circle_zone)]
+pub fn js_add_circle_zone(
+    name: String,
+    center_packed: u32,
+    radius: u32,
+    cost: u8,
+    expires_at: Option<u32>,
+) {
+    let shape = ZoneShape::Circle {
+        center: world_position_from_packed(center_packed),
+        radius,
+    };
+    with_avoid_zones(|zones| {
+        zones.insert(
+            name,
+            AvoidZone {
+                shape,
+                cost,
+                expires_at,
+            },
+        )
+    });
+}
+
+/// Registers (or replaces) an avoidance zone covering a set of tiles.
+#[wasm_bindgen(js_name = clockwork_add_tiles_zone)]
+pub fn js_add_tiles_zone(
+    name: String,
+    packed_positions: Vec<u32>,
+    cost: u8,
+    expires_at: Option<u32>,
+) {
+    let mut tiles: HashMap<RoomName, Vec<RoomXY>> = HashMap::new();
+    for pos in packed_positions {
+        let pos = world_position_from_packed(pos);
+        tiles.entry(pos.room_name()).or_default().push(pos.xy());
+    }
+    with_avoid_zones(|zones| {
+        zones.insert(
+            name,
+            AvoidZone {
+                shape: ZoneShape::Tiles(tiles),
+                cost,
+                expires_at,
+            },
+        )
+    });
+}
+
+/// Removes a zone, returning true if it existed.
+#[wasm_bindgen(js_name = clockwork_remove_zone)]
+pub fn js_remove_zone(name: &str) -> bool {
+    with_avoid_zones(|zones| zones.remove(name).is_some())
+}
+
+/// Lists the names of the zones that haven't expired, in sorted order.
+#[wasm_bindgen(js_name = clockwork_list_zones)]
+pub fn js_list_zones() -> Vec<String> {
+    with_avoid_zones(|zones| {
+        prune_expired(zones);
+        zones.keys().cloned().collect()
+    })
+}
+
+/// Removes every zone.
+#[wasm_bindgen(js_name = clockwork_clear_zones)]
+pub fn js_clear_zones() {
+    with_avoid_zones(|zones| zones.clear());
+}
@@ -0,0 +1,39 @@
+use std::cell::RefCell;
+
+use wasm_bindgen::prelude::*;
+
+/// Hit and miss counts for Clockwork's caches, accumulated since the module
+/// loaded or since the last reset.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    /// Terrain lookups served from the terrain cache
+    pub terrain_hits: u32,
+    /// Terrain lookups that had to fetch terrain from the game
+    pub terrain_misses: u32,
+    /// Room lookups during a search that reused already-loaded room data
+    pub room_data_hits: u32,
+    /// Room lookups during a search that called the cost matrix callback
+    pub room_data_misses: u32,
+}
+
+thread_local! {
+    static CACHE_STATS: RefCell<CacheStats> = RefCell::new(CacheStats::default());
+}
+
+/// Runs a closure with mutable access to the shared cache counters.
+pub fn record_cache_stats(f: impl FnOnce(&mut CacheStats)) {
+    CACHE_STATS.with(|stats| f(&mut stats.borrow_mut()))
+}
+
+/// Returns the current cache hit/miss counts.
+#[wasm_bindgen(js_name = clockwork_cache_stats)]
+pub fn js_cache_stats() -> CacheStats {
+    CACHE_STATS.with(|stats| *stats.borrow())
+}
+
+/// Resets all cache hit/miss counts to zero.
+#[wasm_bindgen(js_name = clockwork_reset_cache_stats)]
+pub fn js_reset_cache_stats() {
+    record_cache_stats(|stats| *stats = CacheStats::default())
+}
@@ -1,32 +1,58 @@
-use screeps::{LocalCostMatrix, LocalRoomTerrain, RoomName, RoomTerrain, Terrain};
+use screeps::{LocalCostMatrix, RoomName, Terrain};
 use wasm_bindgen::{prelude::*, throw_str};
 
 use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::cost_provider::CostProvider;
+use crate::helpers::rooms::world_room_from_packed;
+use crate::helpers::terrain::get_packed_terrain;
 
-#[wasm_bindgen]
-pub fn get_terrain_cost_matrix(
-    room_name: u16,
-    plain_cost: Option<u8>,
-    swamp_cost: Option<u8>,
-    wall_cost: Option<u8>,
-) -> ClockworkCostMatrix {
-    let plain_cost = plain_cost.unwrap_or(1);
-    let swamp_cost = swamp_cost.unwrap_or(5);
-    let wall_cost = wall_cost.unwrap_or(255);
-    let room_name = RoomName::from_packed(room_name);
-    let terrain = RoomTerrain::new(room_name);
-    if terrain.is_none() {
-        throw_str(&format!("Invalid room name: {}", room_name));
-    }
-    let terrain = LocalRoomTerrain::from(terrain.unwrap());
+/// Builds a cost matrix from a room's terrain. Returns `None` if the room
+/// doesn't exist.
+pub fn terrain_cost_matrix(
+    room_name: RoomName,
+    plain_cost: u8,
+    swamp_cost: u8,
+    wall_cost: u8,
+) -> Option<ClockworkCostMatrix> {
+    let terrain = get_packed_terrain(room_name)?;
     let mut local_cost_matrix = LocalCostMatrix::new();
     for (xy, val) in local_cost_matrix.iter_mut() {
-        *val = match terrain.get_xy(xy) {
+        *val = match terrain.get(xy) {
             Terrain::Plain => plain_cost,
             Terrain::Wall => wall_cost,
             Terrain::Swamp => swamp_cost,
         };
     }
 
-    ClockworkCostMatrix::from(local_cost_matrix)
+    Some(ClockworkCostMatrix::from(local_cost_matrix))
+}
+
+#[wasm_bindgen(js_name = clockwork_get_terrain_cost_matrix)]
+pub fn get_terrain_cost_matrix(
+    room_name: u16,
+    plain_cost: Option<u8>,
+    swamp_cost: Option<u8>,
+    wall_cost: Option<u8>,
+) -> ClockworkCostMatrix {
+    let room_name = world_room_from_packed(room_name);
+    match terrain_cost_matrix(
+        room_name,
+        plain_cost.unwrap_or(1),
+        swamp_cost.unwrap_or(5),
+        wall_cost.unwrap_or(255),
+    ) {
+        Some(cost_matrix) => cost_matrix,
+        None => throw_str(&format!("Invalid room name: {}", room_name)),
+    }
+}
+
+/// Wraps a JS cost matrix callback that takes a packed room name, converting
+/// the returned value into a `ClockworkCostMatrix` (or `None` if the callback
+/// returned `undefined`). Results are memoized for the tick if the callback
+/// opted in; see `CostProvider`.
+pub fn packed_room_cost_matrix_callback(
+    get_cost_matrix: &js_sys::Function,
+) -> impl Fn(RoomName) -> Option<ClockworkCostMatrix> + '_ {
+    let provider = CostProvider::packed(get_cost_matrix);
+    move |room| provider.get(room)
 }
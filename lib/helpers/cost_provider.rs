@@ -0,0 +1,208 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use screeps::{game, RoomName};
+use wasm_bindgen::{prelude::*, throw_val};
+
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::rooms::world_room_from_packed;
+use crate::helpers::terrain::get_packed_terrain;
+
+// The most callbacks to remember results for at once
+pub const MAX_CALLBACKS: usize = 16;
+
+/// A cost matrix returned by a callback, along with the JS matrix it was
+/// copied from and that matrix's revision at the time, so changes made to it
+/// afterwards are noticed.
+#[derive(Clone)]
+struct CachedCostMatrix {
+    source: JsValue,
+    revision: u32,
+    cost_matrix: Option<ClockworkCostMatrix>,
+}
+
+impl CachedCostMatrix {
+    fn is_current(&self) -> bool {
+        self.cost_matrix.is_none()
+            || ClockworkCostMatrix::revision_of(&self.source) == Some(self.revision)
+    }
+}
+
+type RoomCostMatrices = HashMap<RoomName, CachedCostMatrix>;
+
+/// The callbacks opted in to memoization, oldest first, and the cost
+/// matrices each has returned this tick.
+#[derive(Default)]
+struct Cache {
+    tick: u32,
+    callbacks: Vec<(js_sys::Function, RoomCostMatrices)>,
+}
+
+impl Cache {
+    /// The results remembered for a callback this tick, if it's memoized.
+    fn rooms(&mut self, get_cost_matrix: &js_sys::Function) -> Option<&mut RoomCostMatrices> {
+        let tick = game::time();
+        if self.tick != tick {
+            self.tick = tick;
+            for (_, rooms) in self.callbacks.iter_mut() {
+                rooms.clear();
+            }
+        }
+        self.callbacks
+            .iter_mut()
+            .find(|(callback, _)| js_sys::Object::is(callback, get_cost_matrix))
+            .map(|(_, rooms)| rooms)
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<Cache> = RefCell::new(Cache::default());
+}
+
+/// Gets cost matrices from a JS callback, converting each one into a
+/// `ClockworkCostMatrix`. Callbacks are called every time a search loads a
+/// room, unless they've been opted in with `memoize`: then each room's result
+/// is shared by every search given the same callback in the same tick, until
+/// the matrix the callback returned is changed.
+pub struct CostProvider<'a> {
+    get_cost_matrix: &'a js_sys::Function,
+    room_argument: fn(RoomName) -> JsValue,
+}
+
+impl<'a> CostProvider<'a> {
+    /// A provider for a callback that takes a packed room name.
+    pub fn packed(get_cost_matrix: &'a js_sys::Function) -> Self {
+        CostProvider {
+            get_cost_matrix,
+            room_argument: |room| JsValue::from_f64(room.packed_repr() as f64),
+        }
+    }
+
+    /// A provider for a callback that takes a room name string.
+    pub fn room_name(get_cost_matrix: &'a js_sys::Function) -> Self {
+        CostProvider {
+            get_cost_matrix,
+            room_argument: |room| JsValue::from_str(&room.to_string()),
+        }
+    }
+
+    /// Opts the callback in to memoization, so its results are shared by
+    /// every search given it for the rest of the tick. Only the most recent
+    /// `MAX_CALLBACKS` callbacks stay memoized.
+    pub fn memoize(&self) {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if cache.rooms(self.get_cost_matrix).is_some() {
+                return;
+            }
+            if cache.callbacks.len() >= MAX_CALLBACKS {
+                cache.callbacks.remove(0);
+            }
+            cache
+                .callbacks
+                .push((self.get_cost_matrix.clone(), HashMap::new()));
+        });
+    }
+
+    /// Gets the cost matrix for a room, or `None` if the callback returned
+    /// `undefined`.
+    pub fn get(&self, room: RoomName) -> Option<ClockworkCostMatrix> {
+        if let Some(cached) = self.cached(room) {
+            return cached;
+        }
+        let cached = self.call(room);
+        let cost_matrix = cached.cost_matrix.clone();
+        CACHE.with(|cache| {
+            if let Some(rooms) = cache.borrow_mut().rooms(self.get_cost_matrix) {
+                rooms.insert(room, cached);
+            }
+        });
+        cost_matrix
+    }
+
+    fn cached(&self, room: RoomName) -> Option<Option<ClockworkCostMatrix>> {
+        let cached = CACHE.with(|cache| {
+            cache
+                .borrow_mut()
+                .rooms(self.get_cost_matrix)
+                .and_then(|rooms| rooms.get(&room).cloned())
+        })?;
+        // Checked outside the cache borrow, since it reads the JS matrix
+        cached.is_current().then_some(cached.cost_matrix)
+    }
+
+    fn call(&self, room: RoomName) -> CachedCostMatrix {
+        let value = match self
+            .get_cost_matrix
+            .call1(&JsValue::null(), &(self.room_argument)(room))
+        {
+            Ok(value) => value,
+            Err(e) => throw_val(e),
+        };
+
+        if value.is_undefined() {
+            return CachedCostMatrix {
+                source: value,
+                revision: 0,
+                cost_matrix: None,
+            };
+        }
+        // Read after the callback returns, so a matrix it built with `set`
+        // is cached at its finished revision
+        let revision = ClockworkCostMatrix::revision_of(&value).unwrap_or(0);
+        let cost_matrix = ClockworkCostMatrix::try_from(value.clone())
+            .ok()
+            .expect_throw("Invalid ClockworkCostMatrix");
+        CachedCostMatrix {
+            source: value,
+            revision,
+            cost_matrix: Some(cost_matrix),
+        }
+    }
+}
+
+/// Forgets every cost matrix remembered this tick, e.g. after changing the
+/// state a cost matrix callback depends on. Memoized callbacks stay memoized.
+#[wasm_bindgen(js_name = clockwork_clear_cost_matrix_cache)]
+pub fn js_clear_cost_matrix_cache() {
+    CACHE.with(|cache| {
+        for (_, rooms) in cache.borrow_mut().callbacks.iter_mut() {
+            rooms.clear();
+        }
+    });
+}
+
+/// Opts a cost matrix callback in to memoization: for the rest of the tick
+/// (and later ticks, while it's among the `MAX_CALLBACKS` most recently
+/// memoized), searches given this callback share the cost matrix it returned
+/// for each room, until that matrix is changed with `set`.
+#[wasm_bindgen(js_name = clockwork_memoize_cost_matrices)]
+pub fn js_memoize_cost_matrices(get_cost_matrix: &js_sys::Function) {
+    CostProvider::packed(get_cost_matrix).memoize();
+}
+
+/// Loads the terrain and cost matrix of each room ahead of a search, so the
+/// search's own loads are cache hits. The callback takes packed room names,
+/// must be the same function the search is given, and is memoized (see
+/// `js_memoize_cost_matrices`).
+///
+/// # Arguments
+/// * `room_names` - The packed names of the rooms to load
+/// * `get_cost_matrix` - JavaScript function that returns cost matrices for rooms
+///
+/// # Returns
+/// The number of rooms the callback returned a cost matrix for
+#[wasm_bindgen(js_name = clockwork_prefetch_rooms)]
+pub fn js_prefetch_rooms(room_names: Vec<u16>, get_cost_matrix: &js_sys::Function) -> usize {
+    let provider = CostProvider::packed(get_cost_matrix);
+    provider.memoize();
+    room_names
+        .into_iter()
+        .map(world_room_from_packed)
+        .filter(|room| {
+            get_packed_terrain(*room);
+            provider.get(*room).is_some()
+        })
+        .count()
+}
@@ -0,0 +1,37 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use screeps::{game, RoomName};
+use wasm_bindgen::prelude::*;
+
+use crate::datatypes::ClockworkCostMatrix;
+
+thread_local! {
+    // The last cost matrix seen for each room, and the tick it was seen on.
+    static KNOWN_ROOMS: RefCell<HashMap<RoomName, (u32, ClockworkCostMatrix)>> = RefCell::new(HashMap::new());
+}
+
+/// Records the cost matrix a search received for a room, so later searches
+/// can fall back to it when the room has no vision.
+pub fn remember_cost_matrix(room_name: RoomName, cost_matrix: &ClockworkCostMatrix) {
+    let now = game::time();
+    KNOWN_ROOMS.with(|rooms| {
+        rooms
+            .borrow_mut()
+            .insert(room_name, (now, cost_matrix.clone()))
+    });
+}
+
+/// Gets the last cost matrix recorded for a room, if it was recorded no more
+/// than `max_age` ticks ago.
+pub fn last_known_cost_matrix(room_name: RoomName, max_age: u32) -> Option<ClockworkCostMatrix> {
+    let (seen_at, cost_matrix) =
+        KNOWN_ROOMS.with(|rooms| rooms.borrow().get(&room_name).cloned())?;
+    (game::time().saturating_sub(seen_at) <= max_age).then_some(cost_matrix)
+}
+
+/// Forgets every recorded cost matrix.
+#[wasm_bindgen(js_name = clockwork_forget_known_rooms)]
+pub fn js_forget_known_rooms() {
+    KNOWN_ROOMS.with(|rooms| rooms.borrow_mut().clear());
+}
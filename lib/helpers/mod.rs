@@ -1,2 +1,11 @@
+pub mod avoid_zones;
+pub mod cache_stats;
 pub mod cost_matrix;
-pub mod profiler;
\ No newline at end of file
+pub mod cost_provider;
+pub mod known_rooms;
+pub mod positions;
+#[allow(dead_code)]
+pub mod profiler;
+pub mod rooms;
+pub mod search_load;
+pub mod terrain;
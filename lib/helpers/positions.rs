@@ -0,0 +1,104 @@
+use std::convert::TryFrom;
+
+use screeps::{Position, RoomCoordinate, RoomName};
+use serde::Deserialize;
+use wasm_bindgen::{prelude::*, throw_str};
+
+use crate::algorithms::distance_map::goals::{Goal, GoalKind};
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::cost_provider::CostProvider;
+use crate::helpers::rooms::{in_world, outside_world_error};
+
+/// A position as a plain JS object. `RoomPosition` instances also match
+/// this shape, since fields are read by name (including prototype getters).
+#[derive(Deserialize)]
+struct PositionObject {
+    x: u8,
+    y: u8,
+    #[serde(rename = "roomName")]
+    room_name: String,
+}
+
+/// A destination with a target range, as a plain JS object. The goal kind
+/// defaults to `GoalKind.Range`.
+#[derive(Deserialize)]
+struct DestinationObject {
+    pos: PositionObject,
+    #[serde(default)]
+    range: usize,
+    #[serde(default)]
+    kind: Option<u32>,
+}
+
+impl TryFrom<PositionObject> for Position {
+    type Error = String;
+
+    fn try_from(value: PositionObject) -> Result<Self, Self::Error> {
+        let x = RoomCoordinate::new(value.x)
+            .map_err(|_| format!("Invalid x coordinate: {}", value.x))?;
+        let y = RoomCoordinate::new(value.y)
+            .map_err(|_| format!("Invalid y coordinate: {}", value.y))?;
+        let room_name = RoomName::new(&value.room_name)
+            .map_err(|_| format!("Invalid room name: {}", value.room_name))?;
+        if !in_world(room_name) {
+            return Err(outside_world_error(room_name));
+        }
+        Ok(Position::new(x, y, room_name))
+    }
+}
+
+/// Converts a `{x, y, roomName}` object into a Position, throwing a JS error
+/// if the object is malformed.
+pub fn position_from_js(value: JsValue) -> Position {
+    let object: PositionObject = serde_wasm_bindgen::from_value(value)
+        .unwrap_or_else(|e| throw_str(&format!("Invalid position: {}", e)));
+    Position::try_from(object).unwrap_or_else(|e| throw_str(&e))
+}
+
+/// Converts an array of `{x, y, roomName}` objects into Positions.
+pub fn positions_from_js(value: JsValue) -> Vec<Position> {
+    let objects: Vec<PositionObject> = serde_wasm_bindgen::from_value(value)
+        .unwrap_or_else(|e| throw_str(&format!("Invalid positions: {}", e)));
+    objects
+        .into_iter()
+        .map(|object| Position::try_from(object).unwrap_or_else(|e| throw_str(&e)))
+        .collect()
+}
+
+/// Converts an optional array of `{pos: {x, y, roomName}, range, kind}` objects
+/// into goals. `undefined` or `null` yields `None`.
+pub fn destinations_from_js(value: JsValue) -> Option<Vec<Goal>> {
+    if value.is_undefined() || value.is_null() {
+        return None;
+    }
+    let objects: Vec<DestinationObject> = serde_wasm_bindgen::from_value(value)
+        .unwrap_or_else(|e| throw_str(&format!("Invalid destinations: {}", e)));
+    Some(
+        objects
+            .into_iter()
+            .map(|object| {
+                let pos = Position::try_from(object.pos).unwrap_or_else(|e| throw_str(&e));
+                let kind = object
+                    .kind
+                    .map(|kind| GoalKind::try_from(kind).unwrap_or_else(|e| throw_str(&e)))
+                    .unwrap_or(GoalKind::Range);
+                Goal::new(pos, object.range, kind)
+            })
+            .collect(),
+    )
+}
+
+/// Wraps a JS cost matrix callback that takes a room name string (rather
+/// than a packed room name).
+pub fn room_name_cost_matrix_callback(
+    get_cost_matrix: &js_sys::Function,
+) -> impl Fn(RoomName) -> Option<ClockworkCostMatrix> + '_ {
+    let provider = CostProvider::room_name(get_cost_matrix);
+    move |room| provider.get(room)
+}
+
+/// Exports the global range calculation between two `{x, y, roomName}` positions.
+#[wasm_bindgen(js_name = clockwork_get_range_between_positions)]
+pub fn get_range_between_positions(pos_1: JsValue, pos_2: JsValue) -> u32 {
+    position_from_js(pos_1).get_range_to(position_from_js(pos_2))
+}
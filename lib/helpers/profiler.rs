@@ -57,10 +57,8 @@ impl Profiler {
         let next_entry = self.next_entry.load(Ordering::Relaxed) as usize;
         
         // Fast path - check existing entries
-        for i in 0..next_entry {
-            if entries[i].name == name {
-                return i;
-            }
+        if let Some(i) = entries[..next_entry].iter().position(|e| e.name == name) {
+            return i;
         }
         
         // Create new entry if space available
@@ -198,9 +196,7 @@ impl Profiler {
             ));
         }
 
-        unsafe {
-            log(&table);
-        }
+        log(&table);
     }
 
     pub fn reset(&self) {
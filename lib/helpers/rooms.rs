@@ -0,0 +1,173 @@
+use std::cell::Cell;
+
+use screeps::{Direction, Position, RoomName};
+use wasm_bindgen::{prelude::*, throw_str};
+
+/// The most rooms along each side of the world that room names can address.
+/// Private servers can be smaller; see `js_set_world_size`.
+pub const MAX_WORLD_SIZE: u32 = 256;
+
+thread_local! {
+    static WORLD_SIZE: Cell<u32> = const { Cell::new(MAX_WORLD_SIZE) };
+}
+
+/// The number of rooms along each side of the world.
+pub fn world_size() -> u32 {
+    WORLD_SIZE.with(Cell::get)
+}
+
+/// Whether a room is inside the world, which is centered on the corner
+/// where W0, E0, N0, and S0 meet.
+pub fn in_world(room_name: RoomName) -> bool {
+    let half = world_size() as i32 / 2;
+    (-half..half).contains(&room_name.x_coord()) && (-half..half).contains(&room_name.y_coord())
+}
+
+/// The room `offset` rooms away, or `None` if it's beyond the edge of the
+/// world.
+pub fn room_at_offset(room_name: RoomName, offset: (i32, i32)) -> Option<RoomName> {
+    room_name.checked_add(offset).filter(|room| in_world(*room))
+}
+
+/// The error for a room from JS that's outside the world.
+pub fn outside_world_error(room_name: RoomName) -> String {
+    format!(
+        "Room {} is outside the world ({} rooms across)",
+        room_name,
+        world_size()
+    )
+}
+
+/// Unpacks a room name passed from JS, throwing if it's outside the world.
+pub fn world_room_from_packed(packed: u16) -> RoomName {
+    let room_name = RoomName::from_packed(packed);
+    if !in_world(room_name) {
+        throw_str(&outside_world_error(room_name));
+    }
+    room_name
+}
+
+/// Unpacks a position passed from JS, throwing if its room is outside the
+/// world.
+pub fn world_position_from_packed(packed: u32) -> Position {
+    let position = Position::from_packed(packed);
+    if !in_world(position.room_name()) {
+        throw_str(&outside_world_error(position.room_name()));
+    }
+    position
+}
+
+/// Sets the number of rooms along each side of the world, for private
+/// servers smaller than the official ones. Rooms beyond the edges are then
+/// skipped when walking between rooms, and room names from JS that are
+/// outside the world are rejected.
+///
+/// # Arguments
+/// * `size` - The world's width in rooms: an even number from 2 to 256
+#[wasm_bindgen(js_name = clockwork_set_world_size)]
+pub fn js_set_world_size(size: u32) {
+    if !(2..=MAX_WORLD_SIZE).contains(&size) || !size.is_multiple_of(2) {
+        throw_str(&format!(
+            "World size must be an even number from 2 to {}, got {}",
+            MAX_WORLD_SIZE, size
+        ));
+    }
+    WORLD_SIZE.with(|world_size| world_size.set(size));
+}
+
+/// The room offset for each exit direction, in the order the game lists exits.
+const EXITS: [(Direction, (i32, i32)); 4] = [
+    (Direction::Top, (0, -1)),
+    (Direction::Right, (1, 0)),
+    (Direction::Bottom, (0, 1)),
+    (Direction::Left, (-1, 0)),
+];
+
+/// The rooms bordering `room_name` (top, right, bottom, left), skipping any
+/// beyond the edge of the world. Whether the rooms exist or are connected by
+/// exits isn't checked.
+pub fn adjacent_rooms(room_name: RoomName) -> Vec<(Direction, RoomName)> {
+    EXITS
+        .iter()
+        .filter_map(|(direction, offset)| {
+            room_at_offset(room_name, *offset).map(|neighbor| (*direction, neighbor))
+        })
+        .collect()
+}
+
+/// The direction of the exit from `from` into `to`, or `None` if the rooms
+/// don't share a border.
+pub fn exit_direction_between(from: RoomName, to: RoomName) -> Option<Direction> {
+    adjacent_rooms(from)
+        .into_iter()
+        .find(|(_, neighbor)| *neighbor == to)
+        .map(|(direction, _)| direction)
+}
+
+/// The rooms along straight lines from `from` to each of `to`, plus any
+/// within `width` rooms of those lines, in the order they're reached.
+/// Includes `from` and every room in `to`.
+pub fn corridor_rooms(from: RoomName, to: &[RoomName], width: u32) -> Vec<RoomName> {
+    let width = width as i32;
+    let mut rooms = vec![from];
+    let mut add = |room: RoomName| {
+        if !rooms.contains(&room) {
+            rooms.push(room);
+        }
+    };
+    for goal in to {
+        let (dx, dy) = *goal - from;
+        let steps = dx.abs().max(dy.abs()).max(1);
+        for step in 0..=steps {
+            let x = (dx as f64 * step as f64 / steps as f64).round() as i32;
+            let y = (dy as f64 * step as f64 / steps as f64).round() as i32;
+            for oy in -width..=width {
+                for ox in -width..=width {
+                    if let Some(room) = room_at_offset(from, (x + ox, y + oy)) {
+                        add(room);
+                    }
+                }
+            }
+        }
+    }
+    rooms
+}
+
+/// Gets the packed names of the rooms bordering a room, in top, right,
+/// bottom, left order.
+#[wasm_bindgen(js_name = clockwork_adjacent_rooms)]
+pub fn js_adjacent_rooms(room_name: u16) -> Vec<u16> {
+    adjacent_rooms(world_room_from_packed(room_name))
+        .into_iter()
+        .map(|(_, neighbor)| neighbor.packed_repr())
+        .collect()
+}
+
+/// Gets the direction of the exit from one room into another, or `undefined`
+/// if they aren't adjacent.
+#[wasm_bindgen(js_name = clockwork_exit_direction_between)]
+pub fn js_exit_direction_between(room_a: u16, room_b: u16) -> Option<Direction> {
+    exit_direction_between(
+        world_room_from_packed(room_a),
+        world_room_from_packed(room_b),
+    )
+}
+
+/// Gets the packed names of the rooms in the corridor between a room and
+/// some goal rooms, for prefetching before a long search.
+///
+/// # Arguments
+/// * `from` - The packed name of the origin room
+/// * `to` - The packed names of the goal rooms
+/// * `width` - How many rooms either side of the straight lines to include
+///
+/// # Returns
+/// The packed room names, in the order the corridor reaches them
+#[wasm_bindgen(js_name = clockwork_corridor_rooms)]
+pub fn js_corridor_rooms(from: u16, to: Vec<u16>, width: u32) -> Vec<u16> {
+    let to: Vec<RoomName> = to.into_iter().map(world_room_from_packed).collect();
+    corridor_rooms(world_room_from_packed(from), &to, width)
+        .into_iter()
+        .map(|room| room.packed_repr())
+        .collect()
+}
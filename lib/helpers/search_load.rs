@@ -0,0 +1,99 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use screeps::{game, RoomName};
+use wasm_bindgen::prelude::*;
+
+#[derive(Default)]
+struct RoomLoad {
+    expanded: u32,
+    cpu: f64,
+}
+
+#[derive(Default)]
+struct SearchLoad {
+    tick: u32,
+    rooms: HashMap<RoomName, RoomLoad>,
+}
+
+thread_local! {
+    static SEARCH_LOAD: RefCell<SearchLoad> = RefCell::new(SearchLoad::default());
+}
+
+/// Adds a finished search to this tick's per-room totals. Only the search's
+/// total CPU is measured, so it's split between rooms by the share of tiles
+/// expanded in each.
+pub fn record_search_load(rooms: impl Iterator<Item = (RoomName, u32)>, cpu: f64) {
+    let rooms: Vec<(RoomName, u32)> = rooms.filter(|(_, expanded)| *expanded > 0).collect();
+    let total: u32 = rooms.iter().map(|(_, expanded)| expanded).sum();
+    if total == 0 {
+        return;
+    }
+    let tick = game::time();
+    SEARCH_LOAD.with(|load| {
+        let mut load = load.borrow_mut();
+        if load.tick != tick {
+            load.tick = tick;
+            load.rooms.clear();
+        }
+        for (room, expanded) in rooms {
+            let room_load = load.rooms.entry(room).or_default();
+            room_load.expanded += expanded;
+            room_load.cpu += cpu * expanded as f64 / total as f64;
+        }
+    });
+}
+
+/// The tiles expanded and CPU spent in each room by this tick's searches.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct RoomSearchLoad {
+    rooms: Vec<u16>,
+    expanded: Vec<u32>,
+    cpu: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl RoomSearchLoad {
+    /// The packed names of the rooms searched this tick.
+    #[wasm_bindgen(getter)]
+    pub fn rooms(&self) -> Vec<u16> {
+        self.rooms.clone()
+    }
+
+    /// The number of tiles expanded in each room.
+    #[wasm_bindgen(getter)]
+    pub fn expanded(&self) -> Vec<u32> {
+        self.expanded.clone()
+    }
+
+    /// The estimated CPU spent in each room.
+    #[wasm_bindgen(getter)]
+    pub fn cpu(&self) -> Vec<f64> {
+        self.cpu.clone()
+    }
+}
+
+/// Reports how much search work each room took this tick, so rooms that eat
+/// pathfinding CPU (e.g. a swampy remote) can be found and given roads or
+/// better cost matrices. Covers the A*, Dijkstra, BFS, and flee searches.
+///
+/// # Returns
+/// The rooms searched this tick, with tiles expanded and estimated CPU for each
+#[wasm_bindgen(js_name = clockwork_room_search_load)]
+pub fn js_room_search_load() -> RoomSearchLoad {
+    let tick = game::time();
+    SEARCH_LOAD.with(|load| {
+        let load = load.borrow();
+        let mut report = RoomSearchLoad::default();
+        if load.tick != tick {
+            return report;
+        }
+        for (room, room_load) in load.rooms.iter() {
+            report.rooms.push(room.packed_repr());
+            report.expanded.push(room_load.expanded);
+            report.cpu.push(room_load.cpu);
+        }
+        report
+    })
+}
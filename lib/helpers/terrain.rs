@@ -0,0 +1,141 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use screeps::{LocalRoomTerrain, RoomName, RoomTerrain, RoomXY, Terrain};
+use wasm_bindgen::{prelude::*, throw_str};
+
+use crate::datatypes::PackedTerrain;
+use crate::helpers::cache_stats::record_cache_stats;
+use crate::helpers::rooms::world_room_from_packed;
+
+thread_local! {
+    // Terrain never changes, so once a room has been fetched it can be
+    // served from here for the lifetime of the module.
+    static TERRAIN_CACHE: RefCell<HashMap<RoomName, PackedTerrain>> = RefCell::new(HashMap::new());
+    // Exit widths are derived from terrain, so they never change either
+    static EXIT_CACHE: RefCell<HashMap<RoomName, [u32; 4]>> = RefCell::new(HashMap::new());
+}
+
+/// Gets the packed terrain for a room, fetching it from the game (and caching
+/// it) the first time it's requested. Returns `None` if the room doesn't exist.
+pub fn get_packed_terrain(room_name: RoomName) -> Option<PackedTerrain> {
+    if let Some(terrain) = TERRAIN_CACHE.with(|cache| cache.borrow().get(&room_name).cloned()) {
+        record_cache_stats(|stats| stats.terrain_hits += 1);
+        return Some(terrain);
+    }
+    record_cache_stats(|stats| stats.terrain_misses += 1);
+    let terrain = PackedTerrain::from(&LocalRoomTerrain::from(RoomTerrain::new(room_name)?));
+    TERRAIN_CACHE.with(|cache| {
+        cache.borrow_mut().insert(room_name, terrain.clone());
+    });
+    Some(terrain)
+}
+
+/// Gets the number of exit tiles on each side of a room (top, right, bottom,
+/// left), computing them from the room's terrain (and caching them) the first
+/// time they're requested. Returns `None` if the room doesn't exist.
+pub fn get_exit_widths(room_name: RoomName) -> Option<[u32; 4]> {
+    if let Some(widths) = EXIT_CACHE.with(|cache| cache.borrow().get(&room_name).copied()) {
+        return Some(widths);
+    }
+    let terrain = get_packed_terrain(room_name)?;
+    let sides: [fn(u8) -> (u8, u8); 4] = [|i| (i, 0), |i| (49, i), |i| (i, 49), |i| (0, i)];
+    let mut widths = [0; 4];
+    for (width, side) in widths.iter_mut().zip(sides.iter()) {
+        // Corner tiles aren't exits
+        *width = (1..49)
+            .map(side)
+            .filter(|(x, y)| {
+                // SAFETY: x and y are both in the range 0..50
+                terrain.get(unsafe { RoomXY::unchecked_new(*x, *y) }) != Terrain::Wall
+            })
+            .count() as u32;
+    }
+    EXIT_CACHE.with(|cache| {
+        cache.borrow_mut().insert(room_name, widths);
+    });
+    Some(widths)
+}
+
+/// Per-room data that can be computed ahead of time with
+/// `js_preprocess_rooms`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PreprocessKind {
+    /// The room's packed terrain.
+    Terrain = 0,
+    /// The number of exit tiles on each side of the room.
+    Exits = 1,
+}
+
+impl TryFrom<u32> for PreprocessKind {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PreprocessKind::Terrain),
+            1 => Ok(PreprocessKind::Exits),
+            _ => Err(format!("Invalid preprocess kind: {}", value)),
+        }
+    }
+}
+
+/// Gets the packed terrain buffer for a room (2 bits per tile, row-major,
+/// four tiles per byte). The same cached terrain is used to build terrain
+/// cost matrices, so this avoids a second `Game.map.getRoomTerrain` call.
+#[wasm_bindgen(js_name = clockwork_get_packed_terrain)]
+pub fn js_get_packed_terrain(room_name: u16) -> Vec<u8> {
+    let room_name = world_room_from_packed(room_name);
+    match get_packed_terrain(room_name) {
+        Some(terrain) => terrain.as_bytes().to_vec(),
+        None => throw_str(&format!("Invalid room name: {}", room_name)),
+    }
+}
+
+/// Gets the number of exit tiles on each side of a room, in top, right,
+/// bottom, left order.
+#[wasm_bindgen(js_name = clockwork_exit_widths)]
+pub fn js_exit_widths(room_name: u16) -> Vec<u32> {
+    let room_name = world_room_from_packed(room_name);
+    match get_exit_widths(room_name) {
+        Some(widths) => widths.to_vec(),
+        None => throw_str(&format!("Invalid room name: {}", room_name)),
+    }
+}
+
+/// Computes and caches the requested data for every room in one call, so a
+/// batch of rooms costs a single trip into wasm rather than one per room and
+/// kind.
+///
+/// # Arguments
+/// * `room_names` - The packed names of the rooms to preprocess
+/// * `kinds` - The `PreprocessKind` values to compute for each room
+///
+/// # Returns
+/// A status per room: a bitmask with bit `kind` set for each kind that's now
+/// cached, or 0 if the room doesn't exist
+#[wasm_bindgen(js_name = clockwork_preprocess_rooms)]
+pub fn js_preprocess_rooms(room_names: Vec<u16>, kinds: Vec<u32>) -> Vec<u8> {
+    let kinds: Vec<PreprocessKind> = kinds
+        .into_iter()
+        .map(|kind| PreprocessKind::try_from(kind).unwrap_or_else(|e| throw_str(&e)))
+        .collect();
+    room_names
+        .into_iter()
+        .map(|room_name| {
+            let room_name = world_room_from_packed(room_name);
+            kinds.iter().fold(0, |status, kind| {
+                let ready = match kind {
+                    PreprocessKind::Terrain => get_packed_terrain(room_name).is_some(),
+                    PreprocessKind::Exits => get_exit_widths(room_name).is_some(),
+                };
+                if ready {
+                    status | (1 << *kind as u8)
+                } else {
+                    status
+                }
+            })
+        })
+        .collect()
+}
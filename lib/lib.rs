@@ -1,9 +1,10 @@
 mod algorithms;
 mod datatypes;
 mod helpers;
+mod scenarios;
 mod utils;
 
-use screeps::Position;
+use screeps::{Position, ROOM_AREA, ROOM_SIZE};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -12,13 +13,120 @@ extern "C" {
     fn log(s: &str);
 }
 
-#[wasm_bindgen]
+use crate::algorithms::distance_map::MAX_RECORDED_EXPANSIONS;
+use crate::helpers::cost_provider::MAX_CALLBACKS;
+use crate::helpers::rooms::world_size;
+
+// Stable names for the search algorithms in this build. Entries are only
+// ever added, so callers can check for one before relying on it.
+const ALGORITHMS: &[&str] = &[
+    "anytime_astar",
+    "astar",
+    "best_origin",
+    "bfs",
+    "closest_of",
+    "depth_map",
+    "dijkstra",
+    "disperse",
+    "flee",
+    "flow_field",
+    "fringe_search",
+    "grow_region",
+    "logistics",
+    "mono_flow_field",
+    "patrol_loop",
+    "repair_tour",
+    "room_astar",
+    "search_with_fallback",
+    "waypoint_path",
+];
+
+#[wasm_bindgen(js_name = clockwork_version)]
 pub fn version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
-/// Exports the global range calculation between two positions.
+/// The version, features, algorithms, and limits of this build.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct ClockworkInfo {
+    features: Vec<String>,
+}
+
 #[wasm_bindgen]
+impl ClockworkInfo {
+    /// The crate version.
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> String {
+        version()
+    }
+
+    /// The Cargo features the wasm was built with.
+    #[wasm_bindgen(getter)]
+    pub fn features(&self) -> Vec<String> {
+        self.features.clone()
+    }
+
+    /// The names of the available search algorithms.
+    #[wasm_bindgen(getter)]
+    pub fn algorithms(&self) -> Vec<String> {
+        ALGORITHMS.iter().map(|name| name.to_string()).collect()
+    }
+
+    /// The width and height of a room, in tiles.
+    #[wasm_bindgen(getter)]
+    pub fn room_size(&self) -> u32 {
+        ROOM_SIZE as u32
+    }
+
+    /// The number of tiles in a room.
+    #[wasm_bindgen(getter)]
+    pub fn room_area(&self) -> u32 {
+        ROOM_AREA as u32
+    }
+
+    /// The number of rooms along each side of the world, as set by
+    /// `js_set_world_size` (256 unless changed).
+    #[wasm_bindgen(getter)]
+    pub fn world_size(&self) -> u32 {
+        world_size()
+    }
+
+    /// The most rooms a search can visit: every room in the world.
+    #[wasm_bindgen(getter)]
+    pub fn max_rooms(&self) -> u32 {
+        world_size() * world_size()
+    }
+
+    /// The most expanded positions a search will record.
+    #[wasm_bindgen(getter)]
+    pub fn max_recorded_expansions(&self) -> u32 {
+        MAX_RECORDED_EXPANSIONS as u32
+    }
+
+    /// The most cost matrix callbacks whose results are cached in a tick.
+    #[wasm_bindgen(getter)]
+    pub fn max_cached_callbacks(&self) -> u32 {
+        MAX_CALLBACKS as u32
+    }
+}
+
+/// Describes this build, so code bundling the wasm can check what it
+/// supports instead of guessing which exports exist.
+///
+/// # Returns
+/// The version, enabled features, available algorithms, and limits
+#[wasm_bindgen(js_name = clockwork_info)]
+pub fn js_clockwork_info() -> ClockworkInfo {
+    let mut features = Vec::new();
+    if cfg!(feature = "console_error_panic_hook") {
+        features.push("console_error_panic_hook".to_string());
+    }
+    ClockworkInfo { features }
+}
+
+/// Exports the global range calculation between two positions.
+#[wasm_bindgen(js_name = clockwork_get_range)]
 pub fn get_range(packed_pos_1: u32, packed_pos_2: u32) -> u32 {
     let pos1 = Position::from_packed(packed_pos_1);
     let pos2 = Position::from_packed(packed_pos_2);
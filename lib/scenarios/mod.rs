@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use screeps::{Position, RoomName, RoomXY};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::throw_str;
+
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::rooms::{room_at_offset, world_room_from_packed};
+
+pub mod noise;
+
+use noise::world_noise;
+
+const PLAIN: u8 = 1;
+const SWAMP: u8 = 5;
+const WALL: u8 = 255;
+
+/// The kinds of synthetic world that can be generated for benchmarking.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioKind {
+    /// Every tile is plain terrain.
+    OpenPlains = 0,
+    /// Each room is a single-width maze with an open border, so rooms connect
+    /// along every edge.
+    Maze = 1,
+    /// Plains crossed by horizontal bands of swamp.
+    SwampBelts = 2,
+    /// Plains with a walled compound in the center of each room, with one
+    /// gap in each side of the wall.
+    WalledBase = 3,
+    /// Plains with noise-generated patches of swamp and walls that continue
+    /// seamlessly across room borders.
+    Rough = 4,
+}
+
+impl TryFrom<u32> for ScenarioKind {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ScenarioKind::OpenPlains),
+            1 => Ok(ScenarioKind::Maze),
+            2 => Ok(ScenarioKind::SwampBelts),
+            3 => Ok(ScenarioKind::WalledBase),
+            4 => Ok(ScenarioKind::Rough),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A small xorshift generator, so scenarios are reproducible from a seed
+/// without depending on the game's RNG.
+struct Rng(u32);
+
+impl Rng {
+    fn new(seed: u32) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    fn below(&mut self, max: u32) -> u32 {
+        self.next() % max
+    }
+}
+
+fn xy(x: u8, y: u8) -> RoomXY {
+    // SAFETY: all generator coordinates are in the range 0..50
+    unsafe { RoomXY::unchecked_new(x, y) }
+}
+
+fn open_plains() -> ClockworkCostMatrix {
+    ClockworkCostMatrix::new(Some(PLAIN))
+}
+
+fn swamp_belts(rng: &mut Rng) -> ClockworkCostMatrix {
+    let mut matrix = open_plains();
+    let mut y = 2 + rng.below(6) as u8;
+    while y < 48 {
+        let width = 2 + rng.below(4) as u8;
+        for row in y..(y + width).min(48) {
+            for x in 1..49 {
+                matrix.set(xy(x, row), SWAMP);
+            }
+        }
+        y += width + 4 + rng.below(6) as u8;
+    }
+    matrix
+}
+
+fn walled_base(rng: &mut Rng) -> ClockworkCostMatrix {
+    let mut matrix = open_plains();
+    let (min, max) = (15, 34);
+    for i in min..=max {
+        for (x, y) in [(i, min), (i, max), (min, i), (max, i)] {
+            matrix.set(xy(x, y), WALL);
+        }
+    }
+    // One gap per side, placed randomly away from the corners
+    let gap = |rng: &mut Rng| min + 2 + rng.below((max - min - 3) as u32) as u8;
+    let (top, bottom, left, right) = (gap(rng), gap(rng), gap(rng), gap(rng));
+    matrix.set(xy(top, min), PLAIN);
+    matrix.set(xy(bottom, max), PLAIN);
+    matrix.set(xy(min, left), PLAIN);
+    matrix.set(xy(max, right), PLAIN);
+    matrix
+}
+
+fn rough(room_name: RoomName, seed: u32) -> ClockworkCostMatrix {
+    let mut matrix = open_plains();
+    for y in 0..50 {
+        for x in 0..50 {
+            let xy = xy(x, y);
+            let noise = world_noise(seed, Position::new(xy.x, xy.y, room_name), 12.0, 3);
+            if noise > 0.75 {
+                matrix.set(xy, WALL);
+            } else if noise > 0.55 {
+                matrix.set(xy, SWAMP);
+            }
+        }
+    }
+    matrix
+}
+
+fn maze(rng: &mut Rng) -> ClockworkCostMatrix {
+    // Maze cells sit on odd coordinates from 1 to 47; the walls between them
+    // are knocked out by a randomized depth-first search.
+    const CELLS: u8 = 24;
+    let mut matrix = ClockworkCostMatrix::new(Some(WALL));
+    for i in 0..50 {
+        for (x, y) in [(i, 0), (i, 49), (0, i), (49, i)] {
+            matrix.set(xy(x, y), PLAIN);
+        }
+    }
+
+    let mut visited = [[false; CELLS as usize]; CELLS as usize];
+    let mut stack = vec![(0u8, 0u8)];
+    visited[0][0] = true;
+    matrix.set(xy(1, 1), PLAIN);
+    while let Some(&(cx, cy)) = stack.last() {
+        let unvisited: Vec<(u8, u8)> = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .map(|(dx, dy)| (cx as i8 + dx, cy as i8 + dy))
+            .filter(|(nx, ny)| (0..CELLS as i8).contains(nx) && (0..CELLS as i8).contains(ny))
+            .map(|(nx, ny)| (nx as u8, ny as u8))
+            .filter(|(nx, ny)| !visited[*nx as usize][*ny as usize])
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, ny) = unvisited[rng.below(unvisited.len() as u32) as usize];
+        visited[nx as usize][ny as usize] = true;
+        matrix.set(xy(nx * 2 + 1, ny * 2 + 1), PLAIN);
+        matrix.set(xy(cx + nx + 1, cy + ny + 1), PLAIN);
+        stack.push((nx, ny));
+    }
+    matrix
+}
+
+/// A synthetic world: a rectangle of rooms, each with a generated cost matrix.
+#[wasm_bindgen]
+pub struct Scenario {
+    rooms: HashMap<RoomName, ClockworkCostMatrix>,
+}
+
+impl Scenario {
+    /// Generates `width` x `height` rooms of the given kind, starting at
+    /// `top_left` and extending east and south. The same seed always produces
+    /// the same world.
+    pub fn generate(
+        kind: ScenarioKind,
+        top_left: RoomName,
+        width: u8,
+        height: u8,
+        seed: u32,
+    ) -> Scenario {
+        let mut rng = Rng::new(seed);
+        let mut rooms = HashMap::new();
+        for dy in 0..height as i32 {
+            for dx in 0..width as i32 {
+                let Some(room_name) = room_at_offset(top_left, (dx, dy)) else {
+                    continue;
+                };
+                let matrix = match kind {
+                    ScenarioKind::OpenPlains => open_plains(),
+                    ScenarioKind::Maze => maze(&mut rng),
+                    ScenarioKind::SwampBelts => swamp_belts(&mut rng),
+                    ScenarioKind::WalledBase => walled_base(&mut rng),
+                    ScenarioKind::Rough => rough(room_name, seed),
+                };
+                rooms.insert(room_name, matrix);
+            }
+        }
+        Scenario { rooms }
+    }
+
+    /// Gets the cost matrix for a room, if it's part of the scenario.
+    pub fn get(&self, room_name: RoomName) -> Option<ClockworkCostMatrix> {
+        self.rooms.get(&room_name).cloned()
+    }
+}
+
+#[wasm_bindgen]
+impl Scenario {
+    /// Gets the cost matrix for a packed room name, if it's part of the
+    /// scenario. Suitable for use as a search's cost matrix callback.
+    #[wasm_bindgen(js_name = get)]
+    pub fn js_get(&self, room_name: u16) -> Option<ClockworkCostMatrix> {
+        self.get(world_room_from_packed(room_name))
+    }
+
+    /// Lists the packed room names in the scenario.
+    #[wasm_bindgen(js_name = rooms)]
+    pub fn js_rooms(&self) -> Vec<u16> {
+        self.rooms.keys().map(|room| room.packed_repr()).collect()
+    }
+}
+
+/// Generates a synthetic world for benchmarking.
+#[wasm_bindgen(js_name = clockwork_generate_scenario)]
+pub fn js_generate_scenario(
+    kind: u32,
+    top_left: u16,
+    width: u8,
+    height: u8,
+    seed: u32,
+) -> Scenario {
+    let kind = ScenarioKind::try_from(kind)
+        .unwrap_or_else(|_| throw_str(&format!("Invalid scenario kind: {}", kind)));
+    Scenario::generate(kind, world_room_from_packed(top_left), width, height, seed)
+}
@@ -0,0 +1,85 @@
+use screeps::{Position, RoomName, RoomXY};
+use wasm_bindgen::prelude::*;
+
+use crate::datatypes::ClockworkCostMatrix;
+use crate::helpers::rooms::world_room_from_packed;
+
+/// Hashes a lattice point to a value in `[0, 1)`.
+fn lattice(seed: u32, x: i32, y: i32) -> f64 {
+    let mut h = seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    h = (h ^ (h >> 15)).wrapping_mul(0x85eb_ca6b);
+    h = (h ^ (h >> 13)).wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h as f64 / (u32::MAX as f64 + 1.0)
+}
+
+fn smoothstep(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Smoothly interpolated value noise at a point, in `[0, 1)`. Lattice points
+/// are `scale` units apart.
+fn value_noise(seed: u32, x: f64, y: f64, scale: f64) -> f64 {
+    let (x, y) = (x / scale, y / scale);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (smoothstep(x - x0), smoothstep(y - y0));
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let top = lattice(seed, x0, y0) * (1.0 - tx) + lattice(seed, x0 + 1, y0) * tx;
+    let bottom = lattice(seed, x0, y0 + 1) * (1.0 - tx) + lattice(seed, x0 + 1, y0 + 1) * tx;
+    top * (1.0 - ty) + bottom * ty
+}
+
+/// Fractal value noise at a world position, in `[0, 1)`. Each octave halves
+/// the scale and the weight of the previous one. Noise is sampled in world
+/// coordinates, so adjacent rooms line up seamlessly.
+pub fn world_noise(seed: u32, position: Position, scale: f64, octaves: u8) -> f64 {
+    let (x, y) = position.world_coords();
+    let (mut total, mut weight, mut weights, mut scale) = (0.0, 1.0, 0.0, scale.max(1.0));
+    for octave in 0..octaves.max(1) {
+        total += value_noise(seed.wrapping_add(octave as u32), x as f64, y as f64, scale) * weight;
+        weights += weight;
+        weight /= 2.0;
+        scale = (scale / 2.0).max(1.0);
+    }
+    total / weights
+}
+
+/// Generates a matrix of noise values for a room, scaled to `0..=amplitude`.
+pub fn noise_cost_matrix(
+    room_name: RoomName,
+    seed: u32,
+    scale: f64,
+    octaves: u8,
+    amplitude: u8,
+) -> ClockworkCostMatrix {
+    let mut matrix = ClockworkCostMatrix::new(Some(0));
+    for y in 0..50 {
+        for x in 0..50 {
+            // SAFETY: x and y are both in the range 0..50
+            let xy = unsafe { RoomXY::unchecked_new(x, y) };
+            let noise = world_noise(seed, Position::new(xy.x, xy.y, room_name), scale, octaves);
+            matrix.set(xy, (noise * (amplitude as f64 + 1.0)) as u8);
+        }
+    }
+    matrix
+}
+
+/// Generates seeded noise for a room as a matrix of values from 0 to
+/// `amplitude`, for randomizing cost penalties. `scale` is the size of noise
+/// features in tiles, and each extra octave adds finer detail.
+#[wasm_bindgen(js_name = clockwork_noise_cost_matrix)]
+pub fn js_noise_cost_matrix(
+    room_name: u16,
+    seed: u32,
+    scale: f64,
+    octaves: u8,
+    amplitude: u8,
+) -> ClockworkCostMatrix {
+    noise_cost_matrix(
+        world_room_from_packed(room_name),
+        seed,
+        scale,
+        octaves,
+        amplitude,
+    )
+}
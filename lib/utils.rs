@@ -1,3 +1,79 @@
+use std::cell::{Cell, RefCell};
+
+use screeps::Position;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(s: &str);
+}
+
+thread_local! {
+    // The algorithms currently running, outermost first, and the position
+    // the innermost one was last working on. Included in panic messages.
+    static PANIC_CONTEXT: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    static PANIC_POSITION: Cell<Option<Position>> = const { Cell::new(None) };
+}
+
+/// Records that an algorithm is running until the guard is dropped, so a
+/// panic can report where it happened.
+pub struct PanicContext;
+
+impl PanicContext {
+    /// Pushes an algorithm name onto the panic context stack.
+    pub fn enter(algorithm: &'static str) -> PanicContext {
+        PANIC_CONTEXT.with(|context| context.borrow_mut().push(algorithm));
+        PanicContext
+    }
+
+    /// Records the position currently being processed. This is a single
+    /// store, so it's cheap enough to call for every expanded node.
+    #[inline]
+    pub fn set_position(position: Position) {
+        PANIC_POSITION.with(|current| current.set(Some(position)));
+    }
+
+    fn describe() -> Option<String> {
+        let algorithms = PANIC_CONTEXT.with(|context| context.borrow().join(" > "));
+        if algorithms.is_empty() {
+            return None;
+        }
+        Some(match PANIC_POSITION.with(|current| current.get()) {
+            Some(position) => format!(
+                "{} (room {}, position {},{})",
+                algorithms,
+                position.room_name(),
+                position.x(),
+                position.y()
+            ),
+            None => algorithms,
+        })
+    }
+}
+
+impl Drop for PanicContext {
+    fn drop(&mut self) {
+        let empty = PANIC_CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            context.pop();
+            context.is_empty()
+        });
+        if empty {
+            PANIC_POSITION.with(|current| current.set(None));
+        }
+    }
+}
+
+/// Describes the algorithms currently running, outermost first, and the
+/// position the innermost one was last working on: the same context a panic
+/// would report. Returns `undefined` when no algorithm is running, so it's
+/// only useful from inside a callback.
+#[wasm_bindgen(js_name = clockwork_panic_context)]
+pub fn js_panic_context() -> Option<String> {
+    PanicContext::describe()
+}
+
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
     // `set_panic_hook` function at least once during initialization, and then
@@ -5,6 +81,23 @@ pub fn set_panic_hook() {
     //
     // For more details see
     // https://github.com/rustwasm/console_error_panic_hook#readme
+    //
+    // The panic context (which algorithm was running, and where) is logged
+    // right after the panic message.
     #[cfg(feature = "console_error_panic_hook")]
-    console_error_panic_hook::set_once();
+    {
+        static SET_HOOK: std::sync::Once = std::sync::Once::new();
+        SET_HOOK.call_once(|| {
+            std::panic::set_hook(Box::new(|info| {
+                console_error_panic_hook::hook(info);
+                if let Some(context) = PanicContext::describe() {
+                    console_error(&format!("[clockwork] panic context: {}", context));
+                }
+                // Guards aren't dropped when a wasm panic aborts, so clear the
+                // stack here to keep it from leaking into the next call.
+                PANIC_CONTEXT.with(|context| context.borrow_mut().clear());
+                PANIC_POSITION.with(|current| current.set(None));
+            }));
+        });
+    }
 }
@@ -2,26 +2,94 @@ import 'fastestsmallesttextencoderdecoder-encodeinto/EncoderDecoderTogether.min.
 
 import {
   type InitOutput,
+  BodySpec,
   ClockworkCostMatrix,
   DistanceMap,
+  FailureReason,
   FlowField,
+  GoalKind,
+  LayoutViolation,
+  MaskComparison,
   MonoFlowField,
-  initSync,
-  version
+  OpenListOverflow,
+  PreprocessKind,
+  ScenarioKind,
+  SearchStrategy,
+  UnknownRoomPolicy,
+  clockwork_version,
+  initSync
 } from './wasm/screeps_clockwork';
-export { ClockworkCostMatrix, DistanceMap, FlowField, MonoFlowField };
+export {
+  BodySpec,
+  ClockworkCostMatrix,
+  DistanceMap,
+  FailureReason,
+  FlowField,
+  GoalKind,
+  LayoutViolation,
+  MaskComparison,
+  MonoFlowField,
+  OpenListOverflow,
+  PreprocessKind,
+  ScenarioKind,
+  SearchStrategy,
+  UnknownRoomPolicy
+};
 
 export * from './utils/cleanup';
+export * from './utils/goals';
+export * from './wrappers/accessibility';
+export * from './wrappers/anytimeAstar';
 export * from './wrappers/astarDistanceMap';
+export * from './wrappers/avoidZones';
+export * from './wrappers/backgroundJobs';
 export * from './wrappers/bfsDistanceMap';
+export * from './wrappers/cacheStats';
+export * from './wrappers/clockworkInfo';
+export * from './wrappers/closestOf';
+export * from './wrappers/costMatrixCache';
 export * from './wrappers/dijkstraDistanceMap';
+export * from './wrappers/disperse';
+export * from './wrappers/flee';
 export * from './wrappers/flowField';
+export * from './wrappers/fringeSearch';
 export * from './wrappers/getRange';
 export * from './wrappers/getTerrainCostMatrix';
+export * from './wrappers/goalSet';
+export * from './wrappers/growRegion';
+export * from './wrappers/heuristicMap';
+export * from './wrappers/heuristicTuner';
+export * from './wrappers/knownRooms';
+export * from './wrappers/layout';
+export * from './wrappers/logistics';
 export * from './wrappers/monoFlowField';
 export * from './wrappers/multiroomFlowField';
 export * from './wrappers/multiroomMonoFlowField';
+export * from './wrappers/namespaces';
+export * from './wrappers/noise';
+export * from './wrappers/nukes';
+export * from './wrappers/packedTerrain';
+export * from './wrappers/panicContext';
 export * from './wrappers/path';
+export * from './wrappers/patrolLoop';
+export * from './wrappers/rawBindings';
+export * from './wrappers/repairTour';
+export * from './wrappers/roomAstarPath';
+export * from './wrappers/roomEdge';
+export * from './wrappers/roomGraph';
+export * from './wrappers/rooms';
+export * from './wrappers/scenario';
+export * from './wrappers/searchLoad';
+export * from './wrappers/searchMemo';
+export * from './wrappers/searchOptions';
+export * from './wrappers/searchWithFallback';
+export * from './wrappers/shardedDistanceMap';
+export * from './wrappers/swampBridges';
+export * from './wrappers/threatMatrix';
+export * from './wrappers/towerCoverage';
+export * from './wrappers/travelTime';
+export * from './wrappers/wallGaps';
+export * from './wrappers/waypointPath';
 
 declare namespace WebAssembly {
   class Module {
@@ -52,7 +120,7 @@ export function initialize(verbose = false) {
   if (!wasm_instance) wasm_instance = initSync({ module: wasm_module });
   if (verbose && !initialized) {
     console.log('[clockwork] wasm_instance loaded');
-    console.log(`[clockwork] version ${version()} initialized with ${(Game.cpu.getUsed() - start).toFixed(2)} CPU`);
+    console.log(`[clockwork] version ${clockwork_version()} initialized with ${(Game.cpu.getUsed() - start).toFixed(2)} CPU`);
   }
   initialized = true;
 }
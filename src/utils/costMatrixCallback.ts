@@ -0,0 +1,21 @@
+import { ClockworkCostMatrix } from '../wasm/screeps_clockwork';
+import { fromPackedRoomName } from './fromPacked';
+
+type CostMatrixCallback = (room: string) => ClockworkCostMatrix | undefined;
+type PackedCostMatrixCallback = (room: number) => ClockworkCostMatrix | undefined;
+
+const packedCallbacks = new WeakMap<CostMatrixCallback, PackedCostMatrixCallback>();
+
+/**
+ * Adapt a cost matrix callback to take packed room names. A callback always
+ * gets the same adapter, so a memoized callback's cost matrices are shared by
+ * every search it's given.
+ */
+export function packedCostMatrixCallback(callback: CostMatrixCallback): PackedCostMatrixCallback {
+  let packed = packedCallbacks.get(callback);
+  if (!packed) {
+    packed = (room: number) => callback(fromPackedRoomName(room));
+    packedCallbacks.set(callback, packed);
+  }
+  return packed;
+}
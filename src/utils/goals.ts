@@ -0,0 +1,17 @@
+import { GoalKind } from '../wasm/screeps_clockwork';
+
+/**
+ * A destination that is reached by entering `roomName`. Searches stop at the
+ * closest entry tile, so there's no need to enumerate the room's exits.
+ *
+ * @example
+ * ```typescript
+ * astarMultiroomDistanceMap([creep.pos], {
+ *   costMatrixCallback,
+ *   anyOfDestinations: [roomGoal('W7N3')]
+ * });
+ * ```
+ */
+export function roomGoal(roomName: string) {
+  return { pos: new RoomPosition(25, 25, roomName), range: 0, kind: GoalKind.Room };
+}
@@ -0,0 +1,39 @@
+import { packRoomName } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_source_accessibility } from '../wasm/screeps_clockwork';
+
+/**
+ * Score how accessible each of a room's sources, controller, and mineral (or
+ * any other targets) are: the number of walkable tiles next to each one, and
+ * the path cost to reach it from `anchor` (e.g. the spawn), or from the
+ * nearest exit if there's no anchor. Handy for picking remotes and placing
+ * containers.
+ *
+ * @param roomName - The room the targets are in.
+ * @param targets - The target positions.
+ * @param costMatrix - The cost matrix for the room (walls must be 255).
+ * @param anchor - The position to measure distance from.
+ * @returns The walkable tile count and distance for each target. Unreachable
+ * targets have a distance of `MAX_USIZE`.
+ */
+export function sourceAccessibility(
+  roomName: string,
+  targets: RoomPosition[],
+  costMatrix: ClockworkCostMatrix,
+  anchor?: RoomPosition
+): { pos: RoomPosition; walkable: number; distance: number }[] {
+  const result = clockwork_source_accessibility(
+    packRoomName(roomName),
+    new Uint32Array(targets.map(pos => pos.__packedPos)),
+    anchor?.__packedPos,
+    costMatrix
+  );
+  const walkable = [...result.walkable];
+  const distances = [...result.distances];
+  result.free();
+
+  return targets.map((pos, i) => ({
+    pos,
+    walkable: walkable[i],
+    distance: distances[i]
+  }));
+}
@@ -0,0 +1,76 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_anytime_astar, GoalKind } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { createOpenListLimit, OpenListOptions } from './openListLimit';
+import { ClockworkPath } from './path';
+
+/**
+ * Find a path with anytime A*: a fast first search with the heuristic
+ * inflated by `initialWeight`, then repeated searches with the weight lowered
+ * by `weightStep` (down to 1) while `maxOps` has budget left. Each search
+ * only looks for paths cheaper than the best so far.
+ *
+ * `maxOps` is the budget for every search together, so it's the knob between
+ * speed and quality: a small budget returns the first feasible path, and a
+ * large one refines it towards optimal.
+ *
+ * @param start - The starting position.
+ * @param options - The options for the search.
+ * @returns The best path found, its cost, and `bound`, the most its cost can
+ * be as a multiple of the optimal path's (1 if it's optimal); or `undefined`
+ * if no path was found.
+ */
+export function anytimeAstar(
+  start: RoomPosition,
+  {
+    costMatrixCallback,
+    destinations,
+    maxRooms = MAX_USIZE,
+    maxOps = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    initialWeight = 3,
+    weightStep = 0.5,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    destinations: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    maxRooms?: number;
+    maxOps?: number;
+    maxPathCost?: number;
+    initialWeight?: number;
+    weightStep?: number;
+  } & CostOverlayOptions & MovementOptions & OpenListOptions
+): { path: ClockworkPath; cost: number; bound: number; iterations: number; ops: number } | undefined {
+  const result = clockwork_anytime_astar(
+    start.__packedPos,
+    new Uint32Array(
+      destinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+        acc.push(pos.__packedPos, range, kind);
+        return acc;
+      }, [] as number[])
+    ),
+    packedCostMatrixCallback(costMatrixCallback),
+    maxRooms,
+    maxOps,
+    maxPathCost,
+    initialWeight,
+    weightStep,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions)
+  );
+  if (!result) {
+    return undefined;
+  }
+  const anytime = {
+    path: new ClockworkPath(result.path),
+    cost: result.cost,
+    bound: result.bound,
+    iterations: result.iterations,
+    ops: result.ops
+  };
+  result.free();
+  return anytime;
+}
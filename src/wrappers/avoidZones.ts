@@ -0,0 +1,75 @@
+import { packRoomName } from '../utils/fromPacked';
+import {
+  clockwork_add_circle_zone,
+  clockwork_add_rect_zone,
+  clockwork_add_tiles_zone,
+  clockwork_clear_zones,
+  clockwork_list_zones,
+  clockwork_remove_zone
+} from '../wasm/screeps_clockwork';
+
+/**
+ * The area covered by an avoidance zone: a rectangle in one room (corners
+ * inclusive), every tile within `radius` of a position, or a set of tiles.
+ */
+export type AvoidZoneShape =
+  | { roomName: string; x1: number; y1: number; x2: number; y2: number }
+  | { center: RoomPosition; radius: number }
+  | { tiles: RoomPosition[] };
+
+/**
+ * Register a named area for searches to avoid, such as a nuke landing site or
+ * an enemy rally point. Registered zones are applied to every cost matrix the
+ * searches load, on top of your cost matrix callback, until they're removed
+ * or expire. Adding a zone with an existing name replaces it.
+ *
+ * @param name - The zone's name.
+ * @param shape - The area the zone covers.
+ * @param options.cost - The minimum cost of tiles in the zone. Defaults to
+ * 255 (impassable).
+ * @param options.expiresAt - The game tick the zone expires on. By default,
+ * zones last until removed.
+ */
+export function addAvoidZone(
+  name: string,
+  shape: AvoidZoneShape,
+  { cost = 255, expiresAt }: { cost?: number; expiresAt?: number } = {}
+) {
+  if ('roomName' in shape) {
+    clockwork_add_rect_zone(
+      name,
+      packRoomName(shape.roomName),
+      shape.x1,
+      shape.y1,
+      shape.x2,
+      shape.y2,
+      cost,
+      expiresAt
+    );
+  } else if ('center' in shape) {
+    clockwork_add_circle_zone(name, shape.center.__packedPos, shape.radius, cost, expiresAt);
+  } else {
+    clockwork_add_tiles_zone(name, new Uint32Array(shape.tiles.map(pos => pos.__packedPos)), cost, expiresAt);
+  }
+}
+
+/**
+ * Remove an avoidance zone. Returns `true` if it existed.
+ */
+export function removeAvoidZone(name: string): boolean {
+  return clockwork_remove_zone(name);
+}
+
+/**
+ * List the names of the avoidance zones that haven't expired.
+ */
+export function listAvoidZones(): string[] {
+  return clockwork_list_zones();
+}
+
+/**
+ * Remove every avoidance zone.
+ */
+export function clearAvoidZones() {
+  clockwork_clear_zones();
+}
@@ -0,0 +1,120 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import {
+  ClockworkCostMatrix,
+  clockwork_pending_background_jobs,
+  clockwork_queue_distance_map_job,
+  clockwork_queue_logistics_job,
+  clockwork_queue_progressive_distance_map_job,
+  clockwork_run_background_jobs,
+  clockwork_take_background_result
+} from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { ClockworkMultiroomDistanceMap } from './multiroomDistanceMap';
+
+/**
+ * Queue a Dijkstra distance map to be computed in the background. Like
+ * `queueProgressiveDistanceMapJob` (with the default `bucketsPerStep`), a
+ * large map is spread across as many `runBackgroundJobs` calls as it needs.
+ * Collect it with `takeBackgroundResult` once the job is done.
+ *
+ * Cost overlay options (e.g. `blockedTiles`, `roomMultipliers`) and movement
+ * options (e.g. `allowDiagonals`) work as in `dijkstraMultiroomDistanceMap`.
+ * Only rooms with a cost matrix count against `maxRooms`.
+ *
+ * @returns The job id.
+ */
+export function queueDistanceMapJob(
+  start: RoomPosition[],
+  {
+    maxRooms = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    ...searchOptions
+  }: { maxRooms?: number; maxPathCost?: number } & CostOverlayOptions & MovementOptions = {}
+): number {
+  if (maxRooms === MAX_USIZE && maxPathCost === MAX_USIZE) {
+    throw new Error('At least one of maxRooms or maxPathCost must be set');
+  }
+  return clockwork_queue_distance_map_job(
+    new Uint32Array(start.map(pos => pos.__packedPos)),
+    maxRooms,
+    maxPathCost,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
+  );
+}
+
+/**
+ * Queue a Dijkstra distance map that's built a little at a time: each step
+ * settles up to `bucketsPerStep` distance values before `runBackgroundJobs`
+ * checks the CPU budget again, and the search picks up where it left off on
+ * the next call. Use this for empire-scale maps (and flow fields, via
+ * `toFlowField`) that would otherwise cause a CPU spike.
+ *
+ * Each room's cost matrix is requested once, when the search first reaches
+ * it, and only rooms with a cost matrix count against `maxRooms`. Cost
+ * overlay and movement options work as in `queueDistanceMapJob`. Collect the
+ * result with `takeBackgroundResult` once the job is done.
+ *
+ * @returns The job id.
+ */
+export function queueProgressiveDistanceMapJob(
+  start: RoomPosition[],
+  {
+    maxRooms = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    bucketsPerStep = 10,
+    ...searchOptions
+  }: { maxRooms?: number; maxPathCost?: number; bucketsPerStep?: number } & CostOverlayOptions & MovementOptions = {}
+): number {
+  if (maxRooms === MAX_USIZE && maxPathCost === MAX_USIZE) {
+    throw new Error('At least one of maxRooms or maxPathCost must be set');
+  }
+  return clockwork_queue_progressive_distance_map_job(
+    new Uint32Array(start.map(pos => pos.__packedPos)),
+    maxRooms,
+    maxPathCost,
+    bucketsPerStep,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
+  );
+}
+
+/**
+ * Queue a job that recomputes dirty logistics anchors until none remain.
+ *
+ * @returns The job id.
+ */
+export function queueLogisticsJob(): number {
+  return clockwork_queue_logistics_job();
+}
+
+/**
+ * Number of background jobs still waiting to run.
+ */
+export function pendingBackgroundJobs(): number {
+  return clockwork_pending_background_jobs();
+}
+
+/**
+ * Run queued background jobs until the queue is empty or `cpuBudget` has been
+ * spent. A job that is still running when the budget runs out continues on
+ * the next call.
+ *
+ * @returns The number of jobs completed.
+ */
+export function runBackgroundJobs(
+  costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined,
+  cpuBudget: number
+): number {
+  return clockwork_run_background_jobs(packedCostMatrixCallback(costMatrixCallback), cpuBudget);
+}
+
+/**
+ * Take the result of a completed distance map job, if it's ready.
+ */
+export function takeBackgroundResult(jobId: number): ClockworkMultiroomDistanceMap | undefined {
+  const result = clockwork_take_background_result(jobId);
+  return result ? new ClockworkMultiroomDistanceMap(result) : undefined;
+}
@@ -1,6 +1,8 @@
 import { MAX_USIZE } from '../utils/constants';
-import { fromPackedRoomName } from '../utils/fromPacked';
-import { ClockworkCostMatrix, js_bfs_multiroom_distance_map } from '../wasm/screeps_clockwork';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_bfs_multiroom_distance_map, GoalKind } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
 import { fromPackedSearchResult } from './searchResult';
 
 /**
@@ -9,12 +11,26 @@ import { fromPackedSearchResult } from './searchResult';
  * matrix as passable), so it's faster but less useful than Dijkstra's algorithm.
  *
  * This calculates a distance map across multiple rooms, with a few configurable limits:
- * - `maxOps`: The maximum number of pathfinding operations to perform.
+ * - `maxOps`: The maximum number of tiles to expand.
  * - `maxRooms`: The maximum number of rooms to explore.
  * - `maxPathCost`: Don't explore tiles with a greater path cost than this.
  *
  * At least one of these limits must be set.
  *
+ * Set `recordExpansions` to get the expanded positions (up to 10,000, in
+ * order) back as `expanded`, e.g. to visualize how far the search spread.
+ *
+ * If there are destinations and the search doesn't reach them, `failure`
+ * says why (see `FailureReason`), how many tiles were left to expand, and
+ * which rooms the cost matrix callback returned `undefined` for.
+ *
+ * `blockedTiles` are treated as impassable for this search only. Preferred
+ * tile discounts and room multipliers have no effect, since BFS ignores tile
+ * costs.
+ *
+ * The cost matrix callback is called once per room loaded, unless it's been
+ * memoized with `memoizeCostMatrixCallback`.
+ *
  * @param start - The starting positions.
  * @param options - The options for the distance map.
  * @returns A multi-room distance map.
@@ -27,15 +43,18 @@ export function bfsMultiroomDistanceMap(
     maxRooms = MAX_USIZE,
     maxPathCost = MAX_USIZE,
     anyOfDestinations,
-    allOfDestinations
+    allOfDestinations,
+    recordExpansions = false,
+    ...searchOptions
   }: {
     costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
     maxOps?: number;
     maxRooms?: number;
     maxPathCost?: number;
-    anyOfDestinations?: { pos: RoomPosition; range: number }[];
-    allOfDestinations?: { pos: RoomPosition; range: number }[];
-  }
+    anyOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    allOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    recordExpansions?: boolean;
+  } & CostOverlayOptions & MovementOptions
 ) {
   if ([maxOps, maxRooms, maxPathCost].every(n => n === MAX_USIZE) && !anyOfDestinations && !allOfDestinations) {
     throw new Error(
@@ -44,28 +63,31 @@ export function bfsMultiroomDistanceMap(
   }
 
   const startPacked = new Uint32Array(start.map(pos => pos.__packedPos));
-  const result = js_bfs_multiroom_distance_map(
+  const result = clockwork_bfs_multiroom_distance_map(
     startPacked,
-    (room: number) => costMatrixCallback(fromPackedRoomName(room)),
+    packedCostMatrixCallback(costMatrixCallback),
     maxOps,
     maxRooms,
     maxPathCost,
     anyOfDestinations
       ? new Uint32Array(
-          anyOfDestinations.reduce((acc, { pos, range }) => {
-            acc.push(pos.__packedPos, range);
+          anyOfDestinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+            acc.push(pos.__packedPos, range, kind);
             return acc;
           }, [] as number[])
         )
       : undefined,
     allOfDestinations
       ? new Uint32Array(
-          allOfDestinations.reduce((acc, { pos, range }) => {
-            acc.push(pos.__packedPos, range);
+          allOfDestinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+            acc.push(pos.__packedPos, range, kind);
             return acc;
           }, [] as number[])
         )
-      : undefined
+      : undefined,
+    recordExpansions,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
   );
 
   return fromPackedSearchResult(result);
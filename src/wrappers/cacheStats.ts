@@ -0,0 +1,79 @@
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { packRoomName } from '../utils/fromPacked';
+import {
+  ClockworkCostMatrix,
+  clockwork_cache_stats,
+  clockwork_prefetch_rooms,
+  clockwork_preprocess_rooms,
+  clockwork_reset_cache_stats,
+  PreprocessKind
+} from '../wasm/screeps_clockwork';
+
+/**
+ * Load terrain and cost data for a list of rooms ahead of time, e.g. before
+ * a planned offensive, so the cost isn't paid mid-search. Terrain is cached
+ * for the lifetime of the module. With a `costMatrixCallback`, each room's
+ * cost matrix is also requested and remembered for the rest of the tick, so
+ * searches given the same callback load it from the cache.
+ *
+ * @param roomNames - The rooms to preload.
+ * @param costMatrixCallback - The callback the searches will use, if any.
+ * @returns With a callback, the number of rooms it returned a cost matrix
+ * for; otherwise, the number of rooms that exist.
+ */
+export function preloadRooms(
+  roomNames: string[],
+  costMatrixCallback?: (room: string) => ClockworkCostMatrix | undefined
+): number {
+  const rooms = new Uint16Array(roomNames.map(packRoomName));
+  if (costMatrixCallback) {
+    return clockwork_prefetch_rooms(rooms, packedCostMatrixCallback(costMatrixCallback));
+  }
+  const statuses = clockwork_preprocess_rooms(rooms, new Uint32Array([PreprocessKind.Terrain]));
+  return [...statuses].filter(status => status !== 0).length;
+}
+
+/**
+ * Compute and cache per-room data for a batch of rooms in a single call into
+ * wasm, e.g. for every room in a remote mining network at startup. Terrain
+ * and exit widths never change, so they're cached for the lifetime of the
+ * module.
+ *
+ * @param roomNames - The rooms to preprocess.
+ * @param kinds - The data to compute for each room. Defaults to everything.
+ * @returns The kinds now cached for each room; rooms that don't exist have
+ * none.
+ */
+export function preprocessRooms(
+  roomNames: string[],
+  kinds: PreprocessKind[] = [PreprocessKind.Terrain, PreprocessKind.Exits]
+): Record<string, PreprocessKind[]> {
+  const statuses = clockwork_preprocess_rooms(new Uint16Array(roomNames.map(packRoomName)), new Uint32Array(kinds));
+  const result: Record<string, PreprocessKind[]> = {};
+  roomNames.forEach((room, i) => {
+    result[room] = kinds.filter(kind => statuses[i] & (1 << kind));
+  });
+  return result;
+}
+
+/**
+ * Get hit/miss counts for Clockwork's caches since the module loaded (or
+ * since the last `resetCacheStats`). Room data misses are calls to a search's
+ * cost matrix callback; hits are lookups of rooms the search already loaded.
+ */
+export function cacheStats() {
+  const stats = clockwork_cache_stats();
+  const result = {
+    terrain: { hits: stats.terrain_hits, misses: stats.terrain_misses },
+    roomData: { hits: stats.room_data_hits, misses: stats.room_data_misses }
+  };
+  stats.free();
+  return result;
+}
+
+/**
+ * Reset all cache hit/miss counts to zero.
+ */
+export function resetCacheStats() {
+  clockwork_reset_cache_stats();
+}
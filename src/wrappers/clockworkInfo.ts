@@ -0,0 +1,42 @@
+import { clockwork_info } from '../wasm/screeps_clockwork';
+
+export interface ClockworkInfo {
+  version: string;
+  features: string[];
+  algorithms: string[];
+  limits: {
+    roomSize: number;
+    roomArea: number;
+    worldSize: number;
+    maxRooms: number;
+    maxRecordedExpansions: number;
+    maxCachedCallbacks: number;
+  };
+}
+
+/**
+ * Describe the loaded build of clockwork: its version, the Cargo features it
+ * was built with, the search algorithms it provides, and its limits. Use this
+ * to check whether a feature exists before calling it, rather than testing
+ * for exports, when the bundled wasm may be from an older version.
+ *
+ * @returns The version, features, algorithms, and limits of this build.
+ */
+export function clockworkInfo(): ClockworkInfo {
+  const result = clockwork_info();
+  const info = {
+    version: result.version,
+    features: result.features,
+    algorithms: result.algorithms,
+    limits: {
+      roomSize: result.room_size,
+      roomArea: result.room_area,
+      worldSize: result.world_size,
+      maxRooms: result.max_rooms,
+      maxRecordedExpansions: result.max_recorded_expansions,
+      maxCachedCallbacks: result.max_cached_callbacks
+    }
+  };
+  result.free();
+  return info;
+}
@@ -0,0 +1,112 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_best_origin, clockwork_closest_of } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { createOpenListLimit, OpenListOptions } from './openListLimit';
+import { ClockworkPath } from './path';
+
+/**
+ * Find the candidate closest to `from` by path cost, with a single search
+ * rather than one per candidate. The search stops as soon as it comes within
+ * `range` of any candidate.
+ *
+ * @example
+ * ```typescript
+ * const index = closestOf(creep.pos, sources.map(source => source.pos), { costMatrixCallback, range: 1 });
+ * const source = index === undefined ? undefined : sources[index];
+ * ```
+ *
+ * @param from - The position to search from.
+ * @param candidates - The positions to choose between.
+ * @param options - The options for the search.
+ * @returns The index of the closest candidate, or `undefined` if none was
+ * reached within the limits.
+ */
+export function closestOf(
+  from: RoomPosition,
+  candidates: RoomPosition[],
+  {
+    costMatrixCallback,
+    range = 0,
+    maxOps = MAX_USIZE,
+    maxRooms = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    range?: number;
+    maxOps?: number;
+    maxRooms?: number;
+    maxPathCost?: number;
+  } & CostOverlayOptions & MovementOptions & OpenListOptions
+): number | undefined {
+  return clockwork_closest_of(
+    from.__packedPos,
+    new Uint32Array(candidates.map(pos => pos.__packedPos)),
+    range,
+    packedCostMatrixCallback(costMatrixCallback),
+    maxOps,
+    maxRooms,
+    maxPathCost,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions)
+  );
+}
+
+/**
+ * Find which of `origins` has the cheapest path to `goal`, e.g. which spawn
+ * should produce a defender, with a single search grown from every origin at
+ * once rather than one per origin.
+ *
+ * @example
+ * ```typescript
+ * const best = bestOrigin(spawns.map(spawn => spawn.pos), target, { costMatrixCallback, range: 1 });
+ * const spawn = best === undefined ? undefined : spawns[best.index];
+ * ```
+ *
+ * @param origins - The positions to choose between.
+ * @param goal - The position to reach.
+ * @param options - The options for the search.
+ * @returns `index`, the index of the best origin, and `path`, from that
+ * origin to the goal; or `undefined` if the goal wasn't reached within the
+ * limits.
+ */
+export function bestOrigin(
+  origins: RoomPosition[],
+  goal: RoomPosition,
+  {
+    costMatrixCallback,
+    range = 0,
+    maxOps = MAX_USIZE,
+    maxRooms = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    range?: number;
+    maxOps?: number;
+    maxRooms?: number;
+    maxPathCost?: number;
+  } & CostOverlayOptions & MovementOptions & OpenListOptions
+): { index: number; path: ClockworkPath } | undefined {
+  const result = clockwork_best_origin(
+    new Uint32Array(origins.map(pos => pos.__packedPos)),
+    goal.__packedPos,
+    range,
+    packedCostMatrixCallback(costMatrixCallback),
+    maxOps,
+    maxRooms,
+    maxPathCost,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions)
+  );
+  if (!result) {
+    return undefined;
+  }
+  const best = { index: result.index, path: new ClockworkPath(result.path) };
+  result.free();
+  return best;
+}
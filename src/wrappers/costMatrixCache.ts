@@ -0,0 +1,64 @@
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { fromPackedRoomName, packRoomName } from '../utils/fromPacked';
+import {
+  ClockworkCostMatrix,
+  clockwork_clear_cost_matrix_cache,
+  clockwork_corridor_rooms,
+  clockwork_memoize_cost_matrices,
+  clockwork_prefetch_rooms
+} from '../wasm/screeps_clockwork';
+
+/**
+ * Opt a cost matrix callback in to memoization. Searches normally call their
+ * `costMatrixCallback` for every room they load; once it's memoized, every
+ * search given the same callback reuses the matrix it returned for a room
+ * for the rest of the tick (until that matrix is changed with `set`). Only
+ * the 16 most recently memoized callbacks stay memoized.
+ *
+ * Only memoize callbacks whose result for a room doesn't change within a
+ * tick, or call `clearCostMatrixCache` when it does.
+ *
+ * @returns The same callback, to pass to searches.
+ */
+export function memoizeCostMatrixCallback<T extends (room: string) => ClockworkCostMatrix | undefined>(
+  costMatrixCallback: T
+): T {
+  clockwork_memoize_cost_matrices(packedCostMatrixCallback(costMatrixCallback));
+  return costMatrixCallback;
+}
+
+/**
+ * Forget the cost matrices remembered from memoized cost matrix callbacks
+ * this tick, e.g. if a callback would now return a different matrix. The
+ * callbacks stay memoized.
+ */
+export function clearCostMatrixCache(): void {
+  clockwork_clear_cost_matrix_cache();
+}
+
+/**
+ * Load the terrain and cost matrices of the rooms along the straight-line
+ * corridor from `origin` to each goal, ahead of a long search. Searches load
+ * rooms on demand anyway, but prefetching moves that work (and any callback
+ * failures) up front, and the search's loads become cache hits as long as it
+ * gets the same `costMatrixCallback` this tick. The callback is memoized (see
+ * `memoizeCostMatrixCallback`).
+ *
+ * @param origin - The room the search starts in.
+ * @param goals - The rooms the search is heading for.
+ * @param options - `costMatrixCallback`, and `width`, how many rooms either
+ * side of the corridor to include (default 1).
+ * @returns The rooms in the corridor.
+ */
+export function prefetchCorridor(
+  origin: string,
+  goals: string[],
+  {
+    costMatrixCallback,
+    width = 1
+  }: { costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined; width?: number }
+): string[] {
+  const rooms = clockwork_corridor_rooms(packRoomName(origin), new Uint16Array(goals.map(packRoomName)), width);
+  clockwork_prefetch_rooms(rooms, packedCostMatrixCallback(costMatrixCallback));
+  return [...rooms].map(room => fromPackedRoomName(room));
+}
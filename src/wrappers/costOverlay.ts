@@ -0,0 +1,177 @@
+import { packRoomName } from '../utils/fromPacked';
+import { CostOverlay, UnknownRoomPolicy } from '../wasm/screeps_clockwork';
+
+// The size of a bit-packed mask covering every tile in a room
+const PROTECTED_MASK_BYTES = Math.ceil(2500 / 8);
+
+/**
+ * Per-search cost adjustments, applied to each room's cost matrix as the
+ * search loads it. Your own cost matrices are never modified.
+ */
+export interface CostOverlayOptions {
+  /**
+   * Tiles to attract paths to (e.g. planned roads that aren't built yet).
+   */
+  preferredTiles?: RoomPosition[];
+  /**
+   * How much to reduce the cost of preferred tiles. Defaults to 1.
+   */
+  preferredDiscount?: number;
+  /**
+   * The lowest cost a preferred tile can be discounted to (at least 1).
+   * Defaults to 1.
+   */
+  preferredFloor?: number;
+  /**
+   * Tiles to treat as impassable for this search only (e.g. tiles reserved by
+   * other creeps this tick).
+   */
+  blockedTiles?: RoomPosition[];
+  /**
+   * Multipliers for the cost of every passable tile in a room, by room name
+   * (e.g. 1 for owned rooms, 1.5 for neutral rooms, 3 for rooms next to
+   * hostiles). Scaled costs are rounded and kept between 1 and 254.
+   */
+  roomMultipliers?: Record<string, number>;
+  /**
+   * Tiles under cover (e.g. owned ramparts), for defenders that need to
+   * reposition without leaving them. In each room with a protected tile,
+   * every other passable tile costs `exposurePenalty` more.
+   */
+  protectedTiles?: RoomPosition[];
+  /**
+   * The extra cost of stepping outside `protectedTiles` (capped at 254), or
+   * 255 to forbid it entirely. Defaults to 255.
+   */
+  exposurePenalty?: number;
+  /**
+   * The leader's position this tick, for a follower that should path while
+   * staying in formation. Every passable tile more than `formationRange`
+   * from it (measured across room edges) costs `formationPenalty` more.
+   */
+  leader?: RoomPosition;
+  /**
+   * How far from `leader` the follower may stray. Defaults to 1.
+   */
+  formationRange?: number;
+  /**
+   * The extra cost of straying beyond `formationRange` (capped at 254), or
+   * 255 to forbid it entirely. Defaults to 255.
+   */
+  formationPenalty?: number;
+  /**
+   * Destructible obstacles (e.g. hostile walls and ramparts) and their hits,
+   * for siege paths that may break through. Each tile becomes passable at a
+   * cost of the ticks it takes to dismantle (`hits / dismantlePower`, rounded
+   * up) times `breachCostPerTick`, kept between 1 and 254. Use
+   * `breachesAlong` to find which of them a path goes through.
+   */
+  breachableTiles?: { pos: RoomPosition; hits: number }[];
+  /**
+   * The hits the attackers remove per tick. Defaults to 50 (one WORK part
+   * dismantling).
+   */
+  dismantlePower?: number;
+  /**
+   * The path cost of each tick spent breaking through. Defaults to 1.
+   */
+  breachCostPerTick?: number;
+  /**
+   * What to do with rooms `costMatrixCallback` returns `undefined` for
+   * (usually rooms without vision). Defaults to `UnknownRoomPolicy.Blocked`.
+   * `UnknownRoomPolicy.LastKnown` reuses the last matrix the callback
+   * returned for the room in a search with the same policy.
+   */
+  unknownRooms?: UnknownRoomPolicy;
+  /**
+   * The oldest (in ticks) a last known cost matrix can be before the room is
+   * blocked instead. Defaults to 1500.
+   */
+  unknownRoomMaxAge?: number;
+}
+
+/**
+ * Build the wasm overlay for a search, or `undefined` if no adjustments were
+ * requested. The overlay is consumed by the search it's passed to.
+ */
+export function createCostOverlay({
+  preferredTiles,
+  preferredDiscount = 1,
+  preferredFloor = 1,
+  blockedTiles,
+  roomMultipliers,
+  protectedTiles,
+  exposurePenalty = 255,
+  leader,
+  formationRange = 1,
+  formationPenalty = 255,
+  breachableTiles,
+  dismantlePower = 50,
+  breachCostPerTick = 1,
+  unknownRooms = UnknownRoomPolicy.Blocked,
+  unknownRoomMaxAge
+}: CostOverlayOptions): CostOverlay | undefined {
+  const multipliedRooms = Object.keys(roomMultipliers ?? {});
+  if (
+    !preferredTiles?.length &&
+    !blockedTiles?.length &&
+    !multipliedRooms.length &&
+    !protectedTiles?.length &&
+    !leader &&
+    !breachableTiles?.length &&
+    unknownRooms === UnknownRoomPolicy.Blocked
+  ) {
+    return undefined;
+  }
+  const overlay = new CostOverlay();
+  if (preferredTiles?.length) {
+    overlay.setPreferredTiles(
+      new Uint32Array(preferredTiles.map(pos => pos.__packedPos)),
+      preferredDiscount,
+      preferredFloor
+    );
+  }
+  if (blockedTiles?.length) {
+    overlay.setBlockedTiles(new Uint32Array(blockedTiles.map(pos => pos.__packedPos)));
+  }
+  if (roomMultipliers && multipliedRooms.length) {
+    overlay.setRoomMultipliers(
+      new Uint16Array(multipliedRooms.map(room => packRoomName(room))),
+      new Float32Array(multipliedRooms.map(room => roomMultipliers[room]))
+    );
+  }
+  if (protectedTiles?.length) {
+    const masks = new Map<string, Uint8Array>();
+    for (const pos of protectedTiles) {
+      let mask = masks.get(pos.roomName);
+      if (!mask) {
+        mask = new Uint8Array(PROTECTED_MASK_BYTES);
+        masks.set(pos.roomName, mask);
+      }
+      const bit = pos.y * 50 + pos.x;
+      mask[bit >> 3] |= 1 << (bit & 7);
+    }
+    const packed = new Uint8Array(masks.size * PROTECTED_MASK_BYTES);
+    [...masks.values()].forEach((mask, i) => packed.set(mask, i * PROTECTED_MASK_BYTES));
+    overlay.setProtectedTiles(
+      new Uint16Array([...masks.keys()].map(room => packRoomName(room))),
+      packed,
+      exposurePenalty
+    );
+  }
+  if (leader) {
+    overlay.setFormation(leader.__packedPos, formationRange, formationPenalty);
+  }
+  if (breachableTiles?.length) {
+    overlay.setBreachableTiles(
+      new Uint32Array(breachableTiles.map(({ pos }) => pos.__packedPos)),
+      new Uint32Array(breachableTiles.map(({ hits }) => hits)),
+      dismantlePower,
+      breachCostPerTick
+    );
+  }
+  if (unknownRooms !== UnknownRoomPolicy.Blocked) {
+    overlay.setUnknownRooms(unknownRooms, unknownRoomMaxAge);
+  }
+  return overlay;
+}
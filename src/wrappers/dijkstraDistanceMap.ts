@@ -1,6 +1,10 @@
 import { MAX_USIZE } from '../utils/constants';
-import { fromPackedRoomName } from '../utils/fromPacked';
-import { ClockworkCostMatrix, js_dijkstra_multiroom_distance_map } from '../wasm/screeps_clockwork';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_dijkstra_multiroom_distance_map, GoalKind } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { createOpenListLimit, OpenListOptions } from './openListLimit';
+import { GoalSet } from './goalSet';
 import { fromPackedSearchResult } from './searchResult';
 
 /**
@@ -8,12 +12,40 @@ import { fromPackedSearchResult } from './searchResult';
  * factor in terrain costs (0-255, where 255 is impassable).
  *
  * This calculates a distance map across multiple rooms, with a few configurable limits:
- * - `maxOps`: The maximum number of pathfinding operations to perform.
+ * - `maxOps`: The maximum number of tiles to expand.
  * - `maxRooms`: The maximum number of rooms to explore.
  * - `maxPathCost`: Don't explore tiles with a greater path cost than this.
  *
  * At least one of these limits must be set.
  *
+ * Set `recordExpansions` to get the expanded positions (up to 10,000, in
+ * order) back as `expanded`, e.g. to visualize how far the search spread.
+ *
+ * If there are destinations and the search doesn't reach them, `failure`
+ * says why (see `FailureReason`), how many tiles were left to expand, and
+ * which rooms the cost matrix callback returned `undefined` for.
+ *
+ * `preferredTiles` are discounted by `preferredDiscount` (down to
+ * `preferredFloor`) to attract paths to them, and `blockedTiles` are treated
+ * as impassable, without changing your cost matrices. `roomMultipliers` scale
+ * the costs of whole rooms, e.g. `{ W2N1: 3 }` to steer around a hostile
+ * neighbor. `protectedTiles` (e.g. owned ramparts) keep defenders under cover,
+ * with `exposurePenalty` setting the cost of stepping outside them.
+ * `leader` keeps a follower within `formationRange` of the leader's position,
+ * with `formationPenalty` setting the cost of straying.
+ *
+ * Set `allowDiagonals: false` to search with orthogonal moves only, or
+ * `cutCorners: false` to disallow diagonal moves past an impassable tile.
+ * `roomChangeCost` adds to every move into another room, so short paths don't
+ * dip into neighbors.
+ *
+ * `anyOfDestinations` can be a `GoalSet`, to reuse destinations that are
+ * searched for often without unpacking them every time.
+ *
+ * `costMatrixCallback` is called for each room the search loads. Wrap it with
+ * `memoizeCostMatrixCallback` to share its cost matrices with every other
+ * search given the same callback this tick.
+ *
  * @param start - The starting positions.
  * @param options - The options for the distance map.
  * @returns A multi-room distance map.
@@ -26,15 +58,18 @@ export function dijkstraMultiroomDistanceMap(
     maxRooms = MAX_USIZE,
     maxPathCost = MAX_USIZE,
     anyOfDestinations,
-    allOfDestinations
+    allOfDestinations,
+    recordExpansions = false,
+    ...searchOptions
   }: {
     costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
     maxOps?: number;
     maxRooms?: number;
     maxPathCost?: number;
-    anyOfDestinations?: { pos: RoomPosition; range: number }[];
-    allOfDestinations?: { pos: RoomPosition; range: number }[];
-  }
+    anyOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[] | GoalSet;
+    allOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    recordExpansions?: boolean;
+  } & CostOverlayOptions & MovementOptions & OpenListOptions
 ) {
   if ([maxOps, maxRooms, maxPathCost].every(n => n === MAX_USIZE) && !anyOfDestinations && !allOfDestinations) {
     throw new Error(
@@ -43,28 +78,33 @@ export function dijkstraMultiroomDistanceMap(
   }
 
   const startPacked = new Uint32Array(start.map(pos => pos.__packedPos));
-  const result = js_dijkstra_multiroom_distance_map(
+  const result = clockwork_dijkstra_multiroom_distance_map(
     startPacked,
-    (room: number) => costMatrixCallback(fromPackedRoomName(room)),
+    packedCostMatrixCallback(costMatrixCallback),
     maxOps,
     maxRooms,
     maxPathCost,
-    anyOfDestinations
+    anyOfDestinations && !(anyOfDestinations instanceof GoalSet)
       ? new Uint32Array(
-          anyOfDestinations.reduce((acc, { pos, range }) => {
-            acc.push(pos.__packedPos, range);
+          anyOfDestinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+            acc.push(pos.__packedPos, range, kind);
             return acc;
           }, [] as number[])
         )
       : undefined,
     allOfDestinations
       ? new Uint32Array(
-          allOfDestinations.reduce((acc, { pos, range }) => {
-            acc.push(pos.__packedPos, range);
+          allOfDestinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+            acc.push(pos.__packedPos, range, kind);
             return acc;
           }, [] as number[])
         )
-      : undefined
+      : undefined,
+    recordExpansions,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions),
+    anyOfDestinations instanceof GoalSet ? anyOfDestinations.id : undefined
   );
 
   return fromPackedSearchResult(result);
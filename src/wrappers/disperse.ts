@@ -0,0 +1,45 @@
+import { fromPacked } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_disperse, clockwork_nearest_free_tile } from '../wasm/screeps_clockwork';
+
+/**
+ * Pick target tiles that spread a group of creeps at least `minSpacing` apart
+ * (by range) within an area. Each creep is assigned, in order, to the nearest
+ * free tile; a creep with no free tile left stays where it is. Useful for
+ * sieges or for parking idle workers off roads.
+ *
+ * @param creeps - The creep positions (must all be in the same room).
+ * @param minSpacing - The minimum range between targets.
+ * @param area - The candidate tiles (must be in the same room as the creeps).
+ * @returns One target position per creep, in the same order.
+ */
+export function disperse(creeps: RoomPosition[], minSpacing: number, area: RoomPosition[]): RoomPosition[] {
+  return [
+    ...clockwork_disperse(
+      new Uint32Array(creeps.map(pos => pos.__packedPos)),
+      minSpacing,
+      new Uint32Array(area.map(pos => pos.__packedPos))
+    )
+  ].map(pos => fromPacked(pos));
+}
+
+/**
+ * Find the tile closest to `origin` (by moves, within its room) with a cost
+ * in `costMatrix` below `threshold`, walking only through passable tiles
+ * (cost below 255). E.g. on a terrain matrix with roads raised to 20 and
+ * occupied tiles set to 255, a threshold of 10 finds the nearest empty plain
+ * off the roads for an idle creep to park on. `origin` counts if it
+ * qualifies.
+ *
+ * @param origin - The position to search from.
+ * @param costMatrix - The cost matrix of the origin's room.
+ * @param threshold - Tiles cheaper than this are free.
+ * @returns The nearest free tile, or `undefined` if none can be reached.
+ */
+export function nearestFreeTile(
+  origin: RoomPosition,
+  costMatrix: ClockworkCostMatrix,
+  threshold: number
+): RoomPosition | undefined {
+  const packed = clockwork_nearest_free_tile(origin.__packedPos, costMatrix, threshold);
+  return packed === undefined ? undefined : fromPacked(packed);
+}
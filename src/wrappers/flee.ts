@@ -0,0 +1,183 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { fromPacked, packRoomName } from '../utils/fromPacked';
+import {
+  ClockworkCostMatrix,
+  clockwork_flee_to_safety,
+  clockwork_flee_with_hysteresis,
+  clockwork_forget_flee_state,
+  clockwork_safe_positions,
+  GoalKind
+} from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { getTerrainCostMatrix } from './getTerrainCostMatrix';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { ClockworkPath } from './path';
+
+/**
+ * Find a path from `start` that gets out of range of every threat and ends in
+ * a safe area, such as a rampart or (with `GoalKind.Room`) an owned room.
+ * Tiles inside a threat's range cost an extra `threatCost` to cross, so the
+ * path leaves danger quickly instead of cutting through it.
+ *
+ * Cost overlay options (e.g. `blockedTiles`, `roomMultipliers`) and movement
+ * options (e.g. `allowDiagonals`) work as in `astarMultiroomDistanceMap`.
+ *
+ * @param start - The starting position.
+ * @param options - The options for the search.
+ * @returns The path to a safe tile, or `undefined` if none was found.
+ */
+export function fleeToSafety(
+  start: RoomPosition,
+  {
+    costMatrixCallback,
+    threats,
+    safeAreas,
+    threatCost = 50,
+    maxOps = MAX_USIZE,
+    maxRooms = MAX_USIZE,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    threats: { pos: RoomPosition; range: number }[];
+    safeAreas: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    threatCost?: number;
+    maxOps?: number;
+    maxRooms?: number;
+  } & CostOverlayOptions & MovementOptions
+): ClockworkPath | undefined {
+  const path = clockwork_flee_to_safety(
+    start.__packedPos,
+    packedCostMatrixCallback(costMatrixCallback),
+    packThreats(threats),
+    packSafeAreas(safeAreas),
+    threatCost,
+    maxOps,
+    maxRooms,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
+  );
+  return path ? new ClockworkPath(path) : undefined;
+}
+
+/**
+ * Like `fleeToSafety`, but for a creep that flees every tick from threats
+ * that keep moving. The tile chosen on earlier calls with the same `key`
+ * (e.g. the creep's name) is kept while it's still safe, unless the best
+ * tile now is more than `hysteresis` cheaper to reach. This stops the creep
+ * flipping between escape routes as the threats jitter.
+ *
+ * The remembered tile is dropped after 100 ticks without a call, or with
+ * `forgetFleeState`.
+ *
+ * @param key - Identifies the fleeing creep.
+ * @param start - The starting position.
+ * @param options - The options for the search.
+ * @returns The path to a safe tile, or `undefined` if none was found.
+ */
+export function fleeWithHysteresis(
+  key: string,
+  start: RoomPosition,
+  {
+    costMatrixCallback,
+    threats,
+    safeAreas,
+    threatCost = 50,
+    maxOps = MAX_USIZE,
+    maxRooms = MAX_USIZE,
+    hysteresis = 10,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    threats: { pos: RoomPosition; range: number }[];
+    safeAreas: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    threatCost?: number;
+    maxOps?: number;
+    maxRooms?: number;
+    hysteresis?: number;
+  } & CostOverlayOptions & MovementOptions
+): ClockworkPath | undefined {
+  const path = clockwork_flee_with_hysteresis(
+    key,
+    start.__packedPos,
+    packedCostMatrixCallback(costMatrixCallback),
+    packThreats(threats),
+    packSafeAreas(safeAreas),
+    threatCost,
+    maxOps,
+    maxRooms,
+    hysteresis,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
+  );
+  return path ? new ClockworkPath(path) : undefined;
+}
+
+/**
+ * Forget the escape route remembered by `fleeWithHysteresis` for `key`, e.g.
+ * when the creep dies. Returns false if there was none.
+ */
+export function forgetFleeState(key: string): boolean {
+  return clockwork_forget_flee_state(key);
+}
+
+function packThreats(threats: { pos: RoomPosition; range: number }[]) {
+  return new Uint32Array(
+    threats.reduce((acc, { pos, range }) => {
+      acc.push(pos.__packedPos, range);
+      return acc;
+    }, [] as number[])
+  );
+}
+
+function packSafeAreas(safeAreas: { pos: RoomPosition; range: number; kind?: GoalKind }[]) {
+  return new Uint32Array(
+    safeAreas.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+      acc.push(pos.__packedPos, range, kind);
+      return acc;
+    }, [] as number[])
+  );
+}
+
+/**
+ * Find tiles in a room where a creep is at least `minRange` moves away from
+ * every threat (walking around walls, not straight-line range) and can still
+ * be reached from `anchor`, e.g. for civilians to wait out an attack without
+ * being cut off from the spawn. Only threats in the room count, and tiles no
+ * threat can reach are safe.
+ *
+ * @param roomName - The room to search.
+ * @param options - `threats`, `anchor`, `minRange`, `count` (the most tiles
+ * to return, default 1), and `costMatrix` (defaults to the room's terrain
+ * matrix; tiles at 255 are impassable).
+ * @returns The safe tiles, closest to the anchor first (then furthest from
+ * the threats first).
+ */
+export function findSafePositions(
+  roomName: string,
+  {
+    threats,
+    anchor,
+    minRange,
+    count = 1,
+    costMatrix
+  }: {
+    threats: RoomPosition[];
+    anchor: RoomPosition;
+    minRange: number;
+    count?: number;
+    costMatrix?: ClockworkCostMatrix;
+  }
+): RoomPosition[] {
+  const terrain = costMatrix ? undefined : getTerrainCostMatrix(roomName);
+  const positions = clockwork_safe_positions(
+    packRoomName(roomName),
+    new Uint32Array(threats.map(pos => pos.__packedPos)),
+    anchor.__packedPos,
+    minRange,
+    count,
+    (costMatrix ?? terrain)!
+  );
+  terrain?.free();
+  return [...positions].map(pos => fromPacked(pos));
+}
@@ -41,6 +41,14 @@ export class ClockworkFlowField {
     this._flowField.addDirection(x, y, direction);
   }
 
+  /**
+   * Get the direction bitmask of every tile, indexed by `y * 50 + x`. Bit `d`
+   * is set when direction `d` is valid.
+   */
+  toArray(): Uint8Array {
+    return this._flowField.toArray();
+  }
+
   /**
    * Free the memory allocated for this flow field.
    */
@@ -0,0 +1,53 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_fringe_search, GoalKind } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { ClockworkPath } from './path';
+
+/**
+ * Find a path from `start` to the nearest destination with Fringe Search, a
+ * low-memory alternative to A*. Instead of keeping a priority queue of open
+ * tiles, it re-scans its fringe with a rising cost limit, and only remembers
+ * a cost and a direction per tile. This costs more CPU than A*, so it's
+ * best kept for rare, very long searches (e.g. right after a global reset).
+ *
+ * Cost overlay options (e.g. `blockedTiles`, `roomMultipliers`), movement
+ * options (e.g. `allowDiagonals`) and memoized cost matrix callbacks (see
+ * `memoizeCostMatrixCallback`) work as in `astarMultiroomDistanceMap`.
+ *
+ * @param start - The starting position.
+ * @param options - The options for the search.
+ * @returns The path to the destination, or `undefined` if none was found.
+ */
+export function fringeSearch(
+  start: RoomPosition,
+  {
+    costMatrixCallback,
+    destinations,
+    maxOps = MAX_USIZE,
+    maxRooms = MAX_USIZE,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    destinations: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    maxOps?: number;
+    maxRooms?: number;
+  } & CostOverlayOptions & MovementOptions
+): ClockworkPath | undefined {
+  const path = clockwork_fringe_search(
+    start.__packedPos,
+    packedCostMatrixCallback(costMatrixCallback),
+    maxOps,
+    maxRooms,
+    new Uint32Array(
+      destinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+        acc.push(pos.__packedPos, range, kind);
+        return acc;
+      }, [] as number[])
+    ),
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
+  );
+  return path ? new ClockworkPath(path) : undefined;
+}
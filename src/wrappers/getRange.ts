@@ -1,4 +1,4 @@
-import { get_range } from '../wasm/screeps_clockwork';
+import { clockwork_get_range } from '../wasm/screeps_clockwork';
 
 /**
  * Get the global range between two positions. This is different
@@ -10,5 +10,5 @@ import { get_range } from '../wasm/screeps_clockwork';
  * @returns The range between the two positions.
  */
 export function getRange(pos1: RoomPosition, pos2: RoomPosition) {
-  return get_range(pos1.__packedPos, pos2.__packedPos);
+  return clockwork_get_range(pos1.__packedPos, pos2.__packedPos);
 }
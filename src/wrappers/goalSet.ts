@@ -0,0 +1,41 @@
+import { clockwork_create_goal_set, clockwork_free_goal_set, GoalKind } from '../wasm/screeps_clockwork';
+
+/**
+ * A set of destinations, unpacked and indexed once so that searches to the
+ * same targets (a storage, the sources of a room) can share it. Pass it as
+ * `anyOfDestinations` to `astarMultiroomDistanceMap` or
+ * `dijkstraMultiroomDistanceMap`.
+ *
+ * Goal sets live until freed: call `free()` when done, or wrap the set in
+ * `ephemeral` to free it at the end of the tick.
+ *
+ * @example
+ * ```typescript
+ * const storage = new GoalSet([{ pos: room.storage.pos, range: 1 }]);
+ * for (const creep of haulers) {
+ *   astarMultiroomDistanceMap([creep.pos], { costMatrixCallback, anyOfDestinations: storage });
+ * }
+ * storage.free();
+ * ```
+ */
+export class GoalSet {
+  readonly id: number;
+
+  constructor(goals: { pos: RoomPosition; range: number; kind?: GoalKind }[]) {
+    this.id = clockwork_create_goal_set(
+      new Uint32Array(
+        goals.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+          acc.push(pos.__packedPos, range, kind);
+          return acc;
+        }, [] as number[])
+      )
+    );
+  }
+
+  /**
+   * Free the goal set. Searches can't use it afterwards.
+   */
+  free() {
+    clockwork_free_goal_set(this.id);
+  }
+}
@@ -0,0 +1,65 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { fromPacked } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_depth_map, clockwork_grow_region } from '../wasm/screeps_clockwork';
+import { ClockworkMultiroomDistanceMap } from './multiroomDistanceMap';
+
+/**
+ * Grow a region outward from the seed positions (which must all be in the
+ * same room), including every tile whose path cost from the nearest seed is at
+ * most `maxCost`. The boundary tiles (adjacent to walkable tiles outside the
+ * region) are returned separately from the interior, which makes this handy
+ * for finding a defensive perimeter at a given distance from a spawn.
+ *
+ * @param seeds - The seed positions.
+ * @param maxCost - The maximum path cost from a seed.
+ * @param costMatrix - The cost matrix for the room.
+ * @returns The interior and boundary tiles of the region.
+ */
+export function growRegion(seeds: RoomPosition[], maxCost: number, costMatrix: ClockworkCostMatrix) {
+  const region = clockwork_grow_region(new Uint32Array(seeds.map(pos => pos.__packedPos)), maxCost, costMatrix);
+  const interior = [...region.interior].map(pos => fromPacked(pos));
+  const boundary = [...region.boundary].map(pos => fromPacked(pos));
+  region.free();
+
+  return {
+    interior,
+    boundary
+  };
+}
+
+/**
+ * Map the walking distance in tiles from the nearest seed, out to `maxDepth`
+ * tiles. Unlike `growRegion`, tile costs are ignored: anything under 255 in
+ * the cost matrix is one step, so swamps count the same as plains. Use it
+ * when only reachability or tile distance matters, e.g. "is this within 5
+ * walkable tiles of the controller?".
+ *
+ * @param seeds - The seed positions.
+ * @param options.costMatrixCallback - Returns the cost matrix for a room, or
+ * `undefined` to leave it out.
+ * @param options.maxDepth - The farthest distance to map, in tiles.
+ * @param options.maxRooms - The most rooms to explore. Defaults to no limit.
+ * @returns A distance map of tile distances from the seeds.
+ */
+export function depthMap(
+  seeds: RoomPosition[],
+  {
+    costMatrixCallback,
+    maxDepth,
+    maxRooms = MAX_USIZE
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    maxDepth: number;
+    maxRooms?: number;
+  }
+) {
+  return new ClockworkMultiroomDistanceMap(
+    clockwork_depth_map(
+      new Uint32Array(seeds.map(pos => pos.__packedPos)),
+      packedCostMatrixCallback(costMatrixCallback),
+      maxDepth,
+      maxRooms
+    )
+  );
+}
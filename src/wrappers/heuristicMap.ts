@@ -0,0 +1,34 @@
+import { clockwork_free_heuristic_map } from '../wasm/screeps_clockwork';
+
+/**
+ * A distance map from a set of destinations, stored for A* to use as its
+ * heuristic. Repeat searches to the same destinations (e.g. haulers returning
+ * to storage) are guided almost straight there and expand far fewer tiles.
+ * Create one with `ClockworkMultiroomDistanceMap.toHeuristicMap` and pass it
+ * as `heuristicMap` to `astarMultiroomDistanceMap`.
+ *
+ * The map is checked against the search's destinations, and the search
+ * throws if they differ from the ones it was built for. Heuristic maps live
+ * until freed: call `free()` when done, or wrap the map in `ephemeral`.
+ *
+ * @example
+ * ```typescript
+ * const destinations = [{ pos: room.storage.pos, range: 1 }];
+ * const fromStorage = dijkstraMultiroomDistanceMap([room.storage.pos], { costMatrixCallback, maxRooms: 4 });
+ * const heuristicMap = fromStorage.distanceMap.toHeuristicMap(destinations);
+ * fromStorage.distanceMap.free();
+ * for (const creep of haulers) {
+ *   astarMultiroomDistanceMap([creep.pos], { costMatrixCallback, anyOfDestinations: destinations, heuristicMap });
+ * }
+ * ```
+ */
+export class HeuristicMap {
+  constructor(readonly id: number) {}
+
+  /**
+   * Free the heuristic map. Searches can't use it afterwards.
+   */
+  free() {
+    clockwork_free_heuristic_map(this.id);
+  }
+}
@@ -0,0 +1,131 @@
+import { getRange } from './getRange';
+
+/**
+ * What a `HeuristicTuner` has learned about routes of one length.
+ */
+export interface HeuristicTunerBucket {
+  /** The heuristic weight to use for routes of this length. */
+  weight: number;
+  /** The number of searches recorded. */
+  samples: number;
+  /** A moving average of the ops the searches took. */
+  averageOps: number;
+  /** A moving average of the searches' suboptimality bounds. */
+  averageBound: number;
+}
+
+/**
+ * Learns a heuristic weight for each route length from the searches you run,
+ * so the weight settles where searches fit your ops budget: it rises a step
+ * when a search takes more than `targetOps`, and falls a step (towards an
+ * optimal search) when one takes less than half of it.
+ *
+ * Use `weightFor` as the `heuristicWeight` of `searchWithFallback` or the
+ * `initialWeight` of `anytimeAstar`, then `record` how the search went. The
+ * state is plain JSON, so it can be kept in `Memory` and restored with
+ * `HeuristicTuner.fromJSON`.
+ *
+ * @example
+ * ```typescript
+ * const tuner = HeuristicTuner.fromJSON(Memory.tuner ?? []);
+ * const weight = tuner.weightFor(creep.pos, target);
+ * const result = anytimeAstar(creep.pos, { costMatrixCallback, destinations, maxOps: 5000, initialWeight: weight });
+ * if (result) tuner.record(creep.pos, target, { ops: result.ops, bound: result.bound });
+ * Memory.tuner = tuner.toJSON();
+ * ```
+ */
+export class HeuristicTuner {
+  /** The longest route (in tiles) of each bucket but the last, which takes the rest. */
+  static readonly BUCKET_LIMITS = [25, 50, 100, 200, 400];
+
+  private buckets: HeuristicTunerBucket[];
+  private targetOps: number;
+  private minWeight: number;
+  private maxWeight: number;
+  private step: number;
+
+  /**
+   * @param options.targetOps - The ops a search should fit in. Defaults to 2000.
+   * @param options.minWeight - The lowest weight to use. Defaults to 1 (optimal).
+   * @param options.maxWeight - The highest weight to use. Defaults to 3.
+   * @param options.step - How much one search moves the weight. Defaults to 0.1.
+   */
+  constructor({
+    targetOps = 2000,
+    minWeight = 1,
+    maxWeight = 3,
+    step = 0.1
+  }: { targetOps?: number; minWeight?: number; maxWeight?: number; step?: number } = {}) {
+    this.targetOps = targetOps;
+    this.minWeight = minWeight;
+    this.maxWeight = maxWeight;
+    this.step = step;
+    this.buckets = [...HeuristicTuner.BUCKET_LIMITS, Infinity].map(() => ({
+      weight: minWeight,
+      samples: 0,
+      averageOps: 0,
+      averageBound: 1
+    }));
+  }
+
+  /**
+   * Restore a tuner from the state returned by `toJSON`. Buckets missing from
+   * the state start fresh.
+   */
+  static fromJSON(state: HeuristicTunerBucket[], options?: ConstructorParameters<typeof HeuristicTuner>[0]) {
+    const tuner = new HeuristicTuner(options);
+    state.slice(0, tuner.buckets.length).forEach((bucket, i) => (tuner.buckets[i] = { ...bucket }));
+    return tuner;
+  }
+
+  /**
+   * The tuner's state, for keeping in `Memory`.
+   */
+  toJSON(): HeuristicTunerBucket[] {
+    return this.buckets.map(bucket => ({ ...bucket }));
+  }
+
+  /**
+   * The heuristic weight to use for a route from `from` to `to`.
+   */
+  weightFor(from: RoomPosition, to: RoomPosition) {
+    return this.bucket(from, to).weight;
+  }
+
+  /**
+   * Record a search from `from` to `to` and tune the weight for its route
+   * length.
+   *
+   * @param stats.ops - The ops the search took.
+   * @param stats.bound - How far from optimal the path can be, as a multiple
+   * of the optimal cost (e.g. `anytimeAstar`'s `bound`). Defaults to the
+   * weight used.
+   */
+  record(from: RoomPosition, to: RoomPosition, { ops, bound }: { ops: number; bound?: number }) {
+    const bucket = this.bucket(from, to);
+    // Weigh recent searches more, but average the first few evenly
+    const rate = 1 / Math.min(bucket.samples + 1, 10);
+    bucket.averageOps += (ops - bucket.averageOps) * rate;
+    bucket.averageBound += ((bound ?? bucket.weight) - bucket.averageBound) * rate;
+    bucket.samples++;
+
+    if (ops > this.targetOps) {
+      bucket.weight = Math.min(this.maxWeight, bucket.weight + this.step);
+    } else if (ops < this.targetOps / 2) {
+      bucket.weight = Math.max(this.minWeight, bucket.weight - this.step);
+    }
+  }
+
+  /**
+   * What the tuner has learned about routes from `from` to `to`.
+   */
+  stats(from: RoomPosition, to: RoomPosition): HeuristicTunerBucket {
+    return { ...this.bucket(from, to) };
+  }
+
+  private bucket(from: RoomPosition, to: RoomPosition) {
+    const range = getRange(from, to);
+    const index = HeuristicTuner.BUCKET_LIMITS.findIndex(limit => range <= limit);
+    return this.buckets[index === -1 ? this.buckets.length - 1 : index];
+  }
+}
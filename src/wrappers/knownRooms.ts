@@ -0,0 +1,9 @@
+import { clockwork_forget_known_rooms } from '../wasm/screeps_clockwork';
+
+/**
+ * Forget the cost matrices remembered for searches that use
+ * `UnknownRoomPolicy.LastKnown`.
+ */
+export function forgetKnownRooms(): void {
+  clockwork_forget_known_rooms();
+}
@@ -0,0 +1,81 @@
+import { fromPacked, packRoomName } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_check_layout, LayoutViolation } from '../wasm/screeps_clockwork';
+import { getTerrainCostMatrix } from './getTerrainCostMatrix';
+
+// Matches the planned tile kinds in the wasm module
+const BLOCKING = 0;
+const ROAD = 1;
+const CONTAINER = 2;
+const RAMPART = 3;
+
+function plannedTileKind(structureType: string): number | undefined {
+  switch (structureType) {
+    case STRUCTURE_ROAD:
+      return ROAD;
+    case STRUCTURE_CONTAINER:
+      return CONTAINER;
+    case STRUCTURE_RAMPART:
+      return RAMPART;
+    default:
+      return (OBSTACLE_OBJECT_TYPES as string[]).includes(structureType) ? BLOCKING : undefined;
+  }
+}
+
+/**
+ * Check a planned room layout before spending energy on it. Reports:
+ *
+ * - `Collision`: more structures on a tile than can share it (ramparts go
+ *   over anything, and containers can sit on roads).
+ * - `OnWall`: a structure other than a road planned on a wall.
+ * - `SpawnBlocked`: a spawn with no free tile next to it.
+ * - `SpawnCutOff`: a spawn that can't reach any exit.
+ * - `SourceCutOff`: a source that can't reach the storage (only checked if
+ *   the layout has a storage).
+ *
+ * Reachability uses `costMatrix` (the room's terrain by default) with the
+ * layout's blocking structures added, so existing structures in the matrix
+ * count too. Structures that don't block movement and aren't roads,
+ * containers, or ramparts (e.g. extractors) are ignored.
+ *
+ * @param roomName - The room the layout is for.
+ * @param options - The planned structure positions by type, the room's
+ * sources, and optionally the cost matrix.
+ * @returns The problems found, each with the tile it's at.
+ */
+export function checkLayout(
+  roomName: string,
+  {
+    layout,
+    sources = [],
+    costMatrix
+  }: {
+    layout: Partial<Record<BuildableStructureConstant, RoomPosition[]>>;
+    sources?: RoomPosition[];
+    costMatrix?: ClockworkCostMatrix;
+  }
+): { violation: LayoutViolation; pos: RoomPosition }[] {
+  const planned: number[] = [];
+  for (const [structureType, positions] of Object.entries(layout)) {
+    const kind = plannedTileKind(structureType);
+    if (kind === undefined) continue;
+    for (const pos of positions ?? []) {
+      planned.push(pos.__packedPos, kind);
+    }
+  }
+  const storage = layout[STRUCTURE_STORAGE]?.[0];
+
+  const terrain = costMatrix ? undefined : getTerrainCostMatrix(roomName);
+  const result = clockwork_check_layout(
+    packRoomName(roomName),
+    new Uint32Array(planned),
+    new Uint32Array((layout[STRUCTURE_SPAWN] ?? []).map(pos => pos.__packedPos)),
+    new Uint32Array(sources.map(pos => pos.__packedPos)),
+    storage?.__packedPos,
+    (costMatrix ?? terrain)!
+  );
+  terrain?.free();
+  const violations = result.violations;
+  const positions = result.positions;
+  result.free();
+  return [...violations].map((violation, i) => ({ violation, pos: fromPacked(positions[i]) }));
+}
@@ -0,0 +1,77 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { packRoomName } from '../utils/fromPacked';
+import {
+  ClockworkCostMatrix,
+  clockwork_logistics_add_anchor,
+  clockwork_logistics_distance,
+  clockwork_logistics_mark_room_dirty,
+  clockwork_logistics_pending,
+  clockwork_logistics_remove_anchor,
+  clockwork_logistics_update
+} from '../wasm/screeps_clockwork';
+
+/**
+ * Register a logistics anchor (e.g. a room's storage). Clockwork maintains a
+ * distance map from the anchor, updating it when rooms it covers are marked
+ * dirty, so distance queries don't need a fresh search.
+ *
+ * @param origin - The anchor positions.
+ * @param options - Limits for the maintained distance map.
+ * @returns The anchor id.
+ */
+export function addLogisticsAnchor(
+  origin: RoomPosition[],
+  { maxRooms = MAX_USIZE, maxPathCost = MAX_USIZE }: { maxRooms?: number; maxPathCost?: number } = {}
+): number {
+  if (maxRooms === MAX_USIZE && maxPathCost === MAX_USIZE) {
+    throw new Error('At least one of maxRooms or maxPathCost must be set');
+  }
+  return clockwork_logistics_add_anchor(new Uint32Array(origin.map(pos => pos.__packedPos)), maxRooms, maxPathCost);
+}
+
+/**
+ * Stop maintaining a logistics anchor.
+ */
+export function removeLogisticsAnchor(anchorId: number): boolean {
+  return clockwork_logistics_remove_anchor(anchorId);
+}
+
+/**
+ * Mark a room's cost matrix as changed. Anchors whose distance maps cover the
+ * room will be updated on the next `updateLogistics` call: the tiles whose
+ * cost changed are repaired, or the whole map is recomputed if many changed
+ * or the room became blocked or unblocked.
+ */
+export function markLogisticsRoomDirty(room: string) {
+  clockwork_logistics_mark_room_dirty(packRoomName(room));
+}
+
+/**
+ * Number of anchors waiting to be recomputed.
+ */
+export function pendingLogisticsUpdates(): number {
+  return clockwork_logistics_pending();
+}
+
+/**
+ * Update up to `maxUpdates` dirty anchors. `costMatrixCallback` may call the
+ * other logistics functions, e.g. to mark more rooms dirty.
+ *
+ * @returns The number of anchors updated.
+ */
+export function updateLogistics(
+  costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined,
+  maxUpdates = 1
+): number {
+  return clockwork_logistics_update(packedCostMatrixCallback(costMatrixCallback), maxUpdates);
+}
+
+/**
+ * Get the maintained distance from an anchor to a position. Returns
+ * `MAX_USIZE` if the position is unreachable or the anchor hasn't been
+ * computed yet.
+ */
+export function logisticsDistance(anchorId: number, pos: RoomPosition): number {
+  return clockwork_logistics_distance(anchorId, pos.__packedPos);
+}
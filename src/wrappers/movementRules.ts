@@ -0,0 +1,39 @@
+import { MovementRules } from '../wasm/screeps_clockwork';
+
+/**
+ * How creeps may move during a search.
+ */
+export interface MovementOptions {
+  /**
+   * Whether creeps may move diagonally. Defaults to true.
+   */
+  allowDiagonals?: boolean;
+  /**
+   * Whether a diagonal move may squeeze past an impassable tile on either
+   * side of it, as the game allows. Defaults to true.
+   */
+  cutCorners?: boolean;
+  /**
+   * Extra cost for every move into another room, so short paths stay in the
+   * current room instead of dipping into a neighbor (which loses ticks to the
+   * room edges). Breadth-first searches count it too, but may stop at a
+   * destination before finding the route with the fewest room changes.
+   * Defaults to 0.
+   */
+  roomChangeCost?: number;
+}
+
+/**
+ * Build the wasm movement rules for a search, or `undefined` for the game's
+ * usual movement. The rules are consumed by the search they're passed to.
+ */
+export function createMovementRules({
+  allowDiagonals = true,
+  cutCorners = true,
+  roomChangeCost = 0
+}: MovementOptions): MovementRules | undefined {
+  if (allowDiagonals && cutCorners && !roomChangeCost) {
+    return undefined;
+  }
+  return new MovementRules(allowDiagonals, cutCorners, roomChangeCost);
+}
@@ -1,15 +1,38 @@
-import { fromPackedRoomName, packRoomName } from '../utils/fromPacked';
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { fromPacked, fromPackedRoomName, packRoomName } from '../utils/fromPacked';
 import {
+  ClockworkCostMatrix,
+  clockwork_create_heuristic_map,
+  clockwork_multiroom_flow_field,
+  clockwork_multiroom_mono_flow_field,
+  clockwork_path_to_multiroom_distance_map_origin,
+  clockwork_repair_distance_map,
   DistanceMap,
-  js_path_to_multiroom_distance_map_origin,
+  GoalKind,
+  MaskComparison,
   MultiroomDistanceMap,
-  multiroomFlowField,
-  multiroomMonoFlowField
+  RoomStats
 } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { HeuristicMap } from './heuristicMap';
+import { createMovementRules, MovementOptions } from './movementRules';
 import { ClockworkMultiroomFlowField } from './multiroomFlowField';
 import { ClockworkMultiroomMonoFlowField } from './multiroomMonoFlowField';
 import { ClockworkPath } from './path';
 
+function fromRoomStats(stats: RoomStats) {
+  const result = {
+    min: stats.min,
+    max: stats.max,
+    mean: stats.mean,
+    count: stats.count,
+    countBelow: stats.count_below
+  };
+  stats.free();
+  return result;
+}
+
 /**
  * A distance map that covers multiple rooms. Typically returned by a function
  * like `bfsMultiroomDistanceMap` rather than created directly.
@@ -45,6 +68,154 @@ export class ClockworkMultiroomDistanceMap {
     return [...this._map.get_rooms()].map(room => fromPackedRoomName(room));
   }
 
+  /**
+   * Summarize the reachable values in a room: min, max, mean, the number of
+   * reachable tiles, and the number below `threshold`. Returns `undefined`
+   * if the map has no data for the room.
+   */
+  roomStats(room: string, threshold = MAX_USIZE) {
+    const stats = this._map.room_stats(packRoomName(room), threshold);
+    return stats ? fromRoomStats(stats) : undefined;
+  }
+
+  /**
+   * Summarize the reachable values across all rooms.
+   */
+  summary(threshold = MAX_USIZE) {
+    return fromRoomStats(this._map.summary(threshold));
+  }
+
+  /**
+   * Count a room's reachable values in buckets of `bucketWidth`, starting
+   * from 0. Returns `undefined` if the map has no data for the room.
+   */
+  histogram(room: string, bucketWidth = 1): number[] | undefined {
+    const histogram = this._map.histogram(packRoomName(room), bucketWidth);
+    return histogram ? [...histogram] : undefined;
+  }
+
+  /**
+   * Find the reachable position with the lowest value, optionally only
+   * searching the given rooms.
+   */
+  argmin(rooms?: string[]): RoomPosition | undefined {
+    const pos = this._map.argmin(rooms ? new Uint16Array(rooms.map(packRoomName)) : undefined);
+    return pos === undefined ? undefined : fromPacked(pos);
+  }
+
+  /**
+   * Find the reachable position with the highest value, optionally only
+   * searching the given rooms.
+   */
+  argmax(rooms?: string[]): RoomPosition | undefined {
+    const pos = this._map.argmax(rooms ? new Uint16Array(rooms.map(packRoomName)) : undefined);
+    return pos === undefined ? undefined : fromPacked(pos);
+  }
+
+  /**
+   * Find the `k` reachable positions with the lowest values (or the highest,
+   * if `largest` is set), best first.
+   */
+  topK(k: number, { largest = false, rooms }: { largest?: boolean; rooms?: string[] } = {}): RoomPosition[] {
+    return [...this._map.top_k(k, largest, rooms ? new Uint16Array(rooms.map(packRoomName)) : undefined)].map(pos =>
+      fromPacked(pos)
+    );
+  }
+
+  /**
+   * Find all reachable positions with a value below `threshold`, optionally
+   * only searching the given rooms.
+   */
+  positionsBelow(threshold: number, rooms?: string[]): RoomPosition[] {
+    return [
+      ...this._map.positions_below(threshold, rooms ? new Uint16Array(rooms.map(packRoomName)) : undefined)
+    ].map(pos => fromPacked(pos));
+  }
+
+  /**
+   * Get the values in the rectangle between two corners (in either order),
+   * which may be in different rooms. Only the rooms overlapping the rectangle
+   * are visited, which makes this cheap enough for minimap rendering.
+   * Rectangles larger than a 3x3 block of rooms (22,500 tiles) throw; read
+   * bigger areas in pieces.
+   *
+   * @returns The rectangle's size in tiles, and its values in row-major order
+   * (top to bottom, west to east). Tiles in rooms without data are
+   * `MAX_USIZE`.
+   */
+  valuesInRect(corner1: RoomPosition, corner2: RoomPosition): { width: number; height: number; values: Uint32Array } {
+    // Packed positions hold the room and tile coordinates in one byte each,
+    // so world coordinate differences can be read straight from them
+    const worldX = (pos: RoomPosition) => (pos.__packedPos >>> 24) * 50 + ((pos.__packedPos >>> 8) & 0xff);
+    const worldY = (pos: RoomPosition) => ((pos.__packedPos >>> 16) & 0xff) * 50 + (pos.__packedPos & 0xff);
+    return {
+      width: Math.abs(worldX(corner1) - worldX(corner2)) + 1,
+      height: Math.abs(worldY(corner1) - worldY(corner2)) + 1,
+      values: this._map.values_in_rect(corner1.__packedPos, corner2.__packedPos)
+    };
+  }
+
+  /**
+   * Replace each value with the smaller of it and the corresponding value in
+   * `other` (in place). Rooms only in `other` are copied over.
+   */
+  minWith(other: ClockworkMultiroomDistanceMap) {
+    this._map.min_with(other._map);
+  }
+
+  /**
+   * Add `k` times each value in `other` to the corresponding value here (in
+   * place). Tiles unreachable in either map become unreachable.
+   */
+  addScaled(other: ClockworkMultiroomDistanceMap, k: number) {
+    this._map.add_scaled(other._map, k);
+  }
+
+  /**
+   * Mark tiles unreachable (in place) wherever the corresponding value in
+   * `other` satisfies the comparison with `threshold`. For example, masking
+   * with a threat map where values are `GreaterThan` 0 removes all threatened
+   * tiles.
+   */
+  maskWhere(other: ClockworkMultiroomDistanceMap, comparison: MaskComparison, threshold: number) {
+    this._map.mask_where(other._map, comparison, threshold);
+  }
+
+  /**
+   * Repair the distance map (in place) after the costs of a few tiles have
+   * changed, e.g. a new construction site or a parked creep, instead of
+   * rerunning the search. Tiles within `radius` of a changed tile are
+   * recalculated from the surrounding values; effects that reach further than
+   * `radius` aren't repaired. Only suited to maps from Dijkstra or complete
+   * A* searches. Pass the same cost overlay and movement options as the
+   * search that made the map.
+   *
+   * @returns The positions whose distance changed. Pass them to a flow
+   * field's `repair` to update it to match.
+   */
+  repair(
+    changed: RoomPosition[],
+    {
+      costMatrixCallback,
+      radius = 3,
+      ...searchOptions
+    }: {
+      costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+      radius?: number;
+    } & CostOverlayOptions & MovementOptions
+  ): RoomPosition[] {
+    return [
+      ...clockwork_repair_distance_map(
+        this._map,
+        new Uint32Array(changed.map(pos => pos.__packedPos)),
+        packedCostMatrixCallback(costMatrixCallback),
+        radius,
+        createCostOverlay(searchOptions),
+        createMovementRules(searchOptions)
+      )
+    ].map(pos => fromPacked(pos));
+  }
+
   /**
    * Free the memory allocated for this distance map.
    */
@@ -56,20 +227,39 @@ export class ClockworkMultiroomDistanceMap {
    * Path to the origin from a given position.
    */
   pathToOrigin(start: RoomPosition): ClockworkPath {
-    return new ClockworkPath(js_path_to_multiroom_distance_map_origin(start.__packedPos, this._map));
+    return new ClockworkPath(clockwork_path_to_multiroom_distance_map_origin(start.__packedPos, this._map));
   }
 
   /**
    * Flow field for this distance map.
    */
   toFlowField(): ClockworkMultiroomFlowField {
-    return new ClockworkMultiroomFlowField(multiroomFlowField(this._map));
+    return new ClockworkMultiroomFlowField(clockwork_multiroom_flow_field(this._map));
+  }
+
+  /**
+   * Store a copy of this map as an A* heuristic for searches to
+   * `destinations`. The map should be measured from the destinations, e.g. by
+   * a Dijkstra search started at them. See `HeuristicMap`.
+   */
+  toHeuristicMap(destinations: { pos: RoomPosition; range: number; kind?: GoalKind }[]): HeuristicMap {
+    return new HeuristicMap(
+      clockwork_create_heuristic_map(
+        this._map,
+        new Uint32Array(
+          destinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+            acc.push(pos.__packedPos, range, kind);
+            return acc;
+          }, [] as number[])
+        )
+      )
+    );
   }
 
   /**
    * Mono-directional flow field for this distance map.
    */
   toMonoFlowField(): ClockworkMultiroomMonoFlowField {
-    return new ClockworkMultiroomMonoFlowField(multiroomMonoFlowField(this._map));
+    return new ClockworkMultiroomMonoFlowField(clockwork_multiroom_mono_flow_field(this._map));
   }
 }
@@ -1,6 +1,11 @@
 import { fromPackedRoomName, packRoomName } from '../utils/fromPacked';
-import { MultiroomFlowField, js_path_to_multiroom_flow_field_origin } from '../wasm/screeps_clockwork';
+import {
+  clockwork_path_to_multiroom_flow_field_origin,
+  clockwork_repair_multiroom_flow_field,
+  MultiroomFlowField
+} from '../wasm/screeps_clockwork';
 import { ClockworkFlowField } from './flowField';
+import type { ClockworkMultiroomDistanceMap } from './multiroomDistanceMap';
 import { ClockworkPath } from './path';
 
 /**
@@ -63,11 +68,41 @@ export class ClockworkMultiroomFlowField {
     return flowField ? new ClockworkFlowField(flowField) : null;
   }
 
+  /**
+   * Get a room's direction bitmasks as a flat array indexed by `y * 50 + x`,
+   * or `null` if the room isn't in the flow field. Bit `d` is set when
+   * direction `d` is valid.
+   */
+  getRoomData(roomName: string): Uint8Array | null {
+    return this._flowField.getRoomData(packRoomName(roomName)) ?? null;
+  }
+
+  /**
+   * Replace a room's direction bitmasks (2500 values, indexed by
+   * `y * 50 + x`), e.g. to restore a saved field or forbid certain lanes in
+   * bulk.
+   */
+  setRoomData(roomName: string, data: Uint8Array): void {
+    this._flowField.setRoomData(packRoomName(roomName), data);
+  }
+
   /**
    * Find a path from a given position to the origin of the flow field.
    */
   pathToOrigin(start: RoomPosition): ClockworkPath {
-    return new ClockworkPath(js_path_to_multiroom_flow_field_origin(start.__packedPos, this._flowField));
+    return new ClockworkPath(clockwork_path_to_multiroom_flow_field_origin(start.__packedPos, this._flowField));
+  }
+
+  /**
+   * Recompute the flow field (in place) around the given positions, usually
+   * the ones returned by the distance map's `repair`.
+   */
+  repair(distanceMap: ClockworkMultiroomDistanceMap, changed: RoomPosition[]): void {
+    clockwork_repair_multiroom_flow_field(
+      this._flowField,
+      distanceMap['_map'],
+      new Uint32Array(changed.map(pos => pos.__packedPos))
+    );
   }
 
   /**
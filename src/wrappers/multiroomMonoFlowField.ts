@@ -1,5 +1,6 @@
+import { MAX_USIZE } from '../utils/constants';
 import { fromPackedRoomName, packRoomName } from '../utils/fromPacked';
-import { MultiroomMonoFlowField, js_path_to_multiroom_mono_flow_field_origin } from '../wasm/screeps_clockwork';
+import { clockwork_path_to_multiroom_mono_flow_field_origin, MultiroomMonoFlowField } from '../wasm/screeps_clockwork';
 import { ClockworkMonoFlowField } from './monoFlowField';
 import { ClockworkPath } from './path';
 
@@ -46,7 +47,17 @@ export class ClockworkMultiroomMonoFlowField {
    * Find a path from a given position to the origin of the flow field.
    */
   pathToOrigin(start: RoomPosition): ClockworkPath {
-    return new ClockworkPath(js_path_to_multiroom_mono_flow_field_origin(start.__packedPos, this._flowField));
+    return new ClockworkPath(clockwork_path_to_multiroom_mono_flow_field_origin(start.__packedPos, this._flowField));
+  }
+
+  /**
+   * Trace the path from any tile to the flow field's target, following the
+   * stored directions. Unlike `pathToOrigin`, the positions are in walking
+   * order, and the path stops early once it has `maxLen` positions. Throws if
+   * the directions form a cycle.
+   */
+  tracePath(from: RoomPosition, maxLen = MAX_USIZE): ClockworkPath {
+    return new ClockworkPath(this._flowField.tracePath(from.__packedPos, maxLen));
   }
 
   /**
@@ -0,0 +1,65 @@
+import { anytimeAstar } from './anytimeAstar';
+import { astarMultiroomDistanceMap } from './astarDistanceMap';
+import { bfsMultiroomDistanceMap } from './bfsDistanceMap';
+import { bestOrigin, closestOf } from './closestOf';
+import { dijkstraMultiroomDistanceMap } from './dijkstraDistanceMap';
+import { findSafePositions, fleeToSafety, fleeWithHysteresis } from './flee';
+import { fringeSearch } from './fringeSearch';
+import { getTerrainCostMatrix } from './getTerrainCostMatrix';
+import { depthMap, growRegion } from './growRegion';
+import { noiseCostMatrix } from './noise';
+import { nukeDamageMap } from './nukes';
+import { getPackedTerrain } from './packedTerrain';
+import { stitchPaths } from './path';
+import { patrolLoop } from './patrolLoop';
+import { planRepairTour } from './repairTour';
+import { roomAstarPath } from './roomAstarPath';
+import { searchWithFallback } from './searchWithFallback';
+import { findSwampBridges } from './swampBridges';
+import { buildThreatMatrix } from './threatMatrix';
+import { prioritizeRamparts, towerCoverage } from './towerCoverage';
+import { findWallGaps } from './wallGaps';
+import { waypointPath } from './waypointPath';
+
+/**
+ * The searches and path builders, grouped in one place. Each member is the
+ * same function that's exported on its own, e.g.
+ * `ClockworkPathfinder.astarMultiroomDistanceMap === astarMultiroomDistanceMap`.
+ */
+export const ClockworkPathfinder = Object.freeze({
+  anytimeAstar,
+  astarMultiroomDistanceMap,
+  bestOrigin,
+  bfsMultiroomDistanceMap,
+  closestOf,
+  dijkstraMultiroomDistanceMap,
+  findSafePositions,
+  fleeToSafety,
+  fleeWithHysteresis,
+  fringeSearch,
+  patrolLoop,
+  planRepairTour,
+  roomAstarPath,
+  searchWithFallback,
+  stitchPaths,
+  waypointPath
+});
+
+/**
+ * The functions that build per-tile maps of a room (cost matrices, terrain,
+ * threat and coverage maps, regions), grouped in one place. Like
+ * `ClockworkPathfinder`, each member is also exported on its own.
+ */
+export const ClockworkMaps = Object.freeze({
+  buildThreatMatrix,
+  depthMap,
+  findSwampBridges,
+  findWallGaps,
+  getPackedTerrain,
+  getTerrainCostMatrix,
+  growRegion,
+  noiseCostMatrix,
+  nukeDamageMap,
+  prioritizeRamparts,
+  towerCoverage
+});
@@ -0,0 +1,24 @@
+import { packRoomName } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_noise_cost_matrix } from '../wasm/screeps_clockwork';
+
+/**
+ * Generate seeded value noise for a room, as a matrix of values from 0 to
+ * `amplitude`. Noise is sampled in world coordinates, so adjacent rooms line
+ * up seamlessly. Useful for randomizing cost penalties (e.g. to make patrol
+ * routes less predictable).
+ *
+ * @param roomName - The room to generate noise for.
+ * @param options - `scale` is the size of noise features in tiles; each extra
+ * octave adds finer detail at half the scale.
+ */
+export function noiseCostMatrix(
+  roomName: string,
+  {
+    seed = 1,
+    scale = 10,
+    octaves = 1,
+    amplitude = 10
+  }: { seed?: number; scale?: number; octaves?: number; amplitude?: number } = {}
+): ClockworkCostMatrix {
+  return clockwork_noise_cost_matrix(packRoomName(roomName), seed, scale, octaves, amplitude);
+}
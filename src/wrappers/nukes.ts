@@ -0,0 +1,35 @@
+import { fromPacked } from '../utils/fromPacked';
+import { clockwork_nuke_damage_map, clockwork_plan_nuke_strike } from '../wasm/screeps_clockwork';
+import { ClockworkDistanceMap } from './distanceMap';
+
+/**
+ * Map the cumulative damage structures on each tile of a room would take
+ * from nukes landing at `centers` (10M on the landing tile, 5M within range
+ * 2).
+ *
+ * @param centers - The landing positions (must all be in the same room).
+ * @returns The damage per tile.
+ */
+export function nukeDamageMap(centers: RoomPosition[]): ClockworkDistanceMap {
+  return new ClockworkDistanceMap(clockwork_nuke_damage_map(new Uint32Array(centers.map(pos => pos.__packedPos))));
+}
+
+/**
+ * Choose landing points for up to `count` nukes, one at a time, each
+ * maximizing the extra damage dealt to `structures`. Damage to a structure
+ * is capped at its hits, so for a structure under a rampart, pass the sum of
+ * both. Fewer points are returned if more nukes wouldn't add any damage.
+ *
+ * @param structures - The structures to target (must all be in the same room).
+ * @param count - The number of nukes available.
+ * @returns The landing positions, in the order they were chosen.
+ */
+export function planNukeStrike(structures: { pos: RoomPosition; hits: number }[], count: number): RoomPosition[] {
+  return [
+    ...clockwork_plan_nuke_strike(
+      new Uint32Array(structures.map(({ pos }) => pos.__packedPos)),
+      new Uint32Array(structures.map(({ hits }) => hits)),
+      count
+    )
+  ].map(pos => fromPacked(pos));
+}
@@ -0,0 +1,39 @@
+import { OpenListLimit, OpenListOverflow } from '../wasm/screeps_clockwork';
+
+/**
+ * A cap on how large a search's open list can grow.
+ */
+export interface OpenListOptions {
+  /**
+   * The most tiles that can wait in the search's open list, to keep
+   * pathological searches from growing memory without bound. Unlimited by
+   * default.
+   */
+  maxOpenNodes?: number;
+  /**
+   * What to do when the open list passes `maxOpenNodes`: stop with the
+   * partial result (the default), or keep only the `beamWidth` most
+   * promising tiles per cost bucket and carry on (no longer optimal).
+   */
+  openListOverflow?: OpenListOverflow;
+  /**
+   * The tiles to keep per cost bucket with `OpenListOverflow.Beam`. Defaults
+   * to 100.
+   */
+  beamWidth?: number;
+}
+
+/**
+ * Build the wasm open list limit for a search, or `undefined` if the open
+ * list is unlimited. The limit is consumed by the search it's passed to.
+ */
+export function createOpenListLimit({
+  maxOpenNodes,
+  openListOverflow = OpenListOverflow.Stop,
+  beamWidth
+}: OpenListOptions): OpenListLimit | undefined {
+  if (maxOpenNodes === undefined) {
+    return undefined;
+  }
+  return new OpenListLimit(maxOpenNodes, openListOverflow, beamWidth);
+}
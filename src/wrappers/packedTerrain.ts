@@ -0,0 +1,34 @@
+import { packRoomName } from '../utils/fromPacked';
+import { clockwork_get_packed_terrain } from '../wasm/screeps_clockwork';
+
+/**
+ * Room terrain packed at 2 bits per tile. This is the same terrain data
+ * Clockwork caches for its own terrain cost matrices, so reading it here
+ * doesn't trigger another `Game.map.getRoomTerrain` call.
+ */
+export class PackedTerrain {
+  constructor(private readonly buffer: Uint8Array) {}
+
+  /**
+   * Get the terrain at a given position, as a `TERRAIN_MASK_*` value
+   * (0 for plain, `TERRAIN_MASK_WALL`, or `TERRAIN_MASK_SWAMP`).
+   */
+  get(x: number, y: number): number {
+    const index = y * 50 + x;
+    return (this.buffer[index >> 2] >> ((index & 3) * 2)) & 0b11;
+  }
+
+  /**
+   * Get the raw packed buffer (row-major, four tiles per byte).
+   */
+  toArray(): Uint8Array {
+    return this.buffer;
+  }
+}
+
+/**
+ * Get the (cached) packed terrain for a room.
+ */
+export function getPackedTerrain(roomName: string): PackedTerrain {
+  return new PackedTerrain(clockwork_get_packed_terrain(packRoomName(roomName)));
+}
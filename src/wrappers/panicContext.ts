@@ -0,0 +1,14 @@
+import { clockwork_panic_context } from '../wasm/screeps_clockwork';
+
+/**
+ * Describe the algorithms currently running, outermost first, and the
+ * position the innermost one was last working on, in the same form that's
+ * logged if clockwork panics (e.g. `dijkstra > astar (room W1N1, position
+ * 0,25)`). Call it from inside a callback, like a `costMatrixCallback`, to see
+ * which search is asking; outside of a search it returns `undefined`.
+ *
+ * @returns The running algorithms and position, or `undefined`.
+ */
+export function panicContext(): string | undefined {
+  return clockwork_panic_context();
+}
@@ -1,5 +1,14 @@
-import { fromPacked } from '../utils/fromPacked';
-import { Path } from '../wasm/screeps_clockwork';
+import { fromPacked, packRoomName } from '../utils/fromPacked';
+import {
+  BodySpec,
+  clockwork_path_chunk,
+  clockwork_path_contains,
+  clockwork_path_drop,
+  clockwork_path_len,
+  clockwork_path_next,
+  clockwork_path_store,
+  Path
+} from '../wasm/screeps_clockwork';
 
 /**
  * A path from a start position to an end position. Typically returned by a
@@ -82,6 +91,109 @@ export class ClockworkPath {
     return this.path.find_next_index(pos.__packedPos);
   }
 
+  /**
+   * Get the direction to move from the position at `index` to the next
+   * position. Returns `undefined` at the end of the path, or at a room border
+   * crossing (the creep moves to the next room without a move intent).
+   */
+  directionAt(index: number): DirectionConstant | undefined {
+    return this.path.direction_at(index) as DirectionConstant | undefined;
+  }
+
+  /**
+   * Get the move directions for the whole path, in order. Room border
+   * crossings are skipped, since they don't need a move intent.
+   */
+  directions(): DirectionConstant[] {
+    return [...this.path.directions()] as DirectionConstant[];
+  }
+
+  /**
+   * Find the index of a position in the path, or `undefined` if it isn't on
+   * the path.
+   */
+  findIndexOf(pos: RoomPosition): number | undefined {
+    return this.path.find_index_of(pos.__packedPos);
+  }
+
+  /**
+   * Find the index of the path position closest to `pos`, if it's within
+   * `tolerance` range; otherwise `undefined`. Use this to check whether a
+   * creep is still on (or near) its path.
+   */
+  findIndexNear(pos: RoomPosition, tolerance = 0): number | undefined {
+    return this.path.find_index_near(pos.__packedPos, tolerance);
+  }
+
+  /**
+   * Get a new path with up to `length` positions, starting at `start`.
+   */
+  slice(start: number, length: number): ClockworkPath {
+    return new ClockworkPath(this.path.slice(start, length));
+  }
+
+  /**
+   * Get a new path that ends just before this path first enters `room`.
+   */
+  truncateAtRoom(room: string): ClockworkPath {
+    return new ClockworkPath(this.path.truncate_at_room(packRoomName(room)));
+  }
+
+  /**
+   * Split the path into one segment per room visit, in order. Each segment
+   * runs from the tile where the path enters the room to the tile where it
+   * leaves, so room-local segments can be cached (see `PathSegmentCache`)
+   * and reused by other routes through the same corridors.
+   */
+  splitByRoom(): PathSegment[] {
+    const positions = this.toArray();
+    let offset = 0;
+    return [...this.path.room_segment_lengths()].map(length => {
+      const segment = positions.slice(offset, offset + length);
+      offset += length;
+      return {
+        roomName: segment[0].roomName,
+        entry: segment[0],
+        exit: segment[segment.length - 1],
+        positions: segment
+      };
+    });
+  }
+
+  /**
+   * Get a new path with `other` appended. Throws if `other` doesn't continue
+   * from the end of this path.
+   */
+  concat(other: ClockworkPath): ClockworkPath {
+    return new ClockworkPath(this.path.concat(other.path));
+  }
+
+  /**
+   * Get a copy of the path without repeated positions or back-and-forth
+   * steps (A → B → A), e.g. after joining paths from several sources.
+   */
+  normalize(): ClockworkPath {
+    return new ClockworkPath(this.path.normalize());
+  }
+
+  /**
+   * Find the index of the first position that isn't adjacent to the one
+   * before it (or its matching tile across a room border), or `undefined` if
+   * the path is continuous.
+   */
+  firstGap(): number | undefined {
+    return this.path.first_gap();
+  }
+
+  /**
+   * Estimate the ticks a creep with the given body takes to walk the path,
+   * accounting for fatigue from terrain and roads. Roads are only detected in
+   * visible rooms. Returns `undefined` if the creep can't move.
+   */
+  estimateTravelTicks(body: BodySpec): number | undefined {
+    return this.path.estimate_travel_ticks(body);
+  }
+
   /**
    * Convert the path to an array of positions.
    */
@@ -104,6 +216,14 @@ export class ClockworkPath {
     return result;
   }
 
+  /**
+   * Store a copy of the path in wasm memory and get a handle to it, so a
+   * creep can follow it tick after tick without copying positions to JS.
+   */
+  store(): PathHandle {
+    return new PathHandle(clockwork_path_store(this.path));
+  }
+
   /**
    * Free the memory allocated for this path.
    */
@@ -111,3 +231,178 @@ export class ClockworkPath {
     this.path.free();
   }
 }
+
+/**
+ * A path stored in wasm memory, referred to by a small id. Only the id needs
+ * to be kept between ticks (e.g. on the creep's heap data); stored paths are
+ * lost on a global reset, so keep the id out of `Memory`.
+ *
+ * @example
+ * ```typescript
+ * const handle = path.store();
+ * // each tick
+ * const direction = handle.next(creep.pos);
+ * if (direction) creep.move(direction);
+ * // when done
+ * handle.drop();
+ * ```
+ */
+export class PathHandle {
+  constructor(readonly id: number) {}
+
+  /**
+   * Get the direction to move from `pos` along the path. Returns `undefined`
+   * at the end of the path, at a room border crossing, or if `pos` is
+   * neither on nor next to the path. Throws if the path was dropped.
+   */
+  next(pos: RoomPosition): DirectionConstant | undefined {
+    return clockwork_path_next(this.id, pos.__packedPos) as DirectionConstant | undefined;
+  }
+
+  /**
+   * The number of positions in the stored path.
+   */
+  get length(): number {
+    return clockwork_path_len(this.id);
+  }
+
+  /**
+   * Get up to `length` positions starting at `offset`, e.g. the next few
+   * steps, or one chunk at a time for serialization, without copying the
+   * whole path out of wasm.
+   */
+  chunk(offset: number, length: number): RoomPosition[] {
+    return [...clockwork_path_chunk(this.id, offset, length)].map(pos => fromPacked(pos));
+  }
+
+  /**
+   * Free the stored path. Returns false if it was already dropped.
+   */
+  drop(): boolean {
+    return clockwork_path_drop(this.id);
+  }
+}
+
+/**
+ * The part of a path within a single room, from the tile where it enters the
+ * room to the tile where it leaves.
+ */
+export interface PathSegment {
+  roomName: string;
+  entry: RoomPosition;
+  exit: RoomPosition;
+  positions: RoomPosition[];
+}
+
+/**
+ * Caches room-local path segments by their entry and exit tiles, so routes
+ * that share corridors can reuse each other's segments instead of searching
+ * every room again.
+ */
+export class PathSegmentCache {
+  private segments = new Map<string, RoomPosition[]>();
+
+  private static key(entry: RoomPosition, exit: RoomPosition) {
+    return `${entry.__packedPos}:${exit.__packedPos}`;
+  }
+
+  /**
+   * Split a path by room and cache each of its segments.
+   */
+  addPath(path: ClockworkPath): void {
+    for (const segment of path.splitByRoom()) {
+      this.segments.set(PathSegmentCache.key(segment.entry, segment.exit), segment.positions);
+    }
+  }
+
+  /**
+   * Get the cached segment from `entry` to `exit`, if there is one.
+   */
+  get(entry: RoomPosition, exit: RoomPosition): RoomPosition[] | undefined {
+    return this.segments.get(PathSegmentCache.key(entry, exit));
+  }
+
+  /**
+   * Stitch a route from cached segments, given the entry and exit tiles of
+   * each room along it. Returns `undefined` if any segment isn't cached, so
+   * the caller can search for the missing rooms instead.
+   */
+  stitch(legs: [entry: RoomPosition, exit: RoomPosition][]): ClockworkPath | undefined {
+    const pieces: RoomPosition[][] = [];
+    for (const [entry, exit] of legs) {
+      const segment = this.get(entry, exit);
+      if (!segment) {
+        return undefined;
+      }
+      pieces.push(segment);
+    }
+    return stitchPaths(pieces);
+  }
+
+  /**
+   * Forget every cached segment.
+   */
+  clear(): void {
+    this.segments.clear();
+  }
+}
+
+/**
+ * Stitch piecewise paths (e.g. from room-restricted searches or cached
+ * segments) into one continuous path. Duplicate positions where pieces meet
+ * are dropped, and a missing edge tile at a room border is filled in. Throws
+ * if two pieces can't be joined.
+ */
+export function stitchPaths(pieces: (ClockworkPath | RoomPosition[])[]): ClockworkPath {
+  const arrays = pieces.map(piece => (piece instanceof ClockworkPath ? piece.toArray() : piece));
+  return new ClockworkPath(
+    Path.stitch(
+      new Uint32Array(
+        arrays.reduce((acc, piece) => {
+          for (const pos of piece) acc.push(pos.__packedPos);
+          return acc;
+        }, [] as number[])
+      ),
+      new Uint32Array(arrays.map(piece => piece.length))
+    )
+  );
+}
+
+/**
+ * Check whether `pos` is on (or within `tolerance` range of) a path, such as
+ * one cached in memory as an array of positions. Returns the index of the
+ * closest path position, or `undefined` if the position has strayed further
+ * than `tolerance`.
+ */
+export function pathContains(
+  path: ClockworkPath | RoomPosition[],
+  pos: RoomPosition,
+  tolerance = 0
+): number | undefined {
+  if (path instanceof ClockworkPath) {
+    return path.findIndexNear(pos, tolerance);
+  }
+  return clockwork_path_contains(new Uint32Array(path.map(p => p.__packedPos)), pos.__packedPos, tolerance);
+}
+
+/**
+ * Find the breachable tiles a path goes through, in path order: the walls and
+ * ramparts that have to be destroyed to follow a path found with
+ * `breachableTiles`.
+ *
+ * @example
+ * ```typescript
+ * const breachableTiles = walls.map(wall => ({ pos: wall.pos, hits: wall.hits }));
+ * const result = astarMultiroomDistanceMap([creep.pos], { costMatrixCallback, anyOfDestinations, breachableTiles });
+ * const path = result.distanceMap.pathToOrigin(result.foundTargets[0]);
+ * const targets = breachesAlong(path, breachableTiles);
+ * ```
+ */
+export function breachesAlong(
+  path: ClockworkPath | RoomPosition[],
+  breachableTiles: { pos: RoomPosition }[]
+): RoomPosition[] {
+  const breachable = new Set(breachableTiles.map(({ pos }) => pos.__packedPos));
+  const positions = path instanceof ClockworkPath ? path.toArray() : path;
+  return positions.filter(pos => breachable.has(pos.__packedPos));
+}
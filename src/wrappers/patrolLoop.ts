@@ -0,0 +1,55 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_patrol_loop } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { createOpenListLimit, OpenListOptions } from './openListLimit';
+import { ClockworkPath } from './path';
+
+/**
+ * Build a closed patrol loop through a set of points, e.g. for defenders and
+ * scouts patrolling remote rooms. The loop starts and ends at the first point
+ * and heads to the closest unvisited point (by path cost) at each step. With
+ * `improve` (the default), the order is then refined with 2-opt, which costs
+ * a search from every point.
+ *
+ * `maxRooms` and `maxOps` apply to each search separately.
+ *
+ * @param points - The positions to patrol.
+ * @param options - The options for the searches.
+ * @returns `order`, the indices of the points in the order they're visited,
+ * and `path`, from the first point through the others and back; or
+ * `undefined` if a point can't be reached.
+ */
+export function patrolLoop(
+  points: RoomPosition[],
+  {
+    costMatrixCallback,
+    maxRooms = MAX_USIZE,
+    maxOps = MAX_USIZE,
+    improve = true,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    maxRooms?: number;
+    maxOps?: number;
+    improve?: boolean;
+  } & CostOverlayOptions & MovementOptions & OpenListOptions
+): { order: number[]; path: ClockworkPath } | undefined {
+  const result = clockwork_patrol_loop(
+    new Uint32Array(points.map(pos => pos.__packedPos)),
+    packedCostMatrixCallback(costMatrixCallback),
+    maxRooms,
+    maxOps,
+    improve,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions)
+  );
+  if (!result) {
+    return undefined;
+  }
+  const loop = { order: [...result.order], path: new ClockworkPath(result.path) };
+  result.free();
+  return loop;
+}
@@ -0,0 +1,535 @@
+import {
+  clockwork_add_circle_zone,
+  clockwork_add_rect_zone,
+  clockwork_add_tiles_zone,
+  clockwork_adjacent_rooms,
+  clockwork_anytime_astar,
+  clockwork_astar_multiroom_distance_map,
+  clockwork_astar_multiroom_distance_map_positions,
+  clockwork_best_origin,
+  clockwork_bfs_multiroom_distance_map,
+  clockwork_bfs_multiroom_distance_map_positions,
+  clockwork_build_threat_matrix,
+  clockwork_cache_stats,
+  clockwork_check_layout,
+  clockwork_clear_cost_matrix_cache,
+  clockwork_clear_zones,
+  clockwork_closest_of,
+  clockwork_corresponding_room_edge,
+  clockwork_corridor_rooms,
+  clockwork_create_goal_set,
+  clockwork_create_heuristic_map,
+  clockwork_default_options,
+  clockwork_depth_map,
+  clockwork_dijkstra_multiroom_distance_map,
+  clockwork_dijkstra_multiroom_distance_map_positions,
+  clockwork_disperse,
+  clockwork_estimate_travel_ticks,
+  clockwork_exit_direction_between,
+  clockwork_exit_widths,
+  clockwork_export_room_graph,
+  clockwork_flee_to_safety,
+  clockwork_flee_with_hysteresis,
+  clockwork_forget_flee_state,
+  clockwork_forget_known_rooms,
+  clockwork_free_goal_set,
+  clockwork_free_heuristic_map,
+  clockwork_fringe_search,
+  clockwork_generate_scenario,
+  clockwork_get_packed_terrain,
+  clockwork_get_range,
+  clockwork_get_range_between_positions,
+  clockwork_get_terrain_cost_matrix,
+  clockwork_grow_region,
+  clockwork_info,
+  clockwork_is_room_edge,
+  clockwork_list_zones,
+  clockwork_logistics_add_anchor,
+  clockwork_logistics_distance,
+  clockwork_logistics_mark_room_dirty,
+  clockwork_logistics_pending,
+  clockwork_logistics_remove_anchor,
+  clockwork_logistics_update,
+  clockwork_memoize_cost_matrices,
+  clockwork_multiroom_flow_field,
+  clockwork_multiroom_mono_flow_field,
+  clockwork_nearest_free_tile,
+  clockwork_noise_cost_matrix,
+  clockwork_nuke_damage_map,
+  clockwork_pack_sharded_position,
+  clockwork_pack_sharded_room_name,
+  clockwork_panic_context,
+  clockwork_path_chunk,
+  clockwork_path_contains,
+  clockwork_path_drop,
+  clockwork_path_len,
+  clockwork_path_next,
+  clockwork_path_store,
+  clockwork_path_to_multiroom_distance_map_origin,
+  clockwork_path_to_multiroom_distance_map_origin_position,
+  clockwork_path_to_multiroom_flow_field_origin,
+  clockwork_path_to_multiroom_flow_field_origin_position,
+  clockwork_path_to_multiroom_mono_flow_field_origin,
+  clockwork_path_to_multiroom_mono_flow_field_origin_position,
+  clockwork_patrol_loop,
+  clockwork_pending_background_jobs,
+  clockwork_plan_nuke_strike,
+  clockwork_plan_repair_tour,
+  clockwork_prefetch_rooms,
+  clockwork_preprocess_rooms,
+  clockwork_prioritize_ramparts,
+  clockwork_queue_distance_map_job,
+  clockwork_queue_logistics_job,
+  clockwork_queue_progressive_distance_map_job,
+  clockwork_remove_zone,
+  clockwork_repair_distance_map,
+  clockwork_repair_multiroom_flow_field,
+  clockwork_reset_cache_stats,
+  clockwork_room_astar_path,
+  clockwork_room_search_load,
+  clockwork_room_traversal_cost,
+  clockwork_run_background_jobs,
+  clockwork_safe_positions,
+  clockwork_scout_frontier,
+  clockwork_search_with_fallback,
+  clockwork_set_world_size,
+  clockwork_sharded_position_pos,
+  clockwork_sharded_position_shard,
+  clockwork_sharded_room_name_room,
+  clockwork_sharded_room_name_shard,
+  clockwork_source_accessibility,
+  clockwork_swamp_bridges,
+  clockwork_take_background_result,
+  clockwork_tower_coverage,
+  clockwork_validate_options,
+  clockwork_version,
+  clockwork_wall_gaps,
+  clockwork_waypoint_path
+} from '../wasm/screeps_clockwork';
+
+/*
+ * The wasm-bindgen functions the wrappers are built on, all named
+ * `clockwork_*`. Most code should use the camelCase wrappers (or the
+ * `ClockworkPathfinder` and `ClockworkMaps` groups) instead, which take
+ * `RoomPosition`s and room names rather than packed numbers.
+ */
+export {
+  clockwork_add_circle_zone,
+  clockwork_add_rect_zone,
+  clockwork_add_tiles_zone,
+  clockwork_adjacent_rooms,
+  clockwork_anytime_astar,
+  clockwork_astar_multiroom_distance_map,
+  clockwork_astar_multiroom_distance_map_positions,
+  clockwork_best_origin,
+  clockwork_bfs_multiroom_distance_map,
+  clockwork_bfs_multiroom_distance_map_positions,
+  clockwork_build_threat_matrix,
+  clockwork_cache_stats,
+  clockwork_check_layout,
+  clockwork_clear_cost_matrix_cache,
+  clockwork_clear_zones,
+  clockwork_closest_of,
+  clockwork_corresponding_room_edge,
+  clockwork_corridor_rooms,
+  clockwork_create_goal_set,
+  clockwork_create_heuristic_map,
+  clockwork_default_options,
+  clockwork_depth_map,
+  clockwork_dijkstra_multiroom_distance_map,
+  clockwork_dijkstra_multiroom_distance_map_positions,
+  clockwork_disperse,
+  clockwork_estimate_travel_ticks,
+  clockwork_exit_direction_between,
+  clockwork_exit_widths,
+  clockwork_export_room_graph,
+  clockwork_flee_to_safety,
+  clockwork_flee_with_hysteresis,
+  clockwork_forget_flee_state,
+  clockwork_forget_known_rooms,
+  clockwork_free_goal_set,
+  clockwork_free_heuristic_map,
+  clockwork_fringe_search,
+  clockwork_generate_scenario,
+  clockwork_get_packed_terrain,
+  clockwork_get_range,
+  clockwork_get_range_between_positions,
+  clockwork_get_terrain_cost_matrix,
+  clockwork_grow_region,
+  clockwork_info,
+  clockwork_is_room_edge,
+  clockwork_list_zones,
+  clockwork_logistics_add_anchor,
+  clockwork_logistics_distance,
+  clockwork_logistics_mark_room_dirty,
+  clockwork_logistics_pending,
+  clockwork_logistics_remove_anchor,
+  clockwork_logistics_update,
+  clockwork_memoize_cost_matrices,
+  clockwork_multiroom_flow_field,
+  clockwork_multiroom_mono_flow_field,
+  clockwork_nearest_free_tile,
+  clockwork_noise_cost_matrix,
+  clockwork_nuke_damage_map,
+  clockwork_pack_sharded_position,
+  clockwork_pack_sharded_room_name,
+  clockwork_panic_context,
+  clockwork_path_chunk,
+  clockwork_path_contains,
+  clockwork_path_drop,
+  clockwork_path_len,
+  clockwork_path_next,
+  clockwork_path_store,
+  clockwork_path_to_multiroom_distance_map_origin,
+  clockwork_path_to_multiroom_distance_map_origin_position,
+  clockwork_path_to_multiroom_flow_field_origin,
+  clockwork_path_to_multiroom_flow_field_origin_position,
+  clockwork_path_to_multiroom_mono_flow_field_origin,
+  clockwork_path_to_multiroom_mono_flow_field_origin_position,
+  clockwork_patrol_loop,
+  clockwork_pending_background_jobs,
+  clockwork_plan_nuke_strike,
+  clockwork_plan_repair_tour,
+  clockwork_prefetch_rooms,
+  clockwork_preprocess_rooms,
+  clockwork_prioritize_ramparts,
+  clockwork_queue_distance_map_job,
+  clockwork_queue_logistics_job,
+  clockwork_queue_progressive_distance_map_job,
+  clockwork_remove_zone,
+  clockwork_repair_distance_map,
+  clockwork_repair_multiroom_flow_field,
+  clockwork_reset_cache_stats,
+  clockwork_room_astar_path,
+  clockwork_room_search_load,
+  clockwork_room_traversal_cost,
+  clockwork_run_background_jobs,
+  clockwork_safe_positions,
+  clockwork_scout_frontier,
+  clockwork_search_with_fallback,
+  clockwork_set_world_size,
+  clockwork_sharded_position_pos,
+  clockwork_sharded_position_shard,
+  clockwork_sharded_room_name_room,
+  clockwork_sharded_room_name_shard,
+  clockwork_source_accessibility,
+  clockwork_swamp_bridges,
+  clockwork_take_background_result,
+  clockwork_tower_coverage,
+  clockwork_validate_options,
+  clockwork_version,
+  clockwork_wall_gaps,
+  clockwork_waypoint_path
+};
+
+/** @deprecated Renamed to `clockwork_get_range`. */
+export const get_range = clockwork_get_range;
+
+/** @deprecated Renamed to `clockwork_get_range_between_positions`. */
+export const get_range_between_positions = clockwork_get_range_between_positions;
+
+/** @deprecated Renamed to `clockwork_get_terrain_cost_matrix`. */
+export const get_terrain_cost_matrix = clockwork_get_terrain_cost_matrix;
+
+/** @deprecated Renamed to `clockwork_add_circle_zone`. */
+export const js_add_circle_zone = clockwork_add_circle_zone;
+
+/** @deprecated Renamed to `clockwork_add_rect_zone`. */
+export const js_add_rect_zone = clockwork_add_rect_zone;
+
+/** @deprecated Renamed to `clockwork_add_tiles_zone`. */
+export const js_add_tiles_zone = clockwork_add_tiles_zone;
+
+/** @deprecated Renamed to `clockwork_adjacent_rooms`. */
+export const js_adjacent_rooms = clockwork_adjacent_rooms;
+
+/** @deprecated Renamed to `clockwork_anytime_astar`. */
+export const js_anytime_astar = clockwork_anytime_astar;
+
+/** @deprecated Renamed to `clockwork_astar_multiroom_distance_map`. */
+export const js_astar_multiroom_distance_map = clockwork_astar_multiroom_distance_map;
+
+/** @deprecated Renamed to `clockwork_astar_multiroom_distance_map_positions`. */
+export const js_astar_multiroom_distance_map_positions = clockwork_astar_multiroom_distance_map_positions;
+
+/** @deprecated Renamed to `clockwork_best_origin`. */
+export const js_best_origin = clockwork_best_origin;
+
+/** @deprecated Renamed to `clockwork_bfs_multiroom_distance_map`. */
+export const js_bfs_multiroom_distance_map = clockwork_bfs_multiroom_distance_map;
+
+/** @deprecated Renamed to `clockwork_bfs_multiroom_distance_map_positions`. */
+export const js_bfs_multiroom_distance_map_positions = clockwork_bfs_multiroom_distance_map_positions;
+
+/** @deprecated Renamed to `clockwork_build_threat_matrix`. */
+export const js_build_threat_matrix = clockwork_build_threat_matrix;
+
+/** @deprecated Renamed to `clockwork_cache_stats`. */
+export const js_cache_stats = clockwork_cache_stats;
+
+/** @deprecated Renamed to `clockwork_check_layout`. */
+export const js_check_layout = clockwork_check_layout;
+
+/** @deprecated Renamed to `clockwork_clear_cost_matrix_cache`. */
+export const js_clear_cost_matrix_cache = clockwork_clear_cost_matrix_cache;
+
+/** @deprecated Renamed to `clockwork_clear_zones`. */
+export const js_clear_zones = clockwork_clear_zones;
+
+/** @deprecated Renamed to `clockwork_info`. */
+export const js_clockwork_info = clockwork_info;
+
+/** @deprecated Renamed to `clockwork_closest_of`. */
+export const js_closest_of = clockwork_closest_of;
+
+/** @deprecated Renamed to `clockwork_corresponding_room_edge`. */
+export const js_corresponding_room_edge = clockwork_corresponding_room_edge;
+
+/** @deprecated Renamed to `clockwork_corridor_rooms`. */
+export const js_corridor_rooms = clockwork_corridor_rooms;
+
+/** @deprecated Renamed to `clockwork_create_goal_set`. */
+export const js_create_goal_set = clockwork_create_goal_set;
+
+/** @deprecated Renamed to `clockwork_create_heuristic_map`. */
+export const js_create_heuristic_map = clockwork_create_heuristic_map;
+
+/** @deprecated Renamed to `clockwork_depth_map`. */
+export const js_depth_map = clockwork_depth_map;
+
+/** @deprecated Renamed to `clockwork_dijkstra_multiroom_distance_map`. */
+export const js_dijkstra_multiroom_distance_map = clockwork_dijkstra_multiroom_distance_map;
+
+/** @deprecated Renamed to `clockwork_dijkstra_multiroom_distance_map_positions`. */
+export const js_dijkstra_multiroom_distance_map_positions = clockwork_dijkstra_multiroom_distance_map_positions;
+
+/** @deprecated Renamed to `clockwork_disperse`. */
+export const js_disperse = clockwork_disperse;
+
+/** @deprecated Renamed to `clockwork_estimate_travel_ticks`. */
+export const js_estimate_travel_ticks = clockwork_estimate_travel_ticks;
+
+/** @deprecated Renamed to `clockwork_exit_direction_between`. */
+export const js_exit_direction_between = clockwork_exit_direction_between;
+
+/** @deprecated Renamed to `clockwork_exit_widths`. */
+export const js_exit_widths = clockwork_exit_widths;
+
+/** @deprecated Renamed to `clockwork_export_room_graph`. */
+export const js_export_room_graph = clockwork_export_room_graph;
+
+/** @deprecated Renamed to `clockwork_flee_to_safety`. */
+export const js_flee_to_safety = clockwork_flee_to_safety;
+
+/** @deprecated Renamed to `clockwork_flee_with_hysteresis`. */
+export const js_flee_with_hysteresis = clockwork_flee_with_hysteresis;
+
+/** @deprecated Renamed to `clockwork_forget_flee_state`. */
+export const js_forget_flee_state = clockwork_forget_flee_state;
+
+/** @deprecated Renamed to `clockwork_forget_known_rooms`. */
+export const js_forget_known_rooms = clockwork_forget_known_rooms;
+
+/** @deprecated Renamed to `clockwork_free_goal_set`. */
+export const js_free_goal_set = clockwork_free_goal_set;
+
+/** @deprecated Renamed to `clockwork_free_heuristic_map`. */
+export const js_free_heuristic_map = clockwork_free_heuristic_map;
+
+/** @deprecated Renamed to `clockwork_fringe_search`. */
+export const js_fringe_search = clockwork_fringe_search;
+
+/** @deprecated Renamed to `clockwork_generate_scenario`. */
+export const js_generate_scenario = clockwork_generate_scenario;
+
+/** @deprecated Renamed to `clockwork_get_packed_terrain`. */
+export const js_get_packed_terrain = clockwork_get_packed_terrain;
+
+/** @deprecated Renamed to `clockwork_grow_region`. */
+export const js_grow_region = clockwork_grow_region;
+
+/** @deprecated Renamed to `clockwork_is_room_edge`. */
+export const js_is_room_edge = clockwork_is_room_edge;
+
+/** @deprecated Renamed to `clockwork_list_zones`. */
+export const js_list_zones = clockwork_list_zones;
+
+/** @deprecated Renamed to `clockwork_logistics_add_anchor`. */
+export const js_logistics_add_anchor = clockwork_logistics_add_anchor;
+
+/** @deprecated Renamed to `clockwork_logistics_distance`. */
+export const js_logistics_distance = clockwork_logistics_distance;
+
+/** @deprecated Renamed to `clockwork_logistics_mark_room_dirty`. */
+export const js_logistics_mark_room_dirty = clockwork_logistics_mark_room_dirty;
+
+/** @deprecated Renamed to `clockwork_logistics_pending`. */
+export const js_logistics_pending = clockwork_logistics_pending;
+
+/** @deprecated Renamed to `clockwork_logistics_remove_anchor`. */
+export const js_logistics_remove_anchor = clockwork_logistics_remove_anchor;
+
+/** @deprecated Renamed to `clockwork_logistics_update`. */
+export const js_logistics_update = clockwork_logistics_update;
+
+/** @deprecated Renamed to `clockwork_memoize_cost_matrices`. */
+export const js_memoize_cost_matrices = clockwork_memoize_cost_matrices;
+
+/** @deprecated Renamed to `clockwork_nearest_free_tile`. */
+export const js_nearest_free_tile = clockwork_nearest_free_tile;
+
+/** @deprecated Renamed to `clockwork_noise_cost_matrix`. */
+export const js_noise_cost_matrix = clockwork_noise_cost_matrix;
+
+/** @deprecated Renamed to `clockwork_nuke_damage_map`. */
+export const js_nuke_damage_map = clockwork_nuke_damage_map;
+
+/** @deprecated Renamed to `clockwork_pack_sharded_position`. */
+export const js_pack_sharded_position = clockwork_pack_sharded_position;
+
+/** @deprecated Renamed to `clockwork_pack_sharded_room_name`. */
+export const js_pack_sharded_room_name = clockwork_pack_sharded_room_name;
+
+/** @deprecated Renamed to `clockwork_panic_context`. */
+export const js_panic_context = clockwork_panic_context;
+
+/** @deprecated Renamed to `clockwork_path_chunk`. */
+export const js_path_chunk = clockwork_path_chunk;
+
+/** @deprecated Renamed to `clockwork_path_contains`. */
+export const js_path_contains = clockwork_path_contains;
+
+/** @deprecated Renamed to `clockwork_path_drop`. */
+export const js_path_drop = clockwork_path_drop;
+
+/** @deprecated Renamed to `clockwork_path_len`. */
+export const js_path_len = clockwork_path_len;
+
+/** @deprecated Renamed to `clockwork_path_next`. */
+export const js_path_next = clockwork_path_next;
+
+/** @deprecated Renamed to `clockwork_path_store`. */
+export const js_path_store = clockwork_path_store;
+
+/** @deprecated Renamed to `clockwork_path_to_multiroom_distance_map_origin`. */
+export const js_path_to_multiroom_distance_map_origin = clockwork_path_to_multiroom_distance_map_origin;
+
+/** @deprecated Renamed to `clockwork_path_to_multiroom_distance_map_origin_position`. */
+export const js_path_to_multiroom_distance_map_origin_position = clockwork_path_to_multiroom_distance_map_origin_position;
+
+/** @deprecated Renamed to `clockwork_path_to_multiroom_flow_field_origin`. */
+export const js_path_to_multiroom_flow_field_origin = clockwork_path_to_multiroom_flow_field_origin;
+
+/** @deprecated Renamed to `clockwork_path_to_multiroom_flow_field_origin_position`. */
+export const js_path_to_multiroom_flow_field_origin_position = clockwork_path_to_multiroom_flow_field_origin_position;
+
+/** @deprecated Renamed to `clockwork_path_to_multiroom_mono_flow_field_origin`. */
+export const js_path_to_multiroom_mono_flow_field_origin = clockwork_path_to_multiroom_mono_flow_field_origin;
+
+/** @deprecated Renamed to `clockwork_path_to_multiroom_mono_flow_field_origin_position`. */
+export const js_path_to_multiroom_mono_flow_field_origin_position = clockwork_path_to_multiroom_mono_flow_field_origin_position;
+
+/** @deprecated Renamed to `clockwork_patrol_loop`. */
+export const js_patrol_loop = clockwork_patrol_loop;
+
+/** @deprecated Renamed to `clockwork_pending_background_jobs`. */
+export const js_pending_background_jobs = clockwork_pending_background_jobs;
+
+/** @deprecated Renamed to `clockwork_plan_nuke_strike`. */
+export const js_plan_nuke_strike = clockwork_plan_nuke_strike;
+
+/** @deprecated Renamed to `clockwork_plan_repair_tour`. */
+export const js_plan_repair_tour = clockwork_plan_repair_tour;
+
+/** @deprecated Renamed to `clockwork_prefetch_rooms`. */
+export const js_prefetch_rooms = clockwork_prefetch_rooms;
+
+/** @deprecated Renamed to `clockwork_preprocess_rooms`. */
+export const js_preprocess_rooms = clockwork_preprocess_rooms;
+
+/** @deprecated Renamed to `clockwork_prioritize_ramparts`. */
+export const js_prioritize_ramparts = clockwork_prioritize_ramparts;
+
+/** @deprecated Renamed to `clockwork_queue_distance_map_job`. */
+export const js_queue_distance_map_job = clockwork_queue_distance_map_job;
+
+/** @deprecated Renamed to `clockwork_queue_logistics_job`. */
+export const js_queue_logistics_job = clockwork_queue_logistics_job;
+
+/** @deprecated Renamed to `clockwork_queue_progressive_distance_map_job`. */
+export const js_queue_progressive_distance_map_job = clockwork_queue_progressive_distance_map_job;
+
+/** @deprecated Renamed to `clockwork_remove_zone`. */
+export const js_remove_zone = clockwork_remove_zone;
+
+/** @deprecated Renamed to `clockwork_repair_distance_map`. */
+export const js_repair_distance_map = clockwork_repair_distance_map;
+
+/** @deprecated Renamed to `clockwork_repair_multiroom_flow_field`. */
+export const js_repair_multiroom_flow_field = clockwork_repair_multiroom_flow_field;
+
+/** @deprecated Renamed to `clockwork_reset_cache_stats`. */
+export const js_reset_cache_stats = clockwork_reset_cache_stats;
+
+/** @deprecated Renamed to `clockwork_room_astar_path`. */
+export const js_room_astar_path = clockwork_room_astar_path;
+
+/** @deprecated Renamed to `clockwork_room_search_load`. */
+export const js_room_search_load = clockwork_room_search_load;
+
+/** @deprecated Renamed to `clockwork_room_traversal_cost`. */
+export const js_room_traversal_cost = clockwork_room_traversal_cost;
+
+/** @deprecated Renamed to `clockwork_run_background_jobs`. */
+export const js_run_background_jobs = clockwork_run_background_jobs;
+
+/** @deprecated Renamed to `clockwork_safe_positions`. */
+export const js_safe_positions = clockwork_safe_positions;
+
+/** @deprecated Renamed to `clockwork_scout_frontier`. */
+export const js_scout_frontier = clockwork_scout_frontier;
+
+/** @deprecated Renamed to `clockwork_search_with_fallback`. */
+export const js_search_with_fallback = clockwork_search_with_fallback;
+
+/** @deprecated Renamed to `clockwork_set_world_size`. */
+export const js_set_world_size = clockwork_set_world_size;
+
+/** @deprecated Renamed to `clockwork_sharded_position_pos`. */
+export const js_sharded_position_pos = clockwork_sharded_position_pos;
+
+/** @deprecated Renamed to `clockwork_sharded_position_shard`. */
+export const js_sharded_position_shard = clockwork_sharded_position_shard;
+
+/** @deprecated Renamed to `clockwork_sharded_room_name_room`. */
+export const js_sharded_room_name_room = clockwork_sharded_room_name_room;
+
+/** @deprecated Renamed to `clockwork_sharded_room_name_shard`. */
+export const js_sharded_room_name_shard = clockwork_sharded_room_name_shard;
+
+/** @deprecated Renamed to `clockwork_source_accessibility`. */
+export const js_source_accessibility = clockwork_source_accessibility;
+
+/** @deprecated Renamed to `clockwork_swamp_bridges`. */
+export const js_swamp_bridges = clockwork_swamp_bridges;
+
+/** @deprecated Renamed to `clockwork_take_background_result`. */
+export const js_take_background_result = clockwork_take_background_result;
+
+/** @deprecated Renamed to `clockwork_tower_coverage`. */
+export const js_tower_coverage = clockwork_tower_coverage;
+
+/** @deprecated Renamed to `clockwork_wall_gaps`. */
+export const js_wall_gaps = clockwork_wall_gaps;
+
+/** @deprecated Renamed to `clockwork_waypoint_path`. */
+export const js_waypoint_path = clockwork_waypoint_path;
+
+/** @deprecated Renamed to `clockwork_multiroom_flow_field`. */
+export const multiroomFlowField = clockwork_multiroom_flow_field;
+
+/** @deprecated Renamed to `clockwork_multiroom_mono_flow_field`. */
+export const multiroomMonoFlowField = clockwork_multiroom_mono_flow_field;
+
+/** @deprecated Renamed to `clockwork_version`. */
+export const version = clockwork_version;
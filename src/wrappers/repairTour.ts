@@ -0,0 +1,57 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_plan_repair_tour } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { ClockworkPath } from './path';
+
+/**
+ * Plan a repair tour for a repairer creep: a path from `start` through every
+ * road with less than `threshold` of its max hits, always heading to the
+ * nearest road still left (roads passed over on the way count as visited).
+ * The tour stops early if the next leg would take its total path cost over
+ * `budget`. Cost overlay and movement options work as in
+ * `astarMultiroomDistanceMap`.
+ *
+ * @example
+ * ```typescript
+ * const roads = room.find(FIND_STRUCTURES, { filter: { structureType: STRUCTURE_ROAD } });
+ * const tour = planRepairTour(creep.pos, roads, { costMatrixCallback, budget: 200 });
+ * ```
+ *
+ * @param start - The repairer's position.
+ * @param roads - The roads to consider, e.g. `StructureRoad` objects.
+ * @param options - The options for the tour.
+ * @returns The path visiting the damaged roads, in order.
+ */
+export function planRepairTour(
+  start: RoomPosition,
+  roads: { pos: RoomPosition; hits: number; hitsMax: number }[],
+  {
+    costMatrixCallback,
+    threshold = 0.5,
+    budget = MAX_USIZE,
+    maxRooms = 1,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    threshold?: number;
+    budget?: number;
+    maxRooms?: number;
+  } & CostOverlayOptions & MovementOptions
+): ClockworkPath {
+  return new ClockworkPath(
+    clockwork_plan_repair_tour(
+      start.__packedPos,
+      new Uint32Array(roads.map(road => road.pos.__packedPos)),
+      new Uint32Array(roads.map(road => road.hits)),
+      new Uint32Array(roads.map(road => road.hitsMax)),
+      threshold,
+      budget,
+      maxRooms,
+      packedCostMatrixCallback(costMatrixCallback),
+      createCostOverlay(searchOptions),
+      createMovementRules(searchOptions)
+    )
+  );
+}
@@ -0,0 +1,56 @@
+import { MAX_USIZE } from '../utils/constants';
+import { ClockworkCostMatrix, clockwork_room_astar_path, GoalKind } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { ClockworkPath } from './path';
+
+/**
+ * Find a path from `start` to the nearest destination without leaving the
+ * start's room. Most moves start and end in the same room, and this A* is
+ * built for them: it works on flat, room-sized arrays and a single cost
+ * matrix, skipping the bookkeeping the multi-room searches need, so it's
+ * cheaper for short, room-local moves. `waypointPath` uses it automatically
+ * for legs that stay in one room.
+ *
+ * Room edge tiles can end the path but aren't walked through, since a creep
+ * stepping onto one leaves the room.
+ *
+ * Cost overlay options (e.g. `blockedTiles`, `roomMultipliers`) and movement
+ * options (e.g. `allowDiagonals`) work as in `astarMultiroomDistanceMap`.
+ *
+ * @param start - The starting position.
+ * @param options - The options for the search.
+ * @returns The path to the destination, or `undefined` if none was found in
+ * the room.
+ */
+export function roomAstarPath(
+  start: RoomPosition,
+  {
+    costMatrix,
+    destinations,
+    maxOps = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    ...searchOptions
+  }: {
+    costMatrix: ClockworkCostMatrix;
+    destinations: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    maxOps?: number;
+    maxPathCost?: number;
+  } & CostOverlayOptions & MovementOptions
+): ClockworkPath | undefined {
+  const path = clockwork_room_astar_path(
+    start.__packedPos,
+    new Uint32Array(
+      destinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+        acc.push(pos.__packedPos, range, kind);
+        return acc;
+      }, [] as number[])
+    ),
+    costMatrix,
+    maxOps,
+    maxPathCost,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
+  );
+  return path ? new ClockworkPath(path) : undefined;
+}
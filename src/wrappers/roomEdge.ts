@@ -0,0 +1,26 @@
+import { fromPacked } from '../utils/fromPacked';
+import { clockwork_corresponding_room_edge, clockwork_is_room_edge } from '../wasm/screeps_clockwork';
+
+/**
+ * Get the tile a creep on a room edge ends up on in the neighboring room, or
+ * `pos` itself if it isn't on an edge. Paths from the searches cross room
+ * borders this way (e.g. x 0 in one room is followed by x 49 in the room to
+ * the west), so movement code can use this to match them.
+ *
+ * @param pos - The position to convert.
+ * @returns The corresponding edge tile across the border.
+ */
+export function correspondingRoomEdge(pos: RoomPosition) {
+  return fromPacked(clockwork_corresponding_room_edge(pos.__packedPos));
+}
+
+/**
+ * Check whether a position is on a room edge, where a creep is moved to the
+ * neighboring room.
+ *
+ * @param pos - The position to check.
+ * @returns Whether x or y is 0 or 49.
+ */
+export function isRoomEdge(pos: RoomPosition) {
+  return clockwork_is_room_edge(pos.__packedPos);
+}
@@ -0,0 +1,67 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { fromPackedRoomName, packRoomName } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_export_room_graph } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+
+/**
+ * A passable border between two adjacent rooms.
+ */
+export interface RoomGraphEdge {
+  from: string;
+  to: string;
+  /** The number of border tiles that are passable on both sides. */
+  width: number;
+  /** The lowest cost of entering `to` across the border, plus `roomChangeCost`. */
+  cost: number;
+}
+
+/**
+ * Export the room-level graph around `startRooms` as a plain, serializable
+ * object, for your own analyses (e.g. centrality or chokepoints between
+ * territories) or for visualizing connectivity. Rooms are explored outward
+ * until `maxRooms` cost matrices have been loaded; rooms whose cost matrix
+ * callback returns `undefined` are listed as blocked. Registered avoidance
+ * zones are applied, as in the searches, along with any cost overlay options
+ * (e.g. `roomMultipliers`). `roomChangeCost` is added to every edge's cost.
+ *
+ * @param startRooms - The rooms to explore from.
+ * @param options - The options for the export.
+ * @returns The explored rooms, blocked rooms, and edges between them. Each
+ * connection appears once in each direction.
+ */
+export function exportRoomGraph(
+  startRooms: string[],
+  {
+    costMatrixCallback,
+    maxRooms = MAX_USIZE,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    maxRooms?: number;
+  } & CostOverlayOptions & MovementOptions
+) {
+  const graph = clockwork_export_room_graph(
+    new Uint16Array(startRooms.map(packRoomName)),
+    packedCostMatrixCallback(costMatrixCallback),
+    maxRooms,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions)
+  );
+  const rooms = [...graph.rooms].map(fromPackedRoomName);
+  const blockedRooms = [...graph.blocked_rooms].map(fromPackedRoomName);
+  const packedEdges = graph.edges;
+  const edges: RoomGraphEdge[] = [];
+  for (let i = 0; i < packedEdges.length; i += 4) {
+    edges.push({
+      from: fromPackedRoomName(packedEdges[i]),
+      to: fromPackedRoomName(packedEdges[i + 1]),
+      width: packedEdges[i + 2],
+      cost: packedEdges[i + 3]
+    });
+  }
+  graph.free();
+
+  return { rooms, blockedRooms, edges };
+}
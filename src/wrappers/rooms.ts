@@ -0,0 +1,73 @@
+import { fromPackedRoomName, packRoomName } from '../utils/fromPacked';
+import {
+  clockwork_adjacent_rooms,
+  clockwork_exit_direction_between,
+  clockwork_exit_widths,
+  clockwork_room_traversal_cost,
+  clockwork_scout_frontier,
+  clockwork_set_world_size
+} from '../wasm/screeps_clockwork';
+
+/**
+ * Get the names of the rooms bordering a room, in top, right, bottom, left
+ * order, using the same coordinate conventions as Clockwork's searches
+ * (e.g. the room below `W1N0` is `W1S0`). Rooms beyond the edge of the world
+ * are skipped; whether the rooms exist or have exits isn't checked.
+ */
+export function adjacentRooms(roomName: string): string[] {
+  return [...clockwork_adjacent_rooms(packRoomName(roomName))].map(room => fromPackedRoomName(room));
+}
+
+/**
+ * Get the direction of the exit from `fromRoom` into `toRoom`, or `undefined`
+ * if the rooms don't share a border.
+ */
+export function exitDirectionBetween(fromRoom: string, toRoom: string): ExitConstant | undefined {
+  return clockwork_exit_direction_between(packRoomName(fromRoom), packRoomName(toRoom)) as ExitConstant | undefined;
+}
+
+/**
+ * Count the exit tiles on each side of a room, from its terrain. Cached for
+ * the lifetime of the module (see `preprocessRooms`).
+ */
+export function exitWidths(roomName: string): { top: number; right: number; bottom: number; left: number } {
+  const [top, right, bottom, left] = clockwork_exit_widths(packRoomName(roomName));
+  return { top, right, bottom, left };
+}
+
+/**
+ * Estimate the cost of crossing a room, as a room-level weight for route
+ * planning (e.g. in a `Game.map.findRoute` `routeCallback`): the average cost
+ * of its passable tiles, times the 50 tiles of a straight crossing. Uses the
+ * cost matrix last remembered for the room by a search with
+ * `UnknownRoomPolicy.LastKnown`, if it's no more than `maxAge` ticks old
+ * (default 1500), and the terrain (plains 1, swamps 5) otherwise.
+ *
+ * @returns The estimated cost, or `undefined` if the room's terrain isn't
+ * available.
+ */
+export function roomTraversalCost(roomName: string, { maxAge }: { maxAge?: number } = {}): number | undefined {
+  return clockwork_room_traversal_cost(packRoomName(roomName), maxAge);
+}
+
+/**
+ * List the unexplored rooms within `maxDepth` rooms of the known rooms, as a
+ * priority queue for scouts: nearest (by route through exits) first, then
+ * those with the widest exits. Rooms that can't be entered are skipped.
+ */
+export function scoutFrontier(knownRooms: string[], maxDepth = 3): string[] {
+  return [...clockwork_scout_frontier(new Uint16Array(knownRooms.map(packRoomName)), maxDepth)].map(room =>
+    fromPackedRoomName(room)
+  );
+}
+
+/**
+ * Set the number of rooms along each side of the world (an even number up to
+ * 256, the default) for private servers with smaller maps. Rooms beyond the
+ * edges are then skipped when walking between rooms, and room names outside
+ * the world are rejected with an error instead of wrapping around. Call it
+ * once after `initialize`.
+ */
+export function setWorldSize(size: number): void {
+  clockwork_set_world_size(size);
+}
@@ -0,0 +1,52 @@
+import { fromPackedRoomName, packRoomName } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_generate_scenario, Scenario, ScenarioKind } from '../wasm/screeps_clockwork';
+
+/**
+ * A synthetic world of generated cost matrices, for benchmarking searches on
+ * standard maps rather than whatever rooms happen to be owned.
+ */
+export class ClockworkScenario {
+  constructor(private _scenario: Scenario) {}
+
+  /**
+   * Get the cost matrix for a room, or `undefined` if the room isn't part of
+   * the scenario. Can be passed directly as a search's `costMatrixCallback`.
+   */
+  getCostMatrix = (room: string): ClockworkCostMatrix | undefined => {
+    return this._scenario.get(packRoomName(room));
+  };
+
+  /**
+   * List all the rooms in the scenario.
+   */
+  rooms(): string[] {
+    return [...this._scenario.rooms()].map(room => fromPackedRoomName(room));
+  }
+
+  /**
+   * Free the memory allocated for this scenario.
+   */
+  free() {
+    this._scenario.free();
+  }
+}
+
+/**
+ * Generate a rectangle of rooms with synthetic terrain. The same seed always
+ * produces the same world.
+ *
+ * @param kind - The kind of terrain to generate.
+ * @param topLeft - The north-west room; rooms extend east and south from here.
+ * @param width - The number of rooms east to west.
+ * @param height - The number of rooms north to south.
+ * @param seed - The random seed.
+ */
+export function generateScenario(
+  kind: ScenarioKind,
+  topLeft: string,
+  width: number,
+  height: number,
+  seed = 1
+): ClockworkScenario {
+  return new ClockworkScenario(clockwork_generate_scenario(kind, packRoomName(topLeft), width, height, seed));
+}
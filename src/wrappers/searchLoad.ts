@@ -0,0 +1,24 @@
+import { fromPackedRoomName } from '../utils/fromPacked';
+import { clockwork_room_search_load } from '../wasm/screeps_clockwork';
+
+/**
+ * Get the tiles expanded and CPU spent in each room by this tick's A*,
+ * Dijkstra, BFS, and flee searches, to find the rooms (e.g. a swampy remote)
+ * that eat pathfinding CPU and could use roads or better cost matrices.
+ *
+ * Only each search's total CPU is measured; it's split between rooms by the
+ * share of tiles expanded in each. Call this at the end of the tick, since
+ * the totals are cleared when the next tick's first search finishes.
+ *
+ * @returns The rooms searched this tick, with the most CPU first.
+ */
+export function roomSearchLoad(): { room: string; expanded: number; cpu: number }[] {
+  const report = clockwork_room_search_load();
+  const rooms = report.rooms;
+  const expanded = report.expanded;
+  const cpu = report.cpu;
+  report.free();
+  return [...rooms]
+    .map((room, i) => ({ room: fromPackedRoomName(room), expanded: expanded[i], cpu: cpu[i] }))
+    .sort((a, b) => b.cpu - a.cpu);
+}
@@ -0,0 +1,97 @@
+import { ephemeral } from '../utils/cleanup';
+
+let memoTick = 0;
+const memo = new Map<string, unknown>();
+const stats = { hits: 0, misses: 0 };
+
+// Callbacks and wasm-backed objects (cost matrices, goal sets, heuristic
+// maps) can't be serialized, so they're keyed by identity instead
+const objectIds = new WeakMap<object, number>();
+let nextObjectId = 1;
+
+function objectId(value: object) {
+  let id = objectIds.get(value);
+  if (id === undefined) {
+    id = nextObjectId++;
+    objectIds.set(value, id);
+  }
+  return id;
+}
+
+function memoKey(search: object, start: RoomPosition[], options: object) {
+  return JSON.stringify([search, start, options], function (this: any, key: string, value: unknown) {
+    const raw = this[key];
+    if (raw instanceof RoomPosition) {
+      return raw.__packedPos;
+    }
+    if (typeof raw === 'function' || (typeof raw === 'object' && raw !== null && 'free' in raw)) {
+      return `#${objectId(raw)}`;
+    }
+    return value;
+  });
+}
+
+/**
+ * Run a search, or return the result of an identical search already run this
+ * tick, so subsystems that ask for the same thing don't pay for it twice.
+ * Searches are identical if they use the same search function, start
+ * positions, and options; callbacks, cost matrices, goal sets, and heuristic
+ * maps only match if they're the same objects, so reuse them (rather than
+ * creating a new `costMatrixCallback` closure per call) to get hits.
+ *
+ * The result is shared by every caller this tick, so don't free its distance
+ * map: it's marked `ephemeral` and freed after the tick. Call `persist` on
+ * it to keep it longer.
+ *
+ * ```ts
+ * const result = memoizedSearch(astarMultiroomDistanceMap, [spawn.pos], {
+ *   costMatrixCallback,
+ *   anyOfDestinations: [{ pos: controller.pos, range: 3 }]
+ * });
+ * ```
+ *
+ * @param search - The search to run, e.g. `astarMultiroomDistanceMap`.
+ * @param start - The starting positions.
+ * @param options - The options for the search.
+ * @returns The search result.
+ */
+export function memoizedSearch<O extends object, R extends { distanceMap: { free: () => void } }>(
+  search: (start: RoomPosition[], options: O) => R,
+  start: RoomPosition[],
+  options: O
+): R {
+  if (memoTick !== Game.time) {
+    memo.clear();
+    memoTick = Game.time;
+  }
+
+  const key = memoKey(search, start, options);
+  const cached = memo.get(key);
+  if (cached !== undefined) {
+    stats.hits++;
+    return cached as R;
+  }
+
+  stats.misses++;
+  const result = search(start, options);
+  ephemeral(result.distanceMap);
+  memo.set(key, result);
+  return result;
+}
+
+/**
+ * Get the number of `memoizedSearch` calls answered from this tick's results
+ * (hits) and the number that ran a search (misses), since the module loaded
+ * or the last `resetSearchMemoStats`.
+ */
+export function searchMemoStats() {
+  return { ...stats };
+}
+
+/**
+ * Reset the `memoizedSearch` hit and miss counts to zero.
+ */
+export function resetSearchMemoStats() {
+  stats.hits = 0;
+  stats.misses = 0;
+}
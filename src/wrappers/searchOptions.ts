@@ -0,0 +1,95 @@
+import { MAX_USIZE } from '../utils/constants';
+import {
+  clockwork_default_options,
+  clockwork_validate_options,
+  GoalKind,
+  OpenListOverflow,
+  UnknownRoomPolicy
+} from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { createOpenListLimit, OpenListOptions } from './openListLimit';
+
+export interface SearchOptionError {
+  /** The option at fault, e.g. `beamWidth`. */
+  field: string;
+  message: string;
+}
+
+/**
+ * Get the values searches use for movement, open list, and unknown room
+ * options that aren't given.
+ */
+export function defaultSearchOptions(): {
+  allowDiagonals: boolean;
+  cutCorners: boolean;
+  roomChangeCost: number;
+  maxOpenNodes: number;
+  openListOverflow: OpenListOverflow;
+  beamWidth: number;
+  unknownRooms: UnknownRoomPolicy;
+  unknownRoomMaxAge: number;
+} {
+  const result = clockwork_default_options();
+  const defaults = {
+    allowDiagonals: result.allow_diagonals,
+    cutCorners: result.cut_corners,
+    roomChangeCost: result.room_change_cost,
+    maxOpenNodes: result.max_open_nodes,
+    openListOverflow: result.open_list_overflow,
+    beamWidth: result.beam_width,
+    unknownRooms: result.unknown_rooms,
+    unknownRoomMaxAge: result.unknown_room_max_age
+  };
+  result.free();
+  return defaults;
+}
+
+/**
+ * Check the options for a distance map search without running it. Options
+ * that would make the search throw partway through (e.g. an invalid goal
+ * kind, or a destination outside the world), or quietly change its result
+ * (e.g. a `beamWidth` of 0 with `OpenListOverflow.Beam`), are reported, so
+ * callers can fail fast instead.
+ *
+ * @param options - The options you'd pass to `astarMultiroomDistanceMap`,
+ * `dijkstraMultiroomDistanceMap`, or `bfsMultiroomDistanceMap`.
+ * @returns The problems found, or an empty array if the options are valid.
+ */
+export function validateSearchOptions({
+  maxOps = MAX_USIZE,
+  maxRooms = MAX_USIZE,
+  maxPathCost = MAX_USIZE,
+  anyOfDestinations,
+  allOfDestinations,
+  ...searchOptions
+}: {
+  maxOps?: number;
+  maxRooms?: number;
+  maxPathCost?: number;
+  anyOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+  allOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+} & CostOverlayOptions & MovementOptions & OpenListOptions): SearchOptionError[] {
+  const packGoals = (goals: { pos: RoomPosition; range: number; kind?: GoalKind }[]) =>
+    new Uint32Array(
+      goals.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+        acc.push(pos.__packedPos, range, kind);
+        return acc;
+      }, [] as number[])
+    );
+  const errors = clockwork_validate_options(
+    maxOps,
+    maxRooms,
+    maxPathCost,
+    anyOfDestinations ? packGoals(anyOfDestinations) : undefined,
+    allOfDestinations ? packGoals(allOfDestinations) : undefined,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions)
+  );
+  return errors.map(error => {
+    const result = { field: error.field, message: error.message };
+    error.free();
+    return result;
+  });
+}
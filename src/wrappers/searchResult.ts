@@ -1,7 +1,22 @@
-import { fromPacked } from '../utils/fromPacked';
-import { SearchResult } from '../wasm/screeps_clockwork';
+import { fromPacked, fromPackedRoomName } from '../utils/fromPacked';
+import { FailureReason, SearchResult } from '../wasm/screeps_clockwork';
 import { ClockworkMultiroomDistanceMap } from './multiroomDistanceMap';
 
+/**
+ * Why a search didn't reach its destinations.
+ */
+export interface SearchFailureDetails {
+  reason: FailureReason;
+  /**
+   * The number of tiles left to expand when the search stopped.
+   */
+  frontier: number;
+  /**
+   * The rooms the cost matrix callback returned `undefined` for.
+   */
+  blockedRooms: string[];
+}
+
 export function fromPackedSearchResult(result: SearchResult) {
   const foundTargets = result.found_targets.reduce((acc, pos) => {
     acc.push(fromPacked(pos));
@@ -9,11 +24,28 @@ export function fromPackedSearchResult(result: SearchResult) {
   }, [] as RoomPosition[]);
   const distanceMap = new ClockworkMultiroomDistanceMap(result.distance_map);
   const ops = result.ops;
+  const pushed = result.pushed;
+  const expanded = [...result.expanded].map(pos => fromPacked(pos));
+  const heuristicWeight = result.heuristic_weight;
+  const packedFailure = result.failure;
+  let failure: SearchFailureDetails | undefined;
+  if (packedFailure) {
+    failure = {
+      reason: packedFailure.reason,
+      frontier: packedFailure.frontier,
+      blockedRooms: [...packedFailure.blocked_rooms].map(room => fromPackedRoomName(room))
+    };
+    packedFailure.free();
+  }
   result.free();
 
   return {
     distanceMap,
     foundTargets,
-    ops
+    ops,
+    pushed,
+    expanded,
+    failure,
+    heuristicWeight
   };
 }
@@ -0,0 +1,87 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import {
+  ClockworkCostMatrix,
+  clockwork_search_with_fallback,
+  GoalKind,
+  SearchStrategy
+} from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { createOpenListLimit, OpenListOptions } from './openListLimit';
+import { fromPackedSearchResult } from './searchResult';
+
+/**
+ * Run a chain of distance map searches in a single call. Each strategy is
+ * tried in order until one reaches the destinations within `maxOps`; e.g.
+ * `[SearchStrategy.AStar, SearchStrategy.WeightedAStar]` falls back to a
+ * greedier A* (with the heuristic scaled by `heuristicWeight`) when the
+ * optimal search runs out of budget.
+ *
+ * Cost matrices are requested once and shared by every attempt. `maxOps` and
+ * `maxRooms` apply to each attempt separately, and the returned `ops` and
+ * `pushed` are the totals across all of them.
+ *
+ * @param start - The starting positions.
+ * @param options - The options for the search.
+ * @returns The first complete search result, or the last attempt's result if
+ * none completed.
+ */
+export function searchWithFallback(
+  start: RoomPosition[],
+  {
+    strategies,
+    costMatrixCallback,
+    maxRooms = MAX_USIZE,
+    maxOps = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    heuristicWeight = 2,
+    anyOfDestinations,
+    allOfDestinations,
+    ...searchOptions
+  }: {
+    strategies: SearchStrategy[];
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    maxRooms?: number;
+    maxOps?: number;
+    maxPathCost?: number;
+    heuristicWeight?: number;
+    anyOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+    allOfDestinations?: { pos: RoomPosition; range: number; kind?: GoalKind }[];
+  } & CostOverlayOptions & MovementOptions & OpenListOptions
+) {
+  if (!anyOfDestinations && !allOfDestinations) {
+    throw new Error('At least one of anyOfDestinations or allOfDestinations must be set');
+  }
+
+  const result = clockwork_search_with_fallback(
+    new Uint32Array(strategies),
+    new Uint32Array(start.map(pos => pos.__packedPos)),
+    packedCostMatrixCallback(costMatrixCallback),
+    maxRooms,
+    maxOps,
+    maxPathCost,
+    heuristicWeight,
+    anyOfDestinations
+      ? new Uint32Array(
+          anyOfDestinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+            acc.push(pos.__packedPos, range, kind);
+            return acc;
+          }, [] as number[])
+        )
+      : undefined,
+    allOfDestinations
+      ? new Uint32Array(
+          allOfDestinations.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+            acc.push(pos.__packedPos, range, kind);
+            return acc;
+          }, [] as number[])
+        )
+      : undefined,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions)
+  );
+
+  return fromPackedSearchResult(result);
+}
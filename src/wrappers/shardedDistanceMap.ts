@@ -0,0 +1,112 @@
+import { fromPacked, fromPackedRoomName, packRoomName } from '../utils/fromPacked';
+import {
+  clockwork_pack_sharded_position,
+  clockwork_pack_sharded_room_name,
+  clockwork_sharded_position_pos,
+  clockwork_sharded_position_shard,
+  clockwork_sharded_room_name_room,
+  clockwork_sharded_room_name_shard,
+  ShardedDistanceMap
+} from '../wasm/screeps_clockwork';
+import { ClockworkMultiroomDistanceMap } from './multiroomDistanceMap';
+
+/**
+ * Get the numeric shard id for a shard name (e.g. `shard3` -> 3).
+ */
+export function shardId(shard: string): number {
+  const match = shard.match(/(\d+)$/);
+  if (!match) throw new Error(`Invalid shard name: ${shard}`);
+  return parseInt(match[1]);
+}
+
+/**
+ * Pack a shard and room name into a single number, unique across shards.
+ */
+export function packShardedRoomName(shard: string, room: string): number {
+  return clockwork_pack_sharded_room_name(shardId(shard), packRoomName(room));
+}
+
+/**
+ * Unpack a key created with `packShardedRoomName`.
+ */
+export function unpackShardedRoomName(key: number): { shard: string; room: string } {
+  return {
+    shard: `shard${clockwork_sharded_room_name_shard(key)}`,
+    room: fromPackedRoomName(clockwork_sharded_room_name_room(key))
+  };
+}
+
+/**
+ * Pack a shard and position into a single BigInt, unique across shards.
+ */
+export function packShardedPosition(shard: string, pos: RoomPosition): bigint {
+  return clockwork_pack_sharded_position(shardId(shard), pos.__packedPos);
+}
+
+/**
+ * Unpack a key created with `packShardedPosition`.
+ */
+export function unpackShardedPosition(key: bigint): { shard: string; pos: RoomPosition } {
+  return {
+    shard: `shard${clockwork_sharded_position_shard(key)}`,
+    pos: fromPacked(clockwork_sharded_position_pos(key))
+  };
+}
+
+/**
+ * A distance map that covers rooms on multiple shards. Rooms with the same
+ * name on different shards are stored separately.
+ */
+export class ClockworkShardedDistanceMap {
+  private _map = new ShardedDistanceMap();
+
+  /**
+   * Get the stored value for a given position on a shard.
+   */
+  get(shard: string, pos: RoomPosition): number {
+    return this._map.get(shardId(shard), pos.__packedPos);
+  }
+
+  /**
+   * Set the stored value for a given position on a shard.
+   */
+  set(shard: string, pos: RoomPosition, value: number) {
+    this._map.set(shardId(shard), pos.__packedPos, value);
+  }
+
+  /**
+   * Get the stored value for a key created with `packShardedPosition`.
+   */
+  getSharded(key: bigint): number {
+    return this._map.getSharded(key);
+  }
+
+  /**
+   * List all the shards covered by this distance map.
+   */
+  getShards(): string[] {
+    return [...this._map.getShards()].map(shard => `shard${shard}`);
+  }
+
+  /**
+   * Get a copy of the distance map for a shard.
+   */
+  getShard(shard: string): ClockworkMultiroomDistanceMap | undefined {
+    const map = this._map.getShard(shardId(shard));
+    return map ? new ClockworkMultiroomDistanceMap(map) : undefined;
+  }
+
+  /**
+   * Store a copy of a distance map for a shard, replacing any existing data.
+   */
+  setShard(shard: string, map: ClockworkMultiroomDistanceMap) {
+    this._map.setShard(shardId(shard), map['_map']);
+  }
+
+  /**
+   * Free the memory allocated for this distance map.
+   */
+  free() {
+    this._map.free();
+  }
+}
@@ -0,0 +1,30 @@
+import { fromPacked, packRoomName } from '../utils/fromPacked';
+import { clockwork_swamp_bridges } from '../wasm/screeps_clockwork';
+
+/**
+ * Find narrow plain-tile corridors through swamp in a room: stretches of plain
+ * at most `maxWidth` tiles wide (horizontally or vertically) bordered by
+ * swamp. These are natural spots for road bridges, and chokepoints worth
+ * considering for ambushes. Gaps between walls alone aren't included.
+ *
+ * @param roomName - The room to analyze.
+ * @param options - `maxWidth` (default 2) and `minLength`, the fewest tiles a
+ * corridor can have (default 3).
+ * @returns Each corridor's tiles.
+ */
+export function findSwampBridges(
+  roomName: string,
+  { maxWidth = 2, minLength = 3 }: { maxWidth?: number; minLength?: number } = {}
+): RoomPosition[][] {
+  const bridges = clockwork_swamp_bridges(packRoomName(roomName), maxWidth, minLength);
+  const tiles = [...bridges.tiles].map(pos => fromPacked(pos));
+  const lengths = [...bridges.lengths];
+  bridges.free();
+
+  let offset = 0;
+  return lengths.map(length => {
+    const corridor = tiles.slice(offset, offset + length);
+    offset += length;
+    return corridor;
+  });
+}
@@ -0,0 +1,48 @@
+import { packRoomName } from '../utils/fromPacked';
+import { ClockworkCostMatrix, clockwork_build_threat_matrix } from '../wasm/screeps_clockwork';
+import { getTerrainCostMatrix } from './getTerrainCostMatrix';
+
+/**
+ * Build a cost matrix for kiting or avoiding hostiles in a room, without
+ * looping over tiles in JS. Every tile within `range` of a hostile costs
+ * `costPerDps` more per point of damage it can deal there, summed over all
+ * hostiles (so a tile covered by two ranged attackers costs more than one
+ * covered by a single one). Hostiles in neighboring rooms count too.
+ * Impassable tiles stay impassable, and other tiles are capped at 254.
+ *
+ * @example
+ * ```typescript
+ * const hostiles = room.find(FIND_HOSTILE_CREEPS).map(creep => ({
+ *   pos: creep.pos,
+ *   range: creep.getActiveBodyparts(RANGED_ATTACK) ? 3 : 1,
+ *   dps: creep.getActiveBodyparts(RANGED_ATTACK) * 10 + creep.getActiveBodyparts(ATTACK) * 30
+ * }));
+ * const matrix = buildThreatMatrix(room.name, hostiles, { costPerDps: 0.2 });
+ * ```
+ *
+ * @param roomName - The room to build the matrix for.
+ * @param hostiles - Each hostile's position, attack range, and damage per tick.
+ * @param options - `base`, the matrix to add penalties to (defaults to the
+ * room's terrain matrix), and `costPerDps` (default 1).
+ * @returns A new cost matrix; `base` isn't modified.
+ */
+export function buildThreatMatrix(
+  roomName: string,
+  hostiles: { pos: RoomPosition; range: number; dps: number }[],
+  { base, costPerDps = 1 }: { base?: ClockworkCostMatrix; costPerDps?: number } = {}
+): ClockworkCostMatrix {
+  const terrain = base ? undefined : getTerrainCostMatrix(roomName);
+  const matrix = clockwork_build_threat_matrix(
+    packRoomName(roomName),
+    new Uint32Array(
+      hostiles.reduce((acc, { pos, range, dps }) => {
+        acc.push(pos.__packedPos, range, dps);
+        return acc;
+      }, [] as number[])
+    ),
+    (base ?? terrain)!,
+    costPerDps
+  );
+  terrain?.free();
+  return matrix;
+}
@@ -0,0 +1,38 @@
+import { fromPacked } from '../utils/fromPacked';
+import {
+  ClockworkCostMatrix,
+  clockwork_prioritize_ramparts,
+  clockwork_tower_coverage
+} from '../wasm/screeps_clockwork';
+
+/**
+ * Count, for each tile in a room, how many towers deal at least `minDamage`
+ * there (accounting for range falloff).
+ *
+ * @param towers - The tower positions (must all be in the same room).
+ * @param minDamage - The minimum effective damage for a tower to count.
+ * @returns A matrix of tower counts per tile.
+ */
+export function towerCoverage(towers: RoomPosition[], minDamage: number): ClockworkCostMatrix {
+  return clockwork_tower_coverage(new Uint32Array(towers.map(pos => pos.__packedPos)), minDamage);
+}
+
+/**
+ * Filter rampart positions (e.g. a min-cut result) down to the ones covered
+ * by fewer than `minTowers` towers, least covered first. Ramparts in the
+ * exclusion zone are left out, since towers can hold those tiles unaided.
+ *
+ * @param ramparts - The rampart positions (must all be in the same room).
+ * @param coverage - A matrix from `towerCoverage`.
+ * @param minTowers - The tower count at which a tile is considered covered.
+ * @returns The ramparts that still need maintaining, in priority order.
+ */
+export function prioritizeRamparts(
+  ramparts: RoomPosition[],
+  coverage: ClockworkCostMatrix,
+  minTowers: number
+): RoomPosition[] {
+  return [
+    ...clockwork_prioritize_ramparts(new Uint32Array(ramparts.map(pos => pos.__packedPos)), coverage, minTowers)
+  ].map(pos => fromPacked(pos));
+}
@@ -0,0 +1,54 @@
+import { BodySpec, clockwork_estimate_travel_ticks } from '../wasm/screeps_clockwork';
+import { ClockworkPath } from './path';
+
+const MOVE_BOOSTS: Record<string, number> = { ZO: 2, ZHO2: 3, XZHO2: 4 };
+const CARRY_BOOSTS: Record<string, number> = { KH: 2, KH2O: 3, XKH2O: 4 };
+
+/**
+ * Describe a creep body for travel time estimates. Accepts a list of body
+ * parts (as in `spawnCreep`) or a creep's `body`, in which case boosts are
+ * taken into account.
+ *
+ * @param body - The body parts.
+ * @param carryLoad - The amount of resources carried.
+ * @returns The body spec. Free it when it's no longer needed.
+ */
+export function bodySpec(body: (BodyPartConstant | BodyPartDefinition)[], carryLoad = 0): BodySpec {
+  let movePower = 0;
+  let fatigueParts = 0;
+  let carryParts = 0;
+  let carryCapacity = 0;
+  for (const part of body) {
+    const type = typeof part === 'string' ? part : part.type;
+    const boost = typeof part === 'string' ? undefined : part.boost;
+    if (type === MOVE) {
+      movePower += (boost && MOVE_BOOSTS[boost]) || 1;
+    } else if (type === CARRY) {
+      carryParts++;
+      carryCapacity += CARRY_CAPACITY * ((boost && CARRY_BOOSTS[boost]) || 1);
+    } else {
+      fatigueParts++;
+    }
+  }
+  return new BodySpec(
+    movePower,
+    fatigueParts,
+    carryParts,
+    carryLoad,
+    carryParts ? Math.floor(carryCapacity / carryParts) : undefined
+  );
+}
+
+/**
+ * Estimate the ticks a creep takes to walk a path, accounting for its ratio of
+ * MOVE parts, carried load, roads, and swamps. Useful for sizing bodies before
+ * spawning. The creep starts on the path's first tile, and crossing a room
+ * border takes a single move.
+ *
+ * @param path - The path to walk.
+ * @param body - The creep's body, from `bodySpec`.
+ * @returns The number of ticks, or `undefined` if the creep can't move.
+ */
+export function estimateTravelTicks(path: ClockworkPath, body: BodySpec): number | undefined {
+  return clockwork_estimate_travel_ticks(path['path'], body);
+}
@@ -0,0 +1,25 @@
+import { fromPacked, packRoomName } from '../utils/fromPacked';
+import { clockwork_wall_gaps } from '../wasm/screeps_clockwork';
+
+/**
+ * Find the gaps in a room's natural walls: the walkable tiles two tiles in
+ * from the edges (the closest ramparts and walls can be built to an exit)
+ * that creeps can reach from an exit. Blocking every gap seals the room off,
+ * so this is a quick first pass at early defense before a full min-cut plan.
+ *
+ * @param roomName - The room to analyze.
+ * @returns Each gap's tiles; a gap's width is its number of tiles.
+ */
+export function findWallGaps(roomName: string): RoomPosition[][] {
+  const gaps = clockwork_wall_gaps(packRoomName(roomName));
+  const tiles = [...gaps.tiles].map(pos => fromPacked(pos));
+  const widths = [...gaps.widths];
+  gaps.free();
+
+  let offset = 0;
+  return widths.map(width => {
+    const gap = tiles.slice(offset, offset + width);
+    offset += width;
+    return gap;
+  });
+}
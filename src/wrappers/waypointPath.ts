@@ -0,0 +1,65 @@
+import { MAX_USIZE } from '../utils/constants';
+import { packedCostMatrixCallback } from '../utils/costMatrixCallback';
+import { ClockworkCostMatrix, clockwork_waypoint_path, GoalKind } from '../wasm/screeps_clockwork';
+import { CostOverlayOptions, createCostOverlay } from './costOverlay';
+import { createMovementRules, MovementOptions } from './movementRules';
+import { createOpenListLimit, OpenListOptions } from './openListLimit';
+import { ClockworkPath } from './path';
+
+/**
+ * Find a single path from `origin` through each waypoint in order, e.g. for a
+ * patrol route, instead of searching each leg and stitching the paths
+ * yourself. Each leg picks up where the previous one reached its waypoint
+ * (within its range), and cost matrices are requested once for every leg.
+ * `maxRooms`, `maxOps`, and `maxPathCost` apply to each leg separately.
+ * Legs that stay in one room are searched with `roomAstarPath` first, unless
+ * diagonal moves or corner cutting are turned off.
+ *
+ * @example
+ * ```typescript
+ * const patrol = waypointPath(creep.pos, [
+ *   { pos: flags.north.pos, range: 1 },
+ *   { pos: flags.south.pos, range: 1 },
+ *   { pos: spawn.pos, range: 1 }
+ * ], { costMatrixCallback });
+ * ```
+ *
+ * @param origin - The starting position.
+ * @param waypoints - The positions to visit, in order, and the range to get within.
+ * @param options - The options for the search.
+ * @returns The path through every waypoint, or `undefined` if one can't be reached.
+ */
+export function waypointPath(
+  origin: RoomPosition,
+  waypoints: { pos: RoomPosition; range: number; kind?: GoalKind }[],
+  {
+    costMatrixCallback,
+    maxRooms = MAX_USIZE,
+    maxOps = MAX_USIZE,
+    maxPathCost = MAX_USIZE,
+    ...searchOptions
+  }: {
+    costMatrixCallback: (room: string) => ClockworkCostMatrix | undefined;
+    maxRooms?: number;
+    maxOps?: number;
+    maxPathCost?: number;
+  } & CostOverlayOptions & MovementOptions & OpenListOptions
+): ClockworkPath | undefined {
+  const path = clockwork_waypoint_path(
+    origin.__packedPos,
+    new Uint32Array(
+      waypoints.reduce((acc, { pos, range, kind = GoalKind.Range }) => {
+        acc.push(pos.__packedPos, range, kind);
+        return acc;
+      }, [] as number[])
+    ),
+    packedCostMatrixCallback(costMatrixCallback),
+    maxRooms,
+    maxOps,
+    maxPathCost,
+    createCostOverlay(searchOptions),
+    createMovementRules(searchOptions),
+    createOpenListLimit(searchOptions)
+  );
+  return path ? new ClockworkPath(path) : undefined;
+}
@@ -0,0 +1,35 @@
+import { anytimeAstar, ClockworkCostMatrix, ephemeral } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('anytimeAstar', () => {
+  it('should refine the path to optimal when the budget allows', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    // A wall between the start and the target, with gaps at both ends
+    for (let y = 5; y <= 44; y++) {
+      costMatrix.set(30, y, 255);
+    }
+    const result = anytimeAstar(new RoomPosition(25, 30, 'W1N1'), {
+      costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+      destinations: [{ pos: new RoomPosition(35, 30, 'W1N1'), range: 0 }],
+      maxRooms: 1
+    });
+
+    expect(result).toBeDefined();
+    ephemeral(result!.path);
+    expect(result!.bound).toBe(1);
+    expect(result!.cost).toBe(30);
+    expect(result!.path.length).toBe(31);
+    expect(result!.iterations).toBeGreaterThan(1);
+  });
+
+  it('should return undefined when the destination is unreachable', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const result = anytimeAstar(new RoomPosition(25, 25, 'W1N1'), {
+      costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+      destinations: [{ pos: new RoomPosition(25, 25, 'W2N1'), range: 0 }],
+      maxRooms: 1
+    });
+
+    expect(result).toBeUndefined();
+  });
+});
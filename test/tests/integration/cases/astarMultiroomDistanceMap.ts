@@ -4,7 +4,13 @@ import {
   astarMultiroomDistanceMap,
   dijkstraMultiroomDistanceMap,
   ephemeral,
-  getTerrainCostMatrix
+  FailureReason,
+  getTerrainCostMatrix,
+  GoalKind,
+  GoalSet,
+  OpenListOverflow,
+  searchWithFallback,
+  SearchStrategy
 } from '../../../../src/index';
 import { ClockworkMultiroomDistanceMap } from '../../../../src/wrappers/multiroomDistanceMap';
 import { cpuTime } from '../../../utils/cpuTime';
@@ -26,6 +32,18 @@ describe('astarMultiroomDistanceMap', () => {
     expect(distanceMap.get(new RoomPosition(1, 1, 'W1N1'))).toBe(UNREACHABLE);
   });
 
+  it('should ignore the range for tile goals', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const result = astarMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+      costMatrixCallback: () => costMatrix,
+      maxOps: 2500,
+      anyOfDestinations: [{ pos: new RoomPosition(30, 25, 'W1N1'), range: 3, kind: GoalKind.Tile }]
+    });
+    ephemeral(result.distanceMap);
+    expect(result.foundTargets.length).toBe(1);
+    expect(result.foundTargets[0].isEqualTo(new RoomPosition(30, 25, 'W1N1'))).toBeTruthy();
+  });
+
   it('should factor in terrain costs', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
     // Add high-cost terrain
@@ -45,6 +63,53 @@ describe('astarMultiroomDistanceMap', () => {
     expect(distanceMap.get(new RoomPosition(1, 1, 'W1N1'))).toBe(UNREACHABLE);
   });
 
+  it('should scale the heuristic by the cheapest tile in the loaded rooms', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(2));
+    const options = {
+      costMatrixCallback: () => costMatrix,
+      maxOps: 2500,
+      anyOfDestinations: [{ pos: new RoomPosition(40, 25, 'W1N1'), range: 0 }]
+    };
+    const derived = astarMultiroomDistanceMap([new RoomPosition(10, 25, 'W1N1')], options);
+    const unscaled = astarMultiroomDistanceMap([new RoomPosition(10, 25, 'W1N1')], { ...options, minTileCost: 1 });
+    const dijkstra = dijkstraMultiroomDistanceMap([new RoomPosition(10, 25, 'W1N1')], options);
+    ephemeral(derived.distanceMap);
+    ephemeral(unscaled.distanceMap);
+    ephemeral(dijkstra.distanceMap);
+
+    expect(derived.heuristicWeight).toBe(2);
+    expect(unscaled.heuristicWeight).toBe(1);
+    expect(dijkstra.heuristicWeight).toBe(0);
+    expect(derived.distanceMap.get(new RoomPosition(40, 25, 'W1N1'))).toBe(60);
+    expect(derived.ops).toBeLessThanOrEqual(unscaled.ops);
+  });
+
+  it('should scale the heuristic by the cost overlay, not the raw cost matrix', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(2));
+    const result = astarMultiroomDistanceMap([new RoomPosition(10, 25, 'W1N1')], {
+      costMatrixCallback: () => costMatrix,
+      maxOps: 2500,
+      anyOfDestinations: [{ pos: new RoomPosition(40, 25, 'W1N1'), range: 0 }],
+      roomMultipliers: { W1N1: 3 }
+    });
+    ephemeral(result.distanceMap);
+
+    expect(result.heuristicWeight).toBe(6);
+    expect(result.distanceMap.get(new RoomPosition(40, 25, 'W1N1'))).toBe(180);
+  });
+
+  it('should stay optimal by default when rooms along the way are cheaper', () => {
+    const plains = ephemeral(new ClockworkCostMatrix(2));
+    const roads = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = (room: string) => (room === 'W1N1' ? plains : roads);
+    const start = [new RoomPosition(45, 25, 'W1N1')];
+    const target = new RoomPosition(25, 25, 'W0N1');
+    const options = { costMatrixCallback, maxOps: 10000, anyOfDestinations: [{ pos: target, range: 0 }] };
+    const astar = ephemeral(astarMultiroomDistanceMap(start, options).distanceMap);
+    const dijkstra = ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(astar.get(target)).toBe(dijkstra.get(target));
+  });
+
   it('should throw for invalid cost matrixes', () => {
     expect(() =>
       astarMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
@@ -85,13 +150,14 @@ describe('astarMultiroomDistanceMap', () => {
   });
   it('should respect maxOps', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
-    const distanceMap = ephemeral(
-      astarMultiroomDistanceMap([new RoomPosition(1, 1, 'W1N1')], {
-        costMatrixCallback: () => costMatrix,
-        maxOps: 100,
-        anyOfDestinations: [{ pos: new RoomPosition(48, 48, 'W1N1'), range: 0 }]
-      }).distanceMap
-    );
+    const result = astarMultiroomDistanceMap([new RoomPosition(1, 1, 'W1N1')], {
+      costMatrixCallback: () => costMatrix,
+      maxOps: 20,
+      anyOfDestinations: [{ pos: new RoomPosition(48, 48, 'W1N1'), range: 0 }]
+    });
+    const distanceMap = ephemeral(result.distanceMap);
+    expect(result.ops).toBe(20);
+    expect(result.foundTargets.length).toBe(0);
     let explored = 0;
     for (let y = 0; y < 50; y++) {
       for (let x = 0; x < 50; x++) {
@@ -100,7 +166,7 @@ describe('astarMultiroomDistanceMap', () => {
         }
       }
     }
-    expect(explored).toBeLessThan(101);
+    expect(explored).toBe(result.pushed);
   }, 10);
   it('should respect maxPathCost', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
@@ -415,10 +481,233 @@ describe('astarMultiroomDistanceMap', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
     const searchResult = astarMultiroomDistanceMap([new RoomPosition(5, 5, 'W1N1')], {
       costMatrixCallback: roomName => (roomName === 'W1N1' || roomName === 'W1N2' ? costMatrix : undefined),
-      anyOfDestinations: [{ pos: new RoomPosition(45, 45, 'W1N2'), range: 50 }]
+      anyOfDestinations: [{ pos: new RoomPosition(45, 45, 'W1N2'), range: 5 }]
     });
     ephemeral(searchResult.distanceMap);
 
     expect(searchResult.foundTargets.every(pos => pos.roomName === 'W1N2')).toBe(true);
   });
+
+  it('should measure range across room edges in world coordinates', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const searchResult = astarMultiroomDistanceMap([new RoomPosition(45, 25, 'W2N1')], {
+      costMatrixCallback: roomName => (roomName === 'W1N1' || roomName === 'W2N1' ? costMatrix : undefined),
+      maxOps: 2500,
+      anyOfDestinations: [{ pos: new RoomPosition(1, 25, 'W1N1'), range: 3 }]
+    });
+    ephemeral(searchResult.distanceMap);
+
+    expect(searchResult.foundTargets.length).toBe(1);
+    expect(searchResult.foundTargets[0].roomName).toBe('W2N1');
+    expect(searchResult.foundTargets[0].x).toBe(48);
+  });
+
+  it('should fall back to the next strategy and share cost matrices', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    let calls = 0;
+    const options = {
+      costMatrixCallback: () => {
+        calls++;
+        return costMatrix;
+      },
+      maxRooms: 1,
+      anyOfDestinations: [{ pos: new RoomPosition(5, 25, 'W1N1'), range: 0 }]
+    };
+
+    const failed = searchWithFallback([new RoomPosition(25, 25, 'W1N1')], {
+      ...options,
+      strategies: [SearchStrategy.AStar, SearchStrategy.WeightedAStar],
+      maxOps: 10
+    });
+    ephemeral(failed.distanceMap);
+    expect(failed.foundTargets.length).toBe(0);
+    expect(failed.ops).toBe(20);
+    expect(calls).toBe(1);
+
+    const found = searchWithFallback([new RoomPosition(25, 25, 'W1N1')], {
+      ...options,
+      strategies: [SearchStrategy.Bfs, SearchStrategy.AStar],
+      maxOps: 100
+    });
+    ephemeral(found.distanceMap);
+    expect(found.foundTargets.length).toBe(1);
+  });
+
+  it('should cap the open list', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const options = {
+      costMatrixCallback: (room: string) => (room === 'W1N1' ? costMatrix : undefined),
+      maxRooms: 1,
+      anyOfDestinations: [{ pos: new RoomPosition(45, 25, 'W1N1'), range: 0 }],
+      maxOpenNodes: 10
+    };
+    const start = [new RoomPosition(5, 25, 'W1N1')];
+
+    const stopped = astarMultiroomDistanceMap(start, options);
+    ephemeral(stopped.distanceMap);
+    expect(stopped.foundTargets.length).toBe(0);
+    expect(stopped.failure?.reason).toBe(FailureReason.OpenListFull);
+
+    const beam = astarMultiroomDistanceMap(start, {
+      ...options,
+      openListOverflow: OpenListOverflow.Beam,
+      beamWidth: 3
+    });
+    ephemeral(beam.distanceMap);
+    expect(beam.foundTargets.length).toBe(1);
+  });
+
+  it('should reuse a goal set across searches', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const goals = ephemeral(
+      new GoalSet([
+        { pos: new RoomPosition(45, 25, 'W1N1'), range: 0, kind: GoalKind.Tile },
+        { pos: new RoomPosition(10, 25, 'W2N1'), range: 3 }
+      ])
+    );
+    const options = {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 2,
+      anyOfDestinations: goals
+    };
+
+    const east = astarMultiroomDistanceMap([new RoomPosition(40, 25, 'W1N1')], options);
+    ephemeral(east.distanceMap);
+    expect(east.foundTargets[0].isEqualTo(new RoomPosition(45, 25, 'W1N1'))).toBeTruthy();
+
+    const west = dijkstraMultiroomDistanceMap([new RoomPosition(2, 25, 'W1N1')], options);
+    ephemeral(west.distanceMap);
+    expect(west.foundTargets[0].roomName).toBe('W2N1');
+    expect(west.foundTargets[0].x).toBe(13);
+  });
+
+  it('should check many goals as quickly as one', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 0; y < 50; y++) {
+      for (let x = 30; x < 50; x++) {
+        costMatrix.set(x, y, 255);
+      }
+    }
+    const start = [new RoomPosition(10, 25, 'W1N1')];
+    const iterations = 20;
+    // Goals behind the wall, so the search checks every tile it reaches against all of them
+    const manyGoals = [];
+    for (let y = 5; y < 45; y += 2) {
+      for (let x = 35; x < 49; x += 2) {
+        manyGoals.push({ pos: new RoomPosition(x, y, 'W1N1'), range: 1 });
+      }
+    }
+    const search = (anyOfDestinations: { pos: RoomPosition; range: number }[]) =>
+      dijkstraMultiroomDistanceMap(start, {
+        costMatrixCallback: () => costMatrix,
+        maxRooms: 1,
+        anyOfDestinations
+      });
+
+    let oneOps = 0;
+    let manyOps = 0;
+    const oneTime = cpuTime(() => {
+      const result = search(manyGoals.slice(0, 1));
+      ephemeral(result.distanceMap);
+      oneOps = result.ops;
+    }, iterations);
+    const manyTime = cpuTime(() => {
+      const result = search(manyGoals);
+      ephemeral(result.distanceMap);
+      manyOps = result.ops;
+    }, iterations);
+
+    console.log('One Goal Time', oneTime);
+    console.log(`${manyGoals.length} Goals Time`, manyTime);
+
+    expect(manyOps).toBe(oneOps);
+    expect(manyTime).toBeLessThan(oneTime * 2 + 1);
+  });
+  it('should use a heuristic map from the destinations', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 5; y < 45; y++) {
+      costMatrix.set(30, y, 255);
+    }
+    const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+    const start = new RoomPosition(20, 25, 'W1N1');
+    const destinations = [{ pos: new RoomPosition(40, 25, 'W1N1'), range: 0 }];
+
+    const fromTarget = dijkstraMultiroomDistanceMap([destinations[0].pos], { costMatrixCallback, maxRooms: 1 });
+    const heuristicMap = ephemeral(fromTarget.distanceMap.toHeuristicMap(destinations));
+    fromTarget.distanceMap.free();
+
+    const plain = astarMultiroomDistanceMap([start], { costMatrixCallback, anyOfDestinations: destinations });
+    const guided = astarMultiroomDistanceMap([start], {
+      costMatrixCallback,
+      anyOfDestinations: destinations,
+      heuristicMap
+    });
+    ephemeral(plain.distanceMap);
+    ephemeral(guided.distanceMap);
+    expect(guided.distanceMap.get(destinations[0].pos)).toBe(plain.distanceMap.get(destinations[0].pos));
+    expect(guided.ops).toBeLessThan(plain.ops);
+
+    expect(() =>
+      astarMultiroomDistanceMap([start], {
+        costMatrixCallback,
+        anyOfDestinations: [{ pos: new RoomPosition(40, 26, 'W1N1'), range: 0 }],
+        heuristicMap
+      })
+    ).toThrow('was built for different destinations');
+  });
+
+  it('should find the cheapest path to a range goal with a heuristic map', () => {
+    // The tiles next to the first destination are expensive, so the map
+    // measures it as far away, though its range is cheap to reach
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let x = 39; x <= 41; x++) {
+      for (let y = 9; y <= 11; y++) {
+        costMatrix.set(x, y, 200);
+      }
+    }
+    const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+    const destinations = [
+      { pos: new RoomPosition(40, 10, 'W1N1'), range: 2 },
+      { pos: new RoomPosition(10, 40, 'W1N1'), range: 0 }
+    ];
+    const fromTargets = dijkstraMultiroomDistanceMap(
+      destinations.map(({ pos }) => pos),
+      { costMatrixCallback, maxRooms: 1 }
+    );
+    const heuristicMap = ephemeral(fromTargets.distanceMap.toHeuristicMap(destinations));
+    fromTargets.distanceMap.free();
+
+    const result = astarMultiroomDistanceMap([new RoomPosition(40, 20, 'W1N1')], {
+      costMatrixCallback,
+      anyOfDestinations: destinations,
+      heuristicMap
+    });
+    ephemeral(result.distanceMap);
+    expect(result.foundTargets.length).toBe(1);
+    expect(result.foundTargets[0].getRangeTo(destinations[0].pos)).toBe(2);
+    expect(result.distanceMap.get(result.foundTargets[0])).toBe(8);
+  });
+
+  it('should record the expanded positions when asked', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const recorded = astarMultiroomDistanceMap([start], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 50,
+      recordExpansions: true
+    });
+    ephemeral(recorded.distanceMap);
+    expect(recorded.expanded.length).toBeGreaterThan(0);
+    expect(recorded.expanded.length).toBeLessThanOrEqual(recorded.ops);
+    expect(recorded.expanded[0].isEqualTo(start)).toBeTruthy();
+
+    const unrecorded = astarMultiroomDistanceMap([start], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 50
+    });
+    ephemeral(unrecorded.distanceMap);
+    expect(unrecorded.expanded.length).toBe(0);
+  });
 });
@@ -0,0 +1,142 @@
+import {
+  ClockworkCostMatrix,
+  dijkstraMultiroomDistanceMap,
+  ephemeral,
+  pendingBackgroundJobs,
+  queueDistanceMapJob,
+  queueProgressiveDistanceMapJob,
+  runBackgroundJobs,
+  takeBackgroundResult
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('backgroundJobs', () => {
+  it('should spread a large distance map job across several calls', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = () => costMatrix;
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const jobId = queueDistanceMapJob([start], { maxRooms: 9 });
+
+    let calls = 0;
+    while (pendingBackgroundJobs() > 0 && calls < 1000) {
+      runBackgroundJobs(costMatrixCallback, 0.001);
+      calls++;
+      if (pendingBackgroundJobs() > 0) {
+        expect(takeBackgroundResult(jobId)).toBeUndefined();
+      }
+    }
+
+    expect(calls).toBeGreaterThan(1);
+    const distanceMap = ephemeral(takeBackgroundResult(jobId)!);
+    expect(distanceMap.get(start)).toBe(0);
+    expect(distanceMap.getRooms().length).toBe(9);
+  });
+
+  it('should build a progressive distance map that matches dijkstra', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let x = 20; x < 30; x++) {
+      costMatrix.set(x, 22, 5);
+    }
+    const requests = new Map<string, number>();
+    const costMatrixCallback = (room: string) => {
+      requests.set(room, (requests.get(room) ?? 0) + 1);
+      return room === 'W1N1' ? costMatrix : undefined;
+    };
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const jobId = queueProgressiveDistanceMapJob([start], { maxRooms: 1, bucketsPerStep: 1 });
+
+    let calls = 0;
+    while (pendingBackgroundJobs() > 0 && calls < 1000) {
+      runBackgroundJobs(costMatrixCallback, 0.001);
+      calls++;
+    }
+
+    expect(calls).toBeGreaterThan(1);
+    expect(requests.get('W1N1')).toBe(1);
+    const progressive = ephemeral(takeBackgroundResult(jobId)!);
+    const expected = ephemeral(
+      dijkstraMultiroomDistanceMap([start], { costMatrixCallback: () => costMatrix, maxRooms: 1 }).distanceMap
+    );
+    let mismatches = 0;
+    for (let y = 0; y < 50; y++) {
+      for (let x = 0; x < 50; x++) {
+        const pos = new RoomPosition(x, y, 'W1N1');
+        if (progressive.get(pos) !== expected.get(pos)) {
+          mismatches++;
+        }
+      }
+    }
+    expect(mismatches).toBe(0);
+  });
+
+  it('should apply the cost overlay and movement rules to progressive jobs', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = () => costMatrix;
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const options = {
+      maxRooms: 1,
+      blockedTiles: [new RoomPosition(26, 25, 'W1N1'), new RoomPosition(24, 25, 'W1N1')],
+      allowDiagonals: false
+    };
+    const jobId = queueProgressiveDistanceMapJob([start], { ...options, bucketsPerStep: 5 });
+    while (pendingBackgroundJobs() > 0) {
+      runBackgroundJobs(costMatrixCallback, 1);
+    }
+
+    const progressive = ephemeral(takeBackgroundResult(jobId)!);
+    const expected = ephemeral(dijkstraMultiroomDistanceMap([start], { costMatrixCallback, ...options }).distanceMap);
+    expect(progressive.get(new RoomPosition(26, 25, 'W1N1'))).toBe(0xffffffff);
+    expect(progressive.get(new RoomPosition(27, 25, 'W1N1'))).toBe(4);
+    let mismatches = 0;
+    for (let y = 0; y < 50; y++) {
+      for (let x = 0; x < 50; x++) {
+        const pos = new RoomPosition(x, y, 'W1N1');
+        if (progressive.get(pos) !== expected.get(pos)) {
+          mismatches++;
+        }
+      }
+    }
+    expect(mismatches).toBe(0);
+  });
+
+  it('should not count blocked rooms against maxRooms', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    // W0N1, just east of the start, is reached first but has no cost matrix
+    const costMatrixCallback = (room: string) => (room === 'W0N1' ? undefined : costMatrix);
+    const jobId = queueDistanceMapJob([new RoomPosition(48, 25, 'W1N1')], { maxRooms: 3 });
+    while (pendingBackgroundJobs() > 0) {
+      runBackgroundJobs(costMatrixCallback, 1);
+    }
+
+    const distanceMap = ephemeral(takeBackgroundResult(jobId)!);
+    expect(distanceMap.getRooms().length).toBe(3);
+    expect(distanceMap.getRooms().includes('W0N1')).toBe(false);
+  });
+
+  it('should require a limit for progressive jobs', () => {
+    expect(() => queueProgressiveDistanceMapJob([new RoomPosition(25, 25, 'W1N1')])).toThrow();
+  });
+
+  it('should let the cost matrix callback queue jobs and collect results', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const firstId = queueDistanceMapJob([start], { maxRooms: 1 });
+    let queuedId: number | undefined;
+    const costMatrixCallback = () => {
+      if (queuedId === undefined) {
+        queuedId = queueDistanceMapJob([start], { maxRooms: 1 });
+        expect(takeBackgroundResult(firstId)).toBeUndefined();
+      }
+      return costMatrix;
+    };
+
+    let calls = 0;
+    while (pendingBackgroundJobs() > 0 && calls < 1000) {
+      runBackgroundJobs(costMatrixCallback, 0.001);
+      calls++;
+    }
+
+    expect(ephemeral(takeBackgroundResult(firstId)!).get(start)).toBe(0);
+    expect(ephemeral(takeBackgroundResult(queuedId!)!).get(start)).toBe(0);
+  });
+});
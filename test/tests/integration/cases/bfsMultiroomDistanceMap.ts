@@ -1,4 +1,11 @@
-import { bfsMultiroomDistanceMap, ClockworkCostMatrix, ephemeral } from '../../../../src/index';
+import {
+  bfsMultiroomDistanceMap,
+  ClockworkCostMatrix,
+  depthMap,
+  ephemeral,
+  roomGoal,
+  roomSearchLoad
+} from '../../../../src/index';
 import { describe, expect, it } from '../../helpers';
 
 const UNREACHABLE = 0xffffffff;
@@ -186,10 +193,73 @@ describe('bfsMultiroomDistanceMap', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
     const searchResult = bfsMultiroomDistanceMap([new RoomPosition(5, 5, 'W1N1')], {
       costMatrixCallback: roomName => (roomName === 'W1N1' || roomName === 'W1N2' ? costMatrix : undefined),
-      anyOfDestinations: [{ pos: new RoomPosition(45, 45, 'W1N2'), range: 50 }]
+      anyOfDestinations: [{ pos: new RoomPosition(45, 45, 'W1N2'), range: 5 }]
     });
     ephemeral(searchResult.distanceMap);
 
     expect(searchResult.foundTargets.every(pos => pos.roomName === 'W1N2')).toBe(true);
   });
+  it('should stop at the closest entry tile for room goals', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const result = bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 2,
+      anyOfDestinations: [roomGoal('W1N2')]
+    });
+    ephemeral(result.distanceMap);
+    expect(result.foundTargets.length).toBe(1);
+    expect(result.foundTargets[0].roomName).toBe('W1N2');
+    expect(result.foundTargets[0].y).toBe(49);
+    expect(result.distanceMap.get(result.foundTargets[0])).toBe(25);
+  });
+  it('should map tile depth ignoring swamp costs', () => {
+    const swamp = ephemeral(new ClockworkCostMatrix(5));
+    swamp.set(25, 26, 255);
+    const depths = ephemeral(
+      depthMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: room => (room === 'W1N1' ? swamp : undefined),
+        maxDepth: 5
+      })
+    );
+
+    expect(depths.get(new RoomPosition(25, 25, 'W1N1'))).toBe(0);
+    expect(depths.get(new RoomPosition(30, 25, 'W1N1'))).toBe(5);
+    expect(depths.get(new RoomPosition(25, 27, 'W1N1'))).toBe(2);
+    expect(depths.get(new RoomPosition(25, 26, 'W1N1'))).toBe(UNREACHABLE);
+    expect(depths.get(new RoomPosition(31, 25, 'W1N1'))).toBe(UNREACHABLE);
+  });
+  it('should report the tiles expanded in each room', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const before = roomSearchLoad().find(load => load.room === 'W1N1')?.expanded ?? 0;
+    const result = bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 100
+    });
+    ephemeral(result.distanceMap);
+    const load = roomSearchLoad().find(load => load.room === 'W1N1');
+    expect(load!.expanded - before).toBe(result.ops);
+  });
+  it('should record the expanded positions when asked', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const recorded = bfsMultiroomDistanceMap([start], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 50,
+      recordExpansions: true
+    });
+    ephemeral(recorded.distanceMap);
+    expect(recorded.expanded.length).toBeGreaterThan(0);
+    expect(recorded.expanded.length).toBeLessThanOrEqual(recorded.ops);
+    expect(recorded.expanded[0].isEqualTo(start)).toBeTruthy();
+
+    const unrecorded = bfsMultiroomDistanceMap([start], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 50
+    });
+    ephemeral(unrecorded.distanceMap);
+    expect(unrecorded.expanded.length).toBe(0);
+  });
 });
@@ -9,6 +9,41 @@ describe('clockworkCostMatrix', () => {
     matrix.set(0, 0, 1);
     expect(matrix.get(0, 0)).toBe(1);
   });
+  it('should survive compression', () => {
+    const matrix = ephemeral(getTerrainCostMatrix('W1N1', { plainCost: 1, swampCost: 5, wallCost: 255 }));
+    matrix.set(10, 10, 42);
+    const compressed = matrix.compress();
+    const restored = ephemeral(ClockworkCostMatrix.decompress(compressed));
+    for (let x = 0; x < 50; x++) {
+      for (let y = 0; y < 50; y++) {
+        expect(restored.get(x, y)).toBe(matrix.get(x, y));
+      }
+    }
+    expect(compressed.length).toBeLessThan(2500);
+  });
+  it('should count differing tiles', () => {
+    const a = ephemeral(new ClockworkCostMatrix(1));
+    const b = ephemeral(new ClockworkCostMatrix(1));
+    expect(a.equals(b)).toBe(true);
+    expect(a.diffCount(b)).toBe(0);
+    b.set(10, 10, 255);
+    b.set(20, 20, 5);
+    expect(a.equals(b)).toBe(false);
+    expect(a.diffCount(b)).toBe(2);
+  });
+  it('should mirror, rotate, and translate', () => {
+    const stamp = ephemeral(new ClockworkCostMatrix(1));
+    stamp.set(10, 20, 255);
+    expect(ephemeral(stamp.mirrorHorizontal()).get(39, 20)).toBe(255);
+    expect(ephemeral(stamp.mirrorVertical()).get(10, 29)).toBe(255);
+    expect(ephemeral(stamp.rotate(90)).get(29, 10)).toBe(255);
+    expect(ephemeral(stamp.rotate(180)).get(39, 29)).toBe(255);
+    expect(ephemeral(stamp.rotate(270)).get(20, 39)).toBe(255);
+    const moved = ephemeral(stamp.translate(5, -5, 0));
+    expect(moved.get(15, 15)).toBe(255);
+    expect(moved.get(0, 0)).toBe(0);
+    expect(moved.get(20, 49)).toBe(0);
+  });
   it('should not be significantly slower than screeps', () => {
     const clockwork_matrix = ephemeral(new ClockworkCostMatrix());
     const screeps_matrix = new PathFinder.CostMatrix();
@@ -0,0 +1,12 @@
+import { clockworkInfo } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('clockworkInfo', () => {
+  it('should describe the loaded build', () => {
+    const info = clockworkInfo();
+    expect(info.algorithms.includes('astar')).toBe(true);
+    expect(info.algorithms.includes('room_astar')).toBe(true);
+    expect(info.limits.roomSize).toBe(50);
+    expect(info.limits.roomArea).toBe(2500);
+  });
+});
@@ -0,0 +1,39 @@
+import { bestOrigin, ClockworkCostMatrix, closestOf, ephemeral } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('closestOf', () => {
+  it('should pick the closest candidate by path cost', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    // Wall off the nearest candidate, so the path to it has to go around
+    for (let y = 10; y <= 40; y++) {
+      costMatrix.set(28, y, 255);
+    }
+    const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+    const from = new RoomPosition(25, 25, 'W1N1');
+    const candidates = [
+      new RoomPosition(30, 25, 'W1N1'),
+      new RoomPosition(15, 25, 'W1N1'),
+      new RoomPosition(10, 10, 'W1N1')
+    ];
+
+    expect(closestOf(from, candidates, { costMatrixCallback, maxRooms: 1 })).toBe(1);
+    expect(closestOf(from, candidates, { costMatrixCallback, maxRooms: 1, maxPathCost: 5 })).toBeUndefined();
+    expect(closestOf(from, candidates, { costMatrixCallback, maxRooms: 1, range: 3 })).toBe(0);
+    expect(closestOf(from, [], { costMatrixCallback, maxRooms: 1 })).toBeUndefined();
+  });
+  it('should pick the origin with the cheapest path to a goal', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 10; y <= 40; y++) {
+      costMatrix.set(28, y, 255);
+    }
+    const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+    const origins = [new RoomPosition(30, 25, 'W1N1'), new RoomPosition(15, 25, 'W1N1')];
+    const best = bestOrigin(origins, new RoomPosition(25, 25, 'W1N1'), { costMatrixCallback, maxRooms: 1 });
+
+    expect(best?.index).toBe(1);
+    const path = ephemeral(best!.path);
+    expect(path.get(0).isEqualTo(origins[1])).toBeTruthy();
+    expect(path.get(path.length - 1).isEqualTo(new RoomPosition(25, 25, 'W1N1'))).toBeTruthy();
+    expect(path.length).toBe(11);
+  });
+});
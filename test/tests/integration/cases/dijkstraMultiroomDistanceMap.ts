@@ -1,4 +1,15 @@
-import { ClockworkCostMatrix, dijkstraMultiroomDistanceMap, ephemeral } from '../../../../src/index';
+import {
+  addAvoidZone,
+  breachesAlong,
+  clearCostMatrixCache,
+  ClockworkCostMatrix,
+  dijkstraMultiroomDistanceMap,
+  ephemeral,
+  FailureReason,
+  listAvoidZones,
+  memoizeCostMatrixCallback,
+  removeAvoidZone
+} from '../../../../src/index';
 import { describe, expect, it } from '../../helpers';
 
 const UNREACHABLE = 0xffffffff;
@@ -36,6 +47,179 @@ describe('dijkstraMultiroomDistanceMap', () => {
     expect(distanceMap.get(new RoomPosition(25, 24, 'W1N1'))).toBe(11);
   });
 
+  it('should discount preferred tiles without changing the cost matrix', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(5));
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: () => costMatrix,
+        maxRooms: 1,
+        maxOps: 2500,
+        preferredTiles: [new RoomPosition(26, 25, 'W1N1'), new RoomPosition(27, 25, 'W1N1')],
+        preferredDiscount: 10,
+        preferredFloor: 2
+      }).distanceMap
+    );
+    expect(distanceMap.get(new RoomPosition(26, 25, 'W1N1'))).toBe(2);
+    expect(distanceMap.get(new RoomPosition(27, 25, 'W1N1'))).toBe(4);
+    expect(distanceMap.get(new RoomPosition(24, 25, 'W1N1'))).toBe(5);
+    expect(costMatrix.get(26, 25)).toBe(5);
+  });
+
+  it('should block tiles for one search only', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: () => costMatrix,
+        maxRooms: 1,
+        maxOps: 2500,
+        blockedTiles: [new RoomPosition(26, 25, 'W1N1')]
+      }).distanceMap
+    );
+    expect(distanceMap.get(new RoomPosition(26, 25, 'W1N1'))).toBe(UNREACHABLE);
+    expect(distanceMap.get(new RoomPosition(27, 25, 'W1N1'))).toBe(2);
+    expect(costMatrix.get(26, 25)).toBe(1);
+  });
+
+  it('should apply registered avoidance zones', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    addAvoidZone('test-zone', { roomName: 'W1N1', x1: 26, y1: 20, x2: 27, y2: 30 }, { cost: 20 });
+    expect(listAvoidZones().includes('test-zone')).toBe(true);
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: () => costMatrix,
+        maxRooms: 1,
+        maxOps: 2500
+      }).distanceMap
+    );
+    expect(removeAvoidZone('test-zone')).toBe(true);
+
+    expect(distanceMap.get(new RoomPosition(26, 25, 'W1N1'))).toBe(20);
+    expect(distanceMap.get(new RoomPosition(24, 25, 'W1N1'))).toBe(1);
+    expect(costMatrix.get(26, 25)).toBe(1);
+  });
+
+  it('should keep to protected tiles', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const protectedTiles = [new RoomPosition(25, 26, 'W1N1')];
+    for (let x = 20; x <= 30; x++) {
+      protectedTiles.push(new RoomPosition(x, 25, 'W1N1'));
+    }
+    const options = {
+      costMatrixCallback: (room: string) => (room === 'W1N1' ? costMatrix : undefined),
+      maxRooms: 1,
+      protectedTiles
+    };
+    const start = [new RoomPosition(20, 25, 'W1N1')];
+
+    const covered = ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(covered.get(new RoomPosition(30, 25, 'W1N1'))).toBe(10);
+    expect(covered.get(new RoomPosition(25, 26, 'W1N1'))).toBe(5);
+    expect(covered.get(new RoomPosition(25, 27, 'W1N1'))).toBe(UNREACHABLE);
+
+    const exposed = ephemeral(
+      dijkstraMultiroomDistanceMap(start, { ...options, exposurePenalty: 10 }).distanceMap
+    );
+    expect(exposed.get(new RoomPosition(25, 27, 'W1N1'))).toBe(16);
+  });
+
+  it('should follow movement restrictions', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    costMatrix.set(26, 25, 255);
+    const options = {
+      costMatrixCallback: (room: string) => (room === 'W1N1' ? costMatrix : undefined),
+      maxRooms: 1
+    };
+    const start = [new RoomPosition(25, 25, 'W1N1')];
+    const cornerTile = new RoomPosition(26, 26, 'W1N1');
+
+    const unrestricted = ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(unrestricted.get(cornerTile)).toBe(1);
+    expect(unrestricted.get(new RoomPosition(30, 30, 'W1N1'))).toBe(5);
+
+    const noCorners = ephemeral(
+      dijkstraMultiroomDistanceMap(start, { ...options, cutCorners: false }).distanceMap
+    );
+    expect(noCorners.get(cornerTile)).toBe(2);
+
+    const noDiagonals = ephemeral(dijkstraMultiroomDistanceMap(start, { ...options, allowDiagonals: false }).distanceMap);
+    expect(noDiagonals.get(new RoomPosition(30, 30, 'W1N1'))).toBe(10);
+  });
+
+  it('should add the room change cost when crossing rooms', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const options = {
+      costMatrixCallback: (room: string) => (room === 'W1N1' || room === 'W2N1' ? costMatrix : undefined),
+      maxOps: 10000
+    };
+    const start = [new RoomPosition(2, 25, 'W1N1')];
+    const target = new RoomPosition(47, 25, 'W2N1');
+
+    const free = ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    const penalized = ephemeral(dijkstraMultiroomDistanceMap(start, { ...options, roomChangeCost: 10 }).distanceMap);
+    expect(penalized.get(target) - free.get(target)).toBe(10);
+    expect(penalized.get(new RoomPosition(25, 25, 'W1N1'))).toBe(free.get(new RoomPosition(25, 25, 'W1N1')));
+  });
+
+  it('should scale costs by room multipliers', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(2));
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: room => (room === 'W1N1' || room === 'W2N1' ? costMatrix : undefined),
+        maxOps: 10000,
+        roomMultipliers: { W2N1: 3 }
+      }).distanceMap
+    );
+
+    expect(distanceMap.get(new RoomPosition(24, 25, 'W1N1'))).toBe(2);
+    expect(
+      distanceMap.get(new RoomPosition(47, 25, 'W2N1')) - distanceMap.get(new RoomPosition(48, 25, 'W2N1'))
+    ).toBe(6);
+    expect(costMatrix.get(25, 25)).toBe(2);
+  });
+
+  it('should keep followers within range of the leader', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const leader = new RoomPosition(25, 25, 'W1N1');
+    const options = {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 2500,
+      leader,
+      formationRange: 2
+    };
+
+    const forbidden = ephemeral(dijkstraMultiroomDistanceMap([leader], options).distanceMap);
+    expect(forbidden.get(new RoomPosition(27, 25, 'W1N1'))).toBe(2);
+    expect(forbidden.get(new RoomPosition(28, 25, 'W1N1'))).toBe(UNREACHABLE);
+
+    const penalized = ephemeral(
+      dijkstraMultiroomDistanceMap([leader], { ...options, formationPenalty: 10 }).distanceMap
+    );
+    expect(penalized.get(new RoomPosition(28, 25, 'W1N1'))).toBe(13);
+  });
+
+  it('should break through breachable walls', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 0; y < 50; y++) {
+      costMatrix.set(25, y, 255);
+    }
+    const start = new RoomPosition(20, 25, 'W1N1');
+    const target = new RoomPosition(30, 25, 'W1N1');
+    const breachableTiles = [{ pos: new RoomPosition(25, 25, 'W1N1'), hits: 300 }];
+    const options = { costMatrixCallback: () => costMatrix, maxRooms: 1, maxOps: 2500 };
+
+    const walled = ephemeral(dijkstraMultiroomDistanceMap([start], options).distanceMap);
+    expect(walled.get(target)).toBe(UNREACHABLE);
+
+    const siege = ephemeral(
+      dijkstraMultiroomDistanceMap([start], { ...options, breachableTiles, dismantlePower: 50 }).distanceMap
+    );
+    expect(siege.get(target)).toBe(15);
+    const breaches = breachesAlong(ephemeral(siege.pathToOrigin(target)), breachableTiles);
+    expect(breaches.length).toBe(1);
+    expect(breaches[0].isEqualTo(breachableTiles[0].pos)).toBeTruthy();
+  });
+
   it('should throw for invalid cost matrixes', () => {
     // cost matrix is a Screeps CostMatrix
     expect(() =>
@@ -90,13 +274,13 @@ describe('dijkstraMultiroomDistanceMap', () => {
   }, 10);
   it('should respect maxOps', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
-    const distanceMap = ephemeral(
-      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
-        costMatrixCallback: () => costMatrix,
-        maxRooms: 1,
-        maxOps: 100
-      }).distanceMap
-    );
+    const result = dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 100
+    });
+    const distanceMap = ephemeral(result.distanceMap);
+    expect(result.ops).toBe(100);
     let explored = 0;
     for (let y = 0; y < 50; y++) {
       for (let x = 0; x < 50; x++) {
@@ -105,7 +289,7 @@ describe('dijkstraMultiroomDistanceMap', () => {
         }
       }
     }
-    expect(explored).toBe(100);
+    expect(explored).toBe(result.pushed);
   });
   it('should respect maxPathCost', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
@@ -185,10 +369,173 @@ describe('dijkstraMultiroomDistanceMap', () => {
     const costMatrix = ephemeral(new ClockworkCostMatrix(1));
     const searchResult = dijkstraMultiroomDistanceMap([new RoomPosition(5, 5, 'W1N1')], {
       costMatrixCallback: roomName => (roomName === 'W1N1' || roomName === 'W1N2' ? costMatrix : undefined),
-      anyOfDestinations: [{ pos: new RoomPosition(45, 45, 'W1N2'), range: 50 }]
+      anyOfDestinations: [{ pos: new RoomPosition(45, 45, 'W1N2'), range: 5 }]
     });
     ephemeral(searchResult.distanceMap);
 
     expect(searchResult.foundTargets.every(pos => pos.roomName === 'W1N2')).toBe(true);
   });
+
+  it('should call a callback that is not memoized for every search', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    let calls = 0;
+    const options = {
+      costMatrixCallback: (room: string) => {
+        calls++;
+        return room === 'W1N1' ? costMatrix : undefined;
+      },
+      maxRooms: 1
+    };
+    const start = [new RoomPosition(25, 25, 'W1N1')];
+
+    ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    const firstCalls = calls;
+    ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(calls).toBe(firstCalls * 2);
+  });
+
+  it('should reuse cost matrices from a memoized callback within a tick', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    let calls = 0;
+    const options = {
+      costMatrixCallback: memoizeCostMatrixCallback((room: string) => {
+        calls++;
+        return room === 'W1N1' ? costMatrix : undefined;
+      }),
+      maxRooms: 1
+    };
+    const start = [new RoomPosition(25, 25, 'W1N1')];
+
+    ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    const firstCalls = calls;
+    ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(calls).toBe(firstCalls);
+
+    costMatrix.set(26, 25, 255);
+    const distanceMap = ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(calls).toBeGreaterThan(firstCalls);
+    expect(distanceMap.get(new RoomPosition(26, 25, 'W1N1'))).toBe(UNREACHABLE);
+
+    const cachedCalls = calls;
+    clearCostMatrixCache();
+    ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(calls).toBeGreaterThan(cachedCalls);
+  });
+
+  it('should reuse cost matrices that a memoized callback builds with set', () => {
+    let calls = 0;
+    const options = {
+      costMatrixCallback: memoizeCostMatrixCallback((room: string) => {
+        calls++;
+        if (room !== 'W1N1') return undefined;
+        const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+        costMatrix.set(26, 25, 255);
+        return costMatrix;
+      }),
+      maxRooms: 1
+    };
+    const start = [new RoomPosition(25, 25, 'W1N1')];
+
+    ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    const firstCalls = calls;
+    // Building a matrix for another callback doesn't invalidate this one's
+    ephemeral(new ClockworkCostMatrix(1)).set(0, 0, 255);
+    const distanceMap = ephemeral(dijkstraMultiroomDistanceMap(start, options).distanceMap);
+    expect(calls).toBe(firstCalls);
+    expect(distanceMap.get(new RoomPosition(26, 25, 'W1N1'))).toBe(UNREACHABLE);
+  });
+
+  it('should explain why destinations were not reached', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let x = 39; x <= 41; x++) {
+      for (let y = 39; y <= 41; y++) {
+        if (x !== 40 || y !== 40) {
+          costMatrix.set(x, y, 255);
+        }
+      }
+    }
+    const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+    const start = [new RoomPosition(2, 25, 'W1N1')];
+
+    const enclosed = dijkstraMultiroomDistanceMap(start, {
+      costMatrixCallback,
+      maxRooms: 2,
+      anyOfDestinations: [{ pos: new RoomPosition(40, 40, 'W1N1'), range: 0 }]
+    });
+    ephemeral(enclosed.distanceMap);
+    expect(enclosed.failure?.reason).toBe(FailureReason.Unreachable);
+
+    const outOfOps = dijkstraMultiroomDistanceMap(start, {
+      costMatrixCallback,
+      maxOps: 10,
+      anyOfDestinations: [{ pos: new RoomPosition(30, 25, 'W1N1'), range: 0 }]
+    });
+    ephemeral(outOfOps.distanceMap);
+    expect(outOfOps.failure?.reason).toBe(FailureReason.MaxOpsReached);
+    expect(outOfOps.failure!.frontier).toBeGreaterThan(0);
+
+    const blockedGoal = dijkstraMultiroomDistanceMap(start, {
+      costMatrixCallback,
+      maxRooms: 2,
+      anyOfDestinations: [{ pos: new RoomPosition(25, 25, 'W2N1'), range: 0 }]
+    });
+    ephemeral(blockedGoal.distanceMap);
+    expect(blockedGoal.failure?.reason).toBe(FailureReason.GoalRoomsBlocked);
+    expect(blockedGoal.failure?.blockedRooms.includes('W2N1')).toBe(true);
+    // The goal room is checked before searching, so nothing is expanded
+    expect(blockedGoal.ops).toBe(0);
+
+    const found = dijkstraMultiroomDistanceMap(start, {
+      costMatrixCallback,
+      maxRooms: 1,
+      anyOfDestinations: [{ pos: new RoomPosition(30, 25, 'W1N1'), range: 0 }]
+    });
+    ephemeral(found.distanceMap);
+    expect(found.failure).toBeUndefined();
+  });
+
+  it('should not count goal rooms checked before the search against maxRooms', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = () => costMatrix;
+    const start = [new RoomPosition(25, 25, 'W1N1')];
+
+    const confined = dijkstraMultiroomDistanceMap(start, {
+      costMatrixCallback,
+      maxRooms: 1,
+      anyOfDestinations: [{ pos: new RoomPosition(25, 25, 'W2N1'), range: 0 }]
+    });
+    ephemeral(confined.distanceMap);
+    expect(confined.distanceMap.getRooms().join()).toBe('W1N1');
+
+    const reached = dijkstraMultiroomDistanceMap(start, {
+      costMatrixCallback,
+      maxRooms: 2,
+      anyOfDestinations: [{ pos: new RoomPosition(25, 25, 'W2N1'), range: 0 }]
+    });
+    ephemeral(reached.distanceMap);
+    expect(reached.failure).toBeUndefined();
+  });
+
+  it('should record the expanded positions when asked', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const recorded = dijkstraMultiroomDistanceMap([start], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 50,
+      recordExpansions: true
+    });
+    ephemeral(recorded.distanceMap);
+    expect(recorded.expanded.length).toBeGreaterThan(0);
+    expect(recorded.expanded.length).toBeLessThanOrEqual(recorded.ops);
+    expect(recorded.expanded[0].isEqualTo(start)).toBeTruthy();
+
+    const unrecorded = dijkstraMultiroomDistanceMap([start], {
+      costMatrixCallback: () => costMatrix,
+      maxRooms: 1,
+      maxOps: 50
+    });
+    ephemeral(unrecorded.distanceMap);
+    expect(unrecorded.expanded.length).toBe(0);
+  });
 });
@@ -0,0 +1,28 @@
+import { disperse } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('disperse', () => {
+  it('should spread creeps at least the minimum spacing apart', () => {
+    const creeps = Array.from({ length: 4 }, () => new RoomPosition(25, 25, 'W1N1'));
+    const area: RoomPosition[] = [];
+    for (let x = 20; x <= 30; x++) {
+      area.push(new RoomPosition(x, 25, 'W1N1'));
+    }
+
+    const targets = disperse(creeps, 3, area);
+    expect(targets.map(pos => pos.x)).toEqual([25, 22, 28, 25]);
+    for (let i = 0; i < 3; i++) {
+      for (let j = i + 1; j < 3; j++) {
+        expect(targets[i].getRangeTo(targets[j])).toBeGreaterThanOrEqual(3);
+      }
+    }
+  });
+
+  it('should return nothing for no creeps', () => {
+    expect(disperse([], 3, [new RoomPosition(25, 25, 'W1N1')])).toEqual([]);
+  });
+
+  it('should reject positions in different rooms', () => {
+    expect(() => disperse([new RoomPosition(25, 25, 'W1N1')], 3, [new RoomPosition(25, 25, 'W2N1')])).toThrow();
+  });
+});
@@ -0,0 +1,137 @@
+import {
+  ClockworkCostMatrix,
+  ephemeral,
+  findSafePositions,
+  fleeToSafety,
+  fleeWithHysteresis,
+  forgetFleeState,
+  GoalKind
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('findSafePositions', () => {
+  it('should prefer safe tiles closest to the anchor', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const threat = new RoomPosition(10, 25, 'W1N1');
+    const anchor = new RoomPosition(40, 25, 'W1N1');
+    const safe = findSafePositions('W1N1', { threats: [threat], anchor, minRange: 20, count: 3, costMatrix });
+    expect(safe.length).toBe(3);
+    expect(safe[0].isEqualTo(anchor)).toBeTruthy();
+    for (const pos of safe) {
+      expect(pos.getRangeTo(threat)).toBeGreaterThanOrEqual(20);
+      expect(pos.getRangeTo(anchor)).toBeLessThanOrEqual(1);
+    }
+  });
+
+  it('should measure the threat range by walking distance', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 0; y < 48; y++) {
+      costMatrix.set(20, y, 255);
+    }
+    const threat = new RoomPosition(10, 25, 'W1N1');
+    const behindWall = new RoomPosition(22, 25, 'W1N1');
+    const safe = findSafePositions('W1N1', {
+      threats: [threat],
+      anchor: new RoomPosition(40, 25, 'W1N1'),
+      minRange: 20,
+      count: 2500,
+      costMatrix
+    });
+    // Only 12 tiles away in a straight line, but the wall makes it a long walk
+    expect(safe.some(pos => pos.isEqualTo(behindWall))).toBeTruthy();
+    expect(safe.some(pos => pos.getRangeTo(threat) < 20 && pos.x < 20)).toBeFalsy();
+  });
+});
+
+describe('fleeToSafety', () => {
+  it('should end in a safe area outside every threat range', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const path = fleeToSafety(new RoomPosition(25, 25, 'W1N1'), {
+      costMatrixCallback: () => costMatrix,
+      threats: [{ pos: new RoomPosition(20, 25, 'W1N1'), range: 3 }],
+      safeAreas: [
+        { pos: new RoomPosition(22, 25, 'W1N1'), range: 0 },
+        { pos: new RoomPosition(35, 25, 'W1N1'), range: 0 }
+      ]
+    });
+    const end = ephemeral(path!).get(path!.length - 1);
+    expect(end.isEqualTo(new RoomPosition(35, 25, 'W1N1'))).toBeTruthy();
+  });
+
+  it('should avoid crossing threatened tiles', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 0; y < 50; y++) {
+      if (y !== 25) {
+        costMatrix.set(28, y, 255);
+      }
+    }
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const safeAreas = [
+      { pos: new RoomPosition(31, 25, 'W1N1'), range: 0 },
+      { pos: new RoomPosition(25, 10, 'W1N1'), range: 0 }
+    ];
+    const fleeFrom = (threats: { pos: RoomPosition; range: number }[]) => {
+      const path = ephemeral(fleeToSafety(start, { costMatrixCallback: () => costMatrix, threats, safeAreas })!);
+      return path.get(path.length - 1);
+    };
+
+    expect(fleeFrom([]).x).toBe(31);
+    // The only way to the near safe area is past the threat, so the longer route wins
+    const end = fleeFrom([{ pos: new RoomPosition(28, 25, 'W1N1'), range: 1 }]);
+    expect(end.isEqualTo(new RoomPosition(25, 10, 'W1N1'))).toBeTruthy();
+  });
+
+  it('should flee to a safe room', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const path = fleeToSafety(new RoomPosition(1, 25, 'W1N1'), {
+      costMatrixCallback: () => costMatrix,
+      threats: [],
+      safeAreas: [{ pos: new RoomPosition(25, 25, 'W2N1'), range: 0, kind: GoalKind.Room }],
+      maxRooms: 2
+    });
+    expect(ephemeral(path!).get(path!.length - 1).roomName).toBe('W2N1');
+  });
+
+  it('should give up when every safe area is threatened', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const path = fleeToSafety(new RoomPosition(25, 25, 'W1N1'), {
+      costMatrixCallback: () => costMatrix,
+      threats: [{ pos: new RoomPosition(30, 25, 'W1N1'), range: 3 }],
+      safeAreas: [{ pos: new RoomPosition(31, 25, 'W1N1'), range: 1 }],
+      maxRooms: 1
+    });
+    expect(path).toBeUndefined();
+  });
+});
+
+describe('fleeWithHysteresis', () => {
+  it('should keep its escape route while threats jitter', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const start = new RoomPosition(25, 25, 'W1N1');
+    const safeAreas = [
+      { pos: new RoomPosition(15, 25, 'W1N1'), range: 0 },
+      { pos: new RoomPosition(35, 25, 'W1N1'), range: 0 }
+    ];
+    const flee = (key: string, threatX: number, hysteresis: number) => {
+      const path = fleeWithHysteresis(key, start, {
+        costMatrixCallback: () => costMatrix,
+        threats: [{ pos: new RoomPosition(threatX, 25, 'W1N1'), range: 3 }],
+        safeAreas,
+        hysteresis
+      });
+      const end = ephemeral(path!).get(path!.length - 1);
+      return end.x;
+    };
+
+    expect(flee('jitter', 26, 200)).toBe(15);
+    // The threat moves to the other side, but not by enough to switch
+    expect(flee('jitter', 24, 200)).toBe(15);
+    expect(flee('fresh', 24, 200)).toBe(35);
+    // A smaller hysteresis lets the creep switch
+    expect(flee('jitter', 24, 50)).toBe(35);
+
+    expect(forgetFleeState('jitter')).toBe(true);
+    expect(forgetFleeState('fresh')).toBe(true);
+    expect(forgetFleeState('jitter')).toBe(false);
+  });
+});
@@ -0,0 +1,75 @@
+import { ClockworkCostMatrix, ephemeral, fringeSearch } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('fringeSearch', () => {
+  it('should find a path around a wall', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 5; y < 45; y++) {
+      costMatrix.set(25, y, 255);
+    }
+    const path = fringeSearch(new RoomPosition(20, 25, 'W1N1'), {
+      costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+      destinations: [{ pos: new RoomPosition(30, 25, 'W1N1'), range: 0 }]
+    });
+
+    expect(path).toBeDefined();
+    const positions = ephemeral(path!).toArray();
+    expect(positions[0].isEqualTo(new RoomPosition(20, 25, 'W1N1'))).toBeTruthy();
+    expect(positions[positions.length - 1].isEqualTo(new RoomPosition(30, 25, 'W1N1'))).toBeTruthy();
+    expect(positions.every(pos => pos.x !== 25 || pos.y < 5 || pos.y >= 45)).toBeTruthy();
+  }, 50);
+  it('should trace a path back across a room edge', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const path = fringeSearch(new RoomPosition(3, 25, 'W1N1'), {
+      costMatrixCallback: room => (room === 'W1N1' || room === 'W2N1' ? costMatrix : undefined),
+      destinations: [{ pos: new RoomPosition(46, 25, 'W2N1'), range: 0 }]
+    });
+
+    expect(path).toBeDefined();
+    const positions = ephemeral(path!).toArray();
+    expect(positions[0].isEqualTo(new RoomPosition(3, 25, 'W1N1'))).toBeTruthy();
+    expect(positions[positions.length - 1].isEqualTo(new RoomPosition(46, 25, 'W2N1'))).toBeTruthy();
+    expect(positions.length).toBe(8);
+    expect(positions[3].isEqualTo(new RoomPosition(0, 25, 'W1N1'))).toBeTruthy();
+    expect(positions[4].isEqualTo(new RoomPosition(49, 25, 'W2N1'))).toBeTruthy();
+  }, 50);
+  it('should list both edge tiles of every room crossing', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const rooms = ['W1N1', 'W2N1', 'W3N1'];
+    const path = fringeSearch(new RoomPosition(25, 25, 'W1N1'), {
+      costMatrixCallback: room => (rooms.includes(room) ? costMatrix : undefined),
+      destinations: [{ pos: new RoomPosition(25, 25, 'W3N1'), range: 0 }]
+    });
+
+    expect(path).toBeDefined();
+    const positions = ephemeral(path!).toArray();
+    const crossings = positions
+      .map((pos, i) => [positions[i - 1], pos])
+      .filter(([previous, pos]) => previous && previous.roomName !== pos.roomName);
+    expect(crossings.length).toBe(2);
+    for (const [previous, pos] of crossings) {
+      expect(previous.x).toBe(0);
+      expect(pos.x).toBe(49);
+      expect(previous.y).toBe(pos.y);
+    }
+    expect(positions.length).toBe(101);
+  }, 50);
+  it('should follow the movement rules and cost overlay', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const blocked = new RoomPosition(22, 25, 'W1N1');
+    const path = fringeSearch(new RoomPosition(20, 25, 'W1N1'), {
+      costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+      destinations: [{ pos: new RoomPosition(25, 30, 'W1N1'), range: 0 }],
+      allowDiagonals: false,
+      blockedTiles: [blocked]
+    });
+
+    expect(path).toBeDefined();
+    const positions = ephemeral(path!).toArray();
+    expect(positions.length).toBe(11);
+    expect(positions.every(pos => !pos.isEqualTo(blocked))).toBeTruthy();
+    expect(positions.every((pos, i) => i === 0 || pos.x === positions[i - 1].x || pos.y === positions[i - 1].y)).toBe(
+      true
+    );
+  }, 50);
+});
@@ -0,0 +1,50 @@
+import { ClockworkCostMatrix, ephemeral, growRegion } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('growRegion', () => {
+  it('should split the region into interior and boundary tiles', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const { interior, boundary } = growRegion([new RoomPosition(25, 25, 'W1N1')], 2, costMatrix);
+
+    expect(interior.length).toBe(9);
+    expect(boundary.length).toBe(16);
+    expect(interior.every(pos => pos.getRangeTo(25, 25) <= 1)).toBeTruthy();
+    expect(boundary.every(pos => pos.getRangeTo(25, 25) === 2)).toBeTruthy();
+  });
+
+  it('should stop at the cost limit and skip walls', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 20; y <= 30; y++) {
+      costMatrix.set(24, y, 255);
+      costMatrix.set(26, y, 10);
+    }
+    const { interior, boundary } = growRegion([new RoomPosition(25, 25, 'W1N1')], 5, costMatrix);
+    const region = [...interior, ...boundary];
+
+    expect(region.some(pos => pos.x <= 24)).toBeFalsy();
+    expect(region.some(pos => pos.x >= 26)).toBeFalsy();
+    expect(region.length).toBe(11);
+  });
+
+  it('should have no boundary when walled in', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    for (let x = 23; x <= 27; x++) {
+      for (let y = 23; y <= 27; y++) {
+        if (x === 23 || x === 27 || y === 23 || y === 27) {
+          costMatrix.set(x, y, 255);
+        }
+      }
+    }
+    const { interior, boundary } = growRegion([new RoomPosition(25, 25, 'W1N1')], 10, costMatrix);
+
+    expect(interior.length).toBe(9);
+    expect(boundary.length).toBe(0);
+  });
+
+  it('should reject seeds in different rooms', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    expect(() =>
+      growRegion([new RoomPosition(25, 25, 'W1N1'), new RoomPosition(25, 25, 'W2N1')], 2, costMatrix)
+    ).toThrow();
+  });
+});
@@ -0,0 +1,39 @@
+import { HeuristicTuner } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('HeuristicTuner', () => {
+  const from = new RoomPosition(25, 25, 'W1N1');
+  const near = new RoomPosition(35, 25, 'W1N1');
+  const far = new RoomPosition(25, 25, 'W5N1');
+
+  it('should raise the weight for routes that take too many ops', () => {
+    const tuner = new HeuristicTuner({ targetOps: 1000, step: 0.5 });
+    expect(tuner.weightFor(from, far)).toBe(1);
+    tuner.record(from, far, { ops: 5000 });
+    tuner.record(from, far, { ops: 5000 });
+    expect(tuner.weightFor(from, far)).toBe(2);
+    // Other route lengths are tuned separately
+    expect(tuner.weightFor(from, near)).toBe(1);
+
+    tuner.record(from, far, { ops: 100, bound: 1 });
+    expect(tuner.weightFor(from, far)).toBe(1.5);
+    expect(tuner.stats(from, far).samples).toBe(3);
+  });
+
+  it('should stay within the weight limits', () => {
+    const tuner = new HeuristicTuner({ targetOps: 1000, maxWeight: 1.5, step: 1 });
+    tuner.record(from, far, { ops: 5000 });
+    tuner.record(from, far, { ops: 5000 });
+    expect(tuner.weightFor(from, far)).toBe(1.5);
+    tuner.record(from, far, { ops: 0 });
+    expect(tuner.weightFor(from, far)).toBe(1);
+  });
+
+  it('should restore its state from JSON', () => {
+    const tuner = new HeuristicTuner({ targetOps: 1000 });
+    tuner.record(from, far, { ops: 5000 });
+    const restored = HeuristicTuner.fromJSON(JSON.parse(JSON.stringify(tuner.toJSON())), { targetOps: 1000 });
+    expect(restored.weightFor(from, far)).toBe(tuner.weightFor(from, far));
+    expect(restored.stats(from, far).averageOps).toBe(5000);
+  });
+});
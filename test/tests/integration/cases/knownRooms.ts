@@ -0,0 +1,39 @@
+import {
+  ClockworkCostMatrix,
+  dijkstraMultiroomDistanceMap,
+  ephemeral,
+  forgetKnownRooms,
+  UnknownRoomPolicy
+} from '../../../../src/index';
+import { MAX_USIZE } from '../../../../src/utils/constants';
+import { describe, expect, it } from '../../helpers';
+
+const start = new RoomPosition(25, 25, 'W1N1');
+const unseen = new RoomPosition(25, 25, 'W2N1');
+
+describe('knownRooms', () => {
+  it('should block, use terrain for, or remember rooms without a cost matrix', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const search = (visible: string[], unknownRooms: UnknownRoomPolicy) =>
+      ephemeral(
+        dijkstraMultiroomDistanceMap([start], {
+          costMatrixCallback: room => (visible.includes(room) ? costMatrix : undefined),
+          maxRooms: 2,
+          anyOfDestinations: [{ pos: unseen, range: 0 }],
+          unknownRooms
+        }).distanceMap
+      );
+    forgetKnownRooms();
+
+    expect(search(['W1N1'], UnknownRoomPolicy.Blocked).get(unseen)).toBe(MAX_USIZE);
+    expect(search(['W1N1'], UnknownRoomPolicy.Terrain).roomStats('W2N1')!.count).toBeGreaterThan(0);
+
+    // Nothing has been seen yet, so the room is blocked
+    expect(search(['W1N1'], UnknownRoomPolicy.LastKnown).get(unseen)).toBe(MAX_USIZE);
+    expect(search(['W1N1', 'W2N1'], UnknownRoomPolicy.LastKnown).get(unseen)).toBe(50);
+    expect(search(['W1N1'], UnknownRoomPolicy.LastKnown).get(unseen)).toBe(50);
+
+    forgetKnownRooms();
+    expect(search(['W1N1'], UnknownRoomPolicy.LastKnown).get(unseen)).toBe(MAX_USIZE);
+  });
+});
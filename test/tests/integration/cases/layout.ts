@@ -0,0 +1,38 @@
+import { checkLayout, ClockworkCostMatrix, ephemeral, LayoutViolation } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('checkLayout', () => {
+  it('should report collisions and cut-off spawns and sources', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const ring = (x: number, y: number) => {
+      const tiles: RoomPosition[] = [];
+      for (let dx = -1; dx <= 1; dx++) {
+        for (let dy = -1; dy <= 1; dy++) {
+          if (dx !== 0 || dy !== 0) tiles.push(new RoomPosition(x + dx, y + dy, 'W1N1'));
+        }
+      }
+      return tiles;
+    };
+
+    const violations = checkLayout('W1N1', {
+      layout: {
+        [STRUCTURE_SPAWN]: [new RoomPosition(10, 10, 'W1N1'), new RoomPosition(20, 10, 'W1N1')],
+        [STRUCTURE_EXTENSION]: [...ring(10, 10), new RoomPosition(30, 30, 'W1N1')],
+        [STRUCTURE_TOWER]: [new RoomPosition(30, 30, 'W1N1')],
+        [STRUCTURE_STORAGE]: [new RoomPosition(25, 25, 'W1N1')],
+        [STRUCTURE_WALL]: ring(40, 40)
+      },
+      sources: [new RoomPosition(40, 40, 'W1N1'), new RoomPosition(25, 35, 'W1N1')],
+      costMatrix
+    }).filter(({ violation }) => violation !== LayoutViolation.OnWall);
+
+    const found = violations.map(({ violation, pos }) => `${violation}:${pos.x},${pos.y}`).sort();
+    expect(found).toEqual(
+      [
+        `${LayoutViolation.Collision}:30,30`,
+        `${LayoutViolation.SpawnBlocked}:10,10`,
+        `${LayoutViolation.SourceCutOff}:40,40`
+      ].sort()
+    );
+  });
+});
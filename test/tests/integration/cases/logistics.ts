@@ -0,0 +1,52 @@
+import {
+  addLogisticsAnchor,
+  ClockworkCostMatrix,
+  ephemeral,
+  logisticsDistance,
+  markLogisticsRoomDirty,
+  pendingLogisticsUpdates,
+  removeLogisticsAnchor,
+  updateLogistics
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('logistics', () => {
+  it('should repair an anchor after a room is marked dirty', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+    const anchor = addLogisticsAnchor([new RoomPosition(20, 25, 'W1N1')], { maxRooms: 1 });
+    try {
+      expect(updateLogistics(costMatrixCallback)).toBe(1);
+      expect(logisticsDistance(anchor, new RoomPosition(22, 25, 'W1N1'))).toBe(2);
+
+      costMatrix.set(21, 24, 255);
+      costMatrix.set(21, 25, 255);
+      costMatrix.set(21, 26, 255);
+      markLogisticsRoomDirty('W1N1');
+      expect(pendingLogisticsUpdates()).toBe(1);
+      expect(updateLogistics(costMatrixCallback)).toBe(1);
+      expect(pendingLogisticsUpdates()).toBe(0);
+      expect(logisticsDistance(anchor, new RoomPosition(22, 25, 'W1N1'))).toBe(4);
+    } finally {
+      removeLogisticsAnchor(anchor);
+    }
+  });
+
+  it('should let the cost matrix callback mark rooms dirty', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const anchor = addLogisticsAnchor([new RoomPosition(20, 25, 'W1N1')], { maxRooms: 1 });
+    try {
+      updateLogistics(room => (room === 'W1N1' ? costMatrix : undefined));
+      markLogisticsRoomDirty('W1N1');
+      const updated = updateLogistics(room => {
+        markLogisticsRoomDirty(room);
+        return room === 'W1N1' ? costMatrix : undefined;
+      });
+
+      expect(updated).toBe(1);
+      expect(pendingLogisticsUpdates()).toBe(1);
+    } finally {
+      removeLogisticsAnchor(anchor);
+    }
+  });
+});
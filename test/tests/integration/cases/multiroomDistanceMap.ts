@@ -0,0 +1,126 @@
+import {
+  bfsMultiroomDistanceMap,
+  ClockworkCostMatrix,
+  dijkstraMultiroomDistanceMap,
+  ephemeral,
+  MaskComparison
+} from '../../../../src/index';
+import { MAX_USIZE } from '../../../../src/utils/constants';
+import { describe, expect, it } from '../../helpers';
+
+describe('multiroomDistanceMap', () => {
+  it('should combine distance maps element-wise', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const options = {
+      costMatrixCallback: (room: string) => (room === 'W1N1' ? costMatrix : undefined),
+      maxRooms: 1
+    };
+    const a = ephemeral(bfsMultiroomDistanceMap([new RoomPosition(10, 25, 'W1N1')], options).distanceMap);
+    const b = ephemeral(bfsMultiroomDistanceMap([new RoomPosition(40, 25, 'W1N1')], options).distanceMap);
+    const midpoint = new RoomPosition(25, 25, 'W1N1');
+
+    a.minWith(b);
+    expect(a.get(new RoomPosition(40, 25, 'W1N1'))).toBe(0);
+    expect(a.get(midpoint)).toBe(15);
+
+    a.addScaled(b, 2);
+    expect(a.get(midpoint)).toBe(45);
+
+    a.maskWhere(b, MaskComparison.LessThan, 10);
+    expect(a.get(new RoomPosition(35, 25, 'W1N1'))).toBe(MAX_USIZE);
+    expect(a.get(midpoint)).toBe(45);
+  }, 15);
+
+  it('should find the lowest and highest values', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+        maxRooms: 1
+      }).distanceMap
+    );
+
+    expect(distanceMap.argmin()?.isEqualTo(new RoomPosition(25, 25, 'W1N1'))).toBeTruthy();
+    expect(distanceMap.get(distanceMap.argmax()!)).toBe(25);
+    expect(distanceMap.topK(3).length).toBe(3);
+    expect(distanceMap.positionsBelow(2).length).toBe(9);
+    expect(distanceMap.argmin(['W2N2'])).toBeUndefined();
+  }, 15);
+
+  it('should read values in a rectangle across rooms', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(48, 25, 'W1N1')], {
+        costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+        maxRooms: 1
+      }).distanceMap
+    );
+
+    const { width, height, values } = distanceMap.valuesInRect(
+      new RoomPosition(1, 26, 'W0N1'),
+      new RoomPosition(46, 25, 'W1N1')
+    );
+    expect(width).toBe(6);
+    expect(height).toBe(2);
+    expect(values[1]).toBe(1);
+    expect(values[2]).toBe(0);
+    expect(values[width + 2]).toBe(1);
+    expect(values[5]).toBe(MAX_USIZE);
+  }, 15);
+
+  it('should refuse rectangles larger than a block of rooms', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+        maxRooms: 1
+      }).distanceMap
+    );
+
+    const { values } = distanceMap.valuesInRect(new RoomPosition(0, 0, 'W2N2'), new RoomPosition(49, 49, 'W0N0'));
+    expect(values.length).toBe(22500);
+    expect(() => distanceMap.valuesInRect(new RoomPosition(0, 0, 'W2N2'), new RoomPosition(0, 0, 'E0S0'))).toThrow();
+    expect(() =>
+      distanceMap.valuesInRect(new RoomPosition(0, 0, 'W60N60'), new RoomPosition(0, 0, 'E60S60'))
+    ).toThrow();
+  });
+
+  it('should repair distances around changed tiles', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const options = {
+      costMatrixCallback: (room: string) => (room === 'W1N1' ? costMatrix : undefined),
+      maxRooms: 1
+    };
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(10, 25, 'W1N1')], options).distanceMap
+    );
+    expect(distanceMap.get(new RoomPosition(15, 25, 'W1N1'))).toBe(5);
+
+    costMatrix.set(15, 25, 255);
+    costMatrix.set(16, 25, 10);
+    const changed = distanceMap.repair([new RoomPosition(15, 25, 'W1N1'), new RoomPosition(16, 25, 'W1N1')], options);
+
+    expect(changed.length).toBe(2);
+    expect(distanceMap.get(new RoomPosition(15, 25, 'W1N1'))).toBe(MAX_USIZE);
+    expect(distanceMap.get(new RoomPosition(16, 25, 'W1N1'))).toBe(15);
+    expect(distanceMap.get(new RoomPosition(17, 25, 'W1N1'))).toBe(7);
+  }, 15);
+
+  it('should summarize and bucket the reachable values in a room', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: (room: string) => (room === 'W1N1' ? costMatrix : undefined),
+        maxPathCost: 2
+      }).distanceMap
+    );
+
+    expect(distanceMap.roomStats('W1N1', 2)).toEqual({ min: 0, max: 2, mean: 1.6, count: 25, countBelow: 9 });
+    expect(distanceMap.summary().count).toBe(25);
+    expect(distanceMap.summary().countBelow).toBe(25);
+    expect(distanceMap.histogram('W1N1')).toEqual([1, 8, 16]);
+    expect(distanceMap.histogram('W1N1', 2)).toEqual([9, 16]);
+    expect(distanceMap.roomStats('W2N1')).toBeUndefined();
+    expect(distanceMap.histogram('W2N1')).toBeUndefined();
+  });
+});
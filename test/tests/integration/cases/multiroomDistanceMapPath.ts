@@ -1,4 +1,11 @@
-import { bfsMultiroomDistanceMap, ClockworkCostMatrix, ephemeral } from '../../../../src/index';
+import {
+  bfsMultiroomDistanceMap,
+  ClockworkCostMatrix,
+  ephemeral,
+  pathContains,
+  PathSegmentCache,
+  stitchPaths
+} from '../../../../src/index';
 import { describe, expect, it } from '../../helpers';
 
 describe('multiroomDistanceMapPath', () => {
@@ -27,4 +34,132 @@ describe('multiroomDistanceMapPath', () => {
     expect(path[path.length - 1].isEqualTo(new RoomPosition(25, 25, 'W1N2'))).toBeTruthy();
     expect(path.length).toBe(51);
   }, 15);
+
+  it('should skip room border crossings in path directions', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback(room) {
+          if (['W1N1', 'W1N2'].includes(room)) {
+            return costMatrix;
+          }
+          return undefined;
+        },
+        maxRooms: 2
+      }).distanceMap
+    );
+    const clockworkPath = ephemeral(distanceMap.pathToOrigin(new RoomPosition(25, 25, 'W1N2')));
+    const directions = clockworkPath.directions();
+
+    expect(directions.length).toBe(49);
+    expect(directions.every(direction => direction === TOP)).toBeTruthy();
+    expect(clockworkPath.directionAt(25)).toBeUndefined();
+  }, 15);
+
+  it('should stitch paths across a room border', () => {
+    const first = [new RoomPosition(25, 2, 'W1N1'), new RoomPosition(25, 1, 'W1N1'), new RoomPosition(25, 0, 'W1N1')];
+    const second = [new RoomPosition(25, 48, 'W1N2'), new RoomPosition(25, 47, 'W1N2')];
+    const path = ephemeral(stitchPaths([first, second]));
+
+    expect(path.length).toBe(6);
+    expect(path.get(3).isEqualTo(new RoomPosition(25, 49, 'W1N2'))).toBeTruthy();
+    expect(() => stitchPaths([first, [new RoomPosition(10, 10, 'W1N2')]])).toThrow();
+  }, 15);
+
+  it('should find positions on or near a path', () => {
+    const positions = [
+      new RoomPosition(10, 10, 'W1N1'),
+      new RoomPosition(11, 10, 'W1N1'),
+      new RoomPosition(12, 10, 'W1N1')
+    ];
+    const path = ephemeral(stitchPaths([positions]));
+
+    expect(path.findIndexNear(new RoomPosition(11, 10, 'W1N1'))).toBe(1);
+    expect(path.findIndexNear(new RoomPosition(13, 12, 'W1N1'))).toBeUndefined();
+    expect(path.findIndexNear(new RoomPosition(13, 12, 'W1N1'), 2)).toBe(1);
+    expect(pathContains(positions, new RoomPosition(13, 11, 'W1N1'), 1)).toBe(2);
+    expect(pathContains(positions, new RoomPosition(20, 20, 'W1N1'), 3)).toBeUndefined();
+  });
+
+  it('should fold back-and-forth steps out of a path', () => {
+    const positions = [10, 11, 12, 11, 12, 13].map(x => new RoomPosition(x, 10, 'W1N1'));
+    const path = ephemeral(ephemeral(stitchPaths([positions])).normalize());
+
+    expect(path.toArray().map(pos => pos.x)).toEqual([10, 11, 12, 13]);
+    expect(path.firstGap()).toBeUndefined();
+  });
+
+  it('should follow a stored path by handle', () => {
+    const positions = [10, 11, 12, 13].map(x => new RoomPosition(x, 10, 'W1N1'));
+    const handle = ephemeral(stitchPaths([positions])).store();
+
+    expect(handle.next(new RoomPosition(10, 10, 'W1N1'))).toBe(RIGHT);
+    expect(handle.next(new RoomPosition(12, 11, 'W1N1'))).toBe(TOP_RIGHT);
+    expect(handle.next(new RoomPosition(13, 10, 'W1N1'))).toBeUndefined();
+    expect(handle.next(new RoomPosition(20, 20, 'W1N1'))).toBeUndefined();
+    expect(handle.length).toBe(4);
+    expect(handle.chunk(1, 2).map(pos => pos.x)).toEqual([11, 12]);
+    expect(handle.chunk(3, 5).map(pos => pos.x)).toEqual([13]);
+    expect(handle.chunk(10, 5)).toEqual([]);
+    expect(handle.drop()).toBe(true);
+    expect(handle.drop()).toBe(false);
+    expect(() => handle.next(new RoomPosition(10, 10, 'W1N1'))).toThrow();
+  });
+
+  it('should split paths by room and stitch cached segments', () => {
+    const first = [new RoomPosition(25, 2, 'W1N1'), new RoomPosition(25, 1, 'W1N1'), new RoomPosition(25, 0, 'W1N1')];
+    const second = [new RoomPosition(25, 49, 'W1N2'), new RoomPosition(25, 48, 'W1N2')];
+    const path = ephemeral(stitchPaths([first, second]));
+    const segments = path.splitByRoom();
+
+    expect(segments.length).toBe(2);
+    expect(segments[0].roomName).toBe('W1N1');
+    expect(segments[0].exit.isEqualTo(first[2])).toBeTruthy();
+    expect(segments[1].entry.isEqualTo(second[0])).toBeTruthy();
+    expect(segments[1].positions.length).toBe(2);
+
+    const cache = new PathSegmentCache();
+    cache.addPath(path);
+    const restitched = ephemeral(
+      cache.stitch([
+        [first[0], first[2]],
+        [second[0], second[1]]
+      ])!
+    );
+    expect(restitched.length).toBe(5);
+    expect(cache.stitch([[first[0], first[1]]])).toBeUndefined();
+  });
+  it('should slice, truncate, concat, and search paths', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: room => (['W1N1', 'W1N2'].includes(room) ? costMatrix : undefined),
+        maxRooms: 2
+      }).distanceMap
+    );
+    const path = ephemeral(distanceMap.pathToOrigin(new RoomPosition(25, 25, 'W1N2')));
+    const positions = path.toArray();
+
+    const head = ephemeral(path.slice(0, 5));
+    expect(head.length).toBe(5);
+    expect(head.get(0).isEqualTo(positions[0])).toBeTruthy();
+    expect(ephemeral(path.slice(49, 10)).length).toBe(2);
+    expect(ephemeral(path.slice(100, 3)).length).toBe(0);
+
+    const inFirstRoom = ephemeral(path.truncateAtRoom('W1N2'));
+    expect(inFirstRoom.length).toBe(26);
+    expect(inFirstRoom.toArray().every(pos => pos.roomName === 'W1N1')).toBeTruthy();
+    expect(ephemeral(path.truncateAtRoom('W1N1')).length).toBe(0);
+    expect(ephemeral(path.truncateAtRoom('W5N5')).length).toBe(51);
+
+    expect(path.findIndexOf(positions[10])).toBe(10);
+    expect(path.findIndexOf(new RoomPosition(0, 0, 'W3N3'))).toBeUndefined();
+
+    // Joins across the room border, and drops a repeated joining position
+    const rejoined = ephemeral(inFirstRoom.concat(ephemeral(path.slice(26, 25))));
+    expect(rejoined.length).toBe(51);
+    expect(rejoined.get(50).isEqualTo(positions[50])).toBeTruthy();
+    expect(ephemeral(ephemeral(path.slice(0, 10)).concat(ephemeral(path.slice(9, 5)))).length).toBe(14);
+    expect(() => ephemeral(path.slice(0, 5)).concat(ephemeral(path.slice(10, 5)))).toThrow();
+  });
 });
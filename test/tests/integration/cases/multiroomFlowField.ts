@@ -20,4 +20,25 @@ describe('bfsMultiroomFlowField', () => {
     expect(flowField.getDirections(new RoomPosition(26, 25, 'W1N1'))).toEqual([LEFT]);
     expect(flowField.getDirections(new RoomPosition(0, 0, 'W1N1'))).toEqual([]);
   });
+
+  it('should round-trip room data', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix());
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: () => costMatrix,
+        maxRooms: 1
+      }).distanceMap
+    );
+    const flowField = ephemeral(distanceMap.toFlowField());
+    const data = flowField.getRoomData('W1N1');
+    expect(data?.length).toBe(2500);
+    expect(flowField.getRoomData('W2N1')).toBe(null);
+
+    // Forbid moving left from one tile, then restore the data elsewhere
+    data![25 * 50 + 26] = 0;
+    flowField.setRoomData('W2N1', data!);
+    expect(flowField.getDirections(new RoomPosition(26, 25, 'W2N1'))).toEqual([]);
+    expect(flowField.getDirections(new RoomPosition(24, 25, 'W2N1'))).toEqual([RIGHT]);
+    expect(flowField.getDirections(new RoomPosition(26, 25, 'W1N1'))).toEqual([LEFT]);
+  });
 });
@@ -22,5 +22,11 @@ describe('multiroomMonoFlowFieldPath', () => {
     expect(path[0].isEqualTo(new RoomPosition(25, 25, 'W1N1'))).toBeTruthy();
     expect(path[path.length - 1].isEqualTo(new RoomPosition(25, 25, 'W2N2'))).toBeTruthy();
     expect(path.length).toBe(53);
+
+    const traced = ephemeral(flowField.tracePath(new RoomPosition(25, 25, 'W2N2'))).toArray();
+    expect(traced[0].isEqualTo(new RoomPosition(25, 25, 'W2N2'))).toBeTruthy();
+    expect(traced[traced.length - 1].isEqualTo(new RoomPosition(25, 25, 'W1N1'))).toBeTruthy();
+    expect(traced.length).toBe(53);
+    expect(ephemeral(flowField.tracePath(new RoomPosition(25, 25, 'W2N2'), 10)).length).toBe(10);
   }, 15);
 });
@@ -0,0 +1,31 @@
+import {
+  astarMultiroomDistanceMap,
+  ClockworkMaps,
+  ClockworkPathfinder,
+  clockwork_fringe_search,
+  clockwork_get_range,
+  clockwork_version,
+  getTerrainCostMatrix,
+  get_range,
+  js_fringe_search,
+  version
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('namespaces', () => {
+  it('should group the wrappers without changing them', () => {
+    expect(ClockworkPathfinder.astarMultiroomDistanceMap === astarMultiroomDistanceMap).toBe(true);
+    expect(ClockworkMaps.getTerrainCostMatrix === getTerrainCostMatrix).toBe(true);
+  });
+
+  it('should keep the old binding names as aliases', () => {
+    expect(get_range === clockwork_get_range).toBe(true);
+    expect(js_fringe_search === clockwork_fringe_search).toBe(true);
+    expect(version()).toBe(clockwork_version());
+    const range = clockwork_get_range(
+      new RoomPosition(10, 10, 'W1N1').__packedPos,
+      new RoomPosition(10, 15, 'W2N1').__packedPos
+    );
+    expect(range).toBe(50);
+  });
+});
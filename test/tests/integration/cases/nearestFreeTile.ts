@@ -0,0 +1,50 @@
+import { ClockworkCostMatrix, ephemeral, nearestFreeTile } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('nearestFreeTile', () => {
+  it('should return the origin if it is free', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const origin = new RoomPosition(25, 25, 'W1N1');
+    expect(nearestFreeTile(origin, costMatrix, 10)!.isEqualTo(origin)).toBeTruthy();
+  });
+
+  it('should step off a road to the nearest free tile', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    // A road running north-south, three tiles wide
+    for (let y = 0; y < 50; y++) {
+      for (let x = 24; x <= 26; x++) {
+        costMatrix.set(x, y, 20);
+      }
+    }
+
+    const free = nearestFreeTile(new RoomPosition(25, 25, 'W1N1'), costMatrix, 10)!;
+    expect(free.getRangeTo(25, 25)).toBe(2);
+    expect(costMatrix.get(free.x, free.y)).toBeLessThan(10);
+  });
+
+  it('should walk around walls rather than through them', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(20));
+    for (let x = 20; x <= 30; x++) {
+      costMatrix.set(x, 24, 255);
+    }
+    costMatrix.set(25, 23, 1);
+    costMatrix.set(25, 35, 1);
+
+    // The tile just past the wall is closer in range, but further to walk
+    const free = nearestFreeTile(new RoomPosition(25, 25, 'W1N1'), costMatrix, 10)!;
+    expect(free.isEqualTo(new RoomPosition(25, 35, 'W1N1'))).toBeTruthy();
+  });
+
+  it('should return undefined when no free tile can be reached', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(20));
+    for (let x = 24; x <= 26; x++) {
+      for (let y = 24; y <= 26; y++) {
+        if (x !== 25 || y !== 25) {
+          costMatrix.set(x, y, 255);
+        }
+      }
+    }
+    costMatrix.set(10, 10, 1);
+    expect(nearestFreeTile(new RoomPosition(25, 25, 'W1N1'), costMatrix, 10)).toBeUndefined();
+  });
+});
@@ -0,0 +1,36 @@
+import { ephemeral, generateScenario, noiseCostMatrix, ScenarioKind } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('noise', () => {
+  it('should stay within the amplitude and repeat for the same seed', () => {
+    const noise = ephemeral(noiseCostMatrix('W1N1', { seed: 3, amplitude: 10 }));
+    let max = 0;
+    for (let y = 0; y < 50; y++) {
+      for (let x = 0; x < 50; x++) {
+        max = Math.max(max, noise.get(x, y));
+      }
+    }
+
+    expect(max).toBeLessThanOrEqual(10);
+    expect(max).toBeGreaterThan(0);
+    expect(noise.equals(ephemeral(noiseCostMatrix('W1N1', { seed: 3, amplitude: 10 })))).toBeTruthy();
+    expect(noise.equals(ephemeral(noiseCostMatrix('W1N1', { seed: 4, amplitude: 10 })))).toBeFalsy();
+  });
+
+  it('should line up across room borders', () => {
+    // W2N1 is west of W1N1, so its east edge borders W1N1's west edge
+    const east = ephemeral(noiseCostMatrix('W1N1', { scale: 10, amplitude: 10 }));
+    const west = ephemeral(noiseCostMatrix('W2N1', { scale: 10, amplitude: 10 }));
+    for (let y = 0; y < 50; y++) {
+      expect(Math.abs(east.get(0, y) - west.get(49, y))).toBeLessThanOrEqual(2);
+    }
+  });
+
+  it('should generate rough scenarios reproducibly', () => {
+    const first = ephemeral(generateScenario(ScenarioKind.Rough, 'W2N2', 2, 2, 5));
+    const second = ephemeral(generateScenario(ScenarioKind.Rough, 'W2N2', 2, 2, 5));
+    for (const room of first.rooms()) {
+      expect(ephemeral(first.getCostMatrix(room)!).equals(ephemeral(second.getCostMatrix(room)!))).toBeTruthy();
+    }
+  });
+});
@@ -0,0 +1,28 @@
+import { nukeDamageMap, planNukeStrike } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('nukes', () => {
+  it('should sum the damage from overlapping nukes', () => {
+    const damage = nukeDamageMap([new RoomPosition(20, 20, 'W1N1'), new RoomPosition(22, 20, 'W1N1')]);
+
+    expect(damage.get(20, 20)).toBe(15_000_000);
+    expect(damage.get(21, 20)).toBe(10_000_000);
+    expect(damage.get(24, 22)).toBe(5_000_000);
+    expect(damage.get(25, 20)).toBe(0);
+  });
+
+  it('should aim nukes where they deal the most damage', () => {
+    const structures = [
+      { pos: new RoomPosition(10, 10, 'W1N1'), hits: 1_000 },
+      { pos: new RoomPosition(30, 30, 'W1N1'), hits: 20_000_000 },
+      { pos: new RoomPosition(31, 31, 'W1N1'), hits: 20_000_000 }
+    ];
+    const landingPoints = planNukeStrike(structures, 5);
+
+    // Three nukes to destroy the two big structures, one for the small one,
+    // and none left over once nothing more can be damaged
+    expect(landingPoints.length).toBe(4);
+    expect(landingPoints[0].getRangeTo(structures[1].pos)).toBeLessThan(2);
+    expect(landingPoints.filter(pos => pos.getRangeTo(structures[0].pos) <= 2).length).toBe(1);
+  });
+});
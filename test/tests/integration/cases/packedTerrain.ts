@@ -0,0 +1,29 @@
+import { cacheStats, getPackedTerrain, resetCacheStats } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('packedTerrain', () => {
+  it('should match the game terrain', () => {
+    const terrain = Game.map.getRoomTerrain('W1N1');
+    const packed = getPackedTerrain('W1N1');
+    let mismatches = 0;
+    for (let y = 0; y < 50; y++) {
+      for (let x = 0; x < 50; x++) {
+        if (packed.get(x, y) !== terrain.get(x, y)) {
+          mismatches++;
+        }
+      }
+    }
+
+    expect(mismatches).toBe(0);
+    expect(packed.toArray().length).toBe(625);
+  });
+
+  it('should serve repeated requests from the terrain cache', () => {
+    getPackedTerrain('W2N1');
+    resetCacheStats();
+    getPackedTerrain('W2N1');
+    getPackedTerrain('W2N1');
+
+    expect(cacheStats().terrain).toEqual({ hits: 2, misses: 0 });
+  });
+});
@@ -0,0 +1,50 @@
+import {
+  bfsMultiroomDistanceMap,
+  ClockworkCostMatrix,
+  dijkstraMultiroomDistanceMap,
+  ephemeral,
+  panicContext
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('panicContext', () => {
+  it('should be empty outside of a search', () => {
+    expect(panicContext()).toBeUndefined();
+  });
+
+  it('should name the running algorithms from inside a callback', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const contexts: (string | undefined)[] = [];
+    ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: () => {
+          contexts.push(panicContext());
+          return costMatrix;
+        },
+        maxRooms: 1
+      }).distanceMap
+    );
+
+    expect(contexts).toEqual(['dijkstra > astar']);
+    expect(panicContext()).toBeUndefined();
+  });
+
+  it('should include the position being expanded', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const contexts = new Map<string, string | undefined>();
+    ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(1, 25, 'W1N1')], {
+        costMatrixCallback: room => {
+          contexts.set(room, panicContext());
+          return costMatrix;
+        },
+        maxRooms: 2,
+        maxOps: 10
+      }).distanceMap
+    );
+
+    // W2N1 is only loaded once the search steps off W1N1's west edge
+    expect(contexts.get('W1N1')).toBe('bfs');
+    expect(contexts.get('W2N1')?.startsWith('bfs (room W1N1, position 0,')).toBeTruthy();
+  });
+});
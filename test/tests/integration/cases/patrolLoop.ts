@@ -0,0 +1,35 @@
+import { ClockworkCostMatrix, ephemeral, patrolLoop } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('patrolLoop', () => {
+  const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+  const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+  const points = [
+    new RoomPosition(10, 10, 'W1N1'),
+    new RoomPosition(40, 40, 'W1N1'),
+    new RoomPosition(12, 10, 'W1N1'),
+    new RoomPosition(25, 30, 'W1N1'),
+    new RoomPosition(38, 12, 'W1N1')
+  ];
+
+  it('should visit every point and return to the first', () => {
+    const loop = patrolLoop(points, { costMatrixCallback, improve: false })!;
+    ephemeral(loop.path);
+    expect(loop.order[0]).toBe(0);
+    expect(loop.order[1]).toBe(2);
+    expect([...loop.order].sort().join()).toBe('0,1,2,3,4');
+    expect(loop.path.get(0).isEqualTo(points[0])).toBeTruthy();
+    expect(loop.path.get(loop.path.length - 1).isEqualTo(points[0])).toBeTruthy();
+    for (const point of points) {
+      expect(loop.path.toArray().some(pos => pos.isEqualTo(point))).toBeTruthy();
+    }
+  });
+
+  it('should not make the loop longer when improving it', () => {
+    const greedy = patrolLoop(points, { costMatrixCallback, improve: false })!;
+    const improved = patrolLoop(points, { costMatrixCallback })!;
+    ephemeral(greedy.path);
+    ephemeral(improved.path);
+    expect(improved.path.length).toBeLessThanOrEqual(greedy.path.length);
+  });
+});
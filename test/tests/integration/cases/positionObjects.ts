@@ -0,0 +1,119 @@
+import { ClockworkCostMatrix, ephemeral } from '../../../../src/index';
+import {
+  clockwork_astar_multiroom_distance_map_positions,
+  clockwork_bfs_multiroom_distance_map_positions,
+  clockwork_dijkstra_multiroom_distance_map_positions
+} from '../../../../src/wasm/screeps_clockwork';
+import { fromPackedSearchResult } from '../../../../src/wrappers/searchResult';
+import { describe, expect, it } from '../../helpers';
+
+const start = [{ x: 25, y: 25, roomName: 'W1N1' }];
+const destinations = [{ pos: { x: 30, y: 25, roomName: 'W1N1' }, range: 0 }];
+
+describe('positionObjects', () => {
+  it('should run a dijkstra distance map from position objects', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const rooms: string[] = [];
+    const result = fromPackedSearchResult(
+      clockwork_dijkstra_multiroom_distance_map_positions(
+        start,
+        (roomName: string) => {
+          rooms.push(roomName);
+          return costMatrix;
+        },
+        2500,
+        1,
+        100,
+        destinations,
+        undefined
+      )
+    );
+    const distanceMap = ephemeral(result.distanceMap);
+
+    expect(rooms).toEqual(['W1N1']);
+    expect(distanceMap.get(new RoomPosition(30, 25, 'W1N1'))).toBe(5);
+    expect(result.foundTargets.length).toBe(1);
+  });
+
+  it('should run an astar distance map from position objects', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const result = fromPackedSearchResult(
+      clockwork_astar_multiroom_distance_map_positions(start, () => costMatrix, 1, 2500, 100, destinations, undefined)
+    );
+    const distanceMap = ephemeral(result.distanceMap);
+
+    expect(distanceMap.get(new RoomPosition(30, 25, 'W1N1'))).toBe(5);
+    expect(result.foundTargets.length).toBe(1);
+  });
+
+  it('should run a bfs distance map from position objects', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const result = fromPackedSearchResult(
+      clockwork_bfs_multiroom_distance_map_positions(start, () => costMatrix, 2500, 1, 100, destinations, undefined)
+    );
+    const distanceMap = ephemeral(result.distanceMap);
+
+    expect(distanceMap.get(new RoomPosition(30, 25, 'W1N1'))).toBe(5);
+    expect(result.foundTargets.length).toBe(1);
+  });
+
+  it('should reject malformed positions', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    expect(() =>
+      clockwork_dijkstra_multiroom_distance_map_positions(
+        [{ x: 25, roomName: 'W1N1' }],
+        () => costMatrix,
+        2500,
+        1,
+        100,
+        undefined,
+        undefined
+      )
+    ).toThrow();
+    expect(() =>
+      clockwork_astar_multiroom_distance_map_positions(start, () => costMatrix, 1, 2500, 100, [{ range: 1 }], undefined)
+    ).toThrow();
+    expect(() =>
+      clockwork_bfs_multiroom_distance_map_positions(
+        { x: 25, y: 25, roomName: 'W1N1' },
+        () => costMatrix,
+        2500,
+        1,
+        100,
+        undefined,
+        undefined
+      )
+    ).toThrow();
+  });
+
+  it('should reject invalid room names', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const badStart = [{ x: 25, y: 25, roomName: 'X9Y9' }];
+    const badDestinations = [{ pos: { x: 25, y: 25, roomName: 'not a room' }, range: 0 }];
+    expect(() =>
+      clockwork_dijkstra_multiroom_distance_map_positions(
+        badStart,
+        () => costMatrix,
+        2500,
+        1,
+        100,
+        undefined,
+        undefined
+      )
+    ).toThrow();
+    expect(() =>
+      clockwork_astar_multiroom_distance_map_positions(
+        start,
+        () => costMatrix,
+        1,
+        2500,
+        100,
+        badDestinations,
+        undefined
+      )
+    ).toThrow();
+    expect(() =>
+      clockwork_bfs_multiroom_distance_map_positions(badStart, () => costMatrix, 2500, 1, 100, undefined, undefined)
+    ).toThrow();
+  });
+});
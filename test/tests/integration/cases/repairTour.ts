@@ -0,0 +1,33 @@
+import { ClockworkCostMatrix, ephemeral, planRepairTour } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('planRepairTour', () => {
+  const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+  const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+  const start = new RoomPosition(10, 25, 'W1N1');
+  const roads = [
+    { pos: new RoomPosition(15, 25, 'W1N1'), hits: 100, hitsMax: 5000 },
+    { pos: new RoomPosition(11, 30, 'W1N1'), hits: 1000, hitsMax: 5000 },
+    { pos: new RoomPosition(20, 25, 'W1N1'), hits: 4000, hitsMax: 5000 },
+    { pos: new RoomPosition(12, 25, 'W1N1'), hits: 2000, hitsMax: 5000 }
+  ];
+
+  it('should visit the nearest damaged road each time', () => {
+    const tour = planRepairTour(start, roads, { costMatrixCallback });
+    const positions = [...Array(tour.length).keys()].map(i => tour.get(i));
+
+    expect(tour.length).toBe(11);
+    expect(positions[0].isEqualTo(start)).toBeTruthy();
+    expect(positions[2].isEqualTo(roads[3].pos)).toBeTruthy();
+    expect(positions[5].isEqualTo(roads[0].pos)).toBeTruthy();
+    expect(positions[10].isEqualTo(roads[1].pos)).toBeTruthy();
+    expect(positions.some(pos => pos.isEqualTo(roads[2].pos))).toBeFalsy();
+  });
+
+  it('should stop when the next leg would exceed the budget', () => {
+    const tour = planRepairTour(start, roads, { costMatrixCallback, budget: 7 });
+
+    expect(tour.length).toBe(6);
+    expect(tour.get(5).isEqualTo(roads[0].pos)).toBeTruthy();
+  });
+});
@@ -0,0 +1,69 @@
+import { astarMultiroomDistanceMap, ClockworkCostMatrix, ephemeral, roomAstarPath } from '../../../../src/index';
+import { cpuTime } from '../../../utils/cpuTime';
+import { describe, expect, it } from '../../helpers';
+
+describe('roomAstarPath', () => {
+  const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+  for (let y = 5; y < 45; y++) {
+    costMatrix.set(25, y, 255);
+  }
+  const start = new RoomPosition(20, 25, 'W1N1');
+  const goal = new RoomPosition(30, 25, 'W1N1');
+
+  it('should find a path around a wall', () => {
+    const path = ephemeral(roomAstarPath(start, { costMatrix, destinations: [{ pos: goal, range: 0 }] })!);
+    const positions = path.toArray();
+
+    expect(positions[0].isEqualTo(start)).toBeTruthy();
+    expect(positions[positions.length - 1].isEqualTo(goal)).toBeTruthy();
+    expect(positions.every(pos => pos.x !== 25 || pos.y < 5 || pos.y >= 45)).toBeTruthy();
+    expect(positions.length).toBe(41);
+  });
+
+  it('should not walk through room edges', () => {
+    const edge = ephemeral(new ClockworkCostMatrix(1));
+    for (let y = 1; y < 49; y++) {
+      edge.set(25, y, 255);
+    }
+    const path = roomAstarPath(start, { costMatrix: edge, destinations: [{ pos: goal, range: 0 }] });
+
+    expect(path).toBeUndefined();
+  });
+
+  it('should follow the movement rules and cost overlay', () => {
+    const open = ephemeral(new ClockworkCostMatrix(1));
+    const blocked = new RoomPosition(22, 25, 'W1N1');
+    const path = ephemeral(
+      roomAstarPath(start, {
+        costMatrix: open,
+        destinations: [{ pos: new RoomPosition(25, 30, 'W1N1'), range: 0 }],
+        allowDiagonals: false,
+        blockedTiles: [blocked]
+      })!
+    );
+    const positions = path.toArray();
+
+    expect(positions.length).toBe(11);
+    expect(positions.every(pos => !pos.isEqualTo(blocked))).toBeTruthy();
+    expect(positions.every((pos, i) => i === 0 || pos.x === positions[i - 1].x || pos.y === positions[i - 1].y)).toBe(
+      true
+    );
+  });
+
+  it('should be faster than the multi-room search for room-local moves', () => {
+    const iterations = 100;
+    const roomTime = cpuTime(() => {
+      ephemeral(roomAstarPath(start, { costMatrix, destinations: [{ pos: goal, range: 0 }] })!);
+    }, iterations);
+    const multiroomTime = cpuTime(() => {
+      const result = astarMultiroomDistanceMap([start], {
+        costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+        anyOfDestinations: [{ pos: goal, range: 0 }]
+      });
+      ephemeral(ephemeral(result.distanceMap).pathToOrigin(result.foundTargets[0]));
+    }, iterations);
+
+    console.log(`roomAstarPath: ${roomTime / iterations} CPU, multi-room A*: ${multiroomTime / iterations} CPU`);
+    expect(roomTime).toBeLessThan(multiroomTime);
+  });
+});
@@ -0,0 +1,33 @@
+import { ClockworkCostMatrix, ephemeral, exportRoomGraph } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('roomGraph', () => {
+  it('should export rooms, blocked rooms, and borders', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const graph = exportRoomGraph(['W1N1'], {
+      costMatrixCallback: room => (room === 'W1N1' || room === 'W2N1' ? costMatrix : undefined),
+      maxRooms: 5
+    });
+
+    expect(graph.rooms.includes('W1N1')).toBe(true);
+    expect(graph.rooms.includes('W2N1')).toBe(true);
+    expect(graph.blockedRooms.includes('W1N2')).toBe(true);
+    const edge = graph.edges.find(edge => edge.from === 'W1N1' && edge.to === 'W2N1');
+    expect(edge?.width).toBe(48);
+    expect(edge?.cost).toBe(1);
+    expect(graph.edges.some(edge => edge.to === 'W1N2')).toBe(false);
+  });
+
+  it('should apply room multipliers and the room change cost to edges', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const graph = exportRoomGraph(['W1N1'], {
+      costMatrixCallback: room => (room === 'W1N1' || room === 'W2N1' ? costMatrix : undefined),
+      maxRooms: 5,
+      roomMultipliers: { W2N1: 3 },
+      roomChangeCost: 10
+    });
+
+    const edge = graph.edges.find(edge => edge.from === 'W1N1' && edge.to === 'W2N1');
+    expect(edge?.cost).toBe(13);
+  });
+});
@@ -0,0 +1,151 @@
+import {
+  adjacentRooms,
+  bfsMultiroomDistanceMap,
+  clearCostMatrixCache,
+  ClockworkCostMatrix,
+  ephemeral,
+  exitDirectionBetween,
+  exitWidths,
+  getPackedTerrain,
+  prefetchCorridor,
+  preloadRooms,
+  PreprocessKind,
+  preprocessRooms,
+  roomTraversalCost,
+  scoutFrontier,
+  setWorldSize
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('rooms', () => {
+  it('should list adjacent rooms across the N/S and E/W boundaries', () => {
+    expect(adjacentRooms('W1N1').join()).toBe('W1N2,W0N1,W1N0,W2N1');
+    expect(adjacentRooms('W0S0').join()).toBe('W0N0,E0S0,W0S1,W1S0');
+  });
+
+  it('should skip rooms beyond the edge of a smaller world', () => {
+    setWorldSize(2);
+    try {
+      expect(adjacentRooms('W0N0').join()).toBe('E0N0,W0S0');
+      expect(() => adjacentRooms('W1N1')).toThrow();
+    } finally {
+      setWorldSize(256);
+    }
+    expect(adjacentRooms('W0N0').length).toBe(4);
+  });
+
+  it('should keep searches inside a smaller world', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = () => costMatrix;
+    setWorldSize(2);
+    try {
+      const distanceMap = ephemeral(
+        bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W0N0')], { costMatrixCallback, maxRooms: 10 }).distanceMap
+      );
+      expect(distanceMap.getRooms().sort().join()).toBe('E0N0,E0S0,W0N0,W0S0');
+      expect(() =>
+        bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], { costMatrixCallback, maxRooms: 10 })
+      ).toThrow();
+      expect(() =>
+        bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W0N0')], {
+          costMatrixCallback,
+          anyOfDestinations: [{ pos: new RoomPosition(25, 25, 'W1N0'), range: 0 }]
+        })
+      ).toThrow();
+    } finally {
+      setWorldSize(256);
+    }
+  });
+
+  it('should reject positions outside a smaller world from every entry point', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = () => costMatrix;
+    setWorldSize(2);
+    try {
+      expect(() => getPackedTerrain('W1N1')).toThrow(/outside the world/);
+      expect(() => exitWidths('W1N1')).toThrow(/outside the world/);
+      expect(() =>
+        bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W0N0')], {
+          costMatrixCallback,
+          maxRooms: 1,
+          blockedTiles: [new RoomPosition(25, 25, 'W1N1')]
+        })
+      ).toThrow(/outside the world/);
+      const distanceMap = ephemeral(
+        bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W0N0')], { costMatrixCallback, maxRooms: 1 }).distanceMap
+      );
+      expect(() => distanceMap.pathToOrigin(new RoomPosition(25, 25, 'W1N1'))).toThrow(/outside the world/);
+    } finally {
+      setWorldSize(256);
+    }
+    expect(getPackedTerrain('W1N1')).toBeDefined();
+  });
+
+  it('should find the exit direction between adjacent rooms', () => {
+    expect(exitDirectionBetween('W1N0', 'W1S0')).toBe(BOTTOM);
+    expect(exitDirectionBetween('W0N1', 'E0N1')).toBe(RIGHT);
+    expect(exitDirectionBetween('W1N1', 'W1N1')).toBeUndefined();
+    expect(exitDirectionBetween('W1N1', 'W3N1')).toBeUndefined();
+  });
+
+  it('should list unexplored rooms nearest first', () => {
+    const neighbors = adjacentRooms('W1N1');
+    const nearby = scoutFrontier(['W1N1'], 1);
+    expect(nearby.length).toBeGreaterThan(0);
+    expect(nearby.every(room => neighbors.includes(room))).toBe(true);
+
+    const farther = scoutFrontier(['W1N1'], 2);
+    expect(farther.length).toBeGreaterThan(nearby.length);
+    expect(farther.slice(0, nearby.length).every(room => nearby.includes(room))).toBe(true);
+    expect(farther.includes('W1N1')).toBe(false);
+  });
+
+  it('should prefetch the rooms along a corridor once', () => {
+    clearCostMatrixCache();
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const requested: string[] = [];
+    const costMatrixCallback = (room: string) => {
+      requested.push(room);
+      return costMatrix;
+    };
+
+    expect(prefetchCorridor('W1N1', ['W4N1'], { costMatrixCallback, width: 0 }).join()).toBe('W1N1,W2N1,W3N1,W4N1');
+    expect(requested.join()).toBe('W1N1,W2N1,W3N1,W4N1');
+    prefetchCorridor('W1N1', ['W3N1'], { costMatrixCallback, width: 0 });
+    expect(requested.length).toBe(4);
+    expect(prefetchCorridor('W1N1', ['W2N1'], { costMatrixCallback }).length).toBe(12);
+  });
+
+  it('should preload terrain and cost matrices', () => {
+    clearCostMatrixCache();
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const requested: string[] = [];
+    const costMatrixCallback = (room: string) => {
+      requested.push(room);
+      return room === 'W1N1' ? costMatrix : undefined;
+    };
+
+    expect(preloadRooms(['W1N1', 'W2N1'])).toBe(2);
+    expect(preloadRooms(['W1N1', 'W2N1'], costMatrixCallback)).toBe(1);
+    expect(requested.join()).toBe('W1N1,W2N1');
+    ephemeral(
+      bfsMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], { costMatrixCallback, maxRooms: 1 }).distanceMap
+    );
+    expect(requested.length).toBe(2);
+  });
+
+  it('should preprocess rooms in one batch', () => {
+    expect(preprocessRooms(['W1N1']).W1N1).toEqual([PreprocessKind.Terrain, PreprocessKind.Exits]);
+    expect(preprocessRooms(['W1N1'], [PreprocessKind.Exits]).W1N1).toEqual([PreprocessKind.Exits]);
+
+    const widths = exitWidths('W1N1');
+    expect(widths.top + widths.right + widths.bottom + widths.left).toBeGreaterThan(0);
+    expect(widths.top).toBeLessThan(49);
+  });
+
+  it('should estimate the cost of crossing a room from its terrain', () => {
+    const cost = roomTraversalCost('W1N1', { maxAge: 0 })!;
+    expect(cost).toBeGreaterThanOrEqual(50);
+    expect(cost).toBeLessThanOrEqual(250);
+  });
+});
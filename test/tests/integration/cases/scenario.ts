@@ -0,0 +1,46 @@
+import { dijkstraMultiroomDistanceMap, ephemeral, generateScenario, ScenarioKind } from '../../../../src/index';
+import { MAX_USIZE } from '../../../../src/utils/constants';
+import { describe, expect, it } from '../../helpers';
+
+describe('scenario', () => {
+  it('should generate a rectangle of rooms east and south of the top left', () => {
+    const scenario = ephemeral(generateScenario(ScenarioKind.OpenPlains, 'W2N2', 2, 2));
+
+    expect(scenario.rooms().sort()).toEqual(['W1N1', 'W1N2', 'W2N1', 'W2N2']);
+    expect(scenario.getCostMatrix('W3N3')).toBeUndefined();
+    expect(ephemeral(scenario.getCostMatrix('W1N1')!).get(25, 25)).toBe(1);
+  });
+
+  it('should produce the same world from the same seed', () => {
+    const first = ephemeral(generateScenario(ScenarioKind.Maze, 'W1N1', 1, 1, 7));
+    const second = ephemeral(generateScenario(ScenarioKind.Maze, 'W1N1', 1, 1, 7));
+    const other = ephemeral(generateScenario(ScenarioKind.Maze, 'W1N1', 1, 1, 8));
+    const matrix = ephemeral(first.getCostMatrix('W1N1')!);
+
+    expect(matrix.equals(ephemeral(second.getCostMatrix('W1N1')!))).toBeTruthy();
+    expect(matrix.equals(ephemeral(other.getCostMatrix('W1N1')!))).toBeFalsy();
+    expect(matrix.get(1, 1)).toBe(1);
+    expect(matrix.get(2, 2)).toBe(255);
+  });
+
+  it('should work as a search cost matrix callback', () => {
+    const scenario = ephemeral(generateScenario(ScenarioKind.WalledBase, 'W1N1', 1, 1));
+    const matrix = ephemeral(scenario.getCostMatrix('W1N1')!);
+    expect(matrix.get(15, 15)).toBe(255);
+
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: scenario.getCostMatrix,
+        maxRooms: 1
+      }).distanceMap
+    );
+    // The compound wall is impassable, but its gaps lead outside
+    expect(distanceMap.get(new RoomPosition(15, 15, 'W1N1'))).toBe(MAX_USIZE);
+    expect(distanceMap.get(new RoomPosition(25, 5, 'W1N1'))).toBeLessThan(MAX_USIZE);
+    expect(distanceMap.get(new RoomPosition(25, 30, 'W1N1'))).toBe(5);
+  });
+
+  it('should reject invalid scenario kinds', () => {
+    expect(() => generateScenario(99 as ScenarioKind, 'W1N1', 1, 1)).toThrow();
+  });
+});
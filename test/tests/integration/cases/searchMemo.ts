@@ -0,0 +1,28 @@
+import {
+  astarMultiroomDistanceMap,
+  ClockworkCostMatrix,
+  ephemeral,
+  memoizedSearch,
+  resetSearchMemoStats,
+  searchMemoStats
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('memoizedSearch', () => {
+  it('should reuse the result of an identical search in the same tick', () => {
+    resetSearchMemoStats();
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const costMatrixCallback = () => costMatrix;
+    const search = (x: number) =>
+      memoizedSearch(astarMultiroomDistanceMap, [new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback,
+        maxOps: 2500,
+        anyOfDestinations: [{ pos: new RoomPosition(x, 25, 'W1N1'), range: 0 }]
+      });
+
+    const first = search(30);
+    expect(search(30)).toBe(first);
+    expect(search(35) === first).toBe(false);
+    expect(searchMemoStats()).toEqual({ hits: 1, misses: 2 });
+  });
+});
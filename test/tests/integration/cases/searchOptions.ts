@@ -0,0 +1,79 @@
+import {
+  defaultSearchOptions,
+  GoalKind,
+  OpenListOverflow,
+  setWorldSize,
+  UnknownRoomPolicy,
+  validateSearchOptions
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('searchOptions', () => {
+  it('should report the defaults searches use', () => {
+    const defaults = defaultSearchOptions();
+    expect(defaults.allowDiagonals).toBe(true);
+    expect(defaults.cutCorners).toBe(true);
+    expect(defaults.roomChangeCost).toBe(0);
+    expect(defaults.maxOpenNodes).toBe(0xffffffff);
+    expect(defaults.openListOverflow).toBe(OpenListOverflow.Stop);
+    expect(defaults.beamWidth).toBe(100);
+    expect(defaults.unknownRooms).toBe(UnknownRoomPolicy.Blocked);
+    expect(defaults.unknownRoomMaxAge).toBe(1500);
+  });
+
+  it('should accept valid options', () => {
+    expect(
+      validateSearchOptions({
+        maxRooms: 4,
+        anyOfDestinations: [{ pos: new RoomPosition(25, 25, 'W1N1'), range: 3 }],
+        roomMultipliers: { W2N1: 2 },
+        maxOpenNodes: 1000,
+        openListOverflow: OpenListOverflow.Beam
+      })
+    ).toEqual([]);
+  });
+
+  it('should report options that would throw or change the result', () => {
+    const fields = (errors: { field: string }[]) => errors.map(error => error.field).join();
+
+    expect(fields(validateSearchOptions({}))).toBe('maxOps');
+    expect(fields(validateSearchOptions({ maxRooms: 0 }))).toBe('maxRooms');
+    expect(
+      fields(
+        validateSearchOptions({
+          allOfDestinations: [{ pos: new RoomPosition(25, 25, 'W1N1'), range: 1, kind: 9 as GoalKind }]
+        })
+      )
+    ).toBe('allOfDestinations');
+    expect(fields(validateSearchOptions({ maxOps: 100, roomMultipliers: { W2N1: -1 } }))).toBe('roomMultipliers');
+    expect(
+      fields(validateSearchOptions({ maxOps: 100, unknownRooms: UnknownRoomPolicy.LastKnown, unknownRoomMaxAge: 0 }))
+    ).toBe('unknownRoomMaxAge');
+    expect(fields(validateSearchOptions({ maxRooms: 2, maxPathCost: 50, roomChangeCost: 100 }))).toBe(
+      'roomChangeCost'
+    );
+    expect(
+      fields(
+        validateSearchOptions({
+          maxOps: 100,
+          maxOpenNodes: 0,
+          openListOverflow: OpenListOverflow.Beam,
+          beamWidth: 0
+        })
+      )
+    ).toBe('maxOpenNodes,beamWidth');
+  });
+
+  it('should report destinations outside the world', () => {
+    setWorldSize(2);
+    try {
+      const errors = validateSearchOptions({
+        anyOfDestinations: [{ pos: new RoomPosition(25, 25, 'W5N5'), range: 0 }]
+      });
+      expect(errors.length).toBe(1);
+      expect(errors[0].field).toBe('anyOfDestinations');
+    } finally {
+      setWorldSize(256);
+    }
+  });
+});
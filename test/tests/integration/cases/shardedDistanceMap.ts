@@ -0,0 +1,55 @@
+import {
+  ClockworkCostMatrix,
+  ClockworkShardedDistanceMap,
+  dijkstraMultiroomDistanceMap,
+  ephemeral,
+  packShardedPosition,
+  packShardedRoomName,
+  unpackShardedPosition,
+  unpackShardedRoomName
+} from '../../../../src/index';
+import { MAX_USIZE } from '../../../../src/utils/constants';
+import { describe, expect, it } from '../../helpers';
+
+describe('shardedDistanceMap', () => {
+  it('should round-trip sharded room names and positions', () => {
+    const pos = new RoomPosition(12, 34, 'W1N1');
+
+    expect(unpackShardedRoomName(packShardedRoomName('shard3', 'W1N1'))).toEqual({ shard: 'shard3', room: 'W1N1' });
+    expect(packShardedRoomName('shard0', 'W1N1') === packShardedRoomName('shard1', 'W1N1')).toBeFalsy();
+
+    const { shard, pos: unpacked } = unpackShardedPosition(packShardedPosition('shard2', pos));
+    expect(shard).toBe('shard2');
+    expect(unpacked.isEqualTo(pos)).toBeTruthy();
+  });
+
+  it('should keep the same room on different shards separate', () => {
+    const map = ephemeral(new ClockworkShardedDistanceMap());
+    const pos = new RoomPosition(25, 25, 'W1N1');
+    map.set('shard0', pos, 5);
+    map.set('shard1', pos, 7);
+
+    expect(map.get('shard0', pos)).toBe(5);
+    expect(map.get('shard1', pos)).toBe(7);
+    expect(map.get('shard2', pos)).toBe(MAX_USIZE);
+    expect(map.getSharded(packShardedPosition('shard1', pos))).toBe(7);
+    expect(map.getShards().sort()).toEqual(['shard0', 'shard1']);
+  });
+
+  it('should store and copy whole shards', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(25, 25, 'W1N1')], {
+        costMatrixCallback: () => costMatrix,
+        maxRooms: 1
+      }).distanceMap
+    );
+    const map = ephemeral(new ClockworkShardedDistanceMap());
+    map.setShard('shard3', distanceMap);
+
+    const shard = ephemeral(map.getShard('shard3')!);
+    expect(shard.get(new RoomPosition(30, 25, 'W1N1'))).toBe(5);
+    expect(map.get('shard3', new RoomPosition(30, 25, 'W1N1'))).toBe(5);
+    expect(map.getShard('shard1')).toBeUndefined();
+  });
+});
@@ -0,0 +1,31 @@
+import { ClockworkCostMatrix, ephemeral, sourceAccessibility } from '../../../../src/index';
+import { MAX_USIZE } from '../../../../src/utils/constants';
+import { describe, expect, it } from '../../helpers';
+
+describe('sourceAccessibility', () => {
+  it('should count walkable tiles and measure distance from the anchor', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const source = new RoomPosition(25, 25, 'W1N1');
+    const walledIn = new RoomPosition(10, 10, 'W1N1');
+    for (let x = 24; x <= 26; x++) {
+      costMatrix.set(x, 24, 255);
+    }
+    for (let x = 9; x <= 11; x++) {
+      for (let y = 9; y <= 11; y++) {
+        costMatrix.set(x, y, 255);
+      }
+    }
+
+    const [sourceScore, walledInScore] = sourceAccessibility(
+      'W1N1',
+      [source, walledIn],
+      costMatrix,
+      new RoomPosition(25, 30, 'W1N1')
+    );
+
+    expect(sourceScore.walkable).toBe(5);
+    expect(sourceScore.distance).toBe(4);
+    expect(walledInScore.walkable).toBe(0);
+    expect(walledInScore.distance).toBe(MAX_USIZE);
+  });
+});
@@ -0,0 +1,57 @@
+import { findSwampBridges } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+// A plain tile is narrow if the run of plains through it (horizontally or
+// vertically) is at most `maxWidth` long and ends in swamp on at least one side
+function referenceNarrowTiles(roomName: string, maxWidth: number) {
+  const terrain = Game.map.getRoomTerrain(roomName);
+  const at = (x: number, y: number) => (x < 0 || x > 49 || y < 0 || y > 49 ? TERRAIN_MASK_WALL : terrain.get(x, y));
+  const isNarrow = (x: number, y: number, dx: number, dy: number) => {
+    let width = 1;
+    let swamp = false;
+    for (const sign of [-1, 1]) {
+      for (let step = 1; ; step++) {
+        const tile = at(x + dx * step * sign, y + dy * step * sign);
+        if (tile === 0) {
+          width++;
+        } else {
+          swamp = swamp || tile === TERRAIN_MASK_SWAMP;
+          break;
+        }
+      }
+    }
+    return swamp && width <= maxWidth;
+  };
+
+  const narrow = new Set<string>();
+  for (let y = 0; y < 50; y++) {
+    for (let x = 0; x < 50; x++) {
+      if (at(x, y) === 0 && (isNarrow(x, y, 1, 0) || isNarrow(x, y, 0, 1))) {
+        narrow.add(`${x},${y}`);
+      }
+    }
+  }
+  return narrow;
+}
+
+describe('swampBridges', () => {
+  it('should find every narrow plain tile bordered by swamp', () => {
+    const corridors = findSwampBridges('W1N1', { maxWidth: 2, minLength: 1 });
+    const found = ([] as RoomPosition[]).concat(...corridors).map(pos => `${pos.x},${pos.y}`);
+    const expected = referenceNarrowTiles('W1N1', 2);
+
+    expect(found.length).toBe(expected.size);
+    expect(found.every(tile => expected.has(tile))).toBeTruthy();
+  });
+
+  it('should group tiles into connected corridors of the minimum length', () => {
+    const corridors = findSwampBridges('W1N1', { maxWidth: 3, minLength: 3 });
+    for (const corridor of corridors) {
+      expect(corridor.length).toBeGreaterThanOrEqual(3);
+      // Every tile touches another tile in its corridor
+      expect(corridor.every(pos => corridor.some(other => other !== pos && pos.isNearTo(other)))).toBeTruthy();
+    }
+    const all = findSwampBridges('W1N1', { maxWidth: 3, minLength: 1 });
+    expect(all.length).toBeGreaterThanOrEqual(corridors.length);
+  });
+});
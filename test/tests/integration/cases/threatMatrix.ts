@@ -0,0 +1,39 @@
+import { buildThreatMatrix, ClockworkCostMatrix, ephemeral } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('buildThreatMatrix', () => {
+  it('should add damage penalties in range of hostiles', () => {
+    const base = ephemeral(new ClockworkCostMatrix(1));
+    base.set(27, 25, 255);
+    const matrix = ephemeral(
+      buildThreatMatrix(
+        'W1N1',
+        [
+          { pos: new RoomPosition(25, 25, 'W1N1'), range: 3, dps: 40 },
+          { pos: new RoomPosition(20, 25, 'W1N1'), range: 1, dps: 90 }
+        ],
+        { base, costPerDps: 0.5 }
+      )
+    );
+
+    expect(matrix.get(25, 25)).toBe(21);
+    expect(matrix.get(28, 28)).toBe(21);
+    expect(matrix.get(29, 25)).toBe(1);
+    expect(matrix.get(27, 25)).toBe(255);
+    expect(matrix.get(21, 25)).toBe(46);
+    expect(matrix.get(22, 25)).toBe(21);
+    expect(matrix.get(20, 24)).toBe(46);
+    expect(base.get(25, 25)).toBe(1);
+  });
+
+  it('should count hostiles across the room border', () => {
+    const base = ephemeral(new ClockworkCostMatrix(1));
+    const matrix = ephemeral(
+      buildThreatMatrix('W1N1', [{ pos: new RoomPosition(48, 25, 'W2N1'), range: 3, dps: 10 }], { base })
+    );
+
+    expect(matrix.get(0, 25)).toBe(11);
+    expect(matrix.get(1, 25)).toBe(11);
+    expect(matrix.get(2, 25)).toBe(1);
+  });
+});
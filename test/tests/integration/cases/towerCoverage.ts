@@ -0,0 +1,42 @@
+import { ephemeral, prioritizeRamparts, towerCoverage } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+const towers = [new RoomPosition(10, 25, 'W1N1'), new RoomPosition(40, 25, 'W1N1')];
+
+describe('towerCoverage', () => {
+  it('should count the towers dealing at least the minimum damage', () => {
+    const fullDamage = ephemeral(towerCoverage(towers, 600));
+    expect(fullDamage.get(10, 25)).toBe(1);
+    expect(fullDamage.get(15, 25)).toBe(1);
+    expect(fullDamage.get(16, 25)).toBe(0);
+    expect(fullDamage.get(25, 25)).toBe(0);
+
+    // Both towers are 15 tiles away, dealing 300 damage each
+    const halfDamage = ephemeral(towerCoverage(towers, 300));
+    expect(halfDamage.get(25, 25)).toBe(2);
+    expect(halfDamage.get(10, 25)).toBe(1);
+
+    const minDamage = ephemeral(towerCoverage(towers, 150));
+    expect(minDamage.get(0, 0)).toBe(2);
+  });
+
+  it('should order exposed ramparts from least to most covered', () => {
+    const coverage = ephemeral(towerCoverage(towers, 300));
+    const ramparts = [
+      new RoomPosition(25, 25, 'W1N1'),
+      new RoomPosition(10, 25, 'W1N1'),
+      new RoomPosition(2, 2, 'W1N1')
+    ];
+
+    const exposed = prioritizeRamparts(ramparts, coverage, 2);
+    expect(exposed.map(pos => [pos.x, pos.y])).toEqual([
+      [2, 2],
+      [10, 25]
+    ]);
+    expect(prioritizeRamparts([], coverage, 2)).toEqual([]);
+  });
+
+  it('should reject towers in different rooms', () => {
+    expect(() => towerCoverage([new RoomPosition(25, 25, 'W1N1'), new RoomPosition(25, 25, 'W2N1')], 600)).toThrow();
+  });
+});
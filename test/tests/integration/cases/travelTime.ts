@@ -0,0 +1,108 @@
+import {
+  bodySpec,
+  ClockworkCostMatrix,
+  ClockworkPath,
+  dijkstraMultiroomDistanceMap,
+  ephemeral,
+  estimateTravelTicks,
+  getTerrainCostMatrix
+} from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+function terrainPath(): ClockworkPath {
+  const terrain = Game.map.getRoomTerrain('W1N1');
+  const tiles: RoomPosition[] = [];
+  for (let y = 2; y < 48; y++) {
+    for (let x = 2; x < 48; x++) {
+      if (terrain.get(x, y) !== TERRAIN_MASK_WALL) {
+        tiles.push(new RoomPosition(x, y, 'W1N1'));
+      }
+    }
+  }
+  const costMatrix = ephemeral(getTerrainCostMatrix('W1N1'));
+  const distanceMap = ephemeral(
+    dijkstraMultiroomDistanceMap([tiles[0]], {
+      costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+      maxRooms: 1
+    }).distanceMap
+  );
+  const target = tiles.reverse().find(pos => distanceMap.get(pos) < 0xffffffff)!;
+  return ephemeral(distanceMap.pathToOrigin(target));
+}
+
+// Walks the path tile by tile, as the game does: the creep starts on the first
+// tile, is carried across room borders without moving, can't move while it has
+// fatigue, and its MOVE parts remove `regen` fatigue per tick.
+function referenceTicks(path: ClockworkPath, weight: number, regen: number) {
+  const positions = path.toArray();
+  let ticks = 0;
+  for (let i = 1; i < positions.length; i++) {
+    const pos = positions[i];
+    if (pos.roomName !== positions[i - 1].roomName) continue;
+    const road = pos.lookFor(LOOK_STRUCTURES).some(s => s.structureType === STRUCTURE_ROAD);
+    const tile = Game.map.getRoomTerrain(pos.roomName).get(pos.x, pos.y);
+    const fatigue = road ? 1 : tile === TERRAIN_MASK_SWAMP ? 10 : tile === TERRAIN_MASK_WALL ? 0 : 2;
+    ticks += 1 + Math.ceil(Math.max(0, fatigue * weight - regen) / regen);
+  }
+  return ticks;
+}
+
+describe('travelTime', () => {
+  it('should match a tick-by-tick walk of the path', () => {
+    const path = terrainPath();
+    const cases: [BodyPartConstant[], number, number][] = [
+      [[MOVE, CARRY], 0, 2],
+      [[WORK, MOVE], 1, 2],
+      [[WORK, WORK, WORK, MOVE], 3, 2],
+      [[WORK, WORK, MOVE, MOVE], 2, 4]
+    ];
+    for (const [body, weight, regen] of cases) {
+      const spec = ephemeral(bodySpec(body));
+      expect(estimateTravelTicks(path, spec)).toBe(referenceTicks(path, weight, regen));
+      expect(path.estimateTravelTicks(spec)).toBe(referenceTicks(path, weight, regen));
+    }
+  });
+
+  it('should weigh loaded carry parts and boosted move parts', () => {
+    const path = terrainPath();
+    const loaded = ephemeral(bodySpec([MOVE, CARRY, CARRY], 60));
+    expect(estimateTravelTicks(path, loaded)).toBe(referenceTicks(path, 2, 2));
+
+    const boosted = ephemeral(
+      bodySpec([
+        { type: WORK, hits: 100 },
+        { type: MOVE, hits: 100, boost: 'XZHO2' }
+      ])
+    );
+    expect(estimateTravelTicks(path, boosted)).toBe(referenceTicks(path, 1, 8));
+  });
+
+  it('should report creeps that cannot move', () => {
+    const path = terrainPath();
+    expect(estimateTravelTicks(path, ephemeral(bodySpec([WORK, CARRY])))).toBeUndefined();
+    expect(estimateTravelTicks(path, ephemeral(bodySpec([CARRY])))).toBe(path.length - 1);
+  });
+
+  it('should not count the start or the far side of room borders as moves', () => {
+    const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+    const distanceMap = ephemeral(
+      dijkstraMultiroomDistanceMap([new RoomPosition(5, 25, 'W1N1')], {
+        costMatrixCallback: room => (room === 'W1N1' || room === 'W2N1' ? costMatrix : undefined),
+        maxRooms: 2
+      }).distanceMap
+    );
+    const path = ephemeral(distanceMap.pathToOrigin(new RoomPosition(45, 25, 'W2N1')));
+    const positions = path.toArray();
+    const crossings = positions.filter((pos, i) => i > 0 && pos.roomName !== positions[i - 1].roomName).length;
+    expect(crossings).toBe(1);
+
+    expect(estimateTravelTicks(path, ephemeral(bodySpec([CARRY])))).toBe(positions.length - 2);
+    for (const [body, weight, regen] of [
+      [[MOVE], 0, 2],
+      [[WORK, MOVE], 1, 2],
+      [[WORK, WORK, WORK, MOVE], 3, 2]
+    ] as [BodyPartConstant[], number, number][]) {
+      expect(estimateTravelTicks(path, ephemeral(bodySpec(body)))).toBe(referenceTicks(path, weight, regen));
+    }
+  });
+});
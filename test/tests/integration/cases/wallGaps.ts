@@ -0,0 +1,54 @@
+import { bfsMultiroomDistanceMap, ephemeral, findWallGaps, getTerrainCostMatrix } from '../../../../src/index';
+import { MAX_USIZE } from '../../../../src/utils/constants';
+import { describe, expect, it } from '../../helpers';
+
+const edgeDistance = (x: number, y: number) => Math.min(x, y, 49 - x, 49 - y);
+
+describe('wallGaps', () => {
+  it('should only include walkable tiles two tiles in from the edges', () => {
+    const terrain = Game.map.getRoomTerrain('W1N1');
+    const gaps = findWallGaps('W1N1');
+    expect(gaps.length).toBeGreaterThan(0);
+    for (const gap of gaps) {
+      expect(gap.length).toBeGreaterThan(0);
+      for (const pos of gap) {
+        expect(edgeDistance(pos.x, pos.y)).toBe(2);
+        expect(terrain.get(pos.x, pos.y) === TERRAIN_MASK_WALL).toBeFalsy();
+      }
+    }
+  });
+
+  it('should seal the room off from its exits when blocked', () => {
+    const terrain = Game.map.getRoomTerrain('W1N1');
+    const costMatrix = ephemeral(getTerrainCostMatrix('W1N1'));
+    for (const gap of findWallGaps('W1N1')) {
+      for (const pos of gap) {
+        costMatrix.set(pos.x, pos.y, 255);
+      }
+    }
+    const exits: RoomPosition[] = [];
+    for (let y = 0; y < 50; y++) {
+      for (let x = 0; x < 50; x++) {
+        if (edgeDistance(x, y) === 0 && terrain.get(x, y) !== TERRAIN_MASK_WALL) {
+          exits.push(new RoomPosition(x, y, 'W1N1'));
+        }
+      }
+    }
+
+    const distanceMap = ephemeral(
+      bfsMultiroomDistanceMap(exits, {
+        costMatrixCallback: room => (room === 'W1N1' ? costMatrix : undefined),
+        maxRooms: 1
+      }).distanceMap
+    );
+    let reachedInside = 0;
+    for (let y = 0; y < 50; y++) {
+      for (let x = 0; x < 50; x++) {
+        if (edgeDistance(x, y) >= 2 && distanceMap.get(new RoomPosition(x, y, 'W1N1')) !== MAX_USIZE) {
+          reachedInside++;
+        }
+      }
+    }
+    expect(reachedInside).toBe(0);
+  });
+});
@@ -0,0 +1,48 @@
+import { ClockworkCostMatrix, ephemeral, waypointPath } from '../../../../src/index';
+import { describe, expect, it } from '../../helpers';
+
+describe('waypointPath', () => {
+  const costMatrix = ephemeral(new ClockworkCostMatrix(1));
+  const costMatrixCallback = (room: string) => (room === 'W1N1' ? costMatrix : undefined);
+  const origin = new RoomPosition(10, 10, 'W1N1');
+
+  it('should visit the waypoints in order', () => {
+    const path = ephemeral(
+      waypointPath(
+        origin,
+        [
+          { pos: new RoomPosition(20, 10, 'W1N1'), range: 0 },
+          { pos: new RoomPosition(20, 20, 'W1N1'), range: 0 }
+        ],
+        { costMatrixCallback }
+      )!
+    );
+    expect(path.length).toBe(21);
+    expect(path.get(0).isEqualTo(origin)).toBeTruthy();
+    expect(path.get(10).isEqualTo(new RoomPosition(20, 10, 'W1N1'))).toBeTruthy();
+    expect(path.get(20).isEqualTo(new RoomPosition(20, 20, 'W1N1'))).toBeTruthy();
+  });
+
+  it('should start each leg where the last one reached its waypoint', () => {
+    const path = ephemeral(
+      waypointPath(
+        origin,
+        [
+          { pos: new RoomPosition(20, 10, 'W1N1'), range: 2 },
+          { pos: new RoomPosition(10, 10, 'W1N1'), range: 0 }
+        ],
+        { costMatrixCallback }
+      )!
+    );
+    expect(path.length).toBe(17);
+    expect(path.get(16).isEqualTo(origin)).toBeTruthy();
+  });
+
+  it('should return undefined if a waypoint is unreachable', () => {
+    const path = waypointPath(origin, [{ pos: new RoomPosition(20, 10, 'W2N1'), range: 0 }], {
+      costMatrixCallback,
+      maxRooms: 2
+    });
+    expect(path).toBeUndefined();
+  });
+});
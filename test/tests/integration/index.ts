@@ -4,13 +4,49 @@
  * require creeps or other additional setup.
  */
 
+import './cases/anytimeAstar';
 import './cases/astarMultiroomDistanceMap';
+import './cases/backgroundJobs';
 import './cases/bfsMultiroomDistanceMap';
 import './cases/clockworkCostMatrix';
+import './cases/clockworkInfo';
+import './cases/closestOf';
 import './cases/dijkstraMultiroomDistanceMap';
+import './cases/disperse';
+import './cases/flee';
+import './cases/fringeSearch';
 import './cases/getRange';
+import './cases/growRegion';
+import './cases/heuristicTuner';
+import './cases/knownRooms';
+import './cases/layout';
+import './cases/logistics';
+import './cases/multiroomDistanceMap';
 import './cases/multiroomDistanceMapPath';
 import './cases/multiroomFlowField';
 import './cases/multiroomFlowFieldPath';
 import './cases/multiroomMonoFlowField';
 import './cases/multiroomMonoFlowFieldPath';
+import './cases/namespaces';
+import './cases/nearestFreeTile';
+import './cases/noise';
+import './cases/nukes';
+import './cases/packedTerrain';
+import './cases/panicContext';
+import './cases/patrolLoop';
+import './cases/positionObjects';
+import './cases/repairTour';
+import './cases/roomAstarPath';
+import './cases/roomGraph';
+import './cases/rooms';
+import './cases/scenario';
+import './cases/searchMemo';
+import './cases/searchOptions';
+import './cases/shardedDistanceMap';
+import './cases/sourceAccessibility';
+import './cases/swampBridges';
+import './cases/threatMatrix';
+import './cases/towerCoverage';
+import './cases/travelTime';
+import './cases/wallGaps';
+import './cases/waypointPath';